@@ -3,7 +3,12 @@ use gpui::*;
 use gpui_component::{
     IconName, Root, Sizable,
     button::{Button, ButtonVariants as _},
-    dock::{ClosePanel, DockArea, DockAreaState, DockEvent, DockItem, DockPlacement, ToggleZoom},
+    dock::{
+        ClosePanel, DockArea, DockAreaState, DockEvent, DockItem, DockPlacement, FloatPanel,
+        FocusPanelDown, FocusPanelLeft, FocusPanelRight, FocusPanelUp, MovePanelDown,
+        MovePanelLeft, MovePanelRight, MovePanelUp, NextTab, PreviousTab, ToggleBottomDock,
+        ToggleLeftDock, ToggleRightDock, ToggleZoom, UnfloatPanel,
+    },
     menu::DropdownMenu,
 };
 
@@ -44,6 +49,21 @@ pub fn init(cx: &mut App) {
     cx.bind_keys(vec![
         KeyBinding::new("shift-escape", ToggleZoom, None),
         KeyBinding::new("ctrl-w", ClosePanel, None),
+        KeyBinding::new("ctrl-alt-f", FloatPanel, None),
+        KeyBinding::new("ctrl-alt-d", UnfloatPanel, None),
+        KeyBinding::new("ctrl-tab", NextTab, None),
+        KeyBinding::new("ctrl-shift-tab", PreviousTab, None),
+        KeyBinding::new("ctrl-alt-left", FocusPanelLeft, None),
+        KeyBinding::new("ctrl-alt-right", FocusPanelRight, None),
+        KeyBinding::new("ctrl-alt-up", FocusPanelUp, None),
+        KeyBinding::new("ctrl-alt-down", FocusPanelDown, None),
+        KeyBinding::new("ctrl-alt-shift-left", MovePanelLeft, None),
+        KeyBinding::new("ctrl-alt-shift-right", MovePanelRight, None),
+        KeyBinding::new("ctrl-alt-shift-up", MovePanelUp, None),
+        KeyBinding::new("ctrl-alt-shift-down", MovePanelDown, None),
+        KeyBinding::new("ctrl-alt-b", ToggleLeftDock, None),
+        KeyBinding::new("ctrl-alt-r", ToggleRightDock, None),
+        KeyBinding::new("ctrl-alt-j", ToggleBottomDock, None),
     ]);
 
     cx.activate(true);
@@ -16,7 +16,8 @@ use gpui_component::{
     highlighter::{Diagnostic, DiagnosticSeverity, Language, LanguageConfig, LanguageRegistry},
     input::{
         self, CodeActionProvider, CompletionProvider, DefinitionProvider, DocumentColorProvider,
-        HoverProvider, Input, InputEvent, InputState, Position, Rope, RopeExt, TabSize,
+        DocumentFormattingProvider, HoverProvider, Input, InputEvent, InputState, Position,
+        RenameProvider, Rope, RopeExt, TabSize,
     },
     list::ListItem,
     resizable::{h_resizable, resizable_panel},
@@ -40,7 +41,16 @@ fn init() {
             tree_sitter_navi::HIGHLIGHTS_QUERY,
             "",
             "",
-        ),
+        )
+        .indents(
+            r#"
+            (_ "[" "]" @end) @indent
+            (_ "{" "}" @end) @indent
+            (_ "(" ")" @end) @indent
+            "#,
+        )
+        .line_comment("//")
+        .block_comment("/*", "*/"),
     );
 }
 
@@ -51,6 +61,7 @@ pub struct Example {
     language: Language,
     line_number: bool,
     indent_guides: bool,
+    minimap: bool,
     soft_wrap: bool,
     lsp_store: ExampleLspStore,
     _subscriptions: Vec<Subscription>,
@@ -364,6 +375,89 @@ impl DefinitionProvider for ExampleLspStore {
     }
 }
 
+impl RenameProvider for ExampleLspStore {
+    fn rename(
+        &self,
+        text: &Rope,
+        offset: usize,
+        new_name: &str,
+        _window: &mut Window,
+        _cx: &mut App,
+    ) -> Task<Result<Vec<TextEdit>>> {
+        let word = text.word_at(offset);
+        if word.is_empty() {
+            return Task::ready(Ok(vec![]));
+        }
+
+        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+        let content = text.to_string();
+        let edits = content
+            .match_indices(&word)
+            .filter(|(start, _)| {
+                let end = start + word.len();
+                !content[..*start].ends_with(is_word_char)
+                    && !content[end..].starts_with(is_word_char)
+            })
+            .map(|(start, _)| TextEdit {
+                range: lsp_types::Range::new(
+                    text.offset_to_position(start),
+                    text.offset_to_position(start + word.len()),
+                ),
+                new_text: new_name.to_string(),
+            })
+            .collect();
+
+        Task::ready(Ok(edits))
+    }
+}
+
+impl DocumentFormattingProvider for ExampleLspStore {
+    fn format_document(
+        &self,
+        text: &Rope,
+        options: &lsp_types::FormattingOptions,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Vec<TextEdit>>> {
+        self.format_range(text, 0..text.len(), options, window, cx)
+    }
+
+    /// Remove the trailing whitespaces of the lines in the range.
+    fn format_range(
+        &self,
+        text: &Rope,
+        range: Range<usize>,
+        _options: &lsp_types::FormattingOptions,
+        _window: &mut Window,
+        _cx: &mut App,
+    ) -> Task<Result<Vec<TextEdit>>> {
+        let start_row = text.offset_to_point(range.start).row;
+        let end_row = text.offset_to_point(range.end).row;
+
+        let mut edits = vec![];
+        for row in start_row..=end_row {
+            let line = text.slice_line(row).to_string();
+            let trimmed_len = line.trim_end().len();
+            if trimmed_len == line.len() {
+                continue;
+            }
+
+            edits.push(TextEdit {
+                range: lsp_types::Range::new(
+                    Position::new(
+                        row as u32,
+                        line[..trimmed_len].encode_utf16().count() as u32,
+                    ),
+                    Position::new(row as u32, line.encode_utf16().count() as u32),
+                ),
+                new_text: "".to_string(),
+            });
+        }
+
+        Task::ready(Ok(edits))
+    }
+}
+
 struct TextConvertor;
 
 impl CodeActionProvider for TextConvertor {
@@ -638,6 +732,7 @@ impl Example {
                 .code_editor(default_language.name())
                 .line_number(true)
                 .indent_guides(true)
+                .minimap(true)
                 .tab_size(TabSize {
                     tab_size: 4,
                     hard_tabs: false,
@@ -652,6 +747,9 @@ impl Example {
             editor.lsp.hover_provider = Some(lsp_store.clone());
             editor.lsp.definition_provider = Some(lsp_store.clone());
             editor.lsp.document_color_provider = Some(lsp_store.clone());
+            editor.lsp.rename_provider = Some(lsp_store.clone());
+            editor.lsp.formatting_provider = Some(lsp_store.clone());
+            editor.set_diff_base(include_str!("./fixtures/test.rs"), cx);
 
             editor
         });
@@ -671,6 +769,7 @@ impl Example {
             language: default_language,
             line_number: true,
             indent_guides: true,
+            minimap: true,
             soft_wrap: false,
             lsp_store,
             _subscriptions,
@@ -836,6 +935,7 @@ impl Example {
                 _ = view.update_in(window, |this, window, cx| {
                     _ = this.editor.update(cx, |this, cx| {
                         this.set_highlighter(language.name(), cx);
+                        this.set_diff_base(content.as_str(), cx);
                         this.set_value(content, window, cx);
                     });
 
@@ -951,6 +1051,21 @@ impl Example {
             }))
     }
 
+    fn render_minimap_button(&self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        Button::new("minimap")
+            .ghost()
+            .xsmall()
+            .when(self.minimap, |this| this.icon(IconName::Check))
+            .label("Minimap")
+            .on_click(cx.listener(|this, _, window, cx| {
+                this.minimap = !this.minimap;
+                this.editor.update(cx, |state, cx| {
+                    state.set_minimap(this.minimap, window, cx);
+                });
+                cx.notify();
+            }))
+    }
+
     fn render_go_to_line_button(&self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let position = self.editor.read(cx).cursor_position();
         let cursor = self.editor.read(cx).cursor();
@@ -1024,7 +1139,8 @@ impl Render for Example {
                                     .gap_3()
                                     .child(self.render_line_number_button(window, cx))
                                     .child(self.render_soft_wrap_button(window, cx))
-                                    .child(self.render_indent_guides_button(window, cx)),
+                                    .child(self.render_indent_guides_button(window, cx))
+                                    .child(self.render_minimap_button(window, cx)),
                             )
                             .child(self.render_go_to_line_button(window, cx)),
                     ),
@@ -4,7 +4,7 @@ use fake::Fake;
 use gpui::{
     App, AppContext, Context, ElementId, Entity, FocusHandle, Focusable, InteractiveElement,
     IntoElement, ParentElement, Render, RenderOnce, ScrollStrategy, SharedString, Styled,
-    Subscription, Task, Timer, Window, actions, div, px,
+    Subscription, Task, Timer, Window, actions, div, prelude::FluentBuilder as _, px,
 };
 
 use gpui_component::{
@@ -63,6 +63,7 @@ impl CompanyListItem {
 impl Selectable for CompanyListItem {
     fn selected(mut self, selected: bool) -> Self {
         self.selected = selected;
+        self.base = self.base.selected(selected);
         self
     }
 
@@ -322,6 +323,9 @@ pub struct ListStory {
     selected_company: Option<Rc<Company>>,
     selectable: bool,
     searchable: bool,
+    multi_select: bool,
+    checkbox: bool,
+    selected_count: usize,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -362,7 +366,7 @@ impl ListStory {
 
         let _subscriptions =
             vec![
-                cx.subscribe(&company_list, |_, _, ev: &ListEvent, _| match ev {
+                cx.subscribe(&company_list, |this, _, ev: &ListEvent, cx| match ev {
                     ListEvent::Select(ix) => {
                         println!("List Selected: {:?}", ix);
                     }
@@ -372,6 +376,13 @@ impl ListStory {
                     ListEvent::Cancel => {
                         println!("List Cancelled");
                     }
+                    ListEvent::Toggle(ix, selected) => {
+                        println!("List Toggled: {:?}, selected: {}", ix, selected);
+                    }
+                    ListEvent::SelectionChange(indices) => {
+                        this.selected_count = indices.len();
+                        cx.notify();
+                    }
                 }),
             ];
 
@@ -401,6 +412,9 @@ impl ListStory {
             focus_handle: cx.focus_handle(),
             searchable: true,
             selectable: true,
+            multi_select: false,
+            checkbox: false,
+            selected_count: 0,
             company_list,
             selected_company: None,
             _subscriptions,
@@ -427,6 +441,31 @@ impl ListStory {
             list.set_searchable(self.searchable, cx);
         })
     }
+
+    fn toggle_multi_select(
+        &mut self,
+        multi_select: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.multi_select = multi_select;
+        if !multi_select {
+            self.checkbox = false;
+            self.selected_count = 0;
+        }
+        self.company_list.update(cx, |list, cx| {
+            list.set_checkbox(self.checkbox, cx);
+            list.set_multi_select(multi_select, window, cx);
+        })
+    }
+
+    fn toggle_checkbox(&mut self, checkbox: bool, _: &mut Window, cx: &mut Context<Self>) {
+        self.checkbox = checkbox;
+        self.multi_select |= checkbox;
+        self.company_list.update(cx, |list, cx| {
+            list.set_checkbox(checkbox, cx);
+        })
+    }
 }
 
 fn random_company() -> Company {
@@ -557,6 +596,22 @@ impl Render for ListStory {
                                 })
                             })),
                     )
+                    .child(
+                        Checkbox::new("multi_select")
+                            .label("Multi Select")
+                            .checked(self.multi_select)
+                            .on_click(cx.listener(|this, check: &bool, window, cx| {
+                                this.toggle_multi_select(*check, window, cx)
+                            })),
+                    )
+                    .child(
+                        Checkbox::new("checkbox")
+                            .label("Checkbox")
+                            .checked(self.checkbox)
+                            .on_click(cx.listener(|this, check: &bool, window, cx| {
+                                this.toggle_checkbox(*check, window, cx)
+                            })),
+                    )
                     .child(
                         Checkbox::new("lazy_load")
                             .label("Lazy Load")
@@ -569,6 +624,13 @@ impl Render for ListStory {
                             })),
                     ),
             )
+            .when(self.multi_select, |this| {
+                this.child(
+                    Label::new(format!("{} items selected", self.selected_count))
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground),
+                )
+            })
             .child(
                 List::new(&self.company_list)
                     .p(px(8.))
@@ -1,4 +1,5 @@
 use std::{
+    cmp::Ordering,
     ops::Range,
     sync::LazyLock,
     time::{self, Duration},
@@ -12,7 +13,7 @@ use gpui::{
     prelude::FluentBuilder as _,
 };
 use gpui_component::{
-    ActiveTheme as _, Selectable, Sizable as _, Size, StyleSized as _, StyledExt,
+    ActiveTheme as _, Disableable as _, Selectable, Sizable as _, Size, StyleSized as _, StyledExt,
     button::Button,
     checkbox::Checkbox,
     h_flex,
@@ -20,7 +21,10 @@ use gpui_component::{
     label::Label,
     menu::{DropdownMenu, PopupMenu},
     spinner::Spinner,
-    table::{Column, ColumnFixed, ColumnSort, Table, TableDelegate, TableEvent, TableState},
+    table::{
+        Column, ColumnAggregate, ColumnFilter, ColumnFilterValue, ColumnFixed, ColumnSort, RowPin,
+        Table, TableCellEditor, TableDelegate, TableEvent, TableState, TableViewState,
+    },
     v_flex,
 };
 use serde::{Deserialize, Serialize};
@@ -184,6 +188,12 @@ fn random_stocks(size: usize) -> Vec<Stock> {
 
 struct StockTableDelegate {
     stocks: Vec<Stock>,
+    /// The stocks filtered out by the column filters.
+    filtered_stocks: Vec<Stock>,
+    filters: Vec<(SharedString, ColumnFilterValue)>,
+    group_by_market: bool,
+    /// The market and rows range of each group.
+    groups: Vec<(SharedString, Range<usize>)>,
     columns: Vec<Column>,
     size: Size,
     loading: bool,
@@ -202,31 +212,45 @@ impl StockTableDelegate {
         Self {
             size: Size::default(),
             stocks: random_stocks(size),
+            filtered_stocks: vec![],
+            filters: vec![],
+            group_by_market: false,
+            groups: vec![],
             lazy_load: false,
             clicked_row: None,
             columns: vec![
                 Column::new("id", "ID")
                     .width(60.)
+                    .aggregate(ColumnAggregate::Count)
                     .fixed(ColumnFixed::Left)
                     .resizable(false),
                 Column::new("market", "Market")
-                    .width(60.)
+                    .width(80.)
                     .fixed(ColumnFixed::Left)
-                    .resizable(false),
+                    .resizable(false)
+                    .filter(ColumnFilter::Enum(vec!["US".into(), "HK".into()])),
                 Column::new("name", "Name")
                     .width(180.)
-                    .fixed(ColumnFixed::Left),
+                    .fixed(ColumnFixed::Left)
+                    .filter(ColumnFilter::Text),
                 Column::new("symbol", "Symbol")
                     .width(100.)
                     .fixed(ColumnFixed::Left)
                     .sortable(),
-                Column::new("price", "Price").sortable().text_right().p_0(),
+                Column::new("price", "Price")
+                    .sortable()
+                    .filter(ColumnFilter::Number)
+                    .aggregate(ColumnAggregate::Avg)
+                    .text_right()
+                    .p_0(),
                 Column::new("change", "Chg").sortable().text_right().p_0(),
                 Column::new("change_percent", "Chg%")
                     .sortable()
                     .text_right()
                     .p_0(),
-                Column::new("volume", "Volume").p_0(),
+                Column::new("volume", "Volume")
+                    .aggregate(ColumnAggregate::Sum)
+                    .p_0(),
                 Column::new("turnover", "Turnover").p_0(),
                 Column::new("market_cap", "Market Cap").p_0(),
                 Column::new("ttm", "TTM").p_0(),
@@ -269,7 +293,7 @@ impl StockTableDelegate {
                 Column::new("day_10_ranking", "10d Ranking"),
                 Column::new("day_30_ranking", "30d Ranking"),
                 Column::new("day_120_ranking", "120d Ranking"),
-                Column::new("day_250_ranking", "250d Ranking"),
+                Column::new("day_250_ranking", "250d Ranking").fixed_right(),
             ],
             loading: false,
             full_loading: false,
@@ -288,11 +312,46 @@ impl StockTableDelegate {
         }
 
         self.stocks = random_stocks(size);
+        self.filtered_stocks.clear();
+        self.apply_filters();
         self.eof = size <= 50;
         self.loading = false;
         self.full_loading = false;
     }
 
+    fn apply_filters(&mut self) {
+        let mut stocks = std::mem::take(&mut self.stocks);
+        stocks.append(&mut self.filtered_stocks);
+        stocks.sort_by_key(|stock| stock.id);
+
+        (self.stocks, self.filtered_stocks) = stocks.into_iter().partition(|stock| {
+            self.filters.iter().all(|(key, filter)| match key.as_ref() {
+                "market" => filter.matches_text(&stock.counter.market),
+                "name" => filter.matches_text(&stock.counter.name),
+                "price" => filter.matches_number(stock.price),
+                _ => true,
+            })
+        });
+        self.update_groups();
+    }
+
+    /// Group the stocks by market, the stocks in the same market are moved together.
+    fn update_groups(&mut self) {
+        self.groups.clear();
+        if !self.group_by_market {
+            return;
+        }
+
+        self.stocks
+            .sort_by(|a, b| a.counter.market.cmp(&b.counter.market));
+        for (ix, stock) in self.stocks.iter().enumerate() {
+            match self.groups.last_mut() {
+                Some((market, rows)) if market == &stock.counter.market => rows.end = ix + 1,
+                _ => self.groups.push((stock.counter.market.clone(), ix..ix + 1)),
+            }
+        }
+    }
+
     fn render_percent(&self, col: &Column, val: f64, cx: &mut App) -> AnyElement {
         let right_num = ((val - val.floor()) * 1000.).floor() as i32;
 
@@ -317,6 +376,96 @@ impl StockTableDelegate {
             .into_any_element()
     }
 
+    fn render_stock_td(&self, stock: &Stock, col_ix: usize, cx: &mut App) -> AnyElement {
+        let col = self.columns.get(col_ix).unwrap();
+        match col.key.as_ref() {
+            "id" => stock.id.to_string().into_any_element(),
+            "market" => div()
+                .map(|this| {
+                    if stock.counter.market == "US" {
+                        this.text_color(cx.theme().blue)
+                    } else {
+                        this.text_color(cx.theme().magenta)
+                    }
+                })
+                .child(stock.counter.market.clone())
+                .into_any_element(),
+            "symbol" => stock.counter.symbol_code().into_any_element(),
+            "name" => stock.counter.name.clone().into_any_element(),
+            "price" => self.render_value_cell(&col, stock.price, cx),
+            "change" => self.render_value_cell(&col, stock.change, cx),
+            "change_percent" => self.render_percent(&col, stock.change_percent, cx),
+            "volume" => self.render_value_cell(&col, stock.volume, cx),
+            "turnover" => self.render_value_cell(&col, stock.turnover, cx),
+            "market_cap" => self.render_value_cell(&col, stock.market_cap, cx),
+            "ttm" => self.render_value_cell(&col, stock.ttm, cx),
+            "five_mins_ranking" => self.render_value_cell(&col, stock.five_mins_ranking, cx),
+            "th60_days_ranking" => stock
+                .th60_days_ranking
+                .floor()
+                .to_string()
+                .into_any_element(),
+            "year_change_percent" => self.render_percent(&col, stock.year_change_percent, cx),
+            "bid" => self.render_value_cell(&col, stock.bid, cx),
+            "bid_volume" => self.render_value_cell(&col, stock.bid_volume, cx),
+            "ask" => self.render_value_cell(&col, stock.ask, cx),
+            "ask_volume" => self.render_value_cell(&col, stock.ask_volume, cx),
+            "open" => self.render_value_cell(&col, stock.open, cx),
+            "prev_close" => self.render_value_cell(&col, stock.prev_close, cx),
+            "high" => self.render_value_cell(&col, stock.high, cx),
+            "low" => self.render_value_cell(&col, stock.low, cx),
+            "turnover_rate" => (stock.turnover_rate * 100.0)
+                .floor()
+                .to_string()
+                .into_any_element(),
+            "rise_rate" => (stock.rise_rate * 100.0)
+                .floor()
+                .to_string()
+                .into_any_element(),
+            "amplitude" => (stock.amplitude * 100.0)
+                .floor()
+                .to_string()
+                .into_any_element(),
+            "pe_status" => stock.pe_status.floor().to_string().into_any_element(),
+            "pb_status" => stock.pb_status.floor().to_string().into_any_element(),
+            "volume_ratio" => self.render_value_cell(&col, stock.volume_ratio, cx),
+            "bid_ask_ratio" => self.render_value_cell(&col, stock.bid_ask_ratio, cx),
+            "latest_pre_close" => stock
+                .latest_pre_close
+                .floor()
+                .to_string()
+                .into_any_element(),
+            "latest_post_close" => stock
+                .latest_post_close
+                .floor()
+                .to_string()
+                .into_any_element(),
+            "pre_market_cap" => stock.pre_market_cap.floor().to_string().into_any_element(),
+            "pre_market_percent" => self.render_percent(&col, stock.pre_market_percent, cx),
+            "pre_market_change" => stock
+                .pre_market_change
+                .floor()
+                .to_string()
+                .into_any_element(),
+            "post_market_cap" => stock.post_market_cap.floor().to_string().into_any_element(),
+            "post_market_percent" => self.render_percent(&col, stock.post_market_percent, cx),
+            "post_market_change" => stock
+                .post_market_change
+                .floor()
+                .to_string()
+                .into_any_element(),
+            "float_cap" => stock.float_cap.floor().to_string().into_any_element(),
+            "shares" => stock.shares.to_string().into_any_element(),
+            "shares_float" => stock.shares_float.to_string().into_any_element(),
+            "day_5_ranking" => stock.day_5_ranking.floor().to_string().into_any_element(),
+            "day_10_ranking" => stock.day_10_ranking.floor().to_string().into_any_element(),
+            "day_30_ranking" => stock.day_30_ranking.floor().to_string().into_any_element(),
+            "day_120_ranking" => stock.day_120_ranking.floor().to_string().into_any_element(),
+            "day_250_ranking" => stock.day_250_ranking.floor().to_string().into_any_element(),
+            _ => "--".to_string().into_any_element(),
+        }
+    }
+
     fn render_value_cell(&self, col: &Column, val: f64, cx: &mut App) -> AnyElement {
         let this = div()
             .h_full()
@@ -419,6 +568,93 @@ impl TableDelegate for StockTableDelegate {
     /// This is means render the full table cells takes 232.745µs. Then 232.745µs / 8.82ms = 2.6% of the frame duration.
     ///
     /// If we improve the td rendering, we can reduce the time to render the full table cells.
+    fn cell_text(&self, row_ix: usize, col_ix: usize, _: &App) -> SharedString {
+        let (Some(stock), Some(col)) = (self.stocks.get(row_ix), self.columns.get(col_ix)) else {
+            return SharedString::default();
+        };
+
+        match col.key.as_ref() {
+            "id" => stock.id.to_string().into(),
+            "market" => stock.counter.market.clone(),
+            "symbol" => stock.counter.symbol_code(),
+            "name" => stock.counter.name.clone(),
+            "price" => format!("{:.3}", stock.price).into(),
+            "change" => format!("{:.3}", stock.change).into(),
+            "change_percent" => format!("{:.2}%", stock.change_percent * 100.0).into(),
+            "volume" => format!("{:.0}", stock.volume).into(),
+            _ => SharedString::default(),
+        }
+    }
+
+    fn is_cell_editable(&self, _: usize, col_ix: usize, _: &App) -> bool {
+        self.columns
+            .get(col_ix)
+            .is_some_and(|col| matches!(col.key.as_ref(), "name" | "price"))
+    }
+
+    fn build_cell_editor(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<TableState<Self>>,
+    ) -> Option<TableCellEditor> {
+        let stock = self.stocks.get(row_ix)?;
+        match self.columns.get(col_ix)?.key.as_ref() {
+            "name" => {
+                let value = stock.counter.name.clone();
+                Some(TableCellEditor::Input(
+                    cx.new(|cx| InputState::new(window, cx).default_value(value)),
+                ))
+            }
+            "price" => {
+                let value = format!("{:.3}", stock.price);
+                Some(TableCellEditor::NumberInput(
+                    cx.new(|cx| InputState::new(window, cx).default_value(value)),
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    fn on_cell_edited(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        editor: &TableCellEditor,
+        _: &mut Window,
+        cx: &mut Context<TableState<Self>>,
+    ) -> Result<(), SharedString> {
+        let (Some(stock), Some(col)) = (self.stocks.get_mut(row_ix), self.columns.get(col_ix))
+        else {
+            return Ok(());
+        };
+
+        match (col.key.as_ref(), editor) {
+            ("name", TableCellEditor::Input(state)) => {
+                let value = state.read(cx).value();
+                if value.trim().is_empty() {
+                    return Err("Name is required.".into());
+                }
+                stock.counter.name = value;
+            }
+            ("price", TableCellEditor::NumberInput(state)) => {
+                let price = state
+                    .read(cx)
+                    .value()
+                    .parse::<f64>()
+                    .map_err(|_| SharedString::from("Price must be a number."))?;
+                if price <= 0. {
+                    return Err("Price must be positive.".into());
+                }
+                stock.price = price;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
     fn render_td(
         &mut self,
         row_ix: usize,
@@ -427,93 +663,48 @@ impl TableDelegate for StockTableDelegate {
         cx: &mut Context<TableState<Self>>,
     ) -> impl IntoElement {
         let stock = self.stocks.get(row_ix).unwrap();
-        let col = self.columns.get(col_ix).unwrap();
+        self.render_stock_td(stock, col_ix, cx)
+    }
 
-        match col.key.as_ref() {
-            "id" => stock.id.to_string().into_any_element(),
-            "market" => div()
-                .map(|this| {
-                    if stock.counter.market == "US" {
-                        this.text_color(cx.theme().blue)
-                    } else {
-                        this.text_color(cx.theme().magenta)
-                    }
-                })
-                .child(stock.counter.market.clone())
-                .into_any_element(),
-            "symbol" => stock.counter.symbol_code().into_any_element(),
-            "name" => stock.counter.name.clone().into_any_element(),
-            "price" => self.render_value_cell(&col, stock.price, cx),
-            "change" => self.render_value_cell(&col, stock.change, cx),
-            "change_percent" => self.render_percent(&col, stock.change_percent, cx),
-            "volume" => self.render_value_cell(&col, stock.volume, cx),
-            "turnover" => self.render_value_cell(&col, stock.turnover, cx),
-            "market_cap" => self.render_value_cell(&col, stock.market_cap, cx),
-            "ttm" => self.render_value_cell(&col, stock.ttm, cx),
-            "five_mins_ranking" => self.render_value_cell(&col, stock.five_mins_ranking, cx),
-            "th60_days_ranking" => stock
-                .th60_days_ranking
-                .floor()
-                .to_string()
-                .into_any_element(),
-            "year_change_percent" => self.render_percent(&col, stock.year_change_percent, cx),
-            "bid" => self.render_value_cell(&col, stock.bid, cx),
-            "bid_volume" => self.render_value_cell(&col, stock.bid_volume, cx),
-            "ask" => self.render_value_cell(&col, stock.ask, cx),
-            "ask_volume" => self.render_value_cell(&col, stock.ask_volume, cx),
-            "open" => self.render_value_cell(&col, stock.open, cx),
-            "prev_close" => self.render_value_cell(&col, stock.prev_close, cx),
-            "high" => self.render_value_cell(&col, stock.high, cx),
-            "low" => self.render_value_cell(&col, stock.low, cx),
-            "turnover_rate" => (stock.turnover_rate * 100.0)
-                .floor()
-                .to_string()
-                .into_any_element(),
-            "rise_rate" => (stock.rise_rate * 100.0)
-                .floor()
-                .to_string()
-                .into_any_element(),
-            "amplitude" => (stock.amplitude * 100.0)
-                .floor()
-                .to_string()
-                .into_any_element(),
-            "pe_status" => stock.pe_status.floor().to_string().into_any_element(),
-            "pb_status" => stock.pb_status.floor().to_string().into_any_element(),
-            "volume_ratio" => self.render_value_cell(&col, stock.volume_ratio, cx),
-            "bid_ask_ratio" => self.render_value_cell(&col, stock.bid_ask_ratio, cx),
-            "latest_pre_close" => stock
-                .latest_pre_close
-                .floor()
-                .to_string()
-                .into_any_element(),
-            "latest_post_close" => stock
-                .latest_post_close
-                .floor()
-                .to_string()
-                .into_any_element(),
-            "pre_market_cap" => stock.pre_market_cap.floor().to_string().into_any_element(),
-            "pre_market_percent" => self.render_percent(&col, stock.pre_market_percent, cx),
-            "pre_market_change" => stock
-                .pre_market_change
-                .floor()
-                .to_string()
-                .into_any_element(),
-            "post_market_cap" => stock.post_market_cap.floor().to_string().into_any_element(),
-            "post_market_percent" => self.render_percent(&col, stock.post_market_percent, cx),
-            "post_market_change" => stock
-                .post_market_change
-                .floor()
-                .to_string()
-                .into_any_element(),
-            "float_cap" => stock.float_cap.floor().to_string().into_any_element(),
-            "shares" => stock.shares.to_string().into_any_element(),
-            "shares_float" => stock.shares_float.to_string().into_any_element(),
-            "day_5_ranking" => stock.day_5_ranking.floor().to_string().into_any_element(),
-            "day_10_ranking" => stock.day_10_ranking.floor().to_string().into_any_element(),
-            "day_30_ranking" => stock.day_30_ranking.floor().to_string().into_any_element(),
-            "day_120_ranking" => stock.day_120_ranking.floor().to_string().into_any_element(),
-            "day_250_ranking" => stock.day_250_ranking.floor().to_string().into_any_element(),
-            _ => "--".to_string().into_any_element(),
+    fn pinned_rows_count(&self, pin: RowPin, _: &App) -> usize {
+        match pin {
+            // The top gainer.
+            RowPin::Top => self.stocks.len().min(1),
+            // The totals.
+            RowPin::Bottom => 1,
+        }
+    }
+
+    fn render_pinned_td(
+        &mut self,
+        pin: RowPin,
+        _: usize,
+        col_ix: usize,
+        _: &mut Window,
+        cx: &mut Context<TableState<Self>>,
+    ) -> impl IntoElement {
+        let col = self.columns.get(col_ix).unwrap();
+        match pin {
+            RowPin::Top => {
+                let Some(top_gainer) = self
+                    .stocks
+                    .iter()
+                    .max_by(|a, b| a.change_percent.total_cmp(&b.change_percent))
+                else {
+                    return "".into_any_element();
+                };
+                self.render_stock_td(top_gainer, col_ix, cx)
+            }
+            RowPin::Bottom => match col.key.as_ref() {
+                "market" => "Total".into_any_element(),
+                "volume" => {
+                    self.render_value_cell(col, self.stocks.iter().map(|s| s.volume).sum(), cx)
+                }
+                "turnover" => {
+                    self.render_value_cell(col, self.stocks.iter().map(|s| s.turnover).sum(), cx)
+                }
+                _ => "".into_any_element(),
+            },
         }
     }
 
@@ -555,6 +746,82 @@ impl TableDelegate for StockTableDelegate {
                 _ => {}
             }
         }
+        self.update_groups();
+    }
+
+    fn perform_multi_sort(
+        &mut self,
+        sorts: Vec<(usize, ColumnSort)>,
+        _: &mut Window,
+        _: &mut Context<TableState<Self>>,
+    ) {
+        fn compare(a: &Stock, b: &Stock, key: &str) -> Ordering {
+            match key {
+                "id" => a.id.cmp(&b.id),
+                "symbol" => a.counter.symbol.cmp(&b.counter.symbol),
+                "price" => a.price.total_cmp(&b.price),
+                "change" => a.change.total_cmp(&b.change),
+                "change_percent" => a.change_percent.total_cmp(&b.change_percent),
+                _ => Ordering::Equal,
+            }
+        }
+
+        let sorts = sorts
+            .into_iter()
+            .filter_map(|(col_ix, sort)| Some((self.columns.get(col_ix)?.key.clone(), sort)))
+            .collect::<Vec<_>>();
+        self.stocks.sort_by(|a, b| {
+            sorts
+                .iter()
+                .fold(Ordering::Equal, |ordering, (key, sort)| {
+                    ordering.then_with(|| match sort {
+                        ColumnSort::Descending => compare(b, a, key),
+                        _ => compare(a, b, key),
+                    })
+                })
+                .then(a.id.cmp(&b.id))
+        });
+        self.update_groups();
+    }
+
+    fn groups_count(&self, _: &App) -> usize {
+        self.groups.len()
+    }
+
+    fn group_rows(&self, group_ix: usize, _: &App) -> Range<usize> {
+        self.groups[group_ix].1.clone()
+    }
+
+    fn group_id(&self, group_ix: usize, _: &App) -> SharedString {
+        self.groups[group_ix].0.clone()
+    }
+
+    fn render_group_header(
+        &mut self,
+        group_ix: usize,
+        _: &mut Window,
+        _: &mut Context<TableState<Self>>,
+    ) -> impl IntoElement {
+        format!("Market: {}", self.groups[group_ix].0)
+    }
+
+    fn perform_filter(
+        &mut self,
+        col_ix: usize,
+        filter: Option<ColumnFilterValue>,
+        _: &mut Window,
+        _: &mut Context<TableState<Self>>,
+    ) {
+        let Some(col) = self.columns.get(col_ix) else {
+            return;
+        };
+
+        let key = col.key.clone();
+        self.filters.retain(|(k, _)| k != &key);
+        if let Some(filter) = filter {
+            self.filters.push((key, filter));
+        }
+        self.apply_filters();
     }
 
     fn loading(&self, _: &App) -> bool {
@@ -585,10 +852,12 @@ impl TableDelegate for StockTableDelegate {
             Timer::after(Duration::from_secs(1)).await;
 
             _ = cx.update(|cx| {
-                let _ = view.update(cx, |view, _| {
+                let _ = view.update(cx, |view, cx| {
                     view.delegate_mut().stocks.extend(random_stocks(200));
+                    view.delegate_mut().update_groups();
                     view.delegate_mut().loading = false;
                     view.delegate_mut().eof = view.delegate().stocks.len() >= 6000;
+                    view.refresh_rows(cx);
                 });
             });
         });
@@ -619,6 +888,8 @@ pub struct TableStory {
     stripe: bool,
     refresh_data: bool,
     size: Size,
+    /// The saved columns layout.
+    saved_layout: Option<TableViewState>,
 
     _subscriptions: Vec<Subscription>,
     _load_task: Task<()>,
@@ -681,7 +952,7 @@ impl TableStory {
                         return;
                     }
 
-                    this.table.update(cx, |table, _| {
+                    this.table.update(cx, |table, cx| {
                         table.delegate_mut().stocks.iter_mut().enumerate().for_each(
                             |(i, stock)| {
                                 let n = (3..10).fake::<usize>();
@@ -691,6 +962,7 @@ impl TableStory {
                                 }
                             },
                         );
+                        table.refresh_rows(cx);
                     });
                     cx.notify();
                 })
@@ -704,6 +976,7 @@ impl TableStory {
             stripe: false,
             refresh_data: false,
             size: Size::default(),
+            saved_layout: None,
             _subscriptions,
             _load_task,
         }
@@ -726,8 +999,9 @@ impl TableStory {
                         return;
                     }
 
-                    self.table.update(cx, |table, _| {
+                    self.table.update(cx, |table, cx| {
                         table.delegate_mut().update_stocks(total_count);
+                        table.refresh_rows(cx);
                     });
                     cx.notify();
                 }
@@ -778,6 +1052,36 @@ impl TableStory {
         });
     }
 
+    fn toggle_multi_sort(&mut self, checked: &bool, _: &mut Window, cx: &mut Context<Self>) {
+        self.table.update(cx, |table, cx| {
+            table.multi_sort = *checked;
+            cx.notify();
+        });
+    }
+
+    fn toggle_multi_select(&mut self, checked: &bool, _: &mut Window, cx: &mut Context<Self>) {
+        self.table.update(cx, |table, cx| {
+            table.multi_select = *checked;
+            cx.notify();
+        });
+    }
+
+    fn toggle_group_by_market(&mut self, checked: &bool, _: &mut Window, cx: &mut Context<Self>) {
+        self.table.update(cx, |table, cx| {
+            table.delegate_mut().group_by_market = *checked;
+            table.delegate_mut().update_groups();
+            table.refresh_rows(cx);
+            table.clear_selection(cx);
+        });
+    }
+
+    fn toggle_cell_selection(&mut self, checked: &bool, _: &mut Window, cx: &mut Context<Self>) {
+        self.table.update(cx, |table, cx| {
+            table.cell_selectable = *checked;
+            table.clear_selection(cx);
+        });
+    }
+
     fn toggle_stripe(&mut self, checked: &bool, _: &mut Window, cx: &mut Context<Self>) {
         self.stripe = *checked;
         cx.notify();
@@ -788,6 +1092,25 @@ impl TableStory {
         cx.notify();
     }
 
+    fn save_layout(&mut self, _: &ClickEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let layout = self.table.read(cx).dump();
+        if let Ok(json) = serde_json::to_string_pretty(&layout) {
+            println!("Save layout: {}", json);
+        }
+        self.saved_layout = Some(layout);
+        cx.notify();
+    }
+
+    fn restore_layout(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(layout) = self.saved_layout.clone() else {
+            return;
+        };
+
+        self.table.update(cx, |table, cx| {
+            table.load(layout, window, cx);
+        });
+    }
+
     fn toggle_refresh_data(&mut self, checked: &bool, _: &mut Window, cx: &mut Context<Self>) {
         self.refresh_data = *checked;
         cx.notify();
@@ -807,9 +1130,14 @@ impl TableStory {
             TableEvent::SelectColumn(ix) => println!("Select col: {}", ix),
             TableEvent::DoubleClickedRow(ix) => println!("Double clicked row: {}", ix),
             TableEvent::SelectRow(ix) => println!("Select row: {}", ix),
+            TableEvent::SelectRows(ranges) => println!("Select rows: {:?}", ranges),
+            TableEvent::SelectCells(ranges) => println!("Select cells: {:?}", ranges),
             TableEvent::MoveColumn(origin_idx, target_idx) => {
                 println!("Move col index: {} -> {}", origin_idx, target_idx);
             }
+            TableEvent::ColumnVisibilityChanged(ix, hidden) => {
+                println!("Column {} hidden: {}", ix, hidden);
+            }
         }
     }
 }
@@ -855,12 +1183,36 @@ impl Render for TableStory {
                             .selected(table.sortable)
                             .on_click(cx.listener(Self::toggle_col_sort)),
                     )
+                    .child(
+                        Checkbox::new("multi-sort")
+                            .label("Multi Sort")
+                            .selected(table.multi_sort)
+                            .on_click(cx.listener(Self::toggle_multi_sort)),
+                    )
                     .child(
                         Checkbox::new("col-selection")
                             .label("Column Selectable")
                             .selected(table.col_selectable)
                             .on_click(cx.listener(Self::toggle_col_selection)),
                     )
+                    .child(
+                        Checkbox::new("multi-select")
+                            .label("Multi Select")
+                            .selected(table.multi_select)
+                            .on_click(cx.listener(Self::toggle_multi_select)),
+                    )
+                    .child(
+                        Checkbox::new("cell-selection")
+                            .label("Cell Selectable")
+                            .selected(table.cell_selectable)
+                            .on_click(cx.listener(Self::toggle_cell_selection)),
+                    )
+                    .child(
+                        Checkbox::new("group-by-market")
+                            .label("Group by Market")
+                            .selected(table.delegate().group_by_market)
+                            .on_click(cx.listener(Self::toggle_group_by_market)),
+                    )
                     .child(
                         Checkbox::new("fixed")
                             .label("Column Fixed")
@@ -943,6 +1295,21 @@ impl Render for TableStory {
                                     table.scroll_to_row(table.delegate().rows_count(cx) - 1, cx);
                                 })
                             })),
+                    )
+                    .child(
+                        Button::new("save-layout")
+                            .outline()
+                            .small()
+                            .child("Save Layout")
+                            .on_click(cx.listener(Self::save_layout)),
+                    )
+                    .child(
+                        Button::new("restore-layout")
+                            .outline()
+                            .small()
+                            .child("Restore Layout")
+                            .disabled(self.saved_layout.is_none())
+                            .on_click(cx.listener(Self::restore_layout)),
                     ), // .child(
                        //     Button::new("scroll-first-col")
                        //         .child("Scroll to First Column")
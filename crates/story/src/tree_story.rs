@@ -1,18 +1,23 @@
-use std::path::PathBuf;
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use autocorrect::ignorer::Ignorer;
 use gpui::{
-    App, AppContext, Context, Entity, InteractiveElement, KeyBinding, ParentElement, Render,
-    Styled, Window, actions, px,
+    App, AppContext, Context, Entity, InteractiveElement, IntoElement, KeyBinding, ParentElement,
+    Render, SharedString, Styled, Subscription, Window, actions, div, px,
 };
 
 use gpui_component::{
-    ActiveTheme as _, IconName, StyledExt as _,
+    ActiveTheme as _, Icon, IconName, Sizable as _, StyledExt as _,
     dock::PanelControl,
     h_flex,
+    input::{Input, InputEvent, InputState},
     label::Label,
     list::ListItem,
-    tree::{TreeItem, TreeState, tree},
+    table::{Column, Table, TableDelegate, TableState},
+    tree::{TreeEvent, TreeItem, TreeState, tree},
     v_flex,
 };
 
@@ -30,34 +35,211 @@ pub(crate) fn init(cx: &mut App) {
 
 pub struct TreeStory {
     tree_state: Entity<TreeState>,
+    filter_input: Entity<InputState>,
+    table_state: Entity<TableState<FileTableDelegate>>,
     selected_item: Option<TreeItem>,
+    _subscriptions: Vec<Subscription>,
 }
 
-fn build_file_items(ignorer: &Ignorer, root: &PathBuf, path: &PathBuf) -> Vec<TreeItem> {
-    let mut items = Vec::new();
-    if let Ok(entries) = std::fs::read_dir(path) {
-        for entry in entries.flatten() {
+/// A file row of the tree table.
+struct FileRow {
+    path: PathBuf,
+    name: SharedString,
+    is_dir: bool,
+    size: u64,
+    depth: usize,
+    expanded: bool,
+    /// The children count, `None` if the children are not loaded.
+    children_count: Option<usize>,
+    /// The descendant rows of the collapsed row.
+    collapsed_rows: Vec<FileRow>,
+}
+
+fn read_file_rows(path: &Path, depth: usize) -> Vec<FileRow> {
+    let mut rows = std::fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_name() != ".git")
+        .map(|entry| {
             let path = entry.path();
-            let relative_path = path.strip_prefix(root).unwrap_or(&path);
-            if ignorer.is_ignored(&relative_path.to_string_lossy())
-                || relative_path.ends_with(".git")
-            {
-                continue;
-            }
-            let file_name = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("Unknown")
-                .to_string();
-            let id = path.to_string_lossy().to_string();
-            if path.is_dir() {
-                let children = build_file_items(ignorer, &root, &path);
-                items.push(TreeItem::new(id, file_name).children(children));
-            } else {
-                items.push(TreeItem::new(id, file_name));
+            let is_dir = path.is_dir();
+            FileRow {
+                name: entry.file_name().to_string_lossy().to_string().into(),
+                size: entry.metadata().map(|meta| meta.len()).unwrap_or(0),
+                depth,
+                expanded: false,
+                children_count: if is_dir { None } else { Some(0) },
+                collapsed_rows: vec![],
+                is_dir,
+                path,
             }
+        })
+        .collect::<Vec<_>>();
+    rows.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then(a.name.cmp(&b.name)));
+    rows
+}
+
+struct FileTableDelegate {
+    rows: Vec<FileRow>,
+    columns: Vec<Column>,
+}
+
+impl FileTableDelegate {
+    fn new(path: &Path) -> Self {
+        Self {
+            rows: read_file_rows(path, 0),
+            columns: vec![
+                Column::new("name", "Name").width(300.).tree(true),
+                Column::new("kind", "Kind").width(100.),
+                Column::new("size", "Size").width(100.).text_right(),
+            ],
         }
     }
+
+    /// Returns the end of the descendant rows of the row.
+    fn descendants_end(&self, row_ix: usize) -> usize {
+        let depth = self.rows[row_ix].depth;
+        self.rows[row_ix + 1..]
+            .iter()
+            .position(|row| row.depth <= depth)
+            .map_or(self.rows.len(), |ix| row_ix + 1 + ix)
+    }
+}
+
+impl TableDelegate for FileTableDelegate {
+    fn columns_count(&self, _: &App) -> usize {
+        self.columns.len()
+    }
+
+    fn rows_count(&self, _: &App) -> usize {
+        self.rows.len()
+    }
+
+    fn column(&self, col_ix: usize, _: &App) -> &Column {
+        &self.columns[col_ix]
+    }
+
+    fn render_td(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        _: &mut Window,
+        _: &mut Context<TableState<Self>>,
+    ) -> impl IntoElement {
+        let row = &self.rows[row_ix];
+        match self.columns[col_ix].key.as_ref() {
+            "name" => h_flex()
+                .gap_2()
+                .child(if !row.is_dir {
+                    IconName::File
+                } else if row.expanded {
+                    IconName::FolderOpen
+                } else {
+                    IconName::Folder
+                })
+                .child(row.name.clone())
+                .into_any_element(),
+            "kind" if row.is_dir => "Folder".into_any_element(),
+            "kind" => "File".into_any_element(),
+            "size" if !row.is_dir => format!("{} B", row.size).into_any_element(),
+            _ => "".into_any_element(),
+        }
+    }
+
+    fn row_depth(&self, row_ix: usize, _: &App) -> usize {
+        self.rows[row_ix].depth
+    }
+
+    fn row_children_count(&self, row_ix: usize, _: &App) -> Option<usize> {
+        self.rows[row_ix].children_count
+    }
+
+    fn row_id(&self, row_ix: usize, _: &App) -> SharedString {
+        self.rows[row_ix].path.to_string_lossy().to_string().into()
+    }
+
+    fn is_row_expanded(&self, row_ix: usize, _: &App) -> bool {
+        self.rows[row_ix].expanded
+    }
+
+    fn set_row_expanded(
+        &mut self,
+        row_ix: usize,
+        expanded: bool,
+        _: &mut Window,
+        _: &mut Context<TableState<Self>>,
+    ) {
+        if expanded {
+            let rows = std::mem::take(&mut self.rows[row_ix].collapsed_rows);
+            self.rows.splice(row_ix + 1..row_ix + 1, rows);
+        } else {
+            let end = self.descendants_end(row_ix);
+            let rows = self.rows.drain(row_ix + 1..end).collect();
+            self.rows[row_ix].collapsed_rows = rows;
+        }
+        self.rows[row_ix].expanded = expanded;
+    }
+
+    fn load_children(&mut self, row_ix: usize, _: &mut Window, cx: &mut Context<TableState<Self>>) {
+        let path = self.rows[row_ix].path.clone();
+        let depth = self.rows[row_ix].depth + 1;
+
+        cx.spawn(async move |view, cx| {
+            let children = cx
+                .background_executor()
+                .spawn({
+                    let path = path.clone();
+                    async move { read_file_rows(&path, depth) }
+                })
+                .await;
+
+            _ = view.update(cx, |view, cx| {
+                // The row index may be changed during loading.
+                let delegate = view.delegate_mut();
+                let Some(row_ix) = delegate.rows.iter().position(|row| row.path == path) else {
+                    return;
+                };
+
+                delegate.rows[row_ix].children_count = Some(children.len());
+                if delegate.rows[row_ix].expanded {
+                    delegate.rows.splice(row_ix + 1..row_ix + 1, children);
+                } else {
+                    delegate.rows[row_ix].collapsed_rows = children;
+                }
+                view.refresh_rows(cx);
+            });
+        })
+        .detach();
+    }
+}
+
+fn read_dir_paths(path: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .collect()
+}
+
+/// Build the file items of a directory, the sub directories are loaded lazily.
+fn build_file_items(root: &Path, paths: Vec<PathBuf>) -> Vec<TreeItem> {
+    let ignorer = Ignorer::new(&root.to_string_lossy());
+    let mut items = Vec::new();
+    for path in paths {
+        let relative_path = path.strip_prefix(root).unwrap_or(&path);
+        if ignorer.is_ignored(&relative_path.to_string_lossy()) || relative_path.ends_with(".git") {
+            continue;
+        }
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("Unknown")
+            .to_string();
+        let id = path.to_string_lossy().to_string();
+        items.push(TreeItem::new(id, file_name).lazy(path.is_dir()));
+    }
     items.sort_by(|a, b| {
         b.is_folder()
             .cmp(&a.is_folder())
@@ -73,8 +255,7 @@ impl TreeStory {
 
     fn load_files(state: Entity<TreeState>, path: PathBuf, cx: &mut App) {
         cx.spawn(async move |cx| {
-            let ignorer = Ignorer::new(&path.to_string_lossy());
-            let items = build_file_items(&ignorer, &path, &path);
+            let items = build_file_items(&path, read_dir_paths(&path));
             _ = state.update(cx, |state, cx| {
                 state.set_items(items, cx);
             });
@@ -82,14 +263,62 @@ impl TreeStory {
         .detach();
     }
 
-    fn new(_: &mut Window, cx: &mut Context<Self>) -> Self {
-        let tree_state = cx.new(|cx| TreeState::new(cx));
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let tree_state = cx.new(|cx| {
+            TreeState::new(cx)
+                .checkable(true)
+                .draggable(true)
+                .on_load_children(|item, _, cx| {
+                    let path = PathBuf::from(item.id.as_ref());
+                    let paths = cx.background_executor().spawn(async move {
+                        // Simulate a slow file system to show the loading row.
+                        smol::Timer::after(Duration::from_millis(300)).await;
+                        read_dir_paths(&path)
+                    });
+
+                    cx.spawn(async move |_| build_file_items(Path::new("./"), paths.await))
+                })
+        });
+        let filter_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Filter the loaded files...")
+                .clean_on_escape()
+        });
+        let _subscriptions = vec![
+            cx.subscribe(&tree_state, |_, _, event: &TreeEvent, cx| {
+                match event {
+                    TreeEvent::Expand(id) => println!("Tree expanded: {}", id),
+                    TreeEvent::Collapse(id) => println!("Tree collapsed: {}", id),
+                    TreeEvent::Check(id, checked) => println!("Tree checked: {} {}", id, checked),
+                    TreeEvent::Move { id, parent, index } => {
+                        println!("Tree moved: {} to {:?} at {}", id, parent, index)
+                    }
+                }
+                cx.notify();
+            }),
+            cx.subscribe(&filter_input, |this, state, event: &InputEvent, cx| {
+                if let InputEvent::Change = event {
+                    let query = state.read(cx).value();
+                    this.tree_state.update(cx, |tree_state, cx| {
+                        tree_state.set_filter_query(query, cx);
+                    });
+                }
+            }),
+        ];
+        let table_state = cx.new(|cx| {
+            TableState::new(FileTableDelegate::new(Path::new("./")), window, cx)
+                .col_selectable(false)
+                .sortable(false)
+        });
 
         Self::load_files(tree_state.clone(), PathBuf::from("./"), cx);
 
         Self {
             tree_state,
+            filter_input,
+            table_state,
             selected_item: None,
+            _subscriptions,
         }
     }
 
@@ -144,9 +373,17 @@ impl Render for TreeStory {
             .size_full()
             .child(
                 section("File tree")
-                    .sub_title("Press `space` to select, `enter` to rename.")
+                    .sub_title(
+                        "Press `space` to select, `enter` to rename, drag and drop to move items, \
+                        type to jump to an item.",
+                    )
                     .v_flex()
                     .max_w_md()
+                    .child(
+                        Input::new(&self.filter_input)
+                            .cleanable(true)
+                            .prefix(Icon::new(IconName::Search).small()),
+                    )
                     .child(
                         tree(
                             &self.tree_state,
@@ -166,9 +403,7 @@ impl Render for TreeStory {
                                         .rounded(cx.theme().radius)
                                         .px_3()
                                         .pl(px(16.) * entry.depth() + px(12.))
-                                        .child(
-                                            h_flex().gap_2().child(icon).child(item.label.clone()),
-                                        )
+                                        .child(h_flex().gap_2().child(icon).child(entry.label()))
                                         .on_click(cx.listener({
                                             let item = item.clone();
                                             move |this, _, _window, cx| {
@@ -200,7 +435,23 @@ impl Render for TreeStory {
                                 self.selected_item
                                     .as_ref()
                                     .map(|item| Label::new("Selected:").secondary(item.id.clone())),
-                            ),
+                            )
+                            .child(format!(
+                                "Checked: {}",
+                                self.tree_state.read(cx).checked_ids().len()
+                            )),
+                    ),
+            )
+            .child(
+                section("Tree Table")
+                    .sub_title("Press `left` / `right` to collapse or expand the folder.")
+                    .v_flex()
+                    .max_w_2xl()
+                    .child(
+                        div()
+                            .w_full()
+                            .h(px(400.))
+                            .child(Table::new(&self.table_state)),
                     ),
             )
     }
@@ -19,6 +19,7 @@ pub struct Checkbox {
     label: Option<Text>,
     children: Vec<AnyElement>,
     checked: bool,
+    indeterminate: bool,
     disabled: bool,
     size: Size,
     tab_stop: bool,
@@ -36,6 +37,7 @@ impl Checkbox {
             label: None,
             children: Vec::new(),
             checked: false,
+            indeterminate: false,
             disabled: false,
             size: Size::default(),
             on_click: None,
@@ -56,6 +58,15 @@ impl Checkbox {
         self
     }
 
+    /// Set the indeterminate state for the checkbox, default is false.
+    ///
+    /// The indeterminate checkbox shows a dash if it is not checked,
+    /// e.g.: a parent checkbox with only some of its children checked.
+    pub fn indeterminate(mut self, indeterminate: bool) -> Self {
+        self.indeterminate = indeterminate;
+        self
+    }
+
     /// Set the click handler for the checkbox.
     ///
     /// The `&bool` parameter indicates the new checked state after the click.
@@ -192,6 +203,7 @@ pub(crate) fn checkbox_check_icon(
 impl RenderOnce for Checkbox {
     fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
         let checked = self.checked;
+        let indeterminate = self.indeterminate && !checked;
 
         let focus_handle = window
             .use_keyed_state(self.id.clone(), cx, |_, cx| cx.focus_handle())
@@ -199,7 +211,7 @@ impl RenderOnce for Checkbox {
             .clone();
         let is_focused = focus_handle.is_focused(window);
 
-        let border_color = if checked {
+        let border_color = if checked || indeterminate {
             cx.theme().primary
         } else {
             cx.theme().input
@@ -254,7 +266,7 @@ impl RenderOnce for Checkbox {
                         .border_color(color)
                         .rounded(radius)
                         .when(cx.theme().shadow && !self.disabled, |this| this.shadow_xs())
-                        .map(|this| match checked {
+                        .map(|this| match checked || indeterminate {
                             false => this.bg(cx.theme().background),
                             _ => this.bg(color),
                         })
@@ -265,7 +277,24 @@ impl RenderOnce for Checkbox {
                             self.disabled,
                             window,
                             cx,
-                        )),
+                        ))
+                        .when(indeterminate, |this| {
+                            this.child(
+                                svg()
+                                    .absolute()
+                                    .top_px()
+                                    .left_px()
+                                    .map(|this| match self.size {
+                                        Size::XSmall => this.size_2(),
+                                        Size::Small => this.size_2p5(),
+                                        Size::Medium => this.size_3(),
+                                        Size::Large => this.size_3p5(),
+                                        _ => this.size_3(),
+                                    })
+                                    .text_color(cx.theme().primary_foreground)
+                                    .path(IconName::Minus.path()),
+                            )
+                        }),
                 )
                 .when(self.label.is_some() || !self.children.is_empty(), |this| {
                     this.child(
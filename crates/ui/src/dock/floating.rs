@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use gpui::{
+    AnyWindowHandle, App, AppContext as _, Axis, Bounds, Context, Entity, IntoElement,
+    ParentElement as _, Pixels, Render, SharedString, Styled as _, Subscription, TitlebarOptions,
+    WeakEntity, Window, WindowBounds, WindowKind, WindowOptions, div, px, size,
+};
+
+use crate::{ActiveTheme as _, Root};
+
+use super::{DockArea, DockEvent, DockItem, PanelEvent, PanelView, StackPanel, TabPanel};
+
+/// The default size of the floating window when a panel is torn out of the dock area.
+pub(crate) const FLOATING_WINDOW_SIZE: gpui::Size<Pixels> = size(px(640.), px(480.));
+
+/// A floating OS window of the [`DockArea`], used to show the panels torn out of the dock area.
+///
+/// Closing the window docks its panels back to the center of the dock area.
+pub struct FloatingWindow {
+    dock_area: WeakEntity<DockArea>,
+    window_handle: AnyWindowHandle,
+    item: DockItem,
+    bounds: Bounds<Pixels>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl FloatingWindow {
+    /// Open a new floating window with the item built by `build_item` in the new window.
+    pub(super) fn open(
+        bounds: Bounds<Pixels>,
+        title: Option<SharedString>,
+        dock_area: WeakEntity<DockArea>,
+        build_item: impl FnOnce(&mut Window, &mut App) -> DockItem,
+        cx: &mut App,
+    ) -> Result<Entity<Self>> {
+        let options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(bounds)),
+            titlebar: Some(TitlebarOptions {
+                title,
+                ..Default::default()
+            }),
+            kind: WindowKind::Normal,
+            ..Default::default()
+        };
+
+        let mut floating_window = None;
+        cx.open_window(options, |window, cx| {
+            let item = build_item(window, cx);
+            let view = cx.new(|cx| Self::new(item, dock_area, window, cx));
+            floating_window = Some(view.clone());
+            cx.new(|cx| Root::new(view, window, cx))
+        })?;
+
+        floating_window.ok_or_else(|| anyhow::anyhow!("failed to open the floating window"))
+    }
+
+    fn new(
+        item: DockItem,
+        dock_area: WeakEntity<DockArea>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut _subscriptions = vec![cx.observe_window_bounds(window, |this, window, cx| {
+            this.bounds = window.bounds();
+            _ = this
+                .dock_area
+                .update(cx, |_, cx| cx.emit(DockEvent::LayoutChanged));
+        })];
+        if let DockItem::Split { view, .. } = &item {
+            _subscriptions.push(cx.subscribe_in(
+                view,
+                window,
+                |this, _, event: &PanelEvent, window, cx| {
+                    if let PanelEvent::LayoutChanged = event {
+                        this.close_if_empty(window, cx);
+                    }
+                },
+            ));
+        }
+
+        let view = cx.entity().downgrade();
+        window.on_window_should_close(cx, move |_, cx| {
+            if let Some(view) = view.upgrade() {
+                let this = view.read(cx);
+                let (dock_area, panels) = (this.dock_area.clone(), this.panels(cx));
+                _ = dock_area.update(cx, |dock_area, cx| {
+                    dock_area.on_floating_window_closed(&view, panels, cx);
+                });
+            }
+            true
+        });
+
+        Self {
+            dock_area,
+            window_handle: window.window_handle(),
+            bounds: window.bounds(),
+            item,
+            _subscriptions,
+        }
+    }
+
+    /// Returns the bounds of the window in screen coordinates.
+    pub fn bounds(&self) -> Bounds<Pixels> {
+        self.bounds
+    }
+
+    /// Returns the items of the window.
+    pub fn items(&self) -> &DockItem {
+        &self.item
+    }
+
+    pub(super) fn window_handle(&self) -> AnyWindowHandle {
+        self.window_handle
+    }
+
+    /// Returns all the panels (not including the `TabPanel` and `StackPanel`) in the window.
+    pub fn panels(&self, cx: &App) -> Vec<Arc<dyn PanelView>> {
+        let mut panels = vec![];
+        collect_panels(self.item.view(), &mut panels, cx);
+        panels
+    }
+
+    fn close_if_empty(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.panels(cx).is_empty() {
+            return;
+        }
+
+        let view = cx.entity();
+        _ = self.dock_area.update(cx, |dock_area, cx| {
+            dock_area.on_floating_window_closed(&view, vec![], cx);
+        });
+        window.remove_window();
+    }
+}
+
+/// Collect the panels (not including the `TabPanel` and `StackPanel`) in the `panel`.
+pub(super) fn collect_panels(
+    panel: Arc<dyn PanelView>,
+    panels: &mut Vec<Arc<dyn PanelView>>,
+    cx: &App,
+) {
+    let view = panel.view();
+    if let Ok(stack_panel) = view.clone().downcast::<StackPanel>() {
+        for panel in stack_panel.read(cx).panels.iter() {
+            collect_panels(panel.clone(), panels, cx);
+        }
+    } else if let Ok(tab_panel) = view.downcast::<TabPanel>() {
+        panels.extend(tab_panel.read(cx).panels.iter().cloned());
+    } else {
+        panels.push(panel);
+    }
+}
+
+/// Build the item of the floating window, the panels are wrapped in a tabs of a split,
+/// to allow splitting in the window.
+pub(super) fn floating_item(
+    panels: Vec<Arc<dyn PanelView>>,
+    dock_area: &WeakEntity<DockArea>,
+    window: &mut Window,
+    cx: &mut App,
+) -> DockItem {
+    DockItem::split(
+        Axis::Horizontal,
+        vec![DockItem::tabs(panels, None, dock_area, window, cx)],
+        dock_area,
+        window,
+        cx,
+    )
+}
+
+impl Render for FloatingWindow {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .size_full()
+            .bg(cx.theme().background)
+            .text_color(cx.theme().foreground)
+            .child(self.item.view().view())
+    }
+}
@@ -0,0 +1,369 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use gpui::{App, Context, SharedString, Window};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{DockArea, DockAreaState, PanelInfo, PanelRegistry, PanelState};
+
+/// A migration function to transform a [`PanelState`] JSON object to the next version.
+///
+/// The JSON object is like `{ "panel_name": "...", "children": [...], "info": {...} }`,
+/// the info of a panel is like `{ "panel": <the PanelInfo::Panel value> }`.
+pub type PanelStateMigration = Arc<dyn Fn(&mut Value)>;
+
+/// A named layout of the [`DockArea`], the state is kept as JSON to be migrated when loading.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DockLayout {
+    pub name: SharedString,
+    pub state: Value,
+}
+
+/// The report of loading a layout, see [`DockLayouts::load`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DockLayoutReport {
+    /// The version of the layout before migration.
+    pub from_version: Option<usize>,
+    /// The versions of the applied migrations, in order.
+    pub migrations: Vec<usize>,
+    /// The names of the panels not registered in [`PanelRegistry`], shown as invalid panels.
+    pub invalid_panels: Vec<String>,
+}
+
+impl DockLayoutReport {
+    /// Returns true if all panels of the layout are registered.
+    pub fn is_valid(&self) -> bool {
+        self.invalid_panels.is_empty()
+    }
+}
+
+/// Manage the named layouts of a [`DockArea`], to save, switch and migrate them.
+///
+/// This can be serialized to persist the layouts, the migrations are not serialized.
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct DockLayouts {
+    /// The name of the active layout.
+    active: Option<SharedString>,
+    layouts: Vec<DockLayout>,
+    #[serde(skip)]
+    migrations: BTreeMap<usize, PanelStateMigration>,
+}
+
+impl std::fmt::Debug for DockLayouts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DockLayouts")
+            .field("active", &self.active)
+            .field("layouts", &self.layouts)
+            .field("migrations", &self.migrations.keys())
+            .finish()
+    }
+}
+
+impl DockLayouts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a migration to transform each [`PanelState`] of the layouts
+    /// from the `version` to `version + 1`.
+    ///
+    /// The layouts without version are treated as version 0.
+    pub fn migration(mut self, version: usize, f: impl Fn(&mut Value) + 'static) -> Self {
+        self.migrations.insert(version, Arc::new(f));
+        self
+    }
+
+    /// Returns the name of the active layout.
+    pub fn active(&self) -> Option<&SharedString> {
+        self.active.as_ref()
+    }
+
+    /// Returns the names of the layouts.
+    pub fn names(&self) -> impl Iterator<Item = &SharedString> {
+        self.layouts.iter().map(|layout| &layout.name)
+    }
+
+    /// Returns the layout by name.
+    pub fn get(&self, name: &str) -> Option<&DockLayout> {
+        self.layouts
+            .iter()
+            .find(|layout| layout.name.as_ref() == name)
+    }
+
+    /// Save the state as the layout of the `name` and make it active, replacing the existing one.
+    pub fn insert(&mut self, name: impl Into<SharedString>, state: &DockAreaState) -> Result<()> {
+        let name = name.into();
+        let state = serde_json::to_value(state)?;
+        match self.layouts.iter_mut().find(|layout| layout.name == name) {
+            Some(layout) => layout.state = state,
+            None => self.layouts.push(DockLayout {
+                name: name.clone(),
+                state,
+            }),
+        }
+        self.active = Some(name);
+        Ok(())
+    }
+
+    /// Save the current layout of the dock area as the layout of the `name`.
+    pub fn save(
+        &mut self,
+        name: impl Into<SharedString>,
+        dock_area: &DockArea,
+        cx: &App,
+    ) -> Result<()> {
+        self.insert(name, &dock_area.dump(cx))
+    }
+
+    /// Remove the layout by name.
+    pub fn remove(&mut self, name: &str) -> Option<DockLayout> {
+        let ix = self
+            .layouts
+            .iter()
+            .position(|layout| layout.name.as_ref() == name)?;
+        if self
+            .active
+            .as_ref()
+            .is_some_and(|active| active.as_ref() == name)
+        {
+            self.active = None;
+        }
+        Some(self.layouts.remove(ix))
+    }
+
+    /// Migrate the state JSON to the `version`, returns the state and the report.
+    ///
+    /// Returns error if the state is newer than the `version` or not a valid [`DockAreaState`].
+    pub fn migrate(
+        &self,
+        mut state: Value,
+        version: Option<usize>,
+    ) -> Result<(DockAreaState, DockLayoutReport)> {
+        let from_version = state
+            .get("version")
+            .and_then(Value::as_u64)
+            .map(|v| v as usize);
+        let mut report = DockLayoutReport {
+            from_version,
+            ..Default::default()
+        };
+
+        let from = from_version.unwrap_or(0);
+        let to = version.unwrap_or(0);
+        if from > to {
+            return Err(anyhow!("the layout version {} is newer than {}", from, to));
+        }
+
+        for (version, migration) in self.migrations.range(from..to) {
+            for_each_panel_state(&mut state, &**migration);
+            report.migrations.push(*version);
+        }
+        if let Some(object) = state.as_object_mut() {
+            object.insert("version".into(), version.into());
+        }
+
+        let state = serde_json::from_value::<DockAreaState>(state)?;
+        Ok((state, report))
+    }
+
+    /// Load the layout of the `name` to the dock area and make it active.
+    ///
+    /// The panels not registered in [`PanelRegistry`] are shown as invalid panels,
+    /// and reported in the [`DockLayoutReport::invalid_panels`].
+    pub fn load(
+        &mut self,
+        name: &str,
+        dock_area: &mut DockArea,
+        window: &mut Window,
+        cx: &mut Context<DockArea>,
+    ) -> Result<DockLayoutReport> {
+        let layout = self
+            .get(name)
+            .ok_or_else(|| anyhow!("the layout `{}` is not found", name))?;
+        let (state, mut report) = self.migrate(layout.state.clone(), dock_area.version)?;
+        report.invalid_panels = unregistered_panels(&state, cx);
+
+        dock_area.load(state, window, cx)?;
+        self.active = Some(layout.name.clone());
+        Ok(report)
+    }
+
+    /// Save the current layout to the active layout, and then load the layout of the `name`.
+    pub fn switch(
+        &mut self,
+        name: &str,
+        dock_area: &mut DockArea,
+        window: &mut Window,
+        cx: &mut Context<DockArea>,
+    ) -> Result<DockLayoutReport> {
+        if let Some(active) = self.active.clone() {
+            self.save(active, dock_area, cx)?;
+        }
+        self.load(name, dock_area, window, cx)
+    }
+}
+
+/// Call the `f` with each [`PanelState`] JSON object of the [`DockAreaState`] JSON.
+fn for_each_panel_state(state: &mut Value, f: &dyn Fn(&mut Value)) {
+    fn visit(panel: &mut Value, f: &dyn Fn(&mut Value)) {
+        f(panel);
+        if let Some(children) = panel.get_mut("children").and_then(Value::as_array_mut) {
+            for child in children {
+                visit(child, f);
+            }
+        }
+    }
+
+    if let Some(center) = state.get_mut("center") {
+        visit(center, f);
+    }
+    for dock in ["left_dock", "right_dock", "bottom_dock"] {
+        if let Some(panel) = state.get_mut(dock).and_then(|dock| dock.get_mut("panel")) {
+            visit(panel, f);
+        }
+    }
+    if let Some(windows) = state
+        .get_mut("floating_windows")
+        .and_then(Value::as_array_mut)
+    {
+        for panel in windows
+            .iter_mut()
+            .filter_map(|window| window.get_mut("panel"))
+        {
+            visit(panel, f);
+        }
+    }
+}
+
+/// Returns the names of the panels in the state that are not registered in [`PanelRegistry`].
+fn unregistered_panels(state: &DockAreaState, cx: &App) -> Vec<String> {
+    fn visit(panel: &PanelState, registry: &PanelRegistry, names: &mut Vec<String>) {
+        if let PanelInfo::Panel(_) = panel.info {
+            if !registry.items.contains_key(&panel.panel_name) && !names.contains(&panel.panel_name)
+            {
+                names.push(panel.panel_name.clone());
+            }
+        }
+        for child in &panel.children {
+            visit(child, registry, names);
+        }
+    }
+
+    let Some(registry) = cx.try_global::<PanelRegistry>() else {
+        return vec![];
+    };
+
+    let mut names = vec![];
+    visit(&state.center, registry, &mut names);
+    for dock in [&state.left_dock, &state.right_dock, &state.bottom_dock]
+        .into_iter()
+        .flatten()
+    {
+        visit(dock.panel(), registry, &mut names);
+    }
+    for window in &state.floating_windows {
+        visit(&window.panel, registry, &mut names);
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn panel(name: &str, info: Value) -> Value {
+        json!({ "panel_name": name, "children": [], "info": { "panel": info } })
+    }
+
+    #[test]
+    fn test_migrate() {
+        let layouts = DockLayouts::new()
+            .migration(0, |panel| {
+                // Rename the panel.
+                if panel["panel_name"] == "OldChart" {
+                    panel["panel_name"] = "Chart".into();
+                }
+            })
+            .migration(1, |panel| {
+                // Rename the field of the panel info.
+                if let Some(info) = panel.pointer_mut("/info/panel")
+                    && let Some(symbol) = info.get("sym").cloned()
+                {
+                    info["symbol"] = symbol;
+                }
+            });
+
+        let state = json!({
+            "center": {
+                "panel_name": "TabPanel",
+                "children": [panel("OldChart", json!({ "sym": "AAPL" }))],
+                "info": { "tabs": { "active_index": 0 } }
+            },
+            "floating_windows": [{
+                "bounds": {
+                    "origin": { "x": 0.0, "y": 0.0 },
+                    "size": { "width": 640.0, "height": 480.0 }
+                },
+                "panel": panel("OldChart", json!({ "sym": "MSFT" }))
+            }]
+        });
+
+        let (migrated, report) = layouts.migrate(state.clone(), Some(2)).unwrap();
+        assert_eq!(migrated.version, Some(2));
+        assert_eq!(report.from_version, None);
+        assert_eq!(report.migrations, vec![0, 1]);
+        let chart = &migrated.center.children[0];
+        assert_eq!(chart.panel_name, "Chart");
+        assert_eq!(
+            chart.info,
+            PanelInfo::Panel(json!({ "sym": "AAPL", "symbol": "AAPL" }))
+        );
+        let chart = &migrated.floating_windows[0].panel;
+        assert_eq!(chart.panel_name, "Chart");
+
+        // Only apply the migrations after the version.
+        let mut state = state;
+        state["version"] = 1.into();
+        let (migrated, report) = layouts.migrate(state.clone(), Some(2)).unwrap();
+        assert_eq!(report.from_version, Some(1));
+        assert_eq!(report.migrations, vec![1]);
+        assert_eq!(migrated.center.children[0].panel_name, "OldChart");
+
+        assert!(layouts.migrate(state, None).is_err());
+    }
+
+    #[test]
+    fn test_layouts() {
+        let mut layouts = DockLayouts::new();
+        let state = DockAreaState {
+            version: Some(1),
+            ..Default::default()
+        };
+        layouts
+            .insert("Default", &DockAreaState::default())
+            .unwrap();
+        layouts.insert("Trading", &state).unwrap();
+        assert_eq!(layouts.active().map(|name| name.as_ref()), Some("Trading"));
+        layouts.insert("Default", &state).unwrap();
+        assert_eq!(
+            layouts
+                .names()
+                .map(|name| name.as_ref())
+                .collect::<Vec<_>>(),
+            vec!["Default", "Trading"]
+        );
+        assert_eq!(layouts.get("Default").unwrap().state["version"], 1);
+
+        let json = serde_json::to_string(&layouts).unwrap();
+        let mut layouts = serde_json::from_str::<DockLayouts>(&json).unwrap();
+        assert_eq!(layouts.active().map(|name| name.as_ref()), Some("Default"));
+
+        assert!(layouts.remove("Default").is_some());
+        assert!(layouts.remove("Default").is_none());
+        assert_eq!(layouts.active(), None);
+        assert_eq!(layouts.names().count(), 1);
+    }
+}
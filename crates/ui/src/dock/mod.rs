@@ -1,5 +1,8 @@
 mod dock;
+mod floating;
 mod invalid_panel;
+mod layout;
+mod navigation;
 mod panel;
 mod stack_panel;
 mod state;
@@ -8,14 +11,16 @@ mod tiles;
 
 use anyhow::Result;
 use gpui::{
-    AnyElement, AnyView, App, AppContext, Axis, Bounds, Context, Edges, Entity, EntityId,
-    EventEmitter, InteractiveElement as _, IntoElement, ParentElement as _, Pixels, Render,
-    SharedString, Styled, Subscription, WeakEntity, Window, actions, canvas, div,
-    prelude::FluentBuilder,
+    AnyElement, AnyView, AnyWindowHandle, App, AppContext, Axis, Bounds, Context, DispatchPhase,
+    DragMoveEvent, Edges, Entity, EntityId, EventEmitter, InteractiveElement as _, IntoElement,
+    MouseUpEvent, ParentElement as _, Pixels, Point, Render, SharedString, Styled, Subscription,
+    WeakEntity, Window, actions, canvas, div, point, prelude::FluentBuilder, px,
 };
 use std::sync::Arc;
 
 pub use dock::*;
+pub use floating::*;
+pub use layout::*;
 pub use panel::*;
 pub use stack_panel::*;
 pub use state::*;
@@ -26,7 +31,28 @@ pub(crate) fn init(cx: &mut App) {
     PanelRegistry::init(cx);
 }
 
-actions!(dock, [ToggleZoom, ClosePanel]);
+actions!(
+    dock,
+    [
+        ToggleZoom,
+        ClosePanel,
+        FloatPanel,
+        UnfloatPanel,
+        FocusPanelLeft,
+        FocusPanelRight,
+        FocusPanelUp,
+        FocusPanelDown,
+        MovePanelLeft,
+        MovePanelRight,
+        MovePanelUp,
+        MovePanelDown,
+        NextTab,
+        PreviousTab,
+        ToggleLeftDock,
+        ToggleRightDock,
+        ToggleBottomDock
+    ]
+);
 
 pub enum DockEvent {
     /// The layout of the dock has changed, subscribers this to save the layout.
@@ -62,6 +88,12 @@ pub struct DockArea {
     right_dock: Option<Entity<Dock>>,
     /// The top zoom view of the dock_area, if any.
     zoom_view: Option<AnyView>,
+    /// The window of the dock_area.
+    window_handle: AnyWindowHandle,
+    /// The floating windows of the panels torn out of the dock_area.
+    floating_windows: Vec<Entity<FloatingWindow>>,
+    /// The panel being dragged, used to tear out the panel when dropped outside the window.
+    dragging_panel: Option<DragPanel>,
 
     /// Lock panels layout, but allow to resize.
     locked: bool,
@@ -458,6 +490,9 @@ impl DockArea {
             bounds: Bounds::default(),
             items: dock_item,
             zoom_view: None,
+            window_handle: window.window_handle(),
+            floating_windows: vec![],
+            dragging_panel: None,
             toggle_button_panels: Edges::default(),
             toggle_button_visible: true,
             left_dock: None,
@@ -828,6 +863,7 @@ impl DockArea {
     ) -> Result<()> {
         self.version = state.version;
         let weak_self = cx.entity().downgrade();
+        self.close_floating_windows(cx);
 
         if let Some(left_dock_state) = state.left_dock {
             self.left_dock = Some(left_dock_state.to_dock(weak_self.clone(), window, cx));
@@ -841,8 +877,32 @@ impl DockArea {
             self.bottom_dock = Some(bottom_dock_state.to_dock(weak_self.clone(), window, cx));
         }
 
-        self.items = state.center.to_item(weak_self, window, cx);
+        self.items = state.center.to_item(weak_self.clone(), window, cx);
         self.update_toggle_button_tab_panels(window, cx);
+
+        for floating_state in state.floating_windows {
+            let result = FloatingWindow::open(
+                floating_state.bounds,
+                None,
+                weak_self.clone(),
+                |window, cx| floating_state.panel.to_item(weak_self.clone(), window, cx),
+                cx,
+            );
+
+            match result {
+                Ok(floating_window) => self.floating_windows.push(floating_window),
+                Err(err) => {
+                    tracing::error!("failed to open the floating window: {:?}", err);
+                    // Dock the panels back to the center, to not lose them.
+                    let item = floating_state.panel.to_item(weak_self.clone(), window, cx);
+                    let mut panels = vec![];
+                    floating::collect_panels(item.view(), &mut panels, cx);
+                    for panel in panels {
+                        self.add_panel(panel, DockPlacement::Center, None, window, cx);
+                    }
+                }
+            }
+        }
         Ok(())
     }
 
@@ -866,15 +926,151 @@ impl DockArea {
             .as_ref()
             .map(|dock| DockState::new(dock.clone(), cx));
 
+        let floating_windows = self
+            .floating_windows
+            .iter()
+            .map(|floating_window| FloatingWindowState::new(floating_window.clone(), cx))
+            .collect();
+
         DockAreaState {
             version: self.version,
             center,
             left_dock,
             right_dock,
             bottom_dock,
+            floating_windows,
         }
     }
 
+    /// Return the floating windows of the dock area.
+    pub fn floating_windows(&self) -> &[Entity<FloatingWindow>] {
+        &self.floating_windows
+    }
+
+    /// Return true if the `window` is a floating window of the dock area.
+    pub fn is_floating_window(&self, window: &Window, cx: &App) -> bool {
+        let window_handle = window.window_handle();
+        self.floating_windows
+            .iter()
+            .any(|floating_window| floating_window.read(cx).window_handle() == window_handle)
+    }
+
+    /// Move the panel into a new floating window.
+    ///
+    /// If `bounds` is `None`, the window is centered on the dock area window.
+    pub fn float_panel(
+        &mut self,
+        panel: Arc<dyn PanelView>,
+        bounds: Option<Bounds<Pixels>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.remove_panel_from_all_docks(panel.clone(), window, cx);
+        let bounds = bounds
+            .unwrap_or_else(|| Bounds::centered_at(window.bounds().center(), FLOATING_WINDOW_SIZE));
+        self.open_floating_window(vec![panel], bounds, cx);
+    }
+
+    /// Open a new floating window with the panels, the panels must be detached before.
+    pub(crate) fn open_floating_window(
+        &mut self,
+        panels: Vec<Arc<dyn PanelView>>,
+        bounds: Bounds<Pixels>,
+        cx: &mut Context<Self>,
+    ) {
+        let title = panels.first().and_then(|panel| panel.tab_name(cx));
+        let dock_area = cx.entity().downgrade();
+        let result = FloatingWindow::open(
+            bounds,
+            title,
+            dock_area.clone(),
+            |window, cx| floating::floating_item(panels.clone(), &dock_area, window, cx),
+            cx,
+        );
+
+        match result {
+            Ok(floating_window) => self.floating_windows.push(floating_window),
+            Err(err) => {
+                tracing::error!("failed to open the floating window: {:?}", err);
+                Self::dock_back_panels(dock_area, panels, cx);
+            }
+        }
+        cx.emit(DockEvent::LayoutChanged);
+        cx.notify();
+    }
+
+    /// Remove the closed floating window, and dock its remaining `panels` back to the center.
+    pub(crate) fn on_floating_window_closed(
+        &mut self,
+        floating_window: &Entity<FloatingWindow>,
+        panels: Vec<Arc<dyn PanelView>>,
+        cx: &mut Context<Self>,
+    ) {
+        self.floating_windows.retain(|view| view != floating_window);
+        if !panels.is_empty() {
+            Self::dock_back_panels(cx.entity().downgrade(), panels, cx);
+        }
+        cx.emit(DockEvent::LayoutChanged);
+    }
+
+    /// Add the panels (detached from the floating window) back to the center of the dock area.
+    pub(crate) fn dock_back_panels(
+        dock_area: WeakEntity<Self>,
+        panels: Vec<Arc<dyn PanelView>>,
+        cx: &mut App,
+    ) {
+        // Defer to update in the window of the dock area, the current window may be a floating one.
+        cx.defer(move |cx| {
+            let Ok(window_handle) = dock_area.read_with(cx, |this, _| this.window_handle) else {
+                return;
+            };
+
+            _ = window_handle.update(cx, |_, window, cx| {
+                _ = dock_area.update(cx, |this, cx| {
+                    for panel in panels {
+                        this.add_panel(panel, DockPlacement::Center, None, window, cx);
+                    }
+                });
+            });
+        });
+    }
+
+    fn close_floating_windows(&mut self, cx: &mut Context<Self>) {
+        for floating_window in self.floating_windows.drain(..) {
+            let window_handle = floating_window.read(cx).window_handle();
+            _ = window_handle.update(cx, |_, window, _| window.remove_window());
+        }
+    }
+
+    /// Record the dragging panel, to tear out it when dropped outside the window.
+    fn on_panel_drag_move(
+        &mut self,
+        event: &DragMoveEvent<DragPanel>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.dragging_panel = Some(event.drag(cx).clone());
+    }
+
+    fn on_mouse_up_out(&mut self, position: Point<Pixels>, window: &mut Window, cx: &mut App) {
+        let Some(drag) = self.dragging_panel.take() else {
+            return;
+        };
+        if Bounds::new(point(px(0.), px(0.)), window.viewport_size()).contains(&position) {
+            return;
+        }
+
+        // Place the window under the mouse in screen coordinates.
+        let origin = window.bounds().origin + position - point(px(40.), px(15.));
+        let bounds = Bounds::new(origin, FLOATING_WINDOW_SIZE);
+        let tab_panel = drag.tab_panel.clone();
+        window.defer(cx, move |window, cx| {
+            tab_panel.update(cx, |tab_panel, cx| {
+                tab_panel.on_drop_out(&drag, bounds, window, cx);
+            });
+        });
+    }
+
     /// Subscribe event on the panels
     #[allow(clippy::only_used_in_recursion)]
     fn subscribe_item(&mut self, item: &DockItem, window: &mut Window, cx: &mut Context<Self>) {
@@ -1017,10 +1213,32 @@ impl Render for DockArea {
             .relative()
             .size_full()
             .overflow_hidden()
+            .on_drag_move(cx.listener(Self::on_panel_drag_move))
+            .on_action(cx.listener(|this, _: &ToggleLeftDock, window, cx| {
+                this.toggle_dock(DockPlacement::Left, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &ToggleRightDock, window, cx| {
+                this.toggle_dock(DockPlacement::Right, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &ToggleBottomDock, window, cx| {
+                this.toggle_dock(DockPlacement::Bottom, window, cx)
+            }))
             .child(
                 canvas(
-                    move |bounds, _, cx| view.update(cx, |r, _| r.bounds = bounds),
-                    |_, _, _, _| {},
+                    {
+                        let view = view.clone();
+                        move |bounds, _, cx| view.update(cx, |r, _| r.bounds = bounds)
+                    },
+                    move |_, _, window, _| {
+                        // Tear out the dragging panel if it is dropped outside the window.
+                        window.on_mouse_event(move |event: &MouseUpEvent, phase, window, cx| {
+                            if phase == DispatchPhase::Capture {
+                                view.update(cx, |this, cx| {
+                                    this.on_mouse_up_out(event.position, window, cx)
+                                });
+                            }
+                        });
+                    },
                 )
                 .absolute()
                 .size_full(),
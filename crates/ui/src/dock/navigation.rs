@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use gpui::{App, Bounds, Entity, Pixels, Window, px};
+
+use crate::Placement;
+
+use super::{DockArea, PanelView, StackPanel, TabPanel, Tiles};
+
+/// The tolerance of the bounds edges, the adjacent panels may overlap by the border.
+const EDGE_TOLERANCE: Pixels = px(2.);
+
+/// Collect the [`TabPanel`]s in the panel recursively.
+fn collect_tab_panels(
+    panel: &Arc<dyn PanelView>,
+    tab_panels: &mut Vec<Entity<TabPanel>>,
+    cx: &App,
+) {
+    let view = panel.view();
+    if let Ok(tab_panel) = view.clone().downcast::<TabPanel>() {
+        tab_panels.push(tab_panel);
+    } else if let Ok(stack_panel) = view.clone().downcast::<StackPanel>() {
+        for panel in stack_panel.read(cx).panels.iter() {
+            collect_tab_panels(panel, tab_panels, cx);
+        }
+    } else if let Ok(tiles) = view.downcast::<Tiles>() {
+        for item in tiles.read(cx).panels.iter() {
+            collect_tab_panels(&item.panel, tab_panels, cx);
+        }
+    }
+}
+
+impl DockArea {
+    /// Returns the visible [`TabPanel`]s in the `window`, including the open docks.
+    ///
+    /// If the `window` is a floating window, returns the [`TabPanel`]s of the floating window.
+    pub(super) fn visible_tab_panels(&self, window: &Window, cx: &App) -> Vec<Entity<TabPanel>> {
+        let mut tab_panels = vec![];
+        if window.window_handle() != self.window_handle {
+            if let Some(floating_window) = self.floating_windows.iter().find(|floating_window| {
+                floating_window.read(cx).window_handle() == window.window_handle()
+            }) {
+                collect_tab_panels(
+                    &floating_window.read(cx).items().view(),
+                    &mut tab_panels,
+                    cx,
+                );
+            }
+            return tab_panels;
+        }
+
+        // Only the zoomed panel is visible.
+        if self.zoom_view.is_some() {
+            return tab_panels;
+        }
+
+        collect_tab_panels(&self.items.view(), &mut tab_panels, cx);
+        for dock in [&self.left_dock, &self.bottom_dock, &self.right_dock]
+            .into_iter()
+            .flatten()
+        {
+            let dock = dock.read(cx);
+            if dock.open {
+                collect_tab_panels(&dock.panel.view(), &mut tab_panels, cx);
+            }
+        }
+        tab_panels
+    }
+}
+
+/// Returns the index of the nearest bounds of the `candidates` in the `direction` of `current`.
+///
+/// The candidates overlapping with `current` on the cross axis are preferred.
+pub(super) fn nearest_in_direction(
+    current: Bounds<Pixels>,
+    candidates: &[Bounds<Pixels>],
+    direction: Placement,
+) -> Option<usize> {
+    let axis_range = |bounds: &Bounds<Pixels>| match direction {
+        Placement::Left | Placement::Right => (bounds.top(), bounds.bottom()),
+        Placement::Top | Placement::Bottom => (bounds.left(), bounds.right()),
+    };
+    let (start, end) = axis_range(&current);
+
+    candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, bounds)| !bounds.is_empty())
+        .filter_map(|(ix, bounds)| {
+            let distance = match direction {
+                Placement::Left => current.left() - bounds.right(),
+                Placement::Right => bounds.left() - current.right(),
+                Placement::Top => current.top() - bounds.bottom(),
+                Placement::Bottom => bounds.top() - current.bottom(),
+            };
+            if distance < -EDGE_TOLERANCE {
+                return None;
+            }
+
+            let (cross_start, cross_end) = axis_range(bounds);
+            let overlap = cross_start < end && cross_end > start;
+            let cross_distance = ((cross_start + cross_end) / 2. - (start + end) / 2.).abs();
+            Some((ix, (!overlap, distance.max(px(0.)), cross_distance)))
+        })
+        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(ix, _)| ix)
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{Bounds, point, px, size};
+
+    use super::nearest_in_direction;
+    use crate::Placement;
+
+    fn bounds(x: f32, y: f32, width: f32, height: f32) -> Bounds<gpui::Pixels> {
+        Bounds::new(point(px(x), px(y)), size(px(width), px(height)))
+    }
+
+    #[test]
+    fn test_nearest_in_direction() {
+        // +------+------+------+
+        // |      |  1   |      |
+        // |  0   +------+  3   |
+        // |      |  2   |      |
+        // +------+------+------+
+        // |          4         |
+        // +--------------------+
+        let candidates = [
+            bounds(0., 0., 100., 200.),
+            bounds(100., 0., 100., 100.),
+            bounds(100., 100., 100., 100.),
+            bounds(200., 0., 100., 200.),
+            bounds(0., 200., 300., 100.),
+        ];
+
+        let current = candidates[1];
+        assert_eq!(
+            nearest_in_direction(current, &candidates, Placement::Left),
+            Some(0)
+        );
+        assert_eq!(
+            nearest_in_direction(current, &candidates, Placement::Right),
+            Some(3)
+        );
+        assert_eq!(
+            nearest_in_direction(current, &candidates, Placement::Bottom),
+            Some(2)
+        );
+        assert_eq!(
+            nearest_in_direction(current, &candidates, Placement::Top),
+            None
+        );
+
+        let current = candidates[2];
+        assert_eq!(
+            nearest_in_direction(current, &candidates, Placement::Top),
+            Some(1)
+        );
+        assert_eq!(
+            nearest_in_direction(current, &candidates, Placement::Bottom),
+            Some(4)
+        );
+
+        let current = candidates[0];
+        assert_eq!(
+            nearest_in_direction(current, &candidates, Placement::Right),
+            Some(1)
+        );
+        assert_eq!(
+            nearest_in_direction(current, &candidates, Placement::Left),
+            None
+        );
+
+        let current = candidates[4];
+        assert_eq!(
+            nearest_in_direction(current, &candidates, Placement::Top),
+            Some(2)
+        );
+    }
+}
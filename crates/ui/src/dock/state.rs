@@ -2,7 +2,7 @@ use gpui::{point, px, size, App, AppContext, Axis, Bounds, Entity, Pixels, WeakE
 use itertools::Itertools as _;
 use serde::{Deserialize, Serialize};
 
-use super::{Dock, DockArea, DockItem, DockPlacement, Panel, PanelRegistry};
+use super::{Dock, DockArea, DockItem, DockPlacement, FloatingWindow, Panel, PanelRegistry};
 
 /// Used to serialize and deserialize the DockArea
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
@@ -19,6 +19,9 @@ pub struct DockAreaState {
     pub right_dock: Option<DockState>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bottom_dock: Option<DockState>,
+    /// The floating windows of the panels torn out of the dock area.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub floating_windows: Vec<FloatingWindowState>,
 }
 
 /// Used to serialize and deserialize the Dock
@@ -42,6 +45,11 @@ impl DockState {
         }
     }
 
+    /// Returns the panel state of the dock.
+    pub fn panel(&self) -> &PanelState {
+        &self.panel
+    }
+
     /// Convert the DockState to Dock
     pub fn to_dock(
         &self,
@@ -64,6 +72,25 @@ impl DockState {
     }
 }
 
+/// Used to serialize and deserialize the [`FloatingWindow`](super::FloatingWindow)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FloatingWindowState {
+    /// The bounds of the window in screen coordinates.
+    pub bounds: Bounds<Pixels>,
+    pub panel: PanelState,
+}
+
+impl FloatingWindowState {
+    pub fn new(window: Entity<FloatingWindow>, cx: &App) -> Self {
+        let window = window.read(cx);
+
+        Self {
+            bounds: window.bounds(),
+            panel: window.items().view().dump(cx),
+        }
+    }
+}
+
 /// Used to serialize and deserialize the DockerItem
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PanelState {
@@ -278,5 +305,32 @@ mod tests {
         assert_eq!(right_dock.panel.panel_name, "TabPanel");
         assert_eq!(right_dock.panel.children.len(), 1);
         assert_eq!(right_dock.panel.children[0].panel_name, "StoryContainer");
+
+        assert!(state.floating_windows.is_empty());
+    }
+
+    #[test]
+    fn test_floating_window_state() {
+        let state = DockAreaState {
+            floating_windows: vec![FloatingWindowState {
+                bounds: Bounds {
+                    origin: point(px(100.), px(50.)),
+                    size: size(px(640.), px(480.)),
+                },
+                panel: PanelState {
+                    panel_name: "StackPanel".into(),
+                    children: vec![PanelState::default()],
+                    info: PanelInfo::stack(vec![px(640.)], Axis::Horizontal),
+                },
+            }],
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&state).unwrap();
+        assert!(json.contains(r#""floating_windows":[{"bounds":"#));
+        assert_eq!(serde_json::from_str::<DockAreaState>(&json).unwrap(), state);
+
+        let json = serde_json::to_string(&DockAreaState::default()).unwrap();
+        assert!(!json.contains("floating_windows"));
     }
 }
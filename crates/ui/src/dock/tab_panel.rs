@@ -1,10 +1,11 @@
 use std::sync::Arc;
 
 use gpui::{
-    App, AppContext, Context, Corner, DismissEvent, Div, DragMoveEvent, Empty, Entity,
-    EventEmitter, FocusHandle, Focusable, InteractiveElement as _, IntoElement, ParentElement,
-    Pixels, Render, ScrollHandle, SharedString, StatefulInteractiveElement, StyleRefinement,
-    Styled, WeakEntity, Window, div, prelude::FluentBuilder, px, relative, rems,
+    App, AppContext, Bounds, Context, Corner, DismissEvent, Div, DragMoveEvent, Empty, Entity,
+    EntityId, EventEmitter, FocusHandle, Focusable, InteractiveElement as _, IntoElement,
+    KeyDownEvent, ModifiersChangedEvent, ParentElement, Pixels, Render, ScrollHandle, SharedString,
+    StatefulInteractiveElement, StyleRefinement, Styled, WeakEntity, Window, canvas, div,
+    prelude::FluentBuilder, px, relative, rems,
 };
 use rust_i18n::t;
 
@@ -19,8 +20,10 @@ use crate::{
 };
 
 use super::{
-    ClosePanel, DockArea, DockPlacement, Panel, PanelControl, PanelEvent, PanelState, PanelStyle,
-    PanelView, StackPanel, ToggleZoom,
+    ClosePanel, DockArea, DockPlacement, FLOATING_WINDOW_SIZE, FloatPanel, FocusPanelDown,
+    FocusPanelLeft, FocusPanelRight, FocusPanelUp, MovePanelDown, MovePanelLeft, MovePanelRight,
+    MovePanelUp, NextTab, Panel, PanelControl, PanelEvent, PanelState, PanelStyle, PanelView,
+    PreviousTab, StackPanel, ToggleZoom, UnfloatPanel, navigation::nearest_in_direction,
 };
 
 #[derive(Clone)]
@@ -29,6 +32,10 @@ struct TabState {
     zoomable: Option<PanelControl>,
     draggable: bool,
     droppable: bool,
+    /// Whether the active panel can be moved into a floating window.
+    floatable: bool,
+    /// Whether the tab panel is in a floating window.
+    floating: bool,
     active_panel: Option<Arc<dyn PanelView>>,
 }
 
@@ -84,6 +91,12 @@ pub struct TabPanel {
     will_split_placement: Option<Placement>,
     /// Is TabPanel used in Tiles.
     in_tiles: bool,
+    /// The bounds of the TabPanel, used to find the adjacent TabPanel for keyboard navigation.
+    bounds: Bounds<Pixels>,
+    /// The entity ids of the panels in the most recently used order.
+    mru: Vec<EntityId>,
+    /// The selected position of the tab switcher in the MRU order, the switcher is open when Some.
+    tab_switcher: Option<usize>,
 }
 
 impl Panel for TabPanel {
@@ -177,6 +190,9 @@ impl TabPanel {
             collapsed: false,
             closable: true,
             in_tiles: false,
+            bounds: Bounds::default(),
+            mru: Vec::new(),
+            tab_switcher: None,
         }
     }
 
@@ -211,6 +227,8 @@ impl TabPanel {
         }
 
         let last_active_ix = self.active_ix;
+        self.record_mru(last_active_ix);
+        self.record_mru(ix);
 
         self.active_ix = ix;
         self.tab_bar_scroll_handle.scroll_to_item(ix);
@@ -344,6 +362,7 @@ impl TabPanel {
         panel.on_removed(window, cx);
         let panel_view = panel.view();
         self.panels.retain(|p| p.view() != panel_view);
+        self.mru.retain(|id| *id != panel_view.entity_id());
         if self.active_ix >= self.panels.len() {
             self.set_active_ix(self.panels.len().saturating_sub(1), window, cx)
         }
@@ -430,6 +449,179 @@ impl TabPanel {
         !self.is_locked(cx)
     }
 
+    /// Return true if the tab panel is in a floating window of the dock area.
+    fn is_floating(&self, window: &Window, cx: &App) -> bool {
+        self.dock_area
+            .upgrade()
+            .is_some_and(|dock_area| dock_area.read(cx).is_floating_window(window, cx))
+    }
+
+    /// Return true if the active panel can be moved into a floating window.
+    fn floatable(&self, window: &Window, cx: &App) -> bool {
+        !self.in_tiles && self.draggable(cx) && !self.is_floating(window, cx)
+    }
+
+    /// Record the panel at the `ix` as the most recently used.
+    fn record_mru(&mut self, ix: usize) {
+        if let Some(panel) = self.panels.get(ix) {
+            let entity_id = panel.view().entity_id();
+            self.mru.retain(|id| *id != entity_id);
+            self.mru.insert(0, entity_id);
+        }
+    }
+
+    /// Return the indexes of the visible panels in the most recently used order,
+    /// the active panel is always the first.
+    fn mru_indexes(&self, cx: &App) -> Vec<usize> {
+        let mut indexes = vec![self.active_ix];
+        indexes.extend(self.mru.iter().filter_map(|id| {
+            self.panels
+                .iter()
+                .position(|panel| panel.view().entity_id() == *id)
+        }));
+        indexes.extend(0..self.panels.len());
+
+        let mut result: Vec<usize> = vec![];
+        for ix in indexes {
+            if !result.contains(&ix) && self.panels.get(ix).is_some_and(|p| p.visible(cx)) {
+                result.push(ix);
+            }
+        }
+        result
+    }
+
+    /// Select the next (or previous) tab in the most recently used order.
+    ///
+    /// If the modifiers are held (e.g. `ctrl-tab`), the tab switcher will be shown,
+    /// and the selected tab will be activated when the modifiers are released.
+    fn cycle_tab(&mut self, forward: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let indexes = self.mru_indexes(cx);
+        let len = indexes.len();
+        if len < 2 {
+            return;
+        }
+
+        let selected = match self.tab_switcher {
+            Some(pos) if forward => (pos + 1) % len,
+            Some(pos) => (pos + len - 1) % len,
+            None if forward => 1,
+            None => len - 1,
+        };
+
+        if window.modifiers().modified() {
+            self.tab_switcher = Some(selected);
+            cx.notify();
+        } else {
+            self.tab_switcher = None;
+            self.set_active_ix(indexes[selected], window, cx);
+        }
+    }
+
+    /// Close the tab switcher and activate the selected tab.
+    fn confirm_tab_switcher(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(selected) = self.tab_switcher.take() else {
+            return;
+        };
+
+        if let Some(ix) = self.mru_indexes(cx).get(selected) {
+            self.set_active_ix(*ix, window, cx);
+        }
+        cx.notify();
+    }
+
+    fn on_modifiers_changed(
+        &mut self,
+        event: &ModifiersChangedEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.tab_switcher.is_some() && !event.modifiers.modified() {
+            self.confirm_tab_switcher(window, cx);
+        }
+    }
+
+    fn on_key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        if self.tab_switcher.is_some() && event.keystroke.key == "escape" {
+            self.tab_switcher = None;
+            cx.stop_propagation();
+            cx.notify();
+        }
+    }
+
+    /// Return the adjacent TabPanel in the direction of the `placement` by the laid-out bounds.
+    fn adjacent_tab_panel(
+        &self,
+        placement: Placement,
+        window: &Window,
+        cx: &Context<Self>,
+    ) -> Option<Entity<TabPanel>> {
+        let dock_area = self.dock_area.upgrade()?;
+        let entity_id = cx.entity_id();
+        let tab_panels = dock_area
+            .read(cx)
+            .visible_tab_panels(window, cx)
+            .into_iter()
+            .filter(|tab_panel| {
+                tab_panel.entity_id() != entity_id && tab_panel.read(cx).visible(cx)
+            })
+            .collect::<Vec<_>>();
+        let bounds = tab_panels
+            .iter()
+            .map(|tab_panel| tab_panel.read(cx).bounds)
+            .collect::<Vec<_>>();
+
+        let ix = nearest_in_direction(self.bounds, &bounds, placement)?;
+        tab_panels.get(ix).cloned()
+    }
+
+    /// Focus the active panel of the adjacent TabPanel in the direction of the `placement`.
+    fn focus_adjacent_panel(
+        &mut self,
+        placement: Placement,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(tab_panel) = self.adjacent_tab_panel(placement, window, cx) {
+            tab_panel.update(cx, |view, cx| view.focus_active_panel(window, cx));
+        }
+    }
+
+    /// Move the active panel into the adjacent TabPanel in the direction of the `placement`,
+    /// or split it to the `placement` if there is no adjacent TabPanel.
+    fn move_active_panel(
+        &mut self,
+        placement: Placement,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.draggable(cx) {
+            return;
+        }
+        let Some(panel) = self.active_panel(cx) else {
+            return;
+        };
+
+        if let Some(tab_panel) = self.adjacent_tab_panel(placement, window, cx) {
+            if !tab_panel.read(cx).droppable(cx) {
+                return;
+            }
+
+            self.detach_panel(panel.clone(), window, cx);
+            tab_panel.update(cx, |view, cx| {
+                view.add_panel(panel.clone(), window, cx);
+            });
+            self.remove_self_if_empty(window, cx);
+        } else if self.panels.len() > 1 {
+            self.detach_panel(panel.clone(), window, cx);
+            self.split_panel(panel.clone(), placement, None, window, cx);
+        } else {
+            return;
+        }
+
+        panel.focus_handle(cx).focus(window);
+        cx.emit(PanelEvent::LayoutChanged);
+    }
+
     fn render_toolbar(
         &mut self,
         state: &TabState,
@@ -489,6 +681,8 @@ impl TabPanel {
                     .dropdown_menu({
                         let zoomable = state.zoomable.map_or(false, |v| v.menu_visible());
                         let closable = state.closable;
+                        let floatable = state.floatable;
+                        let floating = state.floating;
 
                         move |menu, window, cx| {
                             view.update(cx, |this, cx| {
@@ -503,6 +697,12 @@ impl TabPanel {
                                         Box::new(ToggleZoom),
                                         !zoomable,
                                     )
+                                    .when(floatable, |this| {
+                                        this.menu(t!("Dock.Float"), Box::new(FloatPanel))
+                                    })
+                                    .when(floating, |this| {
+                                        this.menu(t!("Dock.Dock Back"), Box::new(UnfloatPanel))
+                                    })
                                     .when(closable, |this| {
                                         this.separator()
                                             .menu(t!("Dock.Close"), Box::new(ClosePanel))
@@ -828,6 +1028,65 @@ impl TabPanel {
             .into_any_element()
     }
 
+    fn render_tab_switcher(
+        &self,
+        selected: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let items = self
+            .mru_indexes(cx)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(pos, ix)| {
+                let panel = self.panels.get(ix)?;
+                Some(
+                    h_flex()
+                        .id(pos)
+                        .px_2()
+                        .py_1()
+                        .overflow_hidden()
+                        .whitespace_nowrap()
+                        .rounded(cx.theme().radius)
+                        .when(pos == selected, |this| {
+                            this.bg(cx.theme().accent)
+                                .text_color(cx.theme().accent_foreground)
+                        })
+                        .child(panel.title(window, cx))
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.tab_switcher = Some(pos);
+                            this.confirm_tab_switcher(window, cx);
+                        })),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        h_flex()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .justify_center()
+            .items_start()
+            .pt_10()
+            .child(
+                v_flex()
+                    .id("tab-switcher")
+                    .occlude()
+                    .w_64()
+                    .max_w_full()
+                    .p_1()
+                    .gap_0p5()
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .rounded(cx.theme().radius)
+                    .bg(cx.theme().popover)
+                    .text_color(cx.theme().popover_foreground)
+                    .shadow_lg()
+                    .children(items),
+            )
+    }
+
     fn render_active_panel(
         &self,
         state: &TabState,
@@ -972,6 +1231,41 @@ impl TabPanel {
         cx.emit(PanelEvent::LayoutChanged);
     }
 
+    /// Handle the drop outside the window when dragging a panel, to move it into a floating window.
+    pub(super) fn on_drop_out(
+        &mut self,
+        drag: &DragPanel,
+        bounds: Bounds<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if drag.tab_panel != cx.entity() || !self.floatable(window, cx) {
+            return;
+        }
+
+        self.float_panel(drag.panel.clone(), bounds, window, cx);
+    }
+
+    /// Detach the panel and move it into a new floating window at the `bounds`.
+    fn float_panel(
+        &mut self,
+        panel: Arc<dyn PanelView>,
+        bounds: Bounds<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.detach_panel(panel.clone(), window, cx);
+        self.remove_self_if_empty(window, cx);
+        cx.emit(PanelEvent::LayoutChanged);
+
+        let dock_area = self.dock_area.clone();
+        cx.defer(move |cx| {
+            _ = dock_area.update(cx, |dock_area, cx| {
+                dock_area.open_floating_window(vec![panel], bounds, cx);
+            });
+        });
+    }
+
     /// Add panel with split placement
     fn split_panel(
         &self,
@@ -1103,7 +1397,7 @@ impl TabPanel {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if self.zoomable(cx).is_none() {
+        if self.zoomable(cx).is_none() || self.is_floating(window, cx) {
             return;
         }
 
@@ -1155,11 +1449,80 @@ impl TabPanel {
         }
     }
 
+    fn on_action_float_panel(
+        &mut self,
+        _: &FloatPanel,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.floatable(window, cx) {
+            return;
+        }
+        let Some(panel) = self.active_panel(cx) else {
+            return;
+        };
+
+        let bounds = Bounds::centered_at(window.bounds().center(), FLOATING_WINDOW_SIZE);
+        self.float_panel(panel, bounds, window, cx);
+    }
+
+    fn on_action_unfloat_panel(
+        &mut self,
+        _: &UnfloatPanel,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.is_floating(window, cx) {
+            return;
+        }
+        let Some(panel) = self.active_panel(cx) else {
+            return;
+        };
+
+        // The floating window will be closed if this is the last panel.
+        self.detach_panel(panel.clone(), window, cx);
+        self.remove_self_if_empty(window, cx);
+        cx.emit(PanelEvent::LayoutChanged);
+        DockArea::dock_back_panels(self.dock_area.clone(), vec![panel], cx);
+    }
+
     // Bind actions to the tab panel, only when the tab panel is not collapsed.
     fn bind_actions(&self, cx: &mut Context<Self>) -> Div {
         v_flex().when(!self.collapsed, |this| {
             this.on_action(cx.listener(Self::on_action_toggle_zoom))
                 .on_action(cx.listener(Self::on_action_close_panel))
+                .on_action(cx.listener(Self::on_action_float_panel))
+                .on_action(cx.listener(Self::on_action_unfloat_panel))
+                .on_action(
+                    cx.listener(|this, _: &NextTab, window, cx| this.cycle_tab(true, window, cx)),
+                )
+                .on_action(cx.listener(|this, _: &PreviousTab, window, cx| {
+                    this.cycle_tab(false, window, cx)
+                }))
+                .on_action(cx.listener(|this, _: &FocusPanelLeft, window, cx| {
+                    this.focus_adjacent_panel(Placement::Left, window, cx)
+                }))
+                .on_action(cx.listener(|this, _: &FocusPanelRight, window, cx| {
+                    this.focus_adjacent_panel(Placement::Right, window, cx)
+                }))
+                .on_action(cx.listener(|this, _: &FocusPanelUp, window, cx| {
+                    this.focus_adjacent_panel(Placement::Top, window, cx)
+                }))
+                .on_action(cx.listener(|this, _: &FocusPanelDown, window, cx| {
+                    this.focus_adjacent_panel(Placement::Bottom, window, cx)
+                }))
+                .on_action(cx.listener(|this, _: &MovePanelLeft, window, cx| {
+                    this.move_active_panel(Placement::Left, window, cx)
+                }))
+                .on_action(cx.listener(|this, _: &MovePanelRight, window, cx| {
+                    this.move_active_panel(Placement::Right, window, cx)
+                }))
+                .on_action(cx.listener(|this, _: &MovePanelUp, window, cx| {
+                    this.move_active_panel(Placement::Top, window, cx)
+                }))
+                .on_action(cx.listener(|this, _: &MovePanelDown, window, cx| {
+                    this.move_active_panel(Placement::Bottom, window, cx)
+                }))
         })
     }
 }
@@ -1179,14 +1542,20 @@ impl Render for TabPanel {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl gpui::IntoElement {
         let focus_handle = self.focus_handle(cx);
         let active_panel = self.active_panel(cx);
+        let floating = self.is_floating(window, cx);
         let state = TabState {
             closable: self.closable(cx),
             draggable: self.draggable(cx),
             droppable: self.droppable(cx),
-            zoomable: self.zoomable(cx),
+            // The zoom is not supported in the floating window.
+            zoomable: if floating { None } else { self.zoomable(cx) },
+            floatable: self.floatable(window, cx),
+            floating,
             active_panel,
         };
 
+        let view = cx.entity();
+
         self.bind_actions(cx)
             .id("tab-panel")
             .track_focus(&focus_handle)
@@ -1194,7 +1563,20 @@ impl Render for TabPanel {
             .size_full()
             .overflow_hidden()
             .bg(cx.theme().background)
+            .on_modifiers_changed(cx.listener(Self::on_modifiers_changed))
+            .on_key_down(cx.listener(Self::on_key_down))
+            .child(
+                canvas(
+                    move |bounds, _, cx| view.update(cx, |this, _| this.bounds = bounds),
+                    |_, _, _, _| {},
+                )
+                .absolute()
+                .size_full(),
+            )
             .child(self.render_title_bar(&state, window, cx))
             .child(self.render_active_panel(&state, window, cx))
+            .when_some(self.tab_switcher, |this, selected| {
+                this.child(self.render_tab_switcher(selected, window, cx))
+            })
     }
 }
@@ -22,6 +22,7 @@ pub struct SyntaxHighlighter {
     language: SharedString,
     query: Option<Query>,
     injection_queries: HashMap<SharedString, Query>,
+    indents_query: Option<Query>,
 
     locals_pattern_index: usize,
     highlights_pattern_index: usize,
@@ -41,8 +42,8 @@ pub struct SyntaxHighlighter {
     tree: Option<Tree>,
 }
 
-struct TextProvider<'a>(&'a Rope);
-struct ByteChunks<'a> {
+pub(crate) struct TextProvider<'a>(pub(crate) &'a Rope);
+pub(crate) struct ByteChunks<'a> {
     cursor: ChunkCursor<'a>,
     end: usize,
 }
@@ -283,12 +284,29 @@ impl SyntaxHighlighter {
             }
         }
 
+        let indents_query = if config.indents.is_empty() {
+            None
+        } else {
+            match Query::new(&config.language, &config.indents) {
+                Ok(q) => Some(q),
+                Err(e) => {
+                    tracing::error!(
+                        "failed to build indents query for {:?}: {:?}",
+                        config.name,
+                        e
+                    );
+                    None
+                }
+            }
+        };
+
         // let highlight_indices = vec![None; query.capture_names().len()];
 
         Ok(Self {
             language: config.name.clone(),
             query: Some(query),
             injection_queries,
+            indents_query,
 
             locals_pattern_index,
             highlights_pattern_index,
@@ -309,6 +327,23 @@ impl SyntaxHighlighter {
         self.text.len() == 0
     }
 
+    /// Returns the language name of this highlighter.
+    ///
+    /// This will be `text` if the language was not registered.
+    pub fn language(&self) -> &SharedString {
+        &self.language
+    }
+
+    /// Returns the indents query of the language, if any.
+    pub fn indents_query(&self) -> Option<&Query> {
+        self.indents_query.as_ref()
+    }
+
+    /// Returns the last parsed syntax tree.
+    pub fn tree(&self) -> Option<&Tree> {
+        self.tree.as_ref()
+    }
+
     /// Highlight the given text, returning a map from byte ranges to highlight captures.
     ///
     /// Uses incremental parsing by `edit` to efficiently update the highlighter's state.
@@ -1,6 +1,6 @@
 use gpui::SharedString;
 
-use crate::highlighter::LanguageConfig;
+use crate::highlighter::{BracketPair, LanguageConfig};
 
 #[cfg(not(feature = "tree-sitter-languages"))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, enum_iterator::Sequence)]
@@ -172,6 +172,101 @@ impl Language {
         .collect()
     }
 
+    /// Returns the bracket pairs of the language.
+    pub(super) fn brackets(&self) -> Vec<BracketPair> {
+        #[cfg(not(feature = "tree-sitter-languages"))]
+        return BracketPair::defaults();
+
+        #[cfg(feature = "tree-sitter-languages")]
+        {
+            let mut brackets = BracketPair::defaults();
+            match self {
+                Self::Plain => brackets.retain(|pair| !pair.is_symmetric()),
+                Self::Diff => brackets.clear(),
+                Self::Markdown | Self::MarkdownInline => {
+                    brackets.push(BracketPair::new("`", "`"));
+                    brackets.push(BracketPair::new("*", "*").close(false));
+                    brackets.push(BracketPair::new("_", "_").close(false));
+                }
+                Self::Rust => {
+                    // The `'` is also used for lifetimes, so only use it for surrounding.
+                    brackets.push(BracketPair::new("'", "'").close(false));
+                }
+                Self::JavaScript | Self::TypeScript | Self::Tsx | Self::Ejs | Self::Go => {
+                    brackets.push(BracketPair::new("'", "'"));
+                    brackets.push(BracketPair::new("`", "`"));
+                }
+                Self::Html | Self::Erb => {
+                    brackets.push(BracketPair::new("'", "'"));
+                    brackets.push(BracketPair::new("<", ">").close(false));
+                }
+                _ => brackets.push(BracketPair::new("'", "'")),
+            }
+            brackets
+        }
+    }
+
+    /// Returns the indents query of the language.
+    pub(super) fn indents(&self) -> &'static str {
+        #[cfg(not(feature = "tree-sitter-languages"))]
+        return include_str!("languages/json/indents.scm");
+
+        #[cfg(feature = "tree-sitter-languages")]
+        match self {
+            Self::Json => include_str!("languages/json/indents.scm"),
+            Self::Rust => include_str!("languages/rust/indents.scm"),
+            Self::JavaScript | Self::TypeScript | Self::Tsx => {
+                include_str!("languages/javascript/indents.scm")
+            }
+            Self::Go => include_str!("languages/go/indents.scm"),
+            Self::C | Self::Cpp => include_str!("languages/c/indents.scm"),
+            Self::Java => include_str!("languages/java/indents.scm"),
+            Self::Css => include_str!("languages/css/indents.scm"),
+            Self::Python => include_str!("languages/python/indents.scm"),
+            Self::Toml => include_str!("languages/toml/indents.scm"),
+            Self::Html => include_str!("languages/html/indents.scm"),
+            Self::Zig => include_str!("languages/zig/indents.scm"),
+            _ => "",
+        }
+    }
+
+    /// Returns the (line, block) comment tokens of the language.
+    pub(super) fn comments(&self) -> (Option<&'static str>, Option<(&'static str, &'static str)>) {
+        #[cfg(not(feature = "tree-sitter-languages"))]
+        return (Some("//"), Some(("/*", "*/")));
+
+        #[cfg(feature = "tree-sitter-languages")]
+        match self {
+            Self::Json
+            | Self::C
+            | Self::Cpp
+            | Self::CSharp
+            | Self::Go
+            | Self::Java
+            | Self::JavaScript
+            | Self::Proto
+            | Self::Rust
+            | Self::Scala
+            | Self::Swift
+            | Self::Tsx
+            | Self::TypeScript => (Some("//"), Some(("/*", "*/"))),
+            Self::Zig => (Some("//"), None),
+            Self::Bash
+            | Self::CMake
+            | Self::Elixir
+            | Self::GraphQL
+            | Self::Make
+            | Self::Python
+            | Self::Ruby
+            | Self::Toml
+            | Self::Yaml => (Some("#"), None),
+            Self::Sql => (Some("--"), Some(("/*", "*/"))),
+            Self::Css => (None, Some(("/*", "*/"))),
+            Self::Html | Self::Markdown | Self::Erb | Self::Ejs => (None, Some(("<!--", "-->"))),
+            _ => (None, None),
+        }
+    }
+
     /// Return the language info for the language.
     ///
     /// (language, query, injection, locals)
@@ -360,7 +455,7 @@ impl Language {
 
         let language = tree_sitter::Language::new(language);
 
-        LanguageConfig::new(
+        let mut config = LanguageConfig::new(
             self.name(),
             language,
             self.injection_languages(),
@@ -368,6 +463,13 @@ impl Language {
             injection,
             locals,
         )
+        .brackets(self.brackets())
+        .indents(self.indents());
+
+        let (line_comment, block_comment) = self.comments();
+        config.line_comment = line_comment.map(Into::into);
+        config.block_comment = block_comment.map(|(start, end)| (start.into(), end.into()));
+        config
     }
 }
 
@@ -397,4 +499,20 @@ mod tests {
         assert_eq!(Language::Erb.name(), "erb");
         assert_eq!(Language::Ejs.name(), "ejs");
     }
+
+    #[test]
+    #[cfg(feature = "tree-sitter-languages")]
+    fn test_indents_query() {
+        use super::*;
+
+        for language in Language::all() {
+            let config = language.config();
+            if config.indents.is_empty() {
+                continue;
+            }
+
+            let query = tree_sitter::Query::new(&config.language, &config.indents);
+            assert!(query.is_ok(), "invalid indents query of {:?}", language);
+        }
+    }
 }
@@ -0,0 +1,4 @@
+(element (start_tag) (end_tag) @end) @indent
+(script_element (start_tag) (end_tag) @end) @indent
+(style_element (start_tag) (end_tag) @end) @indent
+(start_tag "<" ">" @end) @indent
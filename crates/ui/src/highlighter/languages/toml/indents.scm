@@ -0,0 +1,2 @@
+(inline_table "{" "}" @end) @indent
+(array "[" "]" @end) @indent
@@ -64,6 +64,15 @@ pub struct LanguageConfig {
     pub highlights: SharedString,
     pub injections: SharedString,
     pub locals: SharedString,
+    /// The indents query, the `@indent` node increases the indent level of its lines after
+    /// the first line, until the line of the `@end` node.
+    pub indents: SharedString,
+    /// The line comment token, e.g.: `//`.
+    pub line_comment: Option<SharedString>,
+    /// The start and end tokens of the block comment, e.g.: `/*` and `*/`.
+    pub block_comment: Option<(SharedString, SharedString)>,
+    /// The bracket pairs for matching, auto-closing and surrounding in the code editor.
+    pub brackets: Vec<BracketPair>,
 }
 
 impl LanguageConfig {
@@ -82,8 +91,95 @@ impl LanguageConfig {
             highlights: SharedString::from(highlights.to_string()),
             injections: SharedString::from(injections.to_string()),
             locals: SharedString::from(locals.to_string()),
+            indents: SharedString::default(),
+            line_comment: None,
+            block_comment: None,
+            brackets: BracketPair::defaults(),
         }
     }
+
+    /// Set the indents query (`indents.scm`) for the auto-indent in the code editor.
+    ///
+    /// For example: `(_ "{" "}" @end) @indent`
+    pub fn indents(mut self, indents: &str) -> Self {
+        self.indents = SharedString::from(indents.to_string());
+        self
+    }
+
+    /// Set the line comment token of the language, e.g.: `//`.
+    pub fn line_comment(mut self, token: impl Into<SharedString>) -> Self {
+        self.line_comment = Some(token.into());
+        self
+    }
+
+    /// Set the block comment tokens of the language, e.g.: `/*` and `*/`.
+    pub fn block_comment(
+        mut self,
+        start: impl Into<SharedString>,
+        end: impl Into<SharedString>,
+    ) -> Self {
+        self.block_comment = Some((start.into(), end.into()));
+        self
+    }
+
+    /// Set the bracket pairs of the language, default: `()`, `[]`, `{}` and `""`.
+    pub fn brackets(mut self, brackets: Vec<BracketPair>) -> Self {
+        self.brackets = brackets;
+        self
+    }
+}
+
+/// A pair of brackets (or quotes) of a language.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BracketPair {
+    /// The opening text, e.g.: `(`.
+    pub start: SharedString,
+    /// The closing text, e.g.: `)`.
+    pub end: SharedString,
+    /// Whether to insert the `end` automatically when typing the `start`.
+    pub close: bool,
+    /// Whether to wrap the selected text with the pair when typing the `start`.
+    pub surround: bool,
+}
+
+impl BracketPair {
+    /// Create a new bracket pair, auto-closing and surrounding are enabled by default.
+    pub fn new(start: impl Into<SharedString>, end: impl Into<SharedString>) -> Self {
+        Self {
+            start: start.into(),
+            end: end.into(),
+            close: true,
+            surround: true,
+        }
+    }
+
+    /// Set whether to insert the `end` automatically when typing the `start`, default: true.
+    pub fn close(mut self, close: bool) -> Self {
+        self.close = close;
+        self
+    }
+
+    /// Set whether to wrap the selected text when typing the `start`, default: true.
+    pub fn surround(mut self, surround: bool) -> Self {
+        self.surround = surround;
+        self
+    }
+
+    /// Returns true if the `start` and `end` are the same, e.g.: quotes.
+    #[inline]
+    pub(crate) fn is_symmetric(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// The default bracket pairs: `()`, `[]`, `{}` and `""`.
+    pub(crate) fn defaults() -> Vec<Self> {
+        vec![
+            Self::new("(", ")"),
+            Self::new("[", "]"),
+            Self::new("{", "}"),
+            Self::new("\"", "\""),
+        ]
+    }
 }
 
 /// Theme for Tree-sitter Highlight
@@ -59,6 +59,7 @@ pub enum IconName {
     FolderClosed,
     FolderOpen,
     Frame,
+    Funnel,
     GalleryVerticalEnd,
     GitHub,
     Globe,
@@ -88,6 +89,7 @@ pub enum IconName {
     Plus,
     Redo,
     Redo2,
+    Regex,
     Replace,
     ResizeCorner,
     Search,
@@ -99,12 +101,14 @@ pub enum IconName {
     Star,
     StarOff,
     Sun,
+    TextSelect,
     ThumbsDown,
     ThumbsUp,
     TriangleAlert,
     Undo,
     Undo2,
     User,
+    WholeWord,
     WindowClose,
     WindowMaximize,
     WindowMinimize,
@@ -157,6 +161,7 @@ impl IconNamed for IconName {
             Self::FolderClosed => "icons/folder-closed.svg",
             Self::FolderOpen => "icons/folder-open.svg",
             Self::Frame => "icons/frame.svg",
+            Self::Funnel => "icons/funnel.svg",
             Self::GalleryVerticalEnd => "icons/gallery-vertical-end.svg",
             Self::GitHub => "icons/github.svg",
             Self::Globe => "icons/globe.svg",
@@ -186,6 +191,7 @@ impl IconNamed for IconName {
             Self::Plus => "icons/plus.svg",
             Self::Redo => "icons/redo.svg",
             Self::Redo2 => "icons/redo-2.svg",
+            Self::Regex => "icons/regex.svg",
             Self::Replace => "icons/replace.svg",
             Self::ResizeCorner => "icons/resize-corner.svg",
             Self::Search => "icons/search.svg",
@@ -197,12 +203,14 @@ impl IconNamed for IconName {
             Self::Star => "icons/star.svg",
             Self::StarOff => "icons/star-off.svg",
             Self::Sun => "icons/sun.svg",
+            Self::TextSelect => "icons/text-select.svg",
             Self::ThumbsDown => "icons/thumbs-down.svg",
             Self::ThumbsUp => "icons/thumbs-up.svg",
             Self::TriangleAlert => "icons/triangle-alert.svg",
             Self::Undo => "icons/undo.svg",
             Self::Undo2 => "icons/undo-2.svg",
             Self::User => "icons/user.svg",
+            Self::WholeWord => "icons/whole-word.svg",
             Self::WindowClose => "icons/window-close.svg",
             Self::WindowMaximize => "icons/window-maximize.svg",
             Self::WindowMinimize => "icons/window-minimize.svg",
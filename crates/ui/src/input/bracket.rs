@@ -0,0 +1,387 @@
+use std::ops::Range;
+
+use gpui::{Context, Window};
+use ropey::Rope;
+use sum_tree::Bias;
+use tree_sitter::Tree;
+
+use super::{InputState, MoveToMatchingBracket, RopeExt as _, mode::InputMode};
+use crate::highlighter::{BracketPair, LanguageRegistry};
+
+/// The max bytes to scan for the matching bracket when there is no syntax tree.
+const MAX_SCAN_LEN: usize = 100_000;
+
+/// Returns true if the `text` at the `offset` is equal to the `s`.
+fn text_at(text: &Rope, offset: usize, s: &str) -> bool {
+    let end = offset + s.len();
+    if s.is_empty() || end > text.len() || text.clip_offset(end, Bias::Left) != end {
+        return false;
+    }
+
+    text.clip_offset(offset, Bias::Left) == offset && text.slice(offset..end) == s
+}
+
+/// Find the matching bracket of the bracket token at `range` by the syntax tree.
+///
+/// The brackets of a pair are the anonymous children of the same node, e.g.: the `(` and `)`
+/// of the `arguments` node, so the nested brackets will be skipped.
+pub(crate) fn syntax_matching_bracket(
+    tree: &Tree,
+    range: Range<usize>,
+    pairs: &[BracketPair],
+) -> Option<Range<usize>> {
+    let node = tree
+        .root_node()
+        .descendant_for_byte_range(range.start, range.end)?;
+    if node.is_named() || node.byte_range() != range {
+        return None;
+    }
+
+    let parent = node.parent()?;
+    let mut cursor = parent.walk();
+    let siblings = parent.children(&mut cursor).collect::<Vec<_>>();
+    let ix = siblings.iter().position(|sibling| *sibling == node)?;
+
+    let kind = node.kind();
+    for pair in pairs {
+        if pair.start == kind {
+            if let Some(end) = siblings[ix + 1..]
+                .iter()
+                .find(|sibling| !sibling.is_named() && pair.end == sibling.kind())
+            {
+                return Some(end.byte_range());
+            }
+        }
+
+        if pair.end == kind {
+            if let Some(start) = siblings[..ix]
+                .iter()
+                .rev()
+                .find(|sibling| !sibling.is_named() && pair.start == sibling.kind())
+            {
+                return Some(start.byte_range());
+            }
+        }
+    }
+
+    None
+}
+
+/// Find the matching bracket of the bracket at `range` by counting the nested brackets.
+///
+/// Only the pairs with different `start` and `end` are supported.
+pub(crate) fn text_matching_bracket(
+    text: &Rope,
+    range: Range<usize>,
+    pairs: &[BracketPair],
+) -> Option<Range<usize>> {
+    let bracket = text.slice(range.clone()).to_string();
+    let pair = pairs
+        .iter()
+        .find(|pair| !pair.is_symmetric() && (pair.start == bracket || pair.end == bracket))?;
+
+    if pair.end == bracket {
+        return unclosed_bracket_before(text, range.start, pair);
+    }
+
+    let end = text.clip_offset((range.end + MAX_SCAN_LEN).min(text.len()), Bias::Left);
+    let mut depth = 0usize;
+    let mut offset = range.end;
+    while offset < end {
+        if text_at(text, offset, &pair.start) {
+            depth += 1;
+            offset += pair.start.len();
+        } else if text_at(text, offset, &pair.end) {
+            if depth == 0 {
+                return Some(offset..offset + pair.end.len());
+            }
+            depth -= 1;
+            offset += pair.end.len();
+        } else {
+            offset = text.clip_offset(offset + 1, Bias::Right);
+        }
+    }
+
+    None
+}
+
+/// Find the unclosed `start` of the `pair` before the `offset`, by counting the nested brackets.
+pub(crate) fn unclosed_bracket_before(
+    text: &Rope,
+    offset: usize,
+    pair: &BracketPair,
+) -> Option<Range<usize>> {
+    let start = offset.saturating_sub(MAX_SCAN_LEN);
+    let end = offset;
+    let mut depth = 0usize;
+    let mut offset = offset;
+    while offset > start {
+        offset = text.clip_offset(offset - 1, Bias::Left);
+        if text_at(text, offset, &pair.end) && offset + pair.end.len() <= end {
+            depth += 1;
+        } else if text_at(text, offset, &pair.start) && offset + pair.start.len() <= end {
+            if depth == 0 {
+                return Some(offset..offset + pair.start.len());
+            }
+            depth -= 1;
+        }
+    }
+
+    None
+}
+
+impl InputState {
+    /// Returns the bracket pairs of the language, only for [`InputMode::CodeEditor`].
+    pub(super) fn bracket_pairs(&self) -> Vec<BracketPair> {
+        let InputMode::CodeEditor { language, .. } = &self.mode else {
+            return vec![];
+        };
+
+        LanguageRegistry::singleton()
+            .language(language)
+            .map(|config| config.brackets)
+            .unwrap_or_default()
+    }
+
+    /// Find the matching bracket of the bracket at `range`.
+    fn matching_bracket(&self, range: Range<usize>, pairs: &[BracketPair]) -> Option<Range<usize>> {
+        if let InputMode::CodeEditor { highlighter, .. } = &self.mode {
+            if let Some(highlighter) = highlighter.borrow().as_ref() {
+                if let Some(tree) = highlighter.tree() {
+                    if highlighter.language() != "text" {
+                        return syntax_matching_bracket(tree, range, pairs);
+                    }
+                }
+            }
+        }
+
+        text_matching_bracket(&self.text, range, pairs)
+    }
+
+    /// Returns the bracket adjacent to the cursor and its matching bracket.
+    ///
+    /// The bracket after the cursor is preferred, the returned bool is true if
+    /// the cursor is after the bracket.
+    fn bracket_pair_at_cursor(&self) -> Option<(Range<usize>, Range<usize>, bool)> {
+        if !self.selected_range.is_empty() {
+            return None;
+        }
+
+        let pairs = self.bracket_pairs();
+        let offset = self.cursor();
+        let candidates = pairs
+            .iter()
+            .flat_map(|pair| [&pair.start, &pair.end])
+            .filter_map(|bracket| {
+                if text_at(&self.text, offset, bracket) {
+                    Some((offset..offset + bracket.len(), false))
+                } else if offset >= bracket.len()
+                    && text_at(&self.text, offset - bracket.len(), bracket)
+                {
+                    Some((offset - bracket.len()..offset, true))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        candidates
+            .iter()
+            .filter(|(_, after)| !after)
+            .chain(candidates.iter().filter(|(_, after)| *after))
+            .find_map(|(range, after)| {
+                self.matching_bracket(range.clone(), &pairs)
+                    .map(|matched| (range.clone(), matched, *after))
+            })
+    }
+
+    /// Returns the ranges of the bracket pair to highlight around the cursor.
+    pub(super) fn matching_bracket_ranges(&self) -> Option<(Range<usize>, Range<usize>)> {
+        if !self.mode.is_code_editor() {
+            return None;
+        }
+
+        self.bracket_pair_at_cursor()
+            .map(|(bracket, matched, _)| (bracket, matched))
+    }
+
+    /// Move the cursor to the matching bracket of the bracket adjacent to the cursor.
+    pub(super) fn move_to_matching_bracket(
+        &mut self,
+        _: &MoveToMatchingBracket,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.mode.is_code_editor() {
+            return;
+        }
+
+        let Some((_, matched, after)) = self.bracket_pair_at_cursor() else {
+            return;
+        };
+
+        let offset = if after { matched.end } else { matched.start };
+        self.move_to(offset, None, cx);
+    }
+
+    /// Handle the typed text for the bracket pairs, returns true if handled.
+    ///
+    /// - Wrap the selected text with the pair when typing the `start`.
+    /// - Skip the closing text if it is same as the text after the cursor.
+    /// - Outdent the blank line when typing the `end`, see [`Self::insert_closing_bracket`].
+    /// - Insert the `end` after the cursor when typing the `start`.
+    pub(super) fn handle_bracket_input(
+        &mut self,
+        new_text: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if !self.mode.is_code_editor() || new_text.is_empty() {
+            return false;
+        }
+
+        let pairs = self.bracket_pairs();
+        let range: Range<usize> = self.selected_range.into();
+
+        if !range.is_empty() {
+            let Some(pair) = pairs
+                .iter()
+                .find(|pair| pair.surround && pair.start == new_text)
+            else {
+                return false;
+            };
+
+            let selected_text = self.text.slice(range.clone()).to_string();
+            let surrounded = format!("{}{}{}", pair.start, selected_text, pair.end);
+            self.replace_text_in_range_silent(
+                Some(self.range_to_utf16(&range)),
+                &surrounded,
+                window,
+                cx,
+            );
+            let start = range.start + pair.start.len();
+            self.selected_range = (start..start + selected_text.len()).into();
+            return true;
+        }
+
+        let offset = range.start;
+        if pairs
+            .iter()
+            .any(|pair| pair.close && pair.end == new_text && text_at(&self.text, offset, new_text))
+        {
+            self.move_to(offset + new_text.len(), None, cx);
+            return true;
+        }
+
+        if let Some(pair) = pairs
+            .iter()
+            .find(|pair| !pair.is_symmetric() && pair.end == new_text)
+        {
+            return self.insert_closing_bracket(pair, window, cx);
+        }
+
+        let Some(pair) = pairs
+            .iter()
+            .find(|pair| pair.close && pair.start == new_text)
+        else {
+            return false;
+        };
+
+        // Only auto close before whitespace or closing brackets.
+        let next_char = self.text.char_at(offset);
+        let before_closing = pairs
+            .iter()
+            .any(|pair| !pair.is_symmetric() && text_at(&self.text, offset, &pair.end));
+        if !next_char.is_none_or(|c| c.is_whitespace()) && !before_closing {
+            return false;
+        }
+
+        // Avoid to close the quotes after a word, e.g.: `don't`.
+        if pair.is_symmetric() && offset > 0 {
+            let prev_offset = self.text.clip_offset(offset - 1, Bias::Left);
+            if self
+                .text
+                .char_at(prev_offset)
+                .is_some_and(|c| c.is_alphanumeric() || c == '_' || pair.start.starts_with(c))
+            {
+                return false;
+            }
+        }
+
+        let pair_text = format!("{}{}", pair.start, pair.end);
+        self.replace_text_in_range_silent(None, &pair_text, window, cx);
+        let cursor = offset + pair.start.len();
+        self.selected_range = (cursor..cursor).into();
+        true
+    }
+
+    /// Returns the range of an empty pair around the cursor, e.g.: `(|)`.
+    ///
+    /// Used to delete the both brackets by backspace.
+    pub(super) fn empty_bracket_pair_at_cursor(&self) -> Option<Range<usize>> {
+        if !self.selected_range.is_empty() {
+            return None;
+        }
+
+        let offset = self.cursor();
+        self.bracket_pairs().into_iter().find_map(|pair| {
+            let start = offset.checked_sub(pair.start.len())?;
+            (pair.close
+                && text_at(&self.text, start, &pair.start)
+                && text_at(&self.text, offset, &pair.end))
+            .then(|| start..offset + pair.end.len())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ropey::Rope;
+
+    use super::{syntax_matching_bracket, text_matching_bracket};
+    use crate::highlighter::BracketPair;
+
+    #[test]
+    fn test_syntax_matching_bracket() {
+        let text = Rope::from(r#"{"a": [1, [2]], "b": "(x"}"#);
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_json::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(text.to_string(), None).unwrap();
+        let pairs = BracketPair::defaults();
+
+        assert_eq!(syntax_matching_bracket(&tree, 0..1, &pairs), Some(25..26));
+        assert_eq!(syntax_matching_bracket(&tree, 25..26, &pairs), Some(0..1));
+        assert_eq!(syntax_matching_bracket(&tree, 6..7, &pairs), Some(13..14));
+        assert_eq!(syntax_matching_bracket(&tree, 12..13, &pairs), Some(10..11));
+        // Quotes
+        assert_eq!(syntax_matching_bracket(&tree, 1..2, &pairs), Some(3..4));
+        assert_eq!(syntax_matching_bracket(&tree, 24..25, &pairs), Some(21..22));
+        // The `(` in string is not a bracket.
+        assert_eq!(syntax_matching_bracket(&tree, 22..23, &pairs), None);
+        // Not a bracket
+        assert_eq!(syntax_matching_bracket(&tree, 7..8, &pairs), None);
+    }
+
+    #[test]
+    fn test_text_matching_bracket() {
+        let text = Rope::from("fn main() {\n    let a = [1, (2)];\n}");
+        let pairs = BracketPair::defaults();
+
+        assert_eq!(text_matching_bracket(&text, 7..8, &pairs), Some(8..9));
+        assert_eq!(text_matching_bracket(&text, 8..9, &pairs), Some(7..8));
+        assert_eq!(text_matching_bracket(&text, 10..11, &pairs), Some(34..35));
+        assert_eq!(text_matching_bracket(&text, 34..35, &pairs), Some(10..11));
+        assert_eq!(text_matching_bracket(&text, 24..25, &pairs), Some(31..32));
+        assert_eq!(text_matching_bracket(&text, 28..29, &pairs), Some(30..31));
+        assert_eq!(
+            text_matching_bracket(&Rope::from("(()"), 0..1, &pairs),
+            None
+        );
+        assert_eq!(
+            text_matching_bracket(&Rope::from("a\"b\""), 1..2, &pairs),
+            None
+        );
+    }
+}
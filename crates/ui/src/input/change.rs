@@ -1,6 +1,9 @@
 use std::fmt::Debug;
 
-use crate::{history::HistoryItem, input::Selection};
+use crate::{
+    history::HistoryItem,
+    input::{Selection, multi_cursor::CursorSelection},
+};
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Change {
@@ -8,6 +11,8 @@ pub struct Change {
     pub(crate) old_text: String,
     pub(crate) new_range: Selection,
     pub(crate) new_text: String,
+    /// The cursors before this change, only present in multi-cursor editing.
+    pub(crate) cursors: Vec<CursorSelection>,
     version: usize,
 }
 
@@ -23,9 +28,16 @@ impl Change {
             old_text: old_text.to_string(),
             new_range: new_range.into(),
             new_text: new_text.to_string(),
+            cursors: vec![],
             version: 0,
         }
     }
+
+    /// Set the cursors before this change, for restoring them on undo.
+    pub(crate) fn cursors(mut self, cursors: Vec<CursorSelection>) -> Self {
+        self.cursors = cursors;
+        self
+    }
 }
 
 impl HistoryItem for Change {
@@ -0,0 +1,577 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    ops::Range,
+    time::Duration,
+};
+
+use gpui::{Context, Window};
+use ropey::{Rope, RopeSlice};
+
+use super::{GoToNextHunk, GoToPreviousHunk, InputState, RevertHunk, RopeExt as _};
+
+/// The max edit distance of the Myers diff, if exceeded, the whole changed part is one hunk.
+const MAX_EDIT_DISTANCE: usize = 2000;
+/// The delay to update the diff hunks after the text changed, to keep the typing fast.
+const UPDATE_DIFF_HUNKS_DELAY: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiffHunkKind {
+    /// The lines are added, the `base_rows` is empty.
+    Added,
+    /// The lines are modified.
+    Modified,
+    /// The lines are deleted, the `rows` is empty, the lines are deleted before `rows.start`.
+    Deleted,
+}
+
+/// A changed lines hunk of the text compared to the diff base.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DiffHunk {
+    pub kind: DiffHunkKind,
+    /// The 0-based rows in the current text.
+    pub rows: Range<usize>,
+    /// The 0-based rows in the diff base.
+    pub base_rows: Range<usize>,
+}
+
+impl DiffHunk {
+    fn new(rows: Range<usize>, base_rows: Range<usize>) -> Self {
+        let kind = if base_rows.is_empty() {
+            DiffHunkKind::Added
+        } else if rows.is_empty() {
+            DiffHunkKind::Deleted
+        } else {
+            DiffHunkKind::Modified
+        };
+
+        Self {
+            kind,
+            rows,
+            base_rows,
+        }
+    }
+
+    /// Returns true if the `row` is in this hunk.
+    ///
+    /// The deleted hunk contains the rows around the deleted position.
+    pub(super) fn contains_row(&self, row: usize) -> bool {
+        if self.rows.is_empty() {
+            row == self.rows.start || row + 1 == self.rows.start
+        } else {
+            self.rows.contains(&row)
+        }
+    }
+}
+
+/// Returns the lines of the text, the empty text has no lines.
+fn lines(text: &Rope) -> Vec<RopeSlice<'_>> {
+    if text.len() == 0 {
+        return vec![];
+    }
+
+    (0..text.lines_len())
+        .map(|row| text.slice_line(row))
+        .collect()
+}
+
+fn hash_line(line: &RopeSlice) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    line.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Compute the changed line hunks of the `text` compared to the `base`.
+///
+/// The common prefix and suffix lines are skipped, so only the changed part is diffed,
+/// this keep it fast for the small edits of a large text.
+pub(crate) fn diff_hunks(base: &Rope, text: &Rope) -> Vec<DiffHunk> {
+    let base_lines = lines(base);
+    let lines = lines(text);
+
+    let prefix = base_lines
+        .iter()
+        .zip(lines.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = base_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let a = base_lines[prefix..base_lines.len() - suffix]
+        .iter()
+        .map(hash_line)
+        .collect::<Vec<_>>();
+    let b = lines[prefix..lines.len() - suffix]
+        .iter()
+        .map(hash_line)
+        .collect::<Vec<_>>();
+
+    let changes = if a.is_empty() && b.is_empty() {
+        vec![]
+    } else {
+        myers_diff(&a, &b).unwrap_or_else(|| vec![(0..a.len(), 0..b.len())])
+    };
+
+    changes
+        .into_iter()
+        .map(|(base_rows, rows)| {
+            DiffHunk::new(
+                rows.start + prefix..rows.end + prefix,
+                base_rows.start + prefix..base_rows.end + prefix,
+            )
+        })
+        .collect()
+}
+
+/// The Myers diff algorithm, returns the changed (a_range, b_range).
+///
+/// Returns `None` if the edit distance exceeds [`MAX_EDIT_DISTANCE`].
+fn myers_diff(a: &[u64], b: &[u64]) -> Option<Vec<(Range<usize>, Range<usize>)>> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = (a.len() + b.len()).min(MAX_EDIT_DISTANCE) as isize;
+    let offset = max + 1;
+    let mut v = vec![0isize; 2 * max as usize + 3];
+    // The `v[-d..=d]` after each step `d`, to backtrack the edits.
+    let mut trace: Vec<Vec<isize>> = vec![];
+
+    let mut found = false;
+    'outer: for d in 0..=max {
+        for k in (-d..=d).step_by(2) {
+            let ix = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[ix - 1] < v[ix + 1]) {
+                v[ix + 1]
+            } else {
+                v[ix - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[ix] = x;
+
+            if x >= n && y >= m {
+                trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+                found = true;
+                break 'outer;
+            }
+        }
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+    }
+
+    if !found {
+        return None;
+    }
+
+    // Backtrack the edits (x, y, is_insert) from the end.
+    let mut edits = vec![];
+    let (mut x, mut y) = (n, m);
+    for d in (1..trace.len() as isize).rev() {
+        let prev = &trace[d as usize - 1];
+        let get = |k: isize| prev[(k + d - 1) as usize];
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && get(k - 1) < get(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = get(prev_k);
+        let prev_y = prev_x - prev_k;
+        let is_insert = prev_k == k + 1;
+        edits.push((prev_x as usize, prev_y as usize, is_insert));
+        x = prev_x;
+        y = prev_y;
+    }
+    edits.reverse();
+
+    // Group the continuous edits into hunks.
+    let mut changes: Vec<(Range<usize>, Range<usize>)> = vec![];
+    for (x, y, is_insert) in edits {
+        let (x_end, y_end) = if is_insert { (x, y + 1) } else { (x + 1, y) };
+        if let Some((a_range, b_range)) = changes.last_mut() {
+            if a_range.end == x && b_range.end == y {
+                a_range.end = x_end;
+                b_range.end = y_end;
+                continue;
+            }
+        }
+        changes.push((x..x_end, y..y_end));
+    }
+
+    Some(changes)
+}
+
+impl InputState {
+    /// Set the base text to show the changes in the gutter, like the git diff.
+    ///
+    /// The hunks will be updated when the text changed.
+    pub fn set_diff_base(&mut self, base: impl Into<Rope>, cx: &mut Context<Self>) {
+        self.diff_base = Some(base.into());
+        self.diff_hunks = None;
+        cx.notify();
+    }
+
+    /// Clear the diff base, and hide the changes in the gutter.
+    pub fn clear_diff_base(&mut self, cx: &mut Context<Self>) {
+        self.diff_base = None;
+        self.diff_hunks = None;
+        cx.notify();
+    }
+
+    /// Returns the diff hunks (sorted by row) compared to the diff base.
+    pub fn diff_hunks(&mut self) -> &[DiffHunk] {
+        if self.diff_hunks_revision != self.revision {
+            self.diff_hunks = None;
+        }
+        self.update_diff_hunks();
+        self.diff_hunks.as_deref().unwrap_or_default()
+    }
+
+    /// Update the diff hunks if they have not been computed.
+    ///
+    /// The hunks of the edits are updated by [`Self::update_diff_hunks_for_edit`].
+    pub(super) fn update_diff_hunks(&mut self) {
+        if self.diff_hunks.is_some() {
+            return;
+        }
+        let Some(base) = self.diff_base.as_ref() else {
+            return;
+        };
+
+        self.diff_hunks = Some(diff_hunks(base, &self.text));
+        self.diff_hunks_revision = self.revision;
+    }
+
+    /// Update the diff hunks after the `range` of the `old_text` is replaced by the `new_text`.
+    ///
+    /// The hunks after the edited rows are moved at once to keep the gutter in place,
+    /// and all the hunks are diffed again in the background after a delay.
+    pub(super) fn update_diff_hunks_for_edit(
+        &mut self,
+        old_text: &Rope,
+        range: &Range<usize>,
+        new_text: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(base) = self.diff_base.clone() else {
+            return;
+        };
+
+        if let Some(hunks) = self.diff_hunks.as_mut() {
+            let start_row = old_text.offset_to_point(range.start).row;
+            let end_row = old_text.offset_to_point(range.end).row;
+            let (old_lines, new_lines) = (end_row - start_row, new_text.matches('\n').count());
+            for hunk in hunks.iter_mut().filter(|hunk| hunk.rows.start > end_row) {
+                hunk.rows = (hunk.rows.start + new_lines - old_lines)
+                    ..(hunk.rows.end + new_lines - old_lines);
+            }
+        }
+
+        let text = self.text.clone();
+        let revision = self.revision;
+        self._diff_hunks_task = cx.spawn_in(window, async move |editor, cx| {
+            cx.background_executor()
+                .timer(UPDATE_DIFF_HUNKS_DELAY)
+                .await;
+
+            let hunks = cx
+                .background_executor()
+                .spawn(async move { diff_hunks(&base, &text) })
+                .await;
+            editor.update(cx, |editor, cx| {
+                if editor.revision == revision {
+                    editor.diff_hunks = Some(hunks);
+                    editor.diff_hunks_revision = revision;
+                    cx.notify();
+                }
+            })
+        });
+    }
+
+    fn move_to_hunk(&mut self, hunk: &DiffHunk, cx: &mut Context<Self>) {
+        let row = hunk.rows.start.min(self.text.lines_len().saturating_sub(1));
+        let offset = self.text.line_start_offset(row);
+        self.move_to(offset, None, cx);
+    }
+
+    pub(super) fn go_to_next_hunk(
+        &mut self,
+        _: &GoToNextHunk,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let row = self.text.offset_to_point(self.cursor()).row;
+        let hunks = self.diff_hunks();
+        let Some(hunk) = hunks
+            .iter()
+            .find(|hunk| hunk.rows.start > row)
+            .or(hunks.first())
+            .cloned()
+        else {
+            return;
+        };
+
+        self.move_to_hunk(&hunk, cx);
+    }
+
+    pub(super) fn go_to_previous_hunk(
+        &mut self,
+        _: &GoToPreviousHunk,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let row = self.text.offset_to_point(self.cursor()).row;
+        let hunks = self.diff_hunks();
+        let Some(hunk) = hunks
+            .iter()
+            .rev()
+            .find(|hunk| hunk.rows.start < row)
+            .or(hunks.last())
+            .cloned()
+        else {
+            return;
+        };
+
+        self.move_to_hunk(&hunk, cx);
+    }
+
+    /// Revert the hunk at the cursor to the diff base, this can be undone.
+    pub(super) fn revert_hunk(
+        &mut self,
+        _: &RevertHunk,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let row = self.text.offset_to_point(self.cursor()).row;
+        let Some(hunk) = self
+            .diff_hunks()
+            .iter()
+            .find(|hunk| hunk.contains_row(row))
+            .cloned()
+        else {
+            return;
+        };
+        let Some(base) = self.diff_base.as_ref() else {
+            return;
+        };
+
+        let base_text = if hunk.base_rows.is_empty() {
+            String::new()
+        } else {
+            base.slice_lines(hunk.base_rows.clone()).to_string()
+        };
+        let lines_len = if self.text.len() == 0 {
+            0
+        } else {
+            self.text.lines_len()
+        };
+
+        let (range, new_text) = match hunk.kind {
+            DiffHunkKind::Modified => (
+                self.text.line_start_offset(hunk.rows.start)
+                    ..self.text.line_end_offset(hunk.rows.end - 1),
+                base_text,
+            ),
+            DiffHunkKind::Added => {
+                let range = if hunk.rows.end < lines_len {
+                    self.text.line_start_offset(hunk.rows.start)
+                        ..self.text.line_start_offset(hunk.rows.end)
+                } else if hunk.rows.start > 0 {
+                    self.text.line_end_offset(hunk.rows.start - 1)..self.text.len()
+                } else {
+                    0..self.text.len()
+                };
+                (range, String::new())
+            }
+            DiffHunkKind::Deleted => {
+                if hunk.rows.start < lines_len {
+                    let offset = self.text.line_start_offset(hunk.rows.start);
+                    (offset..offset, format!("{}\n", base_text))
+                } else if lines_len > 0 {
+                    let offset = self.text.len();
+                    (offset..offset, format!("\n{}", base_text))
+                } else {
+                    (0..0, base_text)
+                }
+            }
+        };
+
+        let range_utf16 = self.range_to_utf16(&range);
+        self.replace_text_in_range_silent(Some(range_utf16), &new_text, window, cx);
+        let offset = self
+            .text
+            .line_start_offset(hunk.rows.start.min(self.text.lines_len().saturating_sub(1)));
+        self.move_to(offset, None, cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{AppContext as _, TestAppContext};
+    use ropey::Rope;
+
+    use super::{DiffHunk, DiffHunkKind, UPDATE_DIFF_HUNKS_DELAY, diff_hunks, myers_diff};
+    use crate::input::{InputState, RevertHunk, RopeExt as _, Undo};
+
+    #[test]
+    fn test_myers_diff() {
+        assert_eq!(myers_diff(&[1, 2, 3], &[1, 2, 3]), Some(vec![]));
+        assert_eq!(myers_diff(&[1, 2, 3], &[1, 4, 3]), Some(vec![(1..2, 1..2)]));
+        assert_eq!(
+            myers_diff(&[1, 2, 3, 4], &[2, 3, 5, 4, 6]),
+            Some(vec![(0..1, 0..0), (3..3, 2..3), (4..4, 4..5)])
+        );
+        assert_eq!(myers_diff(&[], &[1, 2]), Some(vec![(0..0, 0..2)]));
+    }
+
+    #[test]
+    fn test_diff_hunks() {
+        let base = Rope::from("a\nb\nc\nd\ne\n");
+
+        assert_eq!(diff_hunks(&base, &base), vec![]);
+
+        let text = Rope::from("a\nB\nc\nd\ne\n");
+        assert_eq!(
+            diff_hunks(&base, &text),
+            vec![DiffHunk {
+                kind: DiffHunkKind::Modified,
+                rows: 1..2,
+                base_rows: 1..2,
+            }]
+        );
+
+        let text = Rope::from("a\nb\nnew1\nnew2\nc\ne\n");
+        assert_eq!(
+            diff_hunks(&base, &text),
+            vec![
+                DiffHunk {
+                    kind: DiffHunkKind::Added,
+                    rows: 2..4,
+                    base_rows: 2..2,
+                },
+                DiffHunk {
+                    kind: DiffHunkKind::Deleted,
+                    rows: 5..5,
+                    base_rows: 3..4,
+                },
+            ]
+        );
+
+        let hunks = diff_hunks(&Rope::from(""), &Rope::from("a\nb"));
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].kind, DiffHunkKind::Added);
+        assert_eq!(hunks[0].rows, 0..2);
+
+        let hunks = diff_hunks(&Rope::from("a\nb"), &Rope::from(""));
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].kind, DiffHunkKind::Deleted);
+        assert_eq!(hunks[0].base_rows, 0..2);
+    }
+
+    #[test]
+    fn test_diff_hunk_contains_row() {
+        let hunk = DiffHunk::new(3..3, 1..2);
+        assert_eq!(hunk.kind, DiffHunkKind::Deleted);
+        assert!(hunk.contains_row(2));
+        assert!(hunk.contains_row(3));
+        assert!(!hunk.contains_row(4));
+
+        let hunk = DiffHunk::new(3..5, 3..3);
+        assert_eq!(hunk.kind, DiffHunkKind::Added);
+        assert!(!hunk.contains_row(2));
+        assert!(hunk.contains_row(4));
+        assert!(!hunk.contains_row(5));
+    }
+
+    #[gpui::test]
+    fn test_revert_hunk(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let state = cx.update(|window, cx| {
+            cx.new(|cx| {
+                let mut state = InputState::new(window, cx)
+                    .multi_line(true)
+                    .default_value("a\nB\nc\nnew\nd\n");
+                state.set_diff_base("a\nb\nc\nd\ne\n", cx);
+                state
+            })
+        });
+
+        let revert_at = |row: usize, cx: &mut gpui::VisualTestContext| {
+            cx.update(|window, cx| {
+                state.update(cx, |state, cx| {
+                    let offset = state.text.line_start_offset(row);
+                    state.move_to(offset, None, cx);
+                    state.revert_hunk(&RevertHunk, window, cx);
+                    state.text.to_string()
+                })
+            })
+        };
+
+        // Modified
+        assert_eq!(revert_at(1, cx), "a\nb\nc\nnew\nd\n");
+        // Added
+        assert_eq!(revert_at(3, cx), "a\nb\nc\nd\n");
+        // Deleted
+        assert_eq!(revert_at(4, cx), "a\nb\nc\nd\ne\n");
+        cx.update(|_, cx| state.update(cx, |state, _| assert_eq!(state.diff_hunks(), &[])));
+
+        cx.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                // The reverts in a short time are grouped into one undo step.
+                state.undo(&Undo, window, cx);
+                assert_eq!(state.text.to_string(), "a\nB\nc\nnew\nd\n");
+                // The stale hunks are diffed again when accessed.
+                assert_eq!(
+                    state.diff_hunks(),
+                    &[
+                        DiffHunk::new(1..2, 1..2),
+                        DiffHunk::new(3..4, 3..3),
+                        DiffHunk::new(5..5, 4..5),
+                    ],
+                );
+            })
+        });
+    }
+
+    #[gpui::test]
+    fn test_update_diff_hunks_for_edit(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let state = cx.update(|window, cx| {
+            cx.new(|cx| {
+                let mut state = InputState::new(window, cx)
+                    .multi_line(true)
+                    .default_value("a\nb\nC\n");
+                state.set_diff_base("a\nb\nc\n", cx);
+                state.update_diff_hunks();
+                state
+            })
+        });
+
+        // The hunks after the edit are moved at once.
+        cx.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                state.replace_text_in_range_silent(Some(0..0), "new\n", window, cx);
+                assert_eq!(
+                    state.diff_hunks.as_deref(),
+                    Some(&[DiffHunk::new(3..4, 2..3)][..])
+                );
+            })
+        });
+
+        // All the hunks are diffed again after the delay.
+        cx.executor().advance_clock(UPDATE_DIFF_HUNKS_DELAY);
+        cx.run_until_parked();
+        cx.update(|_, cx| {
+            let state = state.read(cx);
+            assert_eq!(state.diff_hunks_revision, state.revision);
+            assert_eq!(
+                state.diff_hunks.as_deref(),
+                Some(&[DiffHunk::new(0..1, 0..0), DiffHunk::new(3..4, 2..3)][..])
+            );
+        });
+    }
+}
@@ -3,22 +3,31 @@ use std::{ops::Range, rc::Rc};
 use gpui::{
     App, Bounds, Corners, Element, ElementId, ElementInputHandler, Entity, GlobalElementId, Half,
     HighlightStyle, Hitbox, Hsla, IntoElement, LayoutId, MouseButton, MouseMoveEvent, Path, Pixels,
-    Point, ShapedLine, SharedString, Size, Style, TextRun, TextStyle, UnderlineStyle, Window, fill,
-    point, px, relative, size,
+    Point, ShapedLine, SharedString, Size, Style, TextRun, TextStyle, TransformationMatrix,
+    UnderlineStyle, Window, fill, point, px, relative, size,
 };
 use ropey::Rope;
 use smallvec::SmallVec;
 
 use crate::{
-    ActiveTheme as _, Colorize, PixelsExt, Root,
-    input::{RopeExt as _, blink_cursor::CURSOR_WIDTH, text_wrapper::LineLayout},
+    ActiveTheme as _, Colorize, IconName, IconNamed as _, PixelsExt, Root,
+    highlighter::unique_styles,
+    input::{
+        RopeExt as _,
+        blink_cursor::CURSOR_WIDTH,
+        text_wrapper::{LineInlays, LineLayout},
+    },
 };
 
-use super::{InputState, LastLayout, mode::InputMode};
+use super::{DiffHunkKind, InputState, LastLayout, mode::InputMode};
 
 const BOTTOM_MARGIN_ROWS: usize = 3;
 pub(super) const RIGHT_MARGIN: Pixels = px(10.);
 pub(super) const LINE_NUMBER_RIGHT_MARGIN: Pixels = px(10.);
+/// The width of the fold chevrons area in the gutter, between the line numbers and the text.
+pub(super) const FOLD_GUTTER_WIDTH: Pixels = px(14.);
+/// The width of the diff markers area in the gutter, before the line numbers.
+const DIFF_GUTTER_WIDTH: Pixels = px(6.);
 
 pub(super) struct TextElement {
     pub(crate) state: Entity<InputState>,
@@ -222,11 +231,7 @@ impl TextElement {
             }
 
             // cursor bounds
-            let cursor_height = match state.size {
-                crate::Size::Large => 1.,
-                crate::Size::Small => 0.75,
-                _ => 0.85,
-            } * line_height;
+            let cursor_height = Self::cursor_height(state, line_height);
 
             cursor_bounds = Some(Bounds::new(
                 point(
@@ -246,6 +251,205 @@ impl TextElement {
         (cursor_bounds, scroll_offset, current_row)
     }
 
+    fn cursor_height(state: &InputState, line_height: Pixels) -> Pixels {
+        let ratio = match state.size {
+            crate::Size::Large => 1.,
+            crate::Size::Small => 0.75,
+            _ => 0.85,
+        };
+        ratio * line_height
+    }
+
+    /// Layout the secondary cursors (multi-cursor editing) in the visible lines.
+    ///
+    /// Returns the selection paths and the cursor bounds.
+    fn layout_secondary_cursors(
+        &self,
+        last_layout: &LastLayout,
+        bounds: &Bounds<Pixels>,
+        cx: &mut App,
+    ) -> (Vec<Path<Pixels>>, Vec<Bounds<Pixels>>) {
+        let state = self.state.read(cx);
+        if state.secondary_selections.is_empty() {
+            return (vec![], vec![]);
+        }
+
+        let line_height = last_layout.line_height;
+        let line_number_width = last_layout.line_number_width;
+        let visible_range_offset = &last_layout.visible_range_offset;
+        let cursor_height = Self::cursor_height(state, line_height);
+
+        let mut paths = vec![];
+        let mut cursors = vec![];
+        for selection in state.secondary_selections.iter() {
+            let range = selection.range;
+            let start = range.start.max(visible_range_offset.start);
+            let end = range.end.min(visible_range_offset.end);
+            if start < end {
+                if let Some(path) = Self::layout_match_range(start..end, last_layout, bounds) {
+                    paths.push(path);
+                }
+            }
+
+            let cursor = selection.cursor();
+            if cursor < visible_range_offset.start || cursor > visible_range_offset.end {
+                continue;
+            }
+
+            let mut prev_lines_offset = visible_range_offset.start;
+            let mut offset_y = last_layout.visible_top;
+            for line in last_layout.lines.iter() {
+                if let Some(pos) =
+                    line.position_for_index(cursor.saturating_sub(prev_lines_offset), line_height)
+                {
+                    cursors.push(Bounds::new(
+                        bounds.origin
+                            + point(
+                                line_number_width + pos.x,
+                                offset_y + pos.y + (line_height - cursor_height) / 2.,
+                            ),
+                        size(CURSOR_WIDTH, cursor_height),
+                    ));
+                    break;
+                }
+
+                offset_y += line.size(line_height).height;
+                // +1 for the last `\n`
+                prev_lines_offset += line.len() + 1;
+            }
+        }
+
+        (paths, cursors)
+    }
+
+    /// Layout the diff markers in the gutter, the bounds are relative to the gutter origin.
+    fn layout_diff_markers(
+        state: &InputState,
+        last_layout: &LastLayout,
+        cx: &App,
+    ) -> Vec<(Bounds<Pixels>, Hsla)> {
+        let Some(hunks) = state.diff_hunks.as_ref() else {
+            return vec![];
+        };
+        if !state.mode.line_number() || hunks.is_empty() {
+            return vec![];
+        }
+
+        let line_height = last_layout.line_height;
+        let visible_range = &last_layout.visible_range;
+        let status = &cx.theme().highlight_theme.style.status;
+        // The top of the rows in the visible range, and the bottom of the last row.
+        let mut row_tops = Vec::with_capacity(last_layout.lines.len() + 1);
+        let mut offset_y = last_layout.visible_top;
+        for line in last_layout.lines.iter() {
+            row_tops.push(offset_y);
+            offset_y += line.size(line_height).height;
+        }
+        row_tops.push(offset_y);
+        let row_top = |row: usize| {
+            row.checked_sub(visible_range.start)
+                .and_then(|ix| row_tops.get(ix).copied())
+        };
+
+        let mut markers = vec![];
+        for hunk in hunks {
+            if hunk.rows.start > visible_range.end || hunk.rows.end < visible_range.start {
+                continue;
+            }
+
+            if hunk.kind == DiffHunkKind::Deleted {
+                if let Some(top) = row_top(hunk.rows.start) {
+                    markers.push((
+                        Bounds::new(
+                            point(px(0.), top - px(1.5)),
+                            size(DIFF_GUTTER_WIDTH, px(3.)),
+                        ),
+                        status.error(cx),
+                    ));
+                }
+                continue;
+            }
+
+            let start = hunk.rows.start.max(visible_range.start);
+            let end = hunk.rows.end.min(visible_range.end);
+            let (Some(top), Some(bottom)) = (row_top(start), row_top(end)) else {
+                continue;
+            };
+            if bottom <= top {
+                continue;
+            }
+
+            let color = if hunk.kind == DiffHunkKind::Added {
+                status.success(cx)
+            } else {
+                status.info(cx)
+            };
+            markers.push((
+                Bounds::new(
+                    point(px(0.), top),
+                    size(DIFF_GUTTER_WIDTH / 2., bottom - top),
+                ),
+                color,
+            ));
+        }
+
+        markers
+    }
+
+    /// Layout the `⋯` placeholder at the end of the folded lines.
+    fn layout_fold_placeholders(
+        state: &InputState,
+        last_layout: &LastLayout,
+        bounds: &Bounds<Pixels>,
+        text_style: &TextStyle,
+        window: &mut Window,
+        cx: &App,
+    ) -> (Option<ShapedLine>, Vec<Point<Pixels>>) {
+        let folds = state.text_wrapper.folds();
+        if folds.is_empty() {
+            return (None, vec![]);
+        }
+
+        let line_height = last_layout.line_height;
+        let mut origins = vec![];
+        let mut offset_y = last_layout.visible_top;
+        for (ix, line) in last_layout.lines.iter().enumerate() {
+            let row = last_layout.visible_range.start + ix;
+            if folds.iter().any(|fold| fold.start_row == row) {
+                if let Some(pos) = line.position_for_index(line.len(), line_height) {
+                    origins.push(
+                        bounds.origin
+                            + point(
+                                last_layout.line_number_width + pos.x + px(4.),
+                                offset_y + pos.y,
+                            ),
+                    );
+                }
+            }
+            offset_y += line.size(line_height).height;
+        }
+
+        if origins.is_empty() {
+            return (None, vec![]);
+        }
+
+        let text: SharedString = "⋯".into();
+        let run = TextRun {
+            len: text.len(),
+            font: text_style.font(),
+            color: cx.theme().muted_foreground,
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+        };
+        let font_size = text_style.font_size.to_pixels(window.rem_size());
+        let placeholder = window
+            .text_system()
+            .shape_line(text, font_size, &[run], None);
+
+        (Some(placeholder), origins)
+    }
+
     /// Layout the match range to a Path.
     pub(crate) fn layout_match_range(
         range: Range<usize>,
@@ -422,6 +626,28 @@ impl TextElement {
         Self::layout_match_range(symbol_range, last_layout, bounds)
     }
 
+    fn layout_bracket_highlights(
+        &self,
+        last_layout: &LastLayout,
+        bounds: &Bounds<Pixels>,
+        window: &Window,
+        cx: &App,
+    ) -> Vec<Path<Pixels>> {
+        let state = self.state.read(cx);
+        if !state.focus_handle.is_focused(window) {
+            return vec![];
+        }
+
+        let Some((bracket, matched)) = state.matching_bracket_ranges() else {
+            return vec![];
+        };
+
+        [bracket, matched]
+            .into_iter()
+            .filter_map(|range| Self::layout_match_range(range, last_layout, bounds))
+            .collect()
+    }
+
     fn layout_document_colors(
         &self,
         document_colors: &[(Range<usize>, Hsla)],
@@ -492,7 +718,7 @@ impl TextElement {
             return (0..1, visible_top);
         }
 
-        let total_lines = state.text_wrapper.len();
+        let total_lines = state.text_wrapper.lines.len();
         let scroll_top = if let Some(deferred_scroll_offset) = state.deferred_scroll_offset {
             deferred_scroll_offset.y
         } else {
@@ -550,7 +776,17 @@ impl TextElement {
                 None,
             );
 
-            empty_line_number.width + px(6.) + LINE_NUMBER_RIGHT_MARGIN
+            let diff_gutter_width = if state.diff_base.is_some() {
+                DIFF_GUTTER_WIDTH
+            } else {
+                px(0.)
+            };
+
+            diff_gutter_width
+                + empty_line_number.width
+                + px(6.)
+                + FOLD_GUTTER_WIDTH
+                + LINE_NUMBER_RIGHT_MARGIN
         } else {
             px(0.)
         };
@@ -558,6 +794,7 @@ impl TextElement {
         (line_number_width, line_number_len)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn layout_lines(
         state: &InputState,
         display_text: &Rope,
@@ -565,6 +802,8 @@ impl TextElement {
         font_size: Pixels,
         runs: &[TextRun],
         bg_segments: &[(Range<usize>, Hsla)],
+        inlay_hints: &[(usize, SharedString)],
+        inlay_run: &TextRun,
         window: &mut Window,
     ) -> Vec<LineLayout> {
         let is_single_line = state.mode.is_single_line();
@@ -614,10 +853,19 @@ impl TextElement {
 
             debug_assert_eq!(line_item.len(), line.len());
 
+            if line_item.folded {
+                lines.push(LineLayout::folded(line.len()));
+                // +1 for the `\n`
+                offset += line.len() + 1;
+                continue;
+            }
+
             let mut line_layout = LineLayout::new();
             let mut wrapped_lines = SmallVec::with_capacity(1);
+            let mut line_inlays = SmallVec::with_capacity(1);
+            let line_start = visible_range_offset.start + offset;
 
-            for range in &line_item.wrapped_lines {
+            for (wrapped_ix, range) in line_item.wrapped_lines.iter().enumerate() {
                 let line_runs = runs_for_range(runs, offset, &range);
                 let line_runs = if bg_segments.is_empty() {
                     line_runs
@@ -629,14 +877,34 @@ impl TextElement {
                     )
                 };
 
-                let sub_line: SharedString = line[range.clone()].to_string().into();
+                // The inlay at the end of the wrapped line belongs to the next wrapped line.
+                let is_last = wrapped_ix + 1 == line_item.wrapped_lines.len();
+                let hints = inlay_hints
+                    .iter()
+                    .filter(|(hint_offset, _)| {
+                        *hint_offset >= line_start + range.start
+                            && (*hint_offset < line_start + range.end
+                                || is_last && *hint_offset == line_start + range.end)
+                    })
+                    .map(|(hint_offset, label)| (hint_offset - line_start - range.start, label))
+                    .collect::<Vec<_>>();
+
+                let (sub_line, line_runs, inlays) = if hints.is_empty() {
+                    let sub_line: SharedString = line[range.clone()].to_string().into();
+                    (sub_line, line_runs, LineInlays::new())
+                } else {
+                    insert_inlays(&line[range.clone()], &line_runs, &hints, inlay_run)
+                };
+
                 let shaped_line = window
                     .text_system()
                     .shape_line(sub_line, font_size, &line_runs, None);
 
                 wrapped_lines.push(shaped_line);
+                line_inlays.push(inlays);
             }
 
+            line_layout.set_inlays(line_inlays);
             line_layout.set_wrapped_lines(wrapped_lines);
             lines.push(line_layout);
 
@@ -669,6 +937,12 @@ impl TextElement {
         };
         let highlighter = highlighter.as_ref()?;
 
+        let highlight_theme = &cx.theme().highlight_theme;
+        let semantic_styles = state
+            .lsp
+            .semantic_token_styles(text, visible_range, highlight_theme);
+        let mut semantic_ix = 0;
+
         let mut offset = visible_byte_range.start;
         let mut styles = vec![];
 
@@ -685,7 +959,28 @@ impl TextElement {
             };
 
             let range = offset..offset + line_len;
-            let line_styles = highlighter.styles(&range, &cx.theme().highlight_theme);
+            let mut line_styles = highlighter.styles(&range, highlight_theme);
+
+            // Overlay the semantic tokens on the syntax styles.
+            let semantic_start = semantic_ix;
+            while let Some((token_range, _)) = semantic_styles.get(semantic_ix) {
+                if token_range.start >= range.end {
+                    break;
+                }
+                semantic_ix += 1;
+            }
+            let line_semantic_styles = semantic_styles[semantic_start..semantic_ix]
+                .iter()
+                .filter(|(token_range, _)| token_range.start >= range.start)
+                .map(|(token_range, style)| {
+                    (token_range.start..token_range.end.min(range.end), *style)
+                })
+                .collect::<Vec<_>>();
+            if !line_semantic_styles.is_empty() {
+                line_styles.extend(line_semantic_styles);
+                line_styles = unique_styles(&range, line_styles);
+            }
+
             styles = gpui::combine_highlights(styles, line_styles).collect();
 
             offset = range.end;
@@ -719,11 +1014,23 @@ pub(super) struct PrepaintState {
     /// row index (zero based), no wrap, same line as the cursor.
     current_row: Option<usize>,
     selection_path: Option<Path<Pixels>>,
+    /// Selections and cursors of the secondary cursors in multi-cursor editing.
+    secondary_selection_paths: Vec<Path<Pixels>>,
+    secondary_cursor_bounds: Vec<Bounds<Pixels>>,
     hover_highlight_path: Option<Path<Pixels>>,
     search_match_paths: Vec<(Path<Pixels>, bool)>,
     document_color_paths: Vec<(Path<Pixels>, Hsla)>,
+    /// The highlights of the bracket pair around the cursor.
+    bracket_highlight_paths: Vec<Path<Pixels>>,
     hover_definition_hitbox: Option<Hitbox>,
     indent_guides_path: Option<Path<Pixels>>,
+    /// The (row, folded) of the fold chevrons in the gutter.
+    fold_chevrons: Vec<(usize, bool)>,
+    /// The bounds (relative to the gutter origin) and colors of the diff markers.
+    diff_markers: Vec<(Bounds<Pixels>, Hsla)>,
+    /// The placeholder after the folded lines, and the origins to paint it.
+    fold_placeholder: Option<ShapedLine>,
+    fold_placeholder_origins: Vec<Point<Pixels>>,
     bounds: Bounds<Pixels>,
 }
 
@@ -825,6 +1132,8 @@ impl Element for TextElement {
         self.state.update(cx, |state, cx| {
             state.text_wrapper.set_font(font, text_size, cx);
             state.text_wrapper.prepare_if_need(&state.text, cx);
+            state.update_fold_ranges();
+            state.update_diff_hunks();
         });
 
         let state = self.state.read(cx);
@@ -960,6 +1269,22 @@ impl Element for TextElement {
         let document_colors = state
             .lsp
             .document_colors_for_range(&text, &last_layout.visible_range);
+        let inlay_hints = if multi_line && !is_empty && !state.masked {
+            state
+                .lsp
+                .inlay_hints_for_range(&text, &last_layout.visible_range)
+        } else {
+            vec![]
+        };
+        let highlight_theme = &cx.theme().highlight_theme;
+        let inlay_run = TextRun {
+            len: 0,
+            font: style.font(),
+            color: highlight_theme.style.status.hint(cx),
+            background_color: Some(highlight_theme.style.status.hint_background(cx)),
+            underline: None,
+            strikethrough: None,
+        };
         let lines = Self::layout_lines(
             &state,
             &display_text,
@@ -967,6 +1292,8 @@ impl Element for TextElement {
             text_size,
             &runs,
             &document_colors,
+            &inlay_hints,
+            &inlay_run,
             window,
         );
 
@@ -992,6 +1319,13 @@ impl Element for TextElement {
                     wrap_width,
                 )
                 .width;
+
+            // The inlay hints may make the visible lines longer than the longest row.
+            if !inlay_hints.is_empty() {
+                for line in &lines {
+                    longest_line_width = longest_line_width.max(line.longest_width);
+                }
+            }
         }
         last_layout.lines = Rc::new(lines);
 
@@ -1053,9 +1387,13 @@ impl Element for TextElement {
 
         let search_match_paths = self.layout_search_matches(&last_layout, &mut bounds, cx);
         let selection_path = self.layout_selections(&last_layout, &mut bounds, cx);
+        let (secondary_selection_paths, secondary_cursor_bounds) =
+            self.layout_secondary_cursors(&last_layout, &bounds, cx);
         let hover_highlight_path = self.layout_hover_highlight(&last_layout, &mut bounds, cx);
         let document_color_paths =
             self.layout_document_colors(&document_colors, &last_layout, &bounds);
+        let bracket_highlight_paths =
+            self.layout_bracket_highlights(&last_layout, &bounds, window, cx);
 
         let state = self.state.read(cx);
         let line_numbers = if state.mode.line_number() {
@@ -1079,6 +1417,12 @@ impl Element for TextElement {
 
             // build line numbers
             for (ix, line) in last_layout.lines.iter().enumerate() {
+                if line.wrapped_lines.is_empty() {
+                    // The line is hidden by a fold.
+                    line_numbers.push(SmallVec::new());
+                    continue;
+                }
+
                 let ix = last_layout.visible_range.start + ix;
                 let line_no = format!("{:>width$}", ix + 1, width = line_number_len).into();
 
@@ -1104,6 +1448,27 @@ impl Element for TextElement {
             None
         };
 
+        let fold_chevrons = if state.mode.line_number() {
+            last_layout
+                .visible_range
+                .clone()
+                .filter(|row| !state.text_wrapper.is_row_folded(*row))
+                .filter_map(|row| {
+                    let folded = state
+                        .text_wrapper
+                        .folds()
+                        .iter()
+                        .any(|f| f.start_row == row);
+                    (folded || state.fold_range_at_row(row).is_some()).then_some((row, folded))
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+        let diff_markers = Self::layout_diff_markers(state, &last_layout, cx);
+        let (fold_placeholder, fold_placeholder_origins) =
+            Self::layout_fold_placeholders(state, &last_layout, &bounds, &text_style, window, cx);
+
         let hover_definition_hitbox = self.layout_hover_definition_hitbox(state, window, cx);
         let indent_guides_path =
             self.layout_indent_guides(state, &bounds, &last_layout, &text_style, window);
@@ -1117,11 +1482,18 @@ impl Element for TextElement {
             cursor_scroll_offset,
             current_row,
             selection_path,
+            secondary_selection_paths,
+            secondary_cursor_bounds,
             search_match_paths,
             hover_highlight_path,
             hover_definition_hitbox,
             document_color_paths,
+            bracket_highlight_paths,
             indent_guides_path,
+            fold_chevrons,
+            diff_markers,
+            fold_placeholder,
+            fold_placeholder_origins,
         }
     }
 
@@ -1233,6 +1605,9 @@ impl Element for TextElement {
             if let Some(path) = prepaint.selection_path.take() {
                 window.paint_path(path, cx.theme().selection);
             }
+            for path in prepaint.secondary_selection_paths.drain(..) {
+                window.paint_path(path, cx.theme().selection);
+            }
 
             // Paint hover highlight
             if let Some(path) = prepaint.hover_highlight_path.take() {
@@ -1240,6 +1615,11 @@ impl Element for TextElement {
             }
         }
 
+        // Paint bracket highlights
+        for path in prepaint.bracket_highlight_paths.drain(..) {
+            window.paint_path(path, cx.theme().selection.saturation(0.1));
+        }
+
         // Paint document colors
         for (path, color) in prepaint.document_color_paths.iter() {
             window.paint_path(path.clone(), *color);
@@ -1256,12 +1636,22 @@ impl Element for TextElement {
             offset_y += line.size(line_height).height;
         }
 
+        // Paint the placeholder of folded lines
+        if let Some(placeholder) = prepaint.fold_placeholder.as_ref() {
+            for origin in prepaint.fold_placeholder_origins.iter() {
+                _ = placeholder.paint(*origin, line_height, window, cx);
+            }
+        }
+
         // Paint blinking cursor
         if focused && show_cursor {
             if let Some(mut cursor_bounds) = prepaint.cursor_bounds.take() {
                 cursor_bounds.origin.y += prepaint.cursor_scroll_offset.y;
                 window.paint_quad(fill(cursor_bounds, cx.theme().caret));
             }
+            for cursor_bounds in prepaint.secondary_cursor_bounds.drain(..) {
+                window.paint_quad(fill(cursor_bounds, cx.theme().caret));
+            }
         }
 
         // Paint line numbers
@@ -1281,6 +1671,12 @@ impl Element for TextElement {
                 cx.theme().editor_background(),
             ));
 
+            let line_number_offset_x = if self.state.read(cx).diff_base.is_some() {
+                DIFF_GUTTER_WIDTH
+            } else {
+                px(0.)
+            };
+
             // Each item is the normal lines.
             for (ix, lines) in line_numbers.iter().enumerate() {
                 let row = visible_range.start + ix;
@@ -1299,11 +1695,52 @@ impl Element for TextElement {
                     }
                 }
 
+                if let Ok(ix) = prepaint
+                    .fold_chevrons
+                    .binary_search_by_key(&row, |(row, _)| *row)
+                {
+                    let folded = prepaint.fold_chevrons[ix].1;
+                    let icon = if folded {
+                        IconName::ChevronRight
+                    } else {
+                        IconName::ChevronDown
+                    };
+                    let icon_size = FOLD_GUTTER_WIDTH.min(line_height) - px(2.);
+                    let x = prepaint.last_layout.line_number_width
+                        - LINE_NUMBER_RIGHT_MARGIN
+                        - FOLD_GUTTER_WIDTH
+                        + (FOLD_GUTTER_WIDTH - icon_size) / 2.;
+                    _ = window.paint_svg(
+                        Bounds::new(
+                            p + point(x, (line_height - icon_size) / 2.),
+                            size(icon_size, icon_size),
+                        ),
+                        icon.path(),
+                        TransformationMatrix::unit(),
+                        cx.theme().muted_foreground,
+                        cx,
+                    );
+                }
+
                 for line in lines {
-                    _ = line.paint(p, line_height, window, cx);
+                    _ = line.paint(
+                        p + point(line_number_offset_x, px(0.)),
+                        line_height,
+                        window,
+                        cx,
+                    );
                     offset_y += line_height;
                 }
             }
+
+            // Paint diff markers
+            let gutter_origin = point(input_bounds.origin.x, origin.y);
+            for (marker_bounds, color) in prepaint.diff_markers.iter() {
+                window.paint_quad(fill(
+                    Bounds::new(gutter_origin + marker_bounds.origin, marker_bounds.size),
+                    *color,
+                ));
+            }
         }
 
         self.state.update(cx, |state, cx| {
@@ -1366,6 +1803,66 @@ pub(super) fn runs_for_range(
     result
 }
 
+/// Insert the inlays into the wrapped line text, and split the runs for the inlays.
+///
+/// - The `inlays` is the (byte offset in the `text`, label) sorted by offset.
+/// - The `inlay_run` is the style of the inlay text.
+///
+/// Returns the text with inlays, the runs and the [`LineInlays`].
+fn insert_inlays(
+    text: &str,
+    runs: &[TextRun],
+    inlays: &[(usize, &SharedString)],
+    inlay_run: &TextRun,
+) -> (SharedString, Vec<TextRun>, LineInlays) {
+    let inlays_len: usize = inlays.iter().map(|(_, label)| label.len()).sum();
+    let mut new_text = String::with_capacity(text.len() + inlays_len);
+    let mut new_runs = Vec::with_capacity(runs.len() + inlays.len() * 2);
+    let mut line_inlays = LineInlays::new();
+
+    let mut runs = runs.iter().cloned();
+    let mut current_run = runs.next();
+    let mut ix = 0;
+    let mut text_ix = 0;
+    for (offset, label) in inlays {
+        let offset = (*offset).min(text.len());
+        while ix < offset {
+            let Some(run) = current_run.as_mut() else {
+                break;
+            };
+
+            let len = run.len.min(offset - ix);
+            if len > 0 {
+                new_runs.push(TextRun { len, ..run.clone() });
+            }
+            run.len -= len;
+            ix += len;
+            if run.len == 0 {
+                current_run = runs.next();
+            }
+        }
+
+        new_text.push_str(&text[text_ix..offset]);
+        new_text.push_str(label);
+        text_ix = offset;
+        new_runs.push(TextRun {
+            len: label.len(),
+            ..inlay_run.clone()
+        });
+        line_inlays.push((offset, label.len()));
+    }
+
+    new_text.push_str(&text[text_ix..]);
+    while let Some(run) = current_run {
+        if run.len > 0 {
+            new_runs.push(run);
+        }
+        current_run = runs.next();
+    }
+
+    (new_text.into(), new_runs, line_inlays)
+}
+
 fn split_runs_by_bg_segments(
     start_offset: usize,
     runs: &[TextRun],
@@ -1529,4 +2026,61 @@ mod tests {
         assert_eq!(result[4].color, gpui::black());
         assert_eq!(result[5].color, gpui::blue());
     }
+
+    #[test]
+    fn test_insert_inlays() {
+        let run = TextRun {
+            len: 0,
+            font: gpui::font(".SystemUIFont"),
+            color: gpui::black(),
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+        };
+        let inlay_run = TextRun {
+            color: gpui::blue(),
+            ..run.clone()
+        };
+
+        // let a = foo(1);
+        let text = "let a = foo(1);";
+        let runs = vec![
+            TextRun {
+                len: 8,
+                ..run.clone()
+            },
+            TextRun {
+                len: 7,
+                ..run.clone()
+            },
+        ];
+
+        let type_hint = SharedString::from(": i32");
+        let param_hint = SharedString::from("x: ");
+        let (new_text, new_runs, inlays) = insert_inlays(
+            text,
+            &runs,
+            &[(5, &type_hint), (12, &param_hint)],
+            &inlay_run,
+        );
+        assert_eq!(new_text.as_ref(), "let a: i32 = foo(x: 1);");
+        assert_eq!(inlays.as_slice(), &[(5, 5), (12, 3)]);
+        assert_eq!(
+            new_runs.iter().map(|run| run.len).collect::<Vec<_>>(),
+            vec![5, 5, 3, 4, 3, 3]
+        );
+        assert_eq!(new_runs[1].color, gpui::blue());
+        assert_eq!(new_runs[4].color, gpui::blue());
+        assert_eq!(new_runs[5].color, gpui::black());
+
+        // Inlay at the end of line.
+        let (new_text, new_runs, inlays) =
+            insert_inlays(text, &runs, &[(15, &type_hint)], &inlay_run);
+        assert_eq!(new_text.as_ref(), "let a = foo(1);: i32");
+        assert_eq!(inlays.as_slice(), &[(15, 5)]);
+        assert_eq!(
+            new_runs.iter().map(|run| run.len).collect::<Vec<_>>(),
+            vec![8, 7, 5]
+        );
+    }
 }
@@ -0,0 +1,407 @@
+use std::collections::BTreeMap;
+
+use gpui::{Context, MouseDownEvent, Window};
+use ropey::Rope;
+use tree_sitter::Tree;
+
+use super::{
+    Fold, FoldAll, InputState, RopeExt as _, Unfold, UnfoldAll,
+    element::{FOLD_GUTTER_WIDTH, LINE_NUMBER_RIGHT_MARGIN},
+    mode::InputMode,
+};
+
+/// A range of rows that can be folded.
+///
+/// The `start_row` is keep visible as the fold header,
+/// the rows `start_row + 1..=end_row` will be hidden when folded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct FoldRange {
+    /// The 0-based row index of the fold header.
+    pub(crate) start_row: usize,
+    /// The 0-based row index of the last hidden row.
+    pub(crate) end_row: usize,
+}
+
+impl FoldRange {
+    pub(crate) fn new(start_row: usize, end_row: usize) -> Self {
+        Self { start_row, end_row }
+    }
+
+    /// Returns true if the `row` is hidden when this range is folded.
+    #[inline]
+    pub(crate) fn hides(&self, row: usize) -> bool {
+        row > self.start_row && row <= self.end_row
+    }
+
+    /// Returns true if the `row` is in this range (include the header row).
+    #[inline]
+    pub(crate) fn contains(&self, row: usize) -> bool {
+        row >= self.start_row && row <= self.end_row
+    }
+}
+
+/// Collect the fold ranges from the multi-line nodes of the syntax tree.
+///
+/// If there are multiple nodes start at the same row, the outermost one is used.
+/// The last line will keep visible if it starts with a closing bracket, e.g.: `}`.
+pub(crate) fn syntax_fold_ranges(tree: &Tree, text: &Rope) -> Vec<FoldRange> {
+    let mut folds = BTreeMap::<usize, usize>::new();
+    let mut cursor = tree.walk();
+    // Skip the root node.
+    if !cursor.goto_first_child() {
+        return vec![];
+    }
+
+    loop {
+        let node = cursor.node();
+        let start_row = node.start_position().row;
+        let mut end_row = node.end_position().row;
+
+        if end_row > start_row && !folds.contains_key(&start_row) {
+            if node.end_position().column == 0 {
+                // The node ends with a `\n`, e.g.: Markdown section.
+                end_row -= 1;
+            } else {
+                let last_line = text.slice_line(end_row).to_string();
+                if last_line.trim_start().starts_with(['}', ']', ')']) {
+                    end_row -= 1;
+                }
+            }
+
+            // Keep the trailing blank lines visible.
+            while end_row > start_row && text.slice_line(end_row).to_string().trim().is_empty() {
+                end_row -= 1;
+            }
+
+            if end_row > start_row {
+                folds.insert(start_row, end_row);
+            }
+        }
+
+        if cursor.goto_first_child() {
+            continue;
+        }
+
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return folds
+                    .into_iter()
+                    .map(|(start_row, end_row)| FoldRange::new(start_row, end_row))
+                    .collect();
+            }
+        }
+    }
+}
+
+/// Collect the fold ranges by the indentation of lines.
+///
+/// A line followed by more indented lines is foldable, blank lines are ignored.
+pub(crate) fn indent_fold_ranges(text: &Rope) -> Vec<FoldRange> {
+    let mut folds = vec![];
+    // The (row, indent) of the open folds.
+    let mut stack: Vec<(usize, usize)> = vec![];
+    let mut last_row = 0;
+
+    for row in 0..text.lines_len() {
+        let line = text.slice_line(row).to_string();
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let indent = line.len() - line.trim_start().len();
+        while let Some(&(start_row, start_indent)) = stack.last() {
+            if start_indent < indent {
+                break;
+            }
+
+            stack.pop();
+            if last_row > start_row {
+                folds.push(FoldRange::new(start_row, last_row));
+            }
+        }
+
+        stack.push((row, indent));
+        last_row = row;
+    }
+
+    for (start_row, _) in stack {
+        if last_row > start_row {
+            folds.push(FoldRange::new(start_row, last_row));
+        }
+    }
+
+    folds.sort_by_key(|fold| fold.start_row);
+    folds
+}
+
+impl InputState {
+    /// Returns true if the code folding is enabled, only for multi-line [`InputMode::CodeEditor`].
+    pub(super) fn is_foldable(&self) -> bool {
+        self.mode.is_code_editor() && self.mode.is_multi_line()
+    }
+
+    /// Update the foldable ranges if the text has been changed.
+    pub(super) fn update_fold_ranges(&mut self) {
+        if self.fold_ranges.is_some() || !self.is_foldable() {
+            return;
+        }
+
+        let InputMode::CodeEditor { highlighter, .. } = &self.mode else {
+            return;
+        };
+
+        let highlighter = highlighter.borrow();
+        let Some(highlighter) = highlighter.as_ref() else {
+            // Wait for the highlighter to be ready.
+            return;
+        };
+
+        let ranges = match highlighter.tree() {
+            Some(tree) if highlighter.language() != "text" => syntax_fold_ranges(tree, &self.text),
+            _ => indent_fold_ranges(&self.text),
+        };
+        self.fold_ranges = Some(ranges);
+    }
+
+    /// Returns the foldable range that starts at the `row`.
+    pub(super) fn fold_range_at_row(&self, row: usize) -> Option<FoldRange> {
+        let ranges = self.fold_ranges.as_ref()?;
+        ranges
+            .binary_search_by_key(&row, |fold| fold.start_row)
+            .ok()
+            .map(|ix| ranges[ix])
+    }
+
+    /// Fold or unfold the foldable range that starts at the `row`.
+    pub(super) fn toggle_fold_at_row(&mut self, row: usize, cx: &mut Context<Self>) {
+        if self.text_wrapper.unfold(row) {
+            cx.notify();
+            return;
+        }
+
+        self.update_fold_ranges();
+        if let Some(range) = self.fold_range_at_row(row) {
+            self.fold_range(range, cx);
+        }
+    }
+
+    fn fold_range(&mut self, range: FoldRange, cx: &mut Context<Self>) {
+        if !self.text_wrapper.fold(range) {
+            return;
+        }
+
+        // Move the cursor out of the folded lines.
+        let cursor_row = self.text.offset_to_point(self.cursor()).row;
+        if range.hides(cursor_row) {
+            let offset = self.text.line_end_offset(range.start_row);
+            self.move_to(offset, None, cx);
+        }
+        cx.notify();
+    }
+
+    /// Unfold the folds that hide the `offset`, to make sure it is visible.
+    pub(super) fn unfold_offset(&mut self, offset: usize) {
+        if self.text_wrapper.folds().is_empty() {
+            return;
+        }
+
+        let row = self.text.offset_to_point(offset).row;
+        self.text_wrapper.unfold_containing(row);
+    }
+
+    /// Skip the folded lines for the `offset` moved by left or right.
+    ///
+    /// If `forward` is true, move to the start of the next visible line,
+    /// otherwise move to the end of the fold header line.
+    pub(super) fn skip_folded_offset(&self, offset: usize, forward: bool) -> usize {
+        if self.text_wrapper.folds().is_empty() {
+            return offset;
+        }
+
+        let row = self.text.offset_to_point(offset).row;
+        let Some(fold) = self
+            .text_wrapper
+            .folds()
+            .iter()
+            .filter(|fold| fold.hides(row))
+            .min_by_key(|fold| fold.start_row)
+        else {
+            return offset;
+        };
+
+        if forward && fold.end_row + 1 < self.text.lines_len() {
+            self.text.line_start_offset(fold.end_row + 1)
+        } else {
+            self.text.line_end_offset(fold.start_row)
+        }
+    }
+
+    /// Handle the mouse down on the fold chevrons in the gutter, returns true if handled.
+    pub(super) fn handle_fold_gutter_click(
+        &mut self,
+        event: &MouseDownEvent,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if !self.is_foldable() || !self.mode.line_number() {
+            return false;
+        }
+        let (Some(bounds), Some(last_layout)) = (self.last_bounds, self.last_layout.as_ref())
+        else {
+            return false;
+        };
+
+        let gutter_right =
+            self.input_bounds.origin.x + last_layout.line_number_width - LINE_NUMBER_RIGHT_MARGIN;
+        let gutter_left = gutter_right - FOLD_GUTTER_WIDTH;
+        if event.position.x < gutter_left || event.position.x >= gutter_right {
+            return false;
+        }
+
+        let line_height = last_layout.line_height;
+        let y = event.position.y - bounds.origin.y;
+        let mut line_top = last_layout.visible_top;
+        let mut clicked_row = None;
+        for (row, line) in self
+            .text_wrapper
+            .lines
+            .iter()
+            .enumerate()
+            .skip(last_layout.visible_range.start)
+        {
+            let line_bottom = line_top + line.height(line_height);
+            if y >= line_top && y < line_bottom {
+                clicked_row = Some(row);
+                break;
+            }
+            if line_top > y {
+                break;
+            }
+            line_top = line_bottom;
+        }
+
+        let Some(row) = clicked_row else {
+            return false;
+        };
+        if y - line_top >= line_height {
+            // Only the first soft wrapped line has the chevron.
+            return false;
+        }
+
+        let is_folded = self.text_wrapper.folds().iter().any(|f| f.start_row == row);
+        if !is_folded && self.fold_range_at_row(row).is_none() {
+            return false;
+        }
+
+        self.toggle_fold_at_row(row, cx);
+        true
+    }
+
+    pub(super) fn fold(&mut self, _: &Fold, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.is_foldable() {
+            return;
+        }
+
+        self.update_fold_ranges();
+        let row = self.text.offset_to_point(self.cursor()).row;
+        let folds = self.text_wrapper.folds();
+        // The innermost foldable range contains the cursor, and not folded yet.
+        let range = self.fold_ranges.as_ref().and_then(|ranges| {
+            ranges
+                .iter()
+                .filter(|range| range.contains(row) && !folds.contains(range))
+                .max_by_key(|range| range.start_row)
+                .copied()
+        });
+
+        if let Some(range) = range {
+            self.fold_range(range, cx);
+        }
+    }
+
+    pub(super) fn unfold(&mut self, _: &Unfold, _: &mut Window, cx: &mut Context<Self>) {
+        let row = self.text.offset_to_point(self.cursor()).row;
+        if self.text_wrapper.unfold(row) {
+            cx.notify();
+        }
+    }
+
+    pub(super) fn fold_all(&mut self, _: &FoldAll, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.is_foldable() {
+            return;
+        }
+
+        self.update_fold_ranges();
+        let ranges = self.fold_ranges.clone().unwrap_or_default();
+        for range in ranges {
+            self.text_wrapper.fold(range);
+        }
+
+        // Move the cursor to the outermost fold header.
+        let cursor_row = self.text.offset_to_point(self.cursor()).row;
+        if let Some(fold) = self
+            .text_wrapper
+            .folds()
+            .iter()
+            .filter(|fold| fold.hides(cursor_row))
+            .min_by_key(|fold| fold.start_row)
+        {
+            let offset = self.text.line_end_offset(fold.start_row);
+            self.move_to(offset, None, cx);
+        }
+        cx.notify();
+    }
+
+    pub(super) fn unfold_all(&mut self, _: &UnfoldAll, _: &mut Window, cx: &mut Context<Self>) {
+        self.text_wrapper.unfold_all();
+        cx.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ropey::Rope;
+
+    use super::{FoldRange, indent_fold_ranges, syntax_fold_ranges};
+
+    #[test]
+    fn test_syntax_fold_ranges() {
+        let text = Rope::from("{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": {}\n}");
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_json::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(text.to_string(), None).unwrap();
+
+        assert_eq!(
+            syntax_fold_ranges(&tree, &text),
+            vec![FoldRange::new(0, 5), FoldRange::new(1, 3)]
+        );
+    }
+
+    #[test]
+    fn test_indent_fold_ranges() {
+        let text = Rope::from("a:\n  b:\n    c\n\n    d\n  e\nf\n  g\n\nh");
+        assert_eq!(
+            indent_fold_ranges(&text),
+            vec![
+                FoldRange::new(0, 5),
+                FoldRange::new(1, 4),
+                FoldRange::new(6, 7),
+            ]
+        );
+
+        assert_eq!(indent_fold_ranges(&Rope::from("")), vec![]);
+        assert_eq!(indent_fold_ranges(&Rope::from("a\nb\nc")), vec![]);
+    }
+
+    #[test]
+    fn test_fold_range() {
+        let range = FoldRange::new(2, 5);
+        assert!(!range.hides(2));
+        assert!(range.hides(3));
+        assert!(range.hides(5));
+        assert!(!range.hides(6));
+        assert!(range.contains(2));
+        assert!(!range.contains(1));
+    }
+}
@@ -1,14 +1,18 @@
+use std::{collections::HashSet, ops::Range};
+
 use gpui::{
     Bounds, Context, EntityInputHandler as _, Hsla, Path, PathBuilder, Pixels, SharedString,
     TextRun, TextStyle, Window, point, px,
 };
-use ropey::RopeSlice;
+use ropey::{Rope, RopeSlice};
+use tree_sitter::{Query, QueryCursor, StreamingIterator as _, Tree};
 
 use crate::{
     RopeExt,
+    highlighter::{BracketPair, TextProvider},
     input::{
-        Indent, IndentInline, InputState, LastLayout, Outdent, OutdentInline, element::TextElement,
-        mode::InputMode,
+        Indent, IndentInline, InputState, LastLayout, Outdent, OutdentInline, Reindent,
+        bracket::unclosed_bracket_before, element::TextElement, mode::InputMode,
     },
 };
 
@@ -51,6 +55,69 @@ impl TabSize {
 
         count
     }
+
+    /// Returns the indent text for the `count` columns.
+    pub(super) fn indent_string(&self, count: usize) -> String {
+        if self.hard_tabs {
+            let tab_size = self.tab_size.max(1);
+            format!(
+                "{}{}",
+                "\t".repeat(count / tab_size),
+                " ".repeat(count % tab_size)
+            )
+        } else {
+            " ".repeat(count)
+        }
+    }
+}
+
+/// Returns the bytes length of the leading spaces and tabs.
+fn indent_len(line: &RopeSlice) -> usize {
+    line.chars().take_while(|c| *c == ' ' || *c == '\t').count()
+}
+
+/// Returns the indent level of a line by the indents query.
+///
+/// The line is given by its start offset and the offset of the first non-whitespace char.
+/// A `@indent` node that starts before the line, and ends (or its `@end` node starts)
+/// after the `content_start` increases the level, the nodes start at the same row are
+/// counted once, e.g.: `foo({`.
+pub(crate) fn syntax_indent_level(
+    tree: &Tree,
+    query: &Query,
+    text: &Rope,
+    line_start: usize,
+    content_start: usize,
+) -> usize {
+    let Some(indent_ix) = query.capture_index_for_name("indent") else {
+        return 0;
+    };
+    let end_ix = query.capture_index_for_name("end");
+
+    let mut cursor = QueryCursor::new();
+    cursor.set_byte_range(line_start..content_start + 1);
+    let mut matches = cursor.matches(query, tree.root_node(), TextProvider(text));
+
+    let mut start_rows = HashSet::new();
+    while let Some(query_match) = matches.next() {
+        for capture in query_match.captures {
+            if capture.index != indent_ix {
+                continue;
+            }
+
+            let node = capture.node;
+            let end = query_match
+                .captures
+                .iter()
+                .find(|c| Some(c.index) == end_ix)
+                .map_or(node.end_byte(), |c| c.node.start_byte());
+            if node.start_byte() < line_start && end > content_start {
+                start_rows.insert(node.start_position().row);
+            }
+        }
+    }
+
+    start_rows.len()
 }
 
 impl InputMode {
@@ -133,6 +200,10 @@ impl TextElement {
             let Some(line_layout) = last_layout.line(ix) else {
                 continue;
             };
+            if line_layout.wrapped_lines.is_empty() {
+                // The line is hidden by a fold.
+                continue;
+            }
 
             let mut current_indents = vec![];
             if line.len() > 0 {
@@ -216,17 +287,259 @@ impl InputState {
         self
     }
 
+    /// Returns the indent level of the line by the indents query of the language.
+    ///
+    /// Returns None if the language has no indents query.
+    fn indent_level(&self, line_start: usize, content_start: usize) -> Option<usize> {
+        let InputMode::CodeEditor { highlighter, .. } = &self.mode else {
+            return None;
+        };
+
+        let highlighter = highlighter.borrow();
+        let highlighter = highlighter.as_ref()?;
+        let (tree, query) = (highlighter.tree()?, highlighter.indents_query()?);
+        Some(syntax_indent_level(
+            tree,
+            query,
+            &self.text,
+            line_start,
+            content_start,
+        ))
+    }
+
+    /// Returns true if the text before the `offset` in the line ends with an opening bracket.
+    fn is_after_opening_bracket(&self, offset: usize) -> bool {
+        let row = self.text.offset_to_point(offset).row;
+        let before = self
+            .text
+            .slice(self.text.line_start_offset(row)..offset)
+            .to_string();
+        let before = before.trim_end();
+
+        self.bracket_pairs()
+            .iter()
+            .any(|pair| !pair.is_symmetric() && before.ends_with(pair.start.as_ref()))
+    }
+
+    /// Returns the indent of the new line that inserted at the `offset`.
+    ///
+    /// The indent is relative to the current line by the indents query, and always
+    /// increased after an opening bracket.
+    pub(super) fn indent_for_new_line(&self, offset: usize) -> String {
+        let tab = self.mode.tab_size();
+        let row = self.text.offset_to_point(offset).row;
+        let line_start = self.text.line_start_offset(row);
+        let line = self.text.slice_line(row);
+        let content_start = line_start + indent_len(&line);
+        if offset <= content_start {
+            return "".into();
+        }
+
+        let after = self.text.slice(offset..self.text.line_end_offset(row));
+        let new_content_start = offset + indent_len(&after);
+        let delta = match (
+            self.indent_level(line_start, content_start),
+            self.indent_level(offset, new_content_start),
+        ) {
+            (Some(current), Some(next)) => next as isize - current as isize,
+            _ => 0,
+        };
+        // The syntax tree may be broken by the unclosed bracket, e.g.: `fn main() {`.
+        let delta = delta.max(self.is_after_opening_bracket(offset) as isize);
+
+        let count = tab.indent_count(&line) as isize + delta * tab.tab_size as isize;
+        tab.indent_string(count.max(0) as usize)
+    }
+
+    /// Insert a new line with the auto indent for [`InputMode::CodeEditor`].
+    ///
+    /// If the cursor is between an empty bracket pair, e.g.: `{|}`, the closing bracket
+    /// will be moved to the next line, and the cursor is placed in the indented middle line.
+    pub(super) fn insert_new_line(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let tab = self.mode.tab_size();
+        let range: Range<usize> = self.selected_range.into();
+        let offset = range.start;
+        let end_row = self.text.offset_to_point(range.end).row;
+        let after = self
+            .text
+            .slice(range.end..self.text.line_end_offset(end_row));
+        // Remove the whitespaces after the cursor, they are replaced by the indent.
+        let end = range.end + indent_len(&after);
+
+        let closing = self.bracket_pairs().into_iter().find(|pair| {
+            !pair.is_symmetric()
+                && after
+                    .to_string()
+                    .trim_start()
+                    .starts_with(pair.end.as_ref())
+                && self.is_after_opening_bracket(offset)
+        });
+
+        let indent = self.indent_for_new_line(offset);
+        if closing.is_some() {
+            let row = self.text.offset_to_point(offset).row;
+            let current = tab.indent_count(&self.text.slice_line(row));
+            let middle = tab.indent_string(current + tab.tab_size);
+            let new_text = format!("\n{}\n{}", middle, tab.indent_string(current));
+            self.replace_text_in_range_silent(
+                Some(self.range_to_utf16(&(offset..end))),
+                &new_text,
+                window,
+                cx,
+            );
+            let cursor = offset + 1 + middle.len();
+            self.selected_range = (cursor..cursor).into();
+            return;
+        }
+
+        self.replace_text_in_range_silent(
+            Some(self.range_to_utf16(&(offset..end))),
+            &format!("\n{}", indent),
+            window,
+            cx,
+        );
+    }
+
+    /// Insert the closing bracket of the `pair`, and outdent the line to the same indent
+    /// as the line of the opening bracket, if there is only whitespace before the cursor.
+    ///
+    /// Returns false if the line is not changed.
+    pub(super) fn insert_closing_bracket(
+        &mut self,
+        pair: &BracketPair,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let tab = self.mode.tab_size();
+        let offset = self.cursor();
+        let line_start = self.start_of_line();
+        let before = self.text.slice(line_start..offset).to_string();
+        if before.is_empty() || !before.chars().all(|c| c == ' ' || c == '\t') {
+            return false;
+        }
+
+        let Some(opening) = unclosed_bracket_before(&self.text, offset, pair) else {
+            return false;
+        };
+        let opening_row = self.text.offset_to_point(opening.start).row;
+        let indent = tab.indent_string(tab.indent_count(&self.text.slice_line(opening_row)));
+        if before == indent {
+            return false;
+        }
+
+        self.replace_text_in_range_silent(
+            Some(self.range_to_utf16(&(line_start..offset))),
+            &format!("{}{}", indent, pair.end),
+            window,
+            cx,
+        );
+        true
+    }
+
+    pub(super) fn reindent(&mut self, _: &Reindent, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.mode.is_code_editor() || !self.mode.is_multi_line() {
+            return;
+        }
+
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            this.reindent_lines(window, cx);
+        });
+    }
+
+    /// Reindent the lines of the selection by the indents query of the language.
+    ///
+    /// The first line is relative to the previous non-blank line, and the blank lines are cleared.
+    fn reindent_lines(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.indent_level(0, 0).is_none() {
+            return;
+        }
+
+        let tab = self.mode.tab_size();
+        let is_empty = self.selected_range.is_empty();
+        let rows = self.selected_rows();
+
+        // The (indent, level) of the previous non-blank line.
+        let (mut indent, mut level) = (0..rows.start)
+            .rev()
+            .find_map(|row| {
+                let line = self.text.slice_line(row);
+                let len = indent_len(&line);
+                if len == line.len() {
+                    return None;
+                }
+
+                let line_start = self.text.line_start_offset(row);
+                let level = self.indent_level(line_start, line_start + len)?;
+                Some((tab.indent_count(&line), level))
+            })
+            .unwrap_or((0, 0));
+
+        let mut edits = vec![];
+        for row in rows.clone() {
+            let line = self.text.slice_line(row);
+            let line_start = self.text.line_start_offset(row);
+            let len = indent_len(&line);
+            if len == line.len() {
+                if len > 0 {
+                    edits.push((line_start..line_start + len, String::new()));
+                }
+                continue;
+            }
+
+            let row_level = self
+                .indent_level(line_start, line_start + len)
+                .unwrap_or(level);
+            let count =
+                indent as isize + (row_level as isize - level as isize) * tab.tab_size as isize;
+            indent = count.max(0) as usize;
+            level = row_level;
+
+            let new_indent = tab.indent_string(indent);
+            if line.slice(..len) != new_indent.as_str() {
+                edits.push((line_start..line_start + len, new_indent));
+            }
+        }
+
+        let mut cursor = self.cursor();
+        for (edit_range, new_indent) in edits.iter().rev() {
+            if cursor >= edit_range.end {
+                cursor = cursor + new_indent.len() - edit_range.len();
+            } else if cursor > edit_range.start {
+                cursor = edit_range.start + new_indent.len();
+            }
+
+            self.replace_text_in_range_silent(
+                Some(self.range_to_utf16(edit_range)),
+                new_indent,
+                window,
+                cx,
+            );
+        }
+
+        if is_empty {
+            self.selected_range = (cursor..cursor).into();
+        } else {
+            self.selected_range = (self.text.line_start_offset(rows.start)
+                ..self.text.line_end_offset(rows.end - 1))
+                .into();
+        }
+    }
+
     pub(super) fn indent_inline(
         &mut self,
         _: &IndentInline,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        self.indent(false, window, cx);
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            this.indent(false, window, cx);
+        });
     }
 
     pub(super) fn indent_block(&mut self, _: &Indent, window: &mut Window, cx: &mut Context<Self>) {
-        self.indent(true, window, cx);
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            this.indent(true, window, cx);
+        });
     }
 
     pub(super) fn outdent_inline(
@@ -235,7 +548,9 @@ impl InputState {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        self.outdent(false, window, cx);
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            this.outdent(false, window, cx);
+        });
     }
 
     pub(super) fn outdent_block(
@@ -244,7 +559,9 @@ impl InputState {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        self.outdent(true, window, cx);
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            this.outdent(true, window, cx);
+        });
     }
 
     pub(super) fn indent(&mut self, block: bool, window: &mut Window, cx: &mut Context<Self>) {
@@ -382,9 +699,10 @@ impl InputState {
 
 #[cfg(test)]
 mod tests {
-    use ropey::RopeSlice;
+    use ropey::{Rope, RopeSlice};
 
-    use super::TabSize;
+    use super::{TabSize, indent_len, syntax_indent_level};
+    use crate::input::RopeExt as _;
 
     #[test]
     fn test_tab_size() {
@@ -425,4 +743,51 @@ mod tests {
         assert_eq!(tab.indent_count(&RopeSlice::from(" \t abc  ")), 6);
         assert_eq!(tab.indent_count(&RopeSlice::from("abc")), 0);
     }
+
+    #[test]
+    fn test_tab_size_indent_string() {
+        let tab = TabSize {
+            tab_size: 4,
+            hard_tabs: false,
+        };
+        assert_eq!(tab.indent_string(0), "");
+        assert_eq!(tab.indent_string(6), "      ");
+
+        let tab = TabSize {
+            tab_size: 4,
+            hard_tabs: true,
+        };
+        assert_eq!(tab.indent_string(4), "\t");
+        assert_eq!(tab.indent_string(10), "\t\t  ");
+    }
+
+    #[test]
+    fn test_syntax_indent_level() {
+        let text = Rope::from("{\n\"a\": [\n1,\n  2\n],\n\"b\": {}\n}");
+        let mut parser = tree_sitter::Parser::new();
+        let language = tree_sitter_json::LANGUAGE.into();
+        parser.set_language(&language).unwrap();
+        let tree = parser.parse(text.to_string(), None).unwrap();
+        let query = tree_sitter::Query::new(
+            &language,
+            include_str!("../highlighter/languages/json/indents.scm"),
+        )
+        .unwrap();
+
+        let levels = (0..text.lines_len())
+            .map(|row| {
+                let line_start = text.line_start_offset(row);
+                let content_start = line_start + indent_len(&text.slice_line(row));
+                syntax_indent_level(&tree, &query, &text, line_start, content_start)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(levels, vec![0, 1, 2, 2, 1, 1, 0]);
+
+        // A new line inserted between `[` and `]`.
+        let text = Rope::from("[1, [], 2]");
+        let tree = parser.parse(text.to_string(), None).unwrap();
+        assert_eq!(syntax_indent_level(&tree, &query, &text, 1, 1), 1);
+        assert_eq!(syntax_indent_level(&tree, &query, &text, 5, 5), 1);
+        assert_eq!(syntax_indent_level(&tree, &query, &text, 9, 9), 0);
+    }
 }
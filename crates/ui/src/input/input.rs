@@ -15,7 +15,7 @@ use crate::{IconName, Size};
 use crate::{Selectable, StyledExt, h_flex};
 use crate::{Sizable, StyleSized};
 
-use super::InputState;
+use super::{InputState, minimap::Minimap};
 
 /// A text input element bind to an [`InputState`].
 #[derive(IntoElement)]
@@ -193,42 +193,60 @@ impl Input {
                 .unwrap_or(px(0.)),
         };
 
+        let has_minimap = state.mode.has_minimap();
+        let editor = div().flex_1().child(input_state.clone()).map(|this| {
+            if let Some(last_layout) = state.last_layout.as_ref() {
+                let left = if last_layout.line_number_width.is_zero() {
+                    px(0.)
+                } else {
+                    // Align left edge to the Line number.
+                    paddings.left + last_layout.line_number_width - LINE_NUMBER_RIGHT_MARGIN
+                };
+                // The scrollbar is on the left of the minimap.
+                let right = if has_minimap { px(0.) } else { -paddings.right };
+
+                let scroll_size = gpui::Size {
+                    width: state.scroll_size.width - left - right + RIGHT_MARGIN,
+                    height: state.scroll_size.height,
+                };
+
+                let scrollbar = if !state.soft_wrap {
+                    Scrollbar::new(&state.scroll_handle)
+                } else {
+                    Scrollbar::vertical(&state.scroll_handle)
+                };
+
+                this.relative().child(
+                    div()
+                        .absolute()
+                        .top(-paddings.top)
+                        .left(left)
+                        .right(right)
+                        .bottom(-paddings.bottom)
+                        .child(scrollbar.scroll_size(scroll_size)),
+                )
+            } else {
+                this
+            }
+        });
+
         v_flex()
             .size_full()
             .children(state.search_panel.clone())
-            .child(div().flex_1().child(input_state.clone()).map(|this| {
-                if let Some(last_layout) = state.last_layout.as_ref() {
-                    let left = if last_layout.line_number_width.is_zero() {
-                        px(0.)
-                    } else {
-                        // Align left edge to the Line number.
-                        paddings.left + last_layout.line_number_width - LINE_NUMBER_RIGHT_MARGIN
-                    };
-
-                    let scroll_size = gpui::Size {
-                        width: state.scroll_size.width - left + paddings.right + RIGHT_MARGIN,
-                        height: state.scroll_size.height,
-                    };
-
-                    let scrollbar = if !state.soft_wrap {
-                        Scrollbar::new(&state.scroll_handle)
-                    } else {
-                        Scrollbar::vertical(&state.scroll_handle)
-                    };
-
-                    this.relative().child(
-                        div()
-                            .absolute()
-                            .top(-paddings.top)
-                            .left(left)
-                            .right(-paddings.right)
-                            .bottom(-paddings.bottom)
-                            .child(scrollbar.scroll_size(scroll_size)),
+            .map(|this| {
+                if has_minimap {
+                    this.child(
+                        h_flex()
+                            .flex_1()
+                            .w_full()
+                            .items_start()
+                            .child(editor.h_full())
+                            .child(Minimap::new(input_state.clone())),
                     )
                 } else {
-                    this
+                    this.child(editor)
                 }
-            }))
+            })
     }
 }
 
@@ -301,6 +319,29 @@ impl RenderOnce for Input {
                     .on_action(
                         window.listener_for(&self.state, InputState::on_action_toggle_code_actions),
                     )
+                    .on_action(window.listener_for(&self.state, InputState::on_action_rename))
+                    .on_action(window.listener_for(&self.state, InputState::on_action_format))
+                    .on_action(
+                        window.listener_for(&self.state, InputState::on_action_format_selection),
+                    )
+                    .on_action(
+                        window.listener_for(&self.state, InputState::on_action_show_signature_help),
+                    )
+                    .when(state.mode.is_multi_line(), |this| {
+                        this.on_action(window.listener_for(&self.state, InputState::revert_hunk))
+                            .on_action(window.listener_for(&self.state, InputState::reindent))
+                            .on_action(window.listener_for(&self.state, InputState::move_line_up))
+                            .on_action(window.listener_for(&self.state, InputState::move_line_down))
+                            .on_action(window.listener_for(&self.state, InputState::duplicate_line))
+                            .on_action(window.listener_for(&self.state, InputState::join_lines))
+                            .on_action(window.listener_for(&self.state, InputState::delete_line))
+                            .on_action(
+                                window.listener_for(&self.state, InputState::toggle_line_comment),
+                            )
+                            .on_action(
+                                window.listener_for(&self.state, InputState::toggle_block_comment),
+                            )
+                    })
             })
             .on_action(window.listener_for(&self.state, InputState::left))
             .on_action(window.listener_for(&self.state, InputState::right))
@@ -313,6 +354,18 @@ impl RenderOnce for Input {
                     .on_action(window.listener_for(&self.state, InputState::select_down))
                     .on_action(window.listener_for(&self.state, InputState::page_up))
                     .on_action(window.listener_for(&self.state, InputState::page_down))
+                    .on_action(window.listener_for(&self.state, InputState::add_cursor_above))
+                    .on_action(window.listener_for(&self.state, InputState::add_cursor_below))
+                    .on_action(window.listener_for(&self.state, InputState::select_next_occurrence))
+                    .on_action(window.listener_for(&self.state, InputState::fold))
+                    .on_action(window.listener_for(&self.state, InputState::unfold))
+                    .on_action(window.listener_for(&self.state, InputState::fold_all))
+                    .on_action(window.listener_for(&self.state, InputState::unfold_all))
+                    .on_action(window.listener_for(&self.state, InputState::go_to_next_hunk))
+                    .on_action(window.listener_for(&self.state, InputState::go_to_previous_hunk))
+                    .on_action(
+                        window.listener_for(&self.state, InputState::move_to_matching_bracket),
+                    )
                     .on_action(
                         window.listener_for(&self.state, InputState::on_action_go_to_definition),
                     )
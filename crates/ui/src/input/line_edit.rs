@@ -0,0 +1,463 @@
+use std::ops::Range;
+
+use gpui::{Context, Window};
+use sum_tree::Bias;
+
+use super::{
+    DeleteLine, DuplicateLine, InputState, JoinLines, MoveLineDown, MoveLineUp, RopeExt as _,
+    ToggleBlockComment, ToggleLineComment, mode::InputMode,
+};
+use crate::highlighter::LanguageRegistry;
+
+/// A replacement of the range in the original text.
+type Edit = (Range<usize>, String);
+
+/// Map the `offset` in the original text to the text after the `edits` (sorted and not overlapped).
+///
+/// If `insert_before` is true, the offset at an insertion is moved after the inserted text.
+fn map_offset(offset: usize, edits: &[Edit], insert_before: bool) -> usize {
+    let mut new_offset = offset as isize;
+    for (range, new_text) in edits {
+        let shifts = if range.is_empty() {
+            offset > range.start || (offset == range.start && insert_before)
+        } else {
+            offset >= range.end
+        };
+
+        if shifts {
+            new_offset += new_text.len() as isize - range.len() as isize;
+        } else if offset > range.start {
+            // Inside the replaced range.
+            let delta = new_offset - offset as isize;
+            return (range.start as isize + delta) as usize
+                + new_text.len().min(offset - range.start);
+        }
+    }
+
+    new_offset.max(0) as usize
+}
+
+/// Returns the bytes length of the leading spaces and tabs.
+fn leading_whitespace_len(line: &str) -> usize {
+    line.len() - line.trim_start_matches([' ', '\t']).len()
+}
+
+impl InputState {
+    /// Returns the rows of the selection (end exclusive).
+    ///
+    /// The last row is excluded if the selection ends at the start of it.
+    pub(super) fn selected_rows(&self) -> Range<usize> {
+        let range: Range<usize> = self.selected_range.into();
+        let start_row = self.text.offset_to_point(range.start).row;
+        let mut end_row = self.text.offset_to_point(range.end).row;
+        if end_row > start_row && range.end == self.text.line_start_offset(end_row) {
+            end_row -= 1;
+        }
+
+        start_row..end_row + 1
+    }
+
+    /// Apply the `edits` (sorted and not overlapped) as one change, and keep the selection.
+    fn apply_edits(&mut self, edits: Vec<Edit>, window: &mut Window, cx: &mut Context<Self>) {
+        let (Some(first), Some(last)) = (edits.first(), edits.last()) else {
+            return;
+        };
+
+        let range: Range<usize> = self.selected_range.into();
+        let edit_range = first.0.start..last.0.end;
+        let mut new_text = String::new();
+        let mut offset = edit_range.start;
+        for (range, text) in edits.iter() {
+            new_text.push_str(&self.text.slice(offset..range.start).to_string());
+            new_text.push_str(text);
+            offset = range.end;
+        }
+
+        self.replace_text_in_range_silent(
+            Some(self.range_to_utf16(&edit_range)),
+            &new_text,
+            window,
+            cx,
+        );
+
+        let start = map_offset(range.start, &edits, true);
+        let end = map_offset(range.end, &edits, range.is_empty());
+        self.selected_range = (start..end.max(start)).into();
+    }
+
+    /// Replace the `range` with `new_text`, and move the selection by `delta`.
+    fn replace_lines(
+        &mut self,
+        range: Range<usize>,
+        new_text: &str,
+        delta: isize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let selected_range: Range<usize> = self.selected_range.into();
+        self.replace_text_in_range_silent(Some(self.range_to_utf16(&range)), new_text, window, cx);
+
+        let start = (selected_range.start as isize + delta).max(0) as usize;
+        let end = (selected_range.end as isize + delta).max(0) as usize;
+        self.selected_range = (start..end).into();
+    }
+
+    pub(super) fn move_line_up(
+        &mut self,
+        _: &MoveLineUp,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            this.move_lines(true, window, cx);
+        });
+    }
+
+    pub(super) fn move_line_down(
+        &mut self,
+        _: &MoveLineDown,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            this.move_lines(false, window, cx);
+        });
+    }
+
+    /// Swap the selected lines with the line above (or below).
+    fn move_lines(&mut self, up: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let rows = self.selected_rows();
+        let lines = self.text.slice_lines(rows.clone()).to_string();
+
+        if up {
+            let Some(prev_row) = rows.start.checked_sub(1) else {
+                return;
+            };
+
+            let prev_line = self.text.slice_line(prev_row).to_string();
+            let range =
+                self.text.line_start_offset(prev_row)..self.text.line_end_offset(rows.end - 1);
+            let new_text = format!("{}\n{}", lines, prev_line);
+            self.replace_lines(
+                range,
+                &new_text,
+                -(prev_line.len() as isize + 1),
+                window,
+                cx,
+            );
+        } else {
+            let next_row = rows.end;
+            if next_row >= self.text.lines_len() {
+                return;
+            }
+
+            let next_line = self.text.slice_line(next_row).to_string();
+            let range =
+                self.text.line_start_offset(rows.start)..self.text.line_end_offset(next_row);
+            let new_text = format!("{}\n{}", next_line, lines);
+            self.replace_lines(range, &new_text, next_line.len() as isize + 1, window, cx);
+        }
+    }
+
+    /// Duplicate the selected lines, and move the selection to the copied lines below.
+    pub(super) fn duplicate_line(
+        &mut self,
+        _: &DuplicateLine,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            let rows = this.selected_rows();
+            let lines = this.text.slice_lines(rows.clone()).to_string();
+            let offset = this.text.line_start_offset(rows.start);
+            let new_text = format!("{}\n", lines);
+            this.replace_lines(
+                offset..offset,
+                &new_text,
+                new_text.len() as isize,
+                window,
+                cx,
+            );
+        });
+    }
+
+    /// Delete the selected lines, and keep the cursor column in the next line.
+    pub(super) fn delete_line(
+        &mut self,
+        _: &DeleteLine,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            let rows = this.selected_rows();
+            let column = this.text.offset_to_point(this.cursor()).column;
+
+            let (range, row) = if rows.end < this.text.lines_len() {
+                (
+                    this.text.line_start_offset(rows.start)..this.text.line_start_offset(rows.end),
+                    rows.start,
+                )
+            } else if rows.start > 0 {
+                (
+                    this.text.line_end_offset(rows.start - 1)..this.text.len(),
+                    rows.start - 1,
+                )
+            } else {
+                (0..this.text.len(), 0)
+            };
+
+            this.replace_text_in_range_silent(Some(this.range_to_utf16(&range)), "", window, cx);
+            let line_start = this.text.line_start_offset(row);
+            let offset = line_start + column.min(this.text.line_len(row));
+            let offset = this.text.clip_offset(offset, Bias::Left);
+            this.selected_range = (offset..offset).into();
+        });
+    }
+
+    /// Join the selected lines into one line, or join the next line if only one line selected.
+    pub(super) fn join_lines(
+        &mut self,
+        _: &JoinLines,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            let mut rows = this.selected_rows();
+            if rows.len() == 1 {
+                rows.end += 1;
+            }
+            rows.end = rows.end.min(this.text.lines_len());
+
+            let mut edits = vec![];
+            for row in rows.start + 1..rows.end {
+                let prev_line = this.text.slice_line(row - 1).to_string();
+                let line = this.text.slice_line(row).to_string();
+                let prev_trimmed = prev_line.trim_end();
+
+                let start = this.text.line_start_offset(row - 1) + prev_trimmed.len();
+                let end = this.text.line_start_offset(row) + leading_whitespace_len(&line);
+                let separator = if prev_trimmed.is_empty() || line.trim().is_empty() {
+                    ""
+                } else {
+                    " "
+                };
+                edits.push((start..end, separator.to_string()));
+            }
+
+            let join_offset = edits.first().map(|(range, _)| range.start);
+            let is_empty = this.selected_range.is_empty();
+            this.apply_edits(edits, window, cx);
+            if let Some(offset) = join_offset.filter(|_| is_empty) {
+                this.selected_range = (offset..offset).into();
+            }
+        });
+    }
+
+    /// Returns the (line, block) comment tokens of the language, only for [`InputMode::CodeEditor`].
+    fn comment_tokens(&self) -> (Option<String>, Option<(String, String)>) {
+        let InputMode::CodeEditor { language, .. } = &self.mode else {
+            return (None, None);
+        };
+
+        LanguageRegistry::singleton()
+            .language(language)
+            .map(|config| {
+                (
+                    config.line_comment.map(|token| token.to_string()),
+                    config
+                        .block_comment
+                        .map(|(start, end)| (start.to_string(), end.to_string())),
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    /// Toggle the line comment of the selected lines.
+    ///
+    /// If the language has no line comment, each line is wrapped with the block comment.
+    pub(super) fn toggle_line_comment(
+        &mut self,
+        _: &ToggleLineComment,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (line_comment, block_comment) = self.comment_tokens();
+        if line_comment.is_none() && block_comment.is_none() {
+            cx.propagate();
+            return;
+        }
+
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            let rows = this.selected_rows();
+            // The (line start, line) of the rows, the blank lines are skipped if there are others.
+            let mut lines = rows
+                .clone()
+                .map(|row| {
+                    (
+                        this.text.line_start_offset(row),
+                        this.text.slice_line(row).to_string(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            if lines.iter().any(|(_, line)| !line.trim().is_empty()) {
+                lines.retain(|(_, line)| !line.trim().is_empty());
+            }
+
+            let min_indent = lines
+                .iter()
+                .map(|(_, line)| leading_whitespace_len(line))
+                .min()
+                .unwrap_or_default();
+
+            let mut edits = vec![];
+            if let Some(token) = line_comment.as_ref() {
+                let commented = lines
+                    .iter()
+                    .all(|(_, line)| line.trim_start().starts_with(token.as_str()));
+
+                for (line_start, line) in lines.iter() {
+                    if commented {
+                        let start = line_start + leading_whitespace_len(line);
+                        let mut end = start + token.len();
+                        if line[end - line_start..].starts_with(' ') {
+                            end += 1;
+                        }
+                        edits.push((start..end, String::new()));
+                    } else {
+                        let offset = line_start + min_indent;
+                        edits.push((offset..offset, format!("{} ", token)));
+                    }
+                }
+            } else if let Some((start_token, end_token)) = block_comment.as_ref() {
+                let commented = lines.iter().all(|(_, line)| {
+                    let content = line.trim();
+                    content.len() >= start_token.len() + end_token.len()
+                        && content.starts_with(start_token.as_str())
+                        && content.ends_with(end_token.as_str())
+                });
+
+                for (line_start, line) in lines.iter() {
+                    let content_start = line_start + leading_whitespace_len(line);
+                    let content_end = (line_start + line.trim_end().len()).max(content_start);
+                    if commented {
+                        let content = line.trim();
+                        let inner = &content[start_token.len()..content.len() - end_token.len()];
+                        let start_len = start_token.len() + inner.starts_with(' ') as usize;
+                        let end_len = end_token.len() + inner.ends_with(' ') as usize;
+                        if start_len + end_len > content.len() {
+                            edits.push((content_start..content_end, String::new()));
+                        } else {
+                            edits.push((content_start..content_start + start_len, String::new()));
+                            edits.push((content_end - end_len..content_end, String::new()));
+                        }
+                    } else {
+                        let offset = (line_start + min_indent).min(content_end);
+                        edits.push((offset..offset, format!("{} ", start_token)));
+                        edits.push((content_end..content_end, format!(" {}", end_token)));
+                    }
+                }
+            }
+
+            this.apply_edits(edits, window, cx);
+        });
+    }
+
+    /// Toggle the block comment of the selection, or the current line if no selection.
+    pub(super) fn toggle_block_comment(
+        &mut self,
+        _: &ToggleBlockComment,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (_, block_comment) = self.comment_tokens();
+        let Some((start_token, end_token)) = block_comment else {
+            cx.propagate();
+            return;
+        };
+
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            let mut range: Range<usize> = this.selected_range.into();
+            if range.is_empty() {
+                let row = this.text.offset_to_point(range.start).row;
+                let line_start = this.text.line_start_offset(row);
+                let line = this.text.slice_line(row).to_string();
+                range =
+                    line_start + leading_whitespace_len(&line)..line_start + line.trim_end().len();
+                if range.is_empty() {
+                    // Insert an empty comment on the blank line, with the cursor inside.
+                    let offset = this.cursor();
+                    let new_text = format!("{}  {}", start_token, end_token);
+                    this.replace_text_in_range_silent(
+                        Some(this.range_to_utf16(&(offset..offset))),
+                        &new_text,
+                        window,
+                        cx,
+                    );
+                    let cursor = offset + start_token.len() + 1;
+                    this.selected_range = (cursor..cursor).into();
+                    return;
+                }
+            }
+
+            let text = this.text.slice(range.clone()).to_string();
+            let content = text.trim();
+            let content_start = range.start + (text.len() - text.trim_start().len());
+            let content_end = range.start + text.trim_end().len();
+
+            let mut edits = vec![];
+            if content.len() >= start_token.len() + end_token.len()
+                && content.starts_with(start_token.as_str())
+                && content.ends_with(end_token.as_str())
+            {
+                let inner = &content[start_token.len()..content.len() - end_token.len()];
+                let start_len = start_token.len() + inner.starts_with(' ') as usize;
+                let end_len = end_token.len() + inner.ends_with(' ') as usize;
+                if start_len + end_len > content.len() {
+                    edits.push((content_start..content_end, String::new()));
+                } else {
+                    edits.push((content_start..content_start + start_len, String::new()));
+                    edits.push((content_end - end_len..content_end, String::new()));
+                }
+            } else {
+                edits.push((range.start..range.start, format!("{} ", start_token)));
+                edits.push((range.end..range.end, format!(" {}", end_token)));
+            }
+
+            this.apply_edits(edits, window, cx);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{leading_whitespace_len, map_offset};
+
+    #[test]
+    fn test_map_offset() {
+        let edits = vec![
+            (2..2, "// ".to_string()),
+            (5..8, "a".to_string()),
+            (10..10, "!".to_string()),
+        ];
+
+        assert_eq!(map_offset(0, &edits, true), 0);
+        assert_eq!(map_offset(2, &edits, true), 5);
+        assert_eq!(map_offset(2, &edits, false), 2);
+        assert_eq!(map_offset(4, &edits, false), 7);
+        // Inside the replaced range.
+        assert_eq!(map_offset(6, &edits, false), 9);
+        assert_eq!(map_offset(7, &edits, false), 9);
+        assert_eq!(map_offset(8, &edits, false), 9);
+        assert_eq!(map_offset(10, &edits, false), 11);
+        assert_eq!(map_offset(10, &edits, true), 12);
+        assert_eq!(map_offset(12, &edits, true), 14);
+    }
+
+    #[test]
+    fn test_leading_whitespace_len() {
+        assert_eq!(leading_whitespace_len("foo"), 0);
+        assert_eq!(leading_whitespace_len("  foo"), 2);
+        assert_eq!(leading_whitespace_len("\t foo "), 2);
+        assert_eq!(leading_whitespace_len("   "), 3);
+    }
+}
@@ -0,0 +1,197 @@
+use anyhow::Result;
+use gpui::{App, Context, Task, Window};
+use ropey::Rope;
+use std::ops::Range;
+
+use crate::input::{Format, FormatSelection, InputState};
+
+/// Document formatting provider
+///
+/// https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocument_formatting
+pub trait DocumentFormattingProvider {
+    /// textDocument/formatting
+    ///
+    /// https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocument_formatting
+    fn format_document(
+        &self,
+        _text: &Rope,
+        _options: &lsp_types::FormattingOptions,
+        _window: &mut Window,
+        _cx: &mut App,
+    ) -> Task<Result<Vec<lsp_types::TextEdit>>>;
+
+    /// textDocument/rangeFormatting
+    ///
+    /// The `range` is the byte range of the text to format.
+    ///
+    /// Default to return no edits, that means range formatting is not supported.
+    ///
+    /// https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocument_rangeFormatting
+    fn format_range(
+        &self,
+        _text: &Rope,
+        _range: Range<usize>,
+        _options: &lsp_types::FormattingOptions,
+        _window: &mut Window,
+        _cx: &mut App,
+    ) -> Task<Result<Vec<lsp_types::TextEdit>>> {
+        Task::ready(Ok(vec![]))
+    }
+}
+
+impl InputState {
+    /// Returns the [`lsp_types::FormattingOptions`] of the current tab size.
+    fn formatting_options(&self) -> lsp_types::FormattingOptions {
+        let tab_size = self.mode.tab_size();
+
+        lsp_types::FormattingOptions {
+            tab_size: tab_size.tab_size as u32,
+            insert_spaces: !tab_size.hard_tabs,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn on_action_format(
+        &mut self,
+        _: &Format,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.lsp.formatting_provider.is_none() {
+            cx.propagate();
+            return;
+        }
+
+        self.format(window, cx);
+    }
+
+    pub(crate) fn on_action_format_selection(
+        &mut self,
+        _: &FormatSelection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.lsp.formatting_provider.is_none() {
+            cx.propagate();
+            return;
+        }
+
+        let range = self.selected_range.start..self.selected_range.end;
+        if range.is_empty() {
+            self.format(window, cx);
+        } else {
+            self.format_range(range, window, cx);
+        }
+    }
+
+    /// Format the whole document by using the [`DocumentFormattingProvider`].
+    ///
+    /// All the edits will be applied as one undo step.
+    pub fn format(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(provider) = self.lsp.formatting_provider.clone() else {
+            return;
+        };
+
+        let options = self.formatting_options();
+        let task = provider.format_document(&self.text, &options, window, cx);
+        self.apply_formatting_task(task, window, cx);
+    }
+
+    /// Format the given byte range by using the [`DocumentFormattingProvider`].
+    ///
+    /// All the edits will be applied as one undo step.
+    pub fn format_range(
+        &mut self,
+        range: Range<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(provider) = self.lsp.formatting_provider.clone() else {
+            return;
+        };
+
+        let options = self.formatting_options();
+        let task = provider.format_range(&self.text, range, &options, window, cx);
+        self.apply_formatting_task(task, window, cx);
+    }
+
+    fn apply_formatting_task(
+        &mut self,
+        task: Task<Result<Vec<lsp_types::TextEdit>>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let editor = cx.entity();
+        // Skip the edits if the text has changed before the formatting is done.
+        let revision = self.revision;
+        self.lsp._formatting_task = cx.spawn_in(window, async move |_, cx| {
+            let text_edits = task.await?;
+
+            _ = editor.update_in(cx, |editor, window, cx| {
+                if editor.revision != revision {
+                    return;
+                }
+
+                editor.apply_lsp_edits(&text_edits, window, cx);
+            });
+
+            Ok(())
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use gpui::{AppContext as _, TestAppContext};
+    use lsp_types::{Position, TextEdit};
+
+    use super::*;
+
+    struct TestFormattingProvider;
+
+    impl DocumentFormattingProvider for TestFormattingProvider {
+        fn format_document(
+            &self,
+            _: &Rope,
+            _: &lsp_types::FormattingOptions,
+            _: &mut Window,
+            _: &mut App,
+        ) -> Task<Result<Vec<TextEdit>>> {
+            Task::ready(Ok(vec![TextEdit {
+                range: lsp_types::Range::new(Position::new(0, 2), Position::new(0, 4)),
+                new_text: " ".into(),
+            }]))
+        }
+    }
+
+    #[gpui::test]
+    fn test_format(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let state = cx.update(|window, cx| {
+            cx.new(|cx| {
+                let mut state = InputState::new(window, cx)
+                    .multi_line(true)
+                    .default_value("fn  main() {}");
+                state.lsp.formatting_provider = Some(Rc::new(TestFormattingProvider));
+                state
+            })
+        });
+
+        cx.update(|window, cx| state.update(cx, |state, cx| state.format(window, cx)));
+        cx.run_until_parked();
+        cx.update(|_, cx| assert_eq!(state.read(cx).value(), "fn main() {}"));
+
+        // Edit the text between the request and the response, the edits should be dropped.
+        cx.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                state.set_value("fn  main() {}", window, cx);
+                state.format(window, cx);
+                state.replace_text_in_range_silent(Some(0..0), "// ", window, cx);
+            })
+        });
+        cx.run_until_parked();
+        cx.update(|_, cx| assert_eq!(state.read(cx).value(), "// fn  main() {}"));
+    }
+}
@@ -0,0 +1,139 @@
+use std::ops::Range;
+
+use anyhow::Result;
+use gpui::{App, Context, SharedString, Task, Window};
+use ropey::Rope;
+
+use crate::input::{InputState, Lsp, RopeExt};
+
+pub trait InlayHintProvider {
+    /// Fetches inlay hints of the whole document.
+    ///
+    /// textDocument/inlayHint
+    ///
+    /// https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocument_inlayHint
+    fn inlay_hints(
+        &self,
+        _text: &Rope,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Vec<lsp_types::InlayHint>>>;
+}
+
+/// Get the display label of the inlay hint, with the paddings.
+fn inlay_hint_label(hint: &lsp_types::InlayHint) -> SharedString {
+    let mut label = match &hint.label {
+        lsp_types::InlayHintLabel::String(s) => s.clone(),
+        lsp_types::InlayHintLabel::LabelParts(parts) => {
+            parts.iter().map(|part| part.value.as_str()).collect()
+        }
+    };
+    // Avoid to break the line layout.
+    label.retain(|c| c != '\n' && c != '\r');
+
+    if hint.padding_left == Some(true) {
+        label.insert(0, ' ');
+    }
+    if hint.padding_right == Some(true) {
+        label.push(' ');
+    }
+
+    label.into()
+}
+
+impl Lsp {
+    /// Get inlay hints in the visible range (0-based row).
+    ///
+    /// Returns byte offsets and labels, sorted by offset.
+    pub(crate) fn inlay_hints_for_range(
+        &self,
+        text: &Rope,
+        visible_range: &Range<usize>,
+    ) -> Vec<(usize, SharedString)> {
+        self.inlay_hints
+            .iter()
+            .filter_map(|(position, label)| {
+                if (position.line as usize) < visible_range.start
+                    || (position.line as usize) >= visible_range.end
+                {
+                    return None;
+                }
+
+                Some((text.position_to_offset(position), label.clone()))
+            })
+            .collect()
+    }
+
+    pub(crate) fn update_inlay_hints(
+        &mut self,
+        text: &Rope,
+        window: &mut Window,
+        cx: &mut Context<InputState>,
+    ) {
+        let Some(provider) = self.inlay_hint_provider.as_ref() else {
+            return;
+        };
+
+        let task = provider.inlay_hints(text, window, cx);
+        self._inlay_hint_task = cx.spawn_in(window, async move |editor, cx| {
+            let hints = task.await?;
+
+            editor.update(cx, |editor, cx| {
+                let mut inlay_hints: Vec<(lsp_types::Position, SharedString)> = hints
+                    .iter()
+                    .map(|hint| (hint.position, inlay_hint_label(hint)))
+                    .filter(|(_, label)| !label.is_empty())
+                    .collect();
+                inlay_hints.sort_by_key(|(position, _)| *position);
+
+                if inlay_hints == editor.lsp.inlay_hints {
+                    return;
+                }
+                editor.lsp.inlay_hints = inlay_hints;
+                cx.notify();
+            })?;
+
+            Ok(())
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{InlayHint, InlayHintLabel, InlayHintLabelPart, Position};
+
+    use super::inlay_hint_label;
+
+    #[test]
+    fn test_inlay_hint_label() {
+        let mut hint = InlayHint {
+            position: Position::new(0, 5),
+            label: InlayHintLabel::String(": i32".into()),
+            kind: None,
+            text_edits: None,
+            tooltip: None,
+            padding_left: None,
+            padding_right: None,
+            data: None,
+        };
+        assert_eq!(inlay_hint_label(&hint), ": i32");
+
+        hint.label = InlayHintLabel::LabelParts(vec![
+            InlayHintLabelPart {
+                value: "name".into(),
+                ..Default::default()
+            },
+            InlayHintLabelPart {
+                value: ":".into(),
+                ..Default::default()
+            },
+        ]);
+        hint.padding_right = Some(true);
+        assert_eq!(inlay_hint_label(&hint), "name: ");
+
+        hint.label = InlayHintLabel::String("a\nb".into());
+        hint.padding_left = Some(true);
+        hint.padding_right = None;
+        assert_eq!(inlay_hint_label(&hint), " ab");
+    }
+}
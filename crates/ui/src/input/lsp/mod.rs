@@ -1,21 +1,32 @@
 use anyhow::Result;
-use gpui::{App, Context, Hsla, MouseMoveEvent, Task, Window};
+use gpui::{App, Context, Hsla, MouseMoveEvent, SharedString, Task, Window};
 use ropey::Rope;
 use std::rc::Rc;
+use sum_tree::Bias;
 
-use crate::input::{popovers::ContextMenu, InputState, RopeExt};
+use crate::input::{multi_cursor::CursorSelection, popovers::ContextMenu, InputState, RopeExt};
 
 mod code_actions;
 mod completions;
 mod definitions;
 mod document_colors;
+mod formatting;
 mod hover;
+mod inlay_hints;
+mod rename;
+mod semantic_tokens;
+mod signature_help;
 
 pub use code_actions::*;
 pub use completions::*;
 pub use definitions::*;
 pub use document_colors::*;
+pub use formatting::*;
 pub use hover::*;
+pub use inlay_hints::*;
+pub use rename::*;
+pub use semantic_tokens::*;
+pub use signature_help::*;
 
 /// LSP ServerCapabilities
 ///
@@ -31,10 +42,27 @@ pub struct Lsp {
     pub definition_provider: Option<Rc<dyn DefinitionProvider>>,
     /// The document color provider.
     pub document_color_provider: Option<Rc<dyn DocumentColorProvider>>,
+    /// The rename provider.
+    pub rename_provider: Option<Rc<dyn RenameProvider>>,
+    /// The document formatting provider.
+    pub formatting_provider: Option<Rc<dyn DocumentFormattingProvider>>,
+    /// The signature help provider.
+    pub signature_help_provider: Option<Rc<dyn SignatureHelpProvider>>,
+    /// The inlay hint provider.
+    pub inlay_hint_provider: Option<Rc<dyn InlayHintProvider>>,
+    /// The semantic tokens provider.
+    pub semantic_tokens_provider: Option<Rc<dyn SemanticTokensProvider>>,
 
     document_colors: Vec<(lsp_types::Range, Hsla)>,
+    inlay_hints: Vec<(lsp_types::Position, SharedString)>,
+    semantic_tokens: Vec<(lsp_types::Range, &'static str)>,
     _hover_task: Task<Result<()>>,
     _document_color_task: Task<Result<()>>,
+    _rename_task: Task<Result<()>>,
+    _formatting_task: Task<Result<()>>,
+    _signature_help_task: Task<Result<()>>,
+    _inlay_hint_task: Task<Result<()>>,
+    _semantic_tokens_task: Task<Result<()>>,
 }
 
 impl Default for Lsp {
@@ -45,9 +73,21 @@ impl Default for Lsp {
             hover_provider: None,
             definition_provider: None,
             document_color_provider: None,
+            rename_provider: None,
+            formatting_provider: None,
+            signature_help_provider: None,
+            inlay_hint_provider: None,
+            semantic_tokens_provider: None,
             document_colors: vec![],
+            inlay_hints: vec![],
+            semantic_tokens: vec![],
             _hover_task: Task::ready(Ok(())),
             _document_color_task: Task::ready(Ok(())),
+            _rename_task: Task::ready(Ok(())),
+            _formatting_task: Task::ready(Ok(())),
+            _signature_help_task: Task::ready(Ok(())),
+            _inlay_hint_task: Task::ready(Ok(())),
+            _semantic_tokens_task: Task::ready(Ok(())),
         }
     }
 }
@@ -61,13 +101,20 @@ impl Lsp {
         cx: &mut Context<InputState>,
     ) {
         self.update_document_colors(text, window, cx);
+        self.update_inlay_hints(text, window, cx);
+        self.update_semantic_tokens(text, window, cx);
     }
 
     /// Reset all LSP states.
     pub(crate) fn reset(&mut self) {
         self.document_colors.clear();
+        self.inlay_hints.clear();
+        self.semantic_tokens.clear();
         self._hover_task = Task::ready(Ok(()));
         self._document_color_task = Task::ready(Ok(()));
+        self._signature_help_task = Task::ready(Ok(()));
+        self._inlay_hint_task = Task::ready(Ok(()));
+        self._semantic_tokens_task = Task::ready(Ok(()));
     }
 }
 
@@ -119,19 +166,56 @@ impl InputState {
     }
 
     /// Apply a list of [`lsp_types::TextEdit`] to mutate the text.
+    ///
+    /// The ranges of the edits are refer to the text before applying, as the LSP spec,
+    /// and all the edits will be applied as one undo step.
     pub fn apply_lsp_edits(
         &mut self,
         text_edits: &Vec<lsp_types::TextEdit>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        for edit in text_edits {
-            let start = self.text.position_to_offset(&edit.range.start);
-            let end = self.text.position_to_offset(&edit.range.end);
+        if text_edits.is_empty() {
+            return;
+        }
+
+        let mut edits = text_edits
+            .iter()
+            .enumerate()
+            .map(|(ix, edit)| {
+                let start = self.text.position_to_offset(&edit.range.start);
+                let end = self.text.position_to_offset(&edit.range.end);
+                (ix, start..end, edit.new_text.as_str())
+            })
+            .collect::<Vec<_>>();
+        // Apply from the end to keep the offsets of the previous edits valid,
+        // the inserts at the same position will keep the order of the list.
+        edits.sort_by_key(|(ix, range, _)| std::cmp::Reverse((range.start, *ix)));
+
+        let cursor = self.cursor();
+        let mut new_cursor = cursor;
+        self.secondary_selections.clear();
+        self.cursors_before_edit = Some(vec![CursorSelection::new(
+            self.selected_range,
+            self.selection_reversed,
+        )]);
+        for (_, range, new_text) in edits {
+            if range.end <= cursor {
+                new_cursor = (new_cursor + new_text.len()).saturating_sub(range.len());
+            } else if range.start < cursor {
+                new_cursor = range.start + new_text.len();
+            }
 
-            let range_utf16 = self.range_to_utf16(&(start..end));
-            self.replace_text_in_range_silent(Some(range_utf16), &edit.new_text, window, cx);
+            let range_utf16 = self.range_to_utf16(&range);
+            self.replace_text_in_range_silent(Some(range_utf16), new_text, window, cx);
         }
+        self.cursors_before_edit = None;
+        self.history.end_grouping();
+
+        let new_cursor = self.text.clip_offset(new_cursor.min(self.text.len()), Bias::Left);
+        self.selected_range = (new_cursor..new_cursor).into();
+        self.update_preferred_column();
+        cx.notify();
     }
 
     pub(super) fn handle_mouse_move(
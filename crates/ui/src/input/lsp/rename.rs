@@ -0,0 +1,272 @@
+use anyhow::Result;
+use gpui::{App, Context, Focusable as _, Task, Window};
+use ropey::Rope;
+
+use crate::input::{InputState, Rename, RopeExt, popovers::RenamePopover};
+
+/// Rename provider
+///
+/// https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocument_rename
+pub trait RenameProvider {
+    /// textDocument/prepareRename
+    ///
+    /// Returns `None` if the symbol at the `offset` can't be renamed.
+    ///
+    /// Default to use the word range at the offset.
+    ///
+    /// https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocument_prepareRename
+    fn prepare_rename(
+        &self,
+        _text: &Rope,
+        _offset: usize,
+        _window: &mut Window,
+        _cx: &mut App,
+    ) -> Task<Result<Option<lsp_types::PrepareRenameResponse>>> {
+        Task::ready(Ok(Some(
+            lsp_types::PrepareRenameResponse::DefaultBehavior {
+                default_behavior: true,
+            },
+        )))
+    }
+
+    /// textDocument/rename
+    ///
+    /// Returns the text edits of the current document, the edits will be applied as one undo step.
+    ///
+    /// If the [`lsp_types::WorkspaceEdit`] contains changes of other documents,
+    /// the provider should apply them by itself.
+    ///
+    /// https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocument_rename
+    fn rename(
+        &self,
+        _text: &Rope,
+        _offset: usize,
+        _new_name: &str,
+        _window: &mut Window,
+        _cx: &mut App,
+    ) -> Task<Result<Vec<lsp_types::TextEdit>>>;
+}
+
+impl InputState {
+    pub(crate) fn on_action_rename(
+        &mut self,
+        _: &Rename,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(provider) = self.lsp.rename_provider.clone() else {
+            cx.propagate();
+            return;
+        };
+
+        let offset = self.cursor();
+        let task = provider.prepare_rename(&self.text, offset, window, cx);
+        let editor = cx.entity();
+        // Skip the response if the text has changed, the range may be invalid.
+        let revision = self.revision;
+        self.lsp._rename_task = cx.spawn_in(window, async move |_, cx| {
+            let Some(response) = task.await? else {
+                return Ok(());
+            };
+
+            _ = editor.update_in(cx, |editor, window, cx| {
+                if editor.revision != revision {
+                    return;
+                }
+
+                let (symbol_range, placeholder) = match response {
+                    lsp_types::PrepareRenameResponse::Range(range) => {
+                        let start = editor.text.position_to_offset(&range.start);
+                        let end = editor.text.position_to_offset(&range.end);
+                        (start..end, None)
+                    }
+                    lsp_types::PrepareRenameResponse::RangeWithPlaceholder {
+                        range,
+                        placeholder,
+                    } => {
+                        let start = editor.text.position_to_offset(&range.start);
+                        let end = editor.text.position_to_offset(&range.end);
+                        (start..end, Some(placeholder))
+                    }
+                    lsp_types::PrepareRenameResponse::DefaultBehavior { .. } => {
+                        match editor.text.word_range(offset) {
+                            Some(range) => (range, None),
+                            None => return,
+                        }
+                    }
+                };
+
+                let placeholder = placeholder
+                    .unwrap_or_else(|| editor.text.slice(symbol_range.clone()).to_string());
+                editor.hover_popover = None;
+                editor.rename_popover = Some(RenamePopover::new(
+                    cx.entity(),
+                    symbol_range,
+                    placeholder,
+                    window,
+                    cx,
+                ));
+                cx.notify();
+            });
+
+            Ok(())
+        });
+    }
+
+    /// Rename the symbol at the cursor to `new_name` by using the [`RenameProvider`].
+    pub fn rename(&mut self, new_name: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.cursor();
+        self.perform_rename(offset, new_name, window, cx);
+    }
+
+    /// Confirm the rename from the [`RenamePopover`].
+    pub(crate) fn confirm_rename(
+        &mut self,
+        new_name: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(popover) = self.rename_popover.take() else {
+            return;
+        };
+
+        let symbol_range = popover.read(cx).symbol_range.clone();
+        window.focus(&self.focus_handle(cx));
+        if new_name.is_empty() || self.text.slice(symbol_range.clone()) == new_name {
+            cx.notify();
+            return;
+        }
+
+        self.perform_rename(symbol_range.start, new_name, window, cx);
+    }
+
+    /// Cancel the rename and close the [`RenamePopover`].
+    pub(crate) fn cancel_rename(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.rename_popover.take().is_some() {
+            window.focus(&self.focus_handle(cx));
+            cx.notify();
+        }
+    }
+
+    fn perform_rename(
+        &mut self,
+        offset: usize,
+        new_name: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(provider) = self.lsp.rename_provider.clone() else {
+            return;
+        };
+
+        let task = provider.rename(&self.text, offset, new_name, window, cx);
+        let editor = cx.entity();
+        // Skip the edits if the text has changed before the rename is done.
+        let revision = self.revision;
+        self.lsp._rename_task = cx.spawn_in(window, async move |_, cx| {
+            let text_edits = task.await?;
+
+            _ = editor.update_in(cx, |editor, window, cx| {
+                if editor.revision != revision {
+                    return;
+                }
+
+                editor.apply_lsp_edits(&text_edits, window, cx);
+            });
+
+            Ok(())
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use gpui::{AppContext as _, TestAppContext};
+    use lsp_types::{Position, TextEdit};
+
+    use super::*;
+
+    struct TestRenameProvider;
+
+    impl RenameProvider for TestRenameProvider {
+        fn rename(
+            &self,
+            text: &Rope,
+            offset: usize,
+            new_name: &str,
+            _: &mut Window,
+            _: &mut App,
+        ) -> Task<Result<Vec<TextEdit>>> {
+            let range = text.word_range(offset).unwrap_or(offset..offset);
+            Task::ready(Ok(vec![TextEdit {
+                range: lsp_types::Range::new(
+                    text.offset_to_position(range.start),
+                    text.offset_to_position(range.end),
+                ),
+                new_text: new_name.into(),
+            }]))
+        }
+    }
+
+    #[gpui::test]
+    fn test_rename(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let state = cx.update(|window, cx| {
+            cx.new(|cx| {
+                let mut state = InputState::new(window, cx)
+                    .multi_line(true)
+                    .default_value("let foo = 1;");
+                state.lsp.rename_provider = Some(Rc::new(TestRenameProvider));
+                state
+            })
+        });
+
+        cx.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                state.set_cursor_position(Position::new(0, 5), window, cx);
+                state.on_action_rename(&Rename, window, cx);
+            })
+        });
+        cx.run_until_parked();
+        cx.update(|_, cx| {
+            let popover = state.read(cx).rename_popover.clone();
+            assert_eq!(
+                popover.map(|popover| popover.read(cx).symbol_range.clone()),
+                Some(4..7)
+            );
+        });
+
+        // Edit the text before the prepare rename response, the response should be dropped.
+        cx.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                state.rename_popover = None;
+                state.on_action_rename(&Rename, window, cx);
+                state.replace_text_in_range_silent(Some(0..0), "// ", window, cx);
+            })
+        });
+        cx.run_until_parked();
+        cx.update(|_, cx| assert!(state.read(cx).rename_popover.is_none()));
+
+        cx.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                state.set_value("let foo = 1;", window, cx);
+                state.set_cursor_position(Position::new(0, 5), window, cx);
+                state.rename("bar", window, cx);
+            })
+        });
+        cx.run_until_parked();
+        cx.update(|_, cx| assert_eq!(state.read(cx).value(), "let bar = 1;"));
+
+        // Edit the text between the rename request and the response, the edits should be dropped.
+        cx.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                state.rename("baz", window, cx);
+                state.replace_text_in_range_silent(Some(0..0), "// ", window, cx);
+            })
+        });
+        cx.run_until_parked();
+        cx.update(|_, cx| assert_eq!(state.read(cx).value(), "// let bar = 1;"));
+    }
+}
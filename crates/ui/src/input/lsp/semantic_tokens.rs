@@ -0,0 +1,230 @@
+use std::ops::Range;
+
+use anyhow::Result;
+use gpui::{App, Context, HighlightStyle, Task, Window};
+use ropey::Rope;
+
+use crate::{
+    highlighter::HighlightTheme,
+    input::{InputState, Lsp, RopeExt},
+};
+
+pub trait SemanticTokensProvider {
+    /// The legend of the token types and modifiers used by this provider.
+    ///
+    /// https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#semanticTokensLegend
+    fn legend(&self) -> lsp_types::SemanticTokensLegend;
+
+    /// Fetches semantic tokens of the whole document.
+    ///
+    /// textDocument/semanticTokens/full
+    ///
+    /// https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocument_semanticTokens
+    fn semantic_tokens(
+        &self,
+        _text: &Rope,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Option<lsp_types::SemanticTokens>>>;
+}
+
+/// Map the semantic token type and modifiers to the highlight name of [`HighlightTheme`].
+///
+/// Returns `None` for the unknown token type, to keep the tree-sitter style.
+fn highlight_name(token_type: &str, modifiers: &[&str]) -> Option<&'static str> {
+    let name = match token_type {
+        "namespace" | "type" | "class" | "struct" | "interface" | "typeParameter" => "type",
+        "enum" => "enum",
+        "enumMember" => "variant",
+        "parameter" => "variable",
+        "variable" if modifiers.contains(&"readonly") => "constant",
+        "variable" => "variable",
+        "property" | "event" => "property",
+        "function" | "method" | "macro" => "function",
+        "keyword" | "modifier" => "keyword",
+        "comment" if modifiers.contains(&"documentation") => "comment.doc",
+        "comment" => "comment",
+        "string" => "string",
+        "number" => "number",
+        "regexp" => "string.regex",
+        "operator" => "operator",
+        "decorator" => "attribute",
+        "label" => "label",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+/// Decode the relative encoded semantic tokens to the ranges with highlight names.
+fn decode_semantic_tokens(
+    tokens: &[lsp_types::SemanticToken],
+    legend: &lsp_types::SemanticTokensLegend,
+) -> Vec<(lsp_types::Range, &'static str)> {
+    let mut result = Vec::with_capacity(tokens.len());
+    let mut line = 0;
+    let mut start = 0;
+    for token in tokens {
+        if token.delta_line > 0 {
+            line += token.delta_line;
+            start = token.delta_start;
+        } else {
+            start += token.delta_start;
+        }
+
+        let Some(token_type) = legend.token_types.get(token.token_type as usize) else {
+            continue;
+        };
+        let modifiers = legend
+            .token_modifiers
+            .iter()
+            .enumerate()
+            .filter(|(ix, _)| *ix < 32 && token.token_modifiers_bitset & (1 << ix) != 0)
+            .map(|(_, modifier)| modifier.as_str())
+            .collect::<Vec<_>>();
+
+        if let Some(name) = highlight_name(token_type.as_str(), &modifiers) {
+            let range = lsp_types::Range::new(
+                lsp_types::Position::new(line, start),
+                lsp_types::Position::new(line, start + token.length),
+            );
+            result.push((range, name));
+        }
+    }
+
+    result
+}
+
+impl Lsp {
+    /// Get the highlight styles of the semantic tokens in the visible range (0-based row).
+    ///
+    /// Returns byte ranges and styles, sorted by range.
+    pub(crate) fn semantic_token_styles(
+        &self,
+        text: &Rope,
+        visible_range: &Range<usize>,
+        theme: &HighlightTheme,
+    ) -> Vec<(Range<usize>, HighlightStyle)> {
+        self.semantic_tokens
+            .iter()
+            .filter_map(|(range, name)| {
+                if (range.start.line as usize) < visible_range.start
+                    || (range.start.line as usize) >= visible_range.end
+                {
+                    return None;
+                }
+
+                let style = theme.style(name)?;
+                let start = text.position_to_offset(&range.start);
+                let end = text.position_to_offset(&range.end);
+
+                Some((start..end, style))
+            })
+            .collect()
+    }
+
+    pub(crate) fn update_semantic_tokens(
+        &mut self,
+        text: &Rope,
+        window: &mut Window,
+        cx: &mut Context<InputState>,
+    ) {
+        let Some(provider) = self.semantic_tokens_provider.clone() else {
+            return;
+        };
+
+        let task = provider.semantic_tokens(text, window, cx);
+        self._semantic_tokens_task = cx.spawn_in(window, async move |editor, cx| {
+            let Some(tokens) = task.await? else {
+                return Ok(());
+            };
+
+            let semantic_tokens = decode_semantic_tokens(&tokens.data, &provider.legend());
+            editor.update(cx, |editor, cx| {
+                if semantic_tokens == editor.lsp.semantic_tokens {
+                    return;
+                }
+                editor.lsp.semantic_tokens = semantic_tokens;
+                cx.notify();
+            })?;
+
+            Ok(())
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{
+        Position, Range, SemanticToken, SemanticTokenModifier, SemanticTokenType,
+        SemanticTokensLegend,
+    };
+
+    use super::{decode_semantic_tokens, highlight_name};
+
+    #[test]
+    fn test_highlight_name() {
+        assert_eq!(highlight_name("struct", &[]), Some("type"));
+        assert_eq!(highlight_name("enumMember", &[]), Some("variant"));
+        assert_eq!(highlight_name("variable", &[]), Some("variable"));
+        assert_eq!(highlight_name("variable", &["readonly"]), Some("constant"));
+        assert_eq!(
+            highlight_name("comment", &["documentation"]),
+            Some("comment.doc")
+        );
+        assert_eq!(highlight_name("unknown", &[]), None);
+    }
+
+    #[test]
+    fn test_decode_semantic_tokens() {
+        let legend = SemanticTokensLegend {
+            token_types: vec![
+                SemanticTokenType::FUNCTION,
+                SemanticTokenType::VARIABLE,
+                SemanticTokenType::new("lifetime"),
+            ],
+            token_modifiers: vec![
+                SemanticTokenModifier::DECLARATION,
+                SemanticTokenModifier::READONLY,
+            ],
+        };
+
+        let token =
+            |delta_line, delta_start, length, token_type, token_modifiers_bitset| SemanticToken {
+                delta_line,
+                delta_start,
+                length,
+                token_type,
+                token_modifiers_bitset,
+            };
+
+        // fn foo() {
+        //     let a = 1;
+        //     let b: &'a = 2;
+        // }
+        let tokens = [
+            token(0, 3, 3, 0, 0b01),
+            token(1, 8, 1, 1, 0b11),
+            token(1, 8, 1, 1, 0),
+            token(0, 4, 2, 2, 0),
+        ];
+
+        assert_eq!(
+            decode_semantic_tokens(&tokens, &legend),
+            vec![
+                (
+                    Range::new(Position::new(0, 3), Position::new(0, 6)),
+                    "function"
+                ),
+                (
+                    Range::new(Position::new(1, 8), Position::new(1, 9)),
+                    "constant"
+                ),
+                (
+                    Range::new(Position::new(2, 8), Position::new(2, 9)),
+                    "variable"
+                ),
+            ]
+        );
+    }
+}
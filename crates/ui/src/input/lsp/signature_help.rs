@@ -0,0 +1,107 @@
+use anyhow::Result;
+use gpui::{App, Context, Task, Window};
+use ropey::Rope;
+
+use crate::input::{InputState, ShowSignatureHelp, popovers::SignatureHelpPopover};
+
+/// Signature help provider
+///
+/// https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocument_signatureHelp
+pub trait SignatureHelpProvider {
+    /// textDocument/signatureHelp
+    ///
+    /// https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocument_signatureHelp
+    fn signature_help(
+        &self,
+        _text: &Rope,
+        _offset: usize,
+        _window: &mut Window,
+        _cx: &mut App,
+    ) -> Task<Result<Option<lsp_types::SignatureHelp>>>;
+
+    /// Determines if the signature help should be triggered by the inserted `new_text`.
+    ///
+    /// Default to trigger on `(` and `,`.
+    ///
+    /// When the signature help is showing, any text change will update it.
+    fn is_signature_help_trigger(&self, _offset: usize, new_text: &str, _cx: &mut App) -> bool {
+        matches!(new_text, "(" | ",")
+    }
+}
+
+impl InputState {
+    pub(crate) fn on_action_show_signature_help(
+        &mut self,
+        _: &ShowSignatureHelp,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.lsp.signature_help_provider.is_none() {
+            cx.propagate();
+            return;
+        }
+
+        self.request_signature_help(window, cx);
+    }
+
+    /// Handle the signature help trigger after the text changed.
+    pub(crate) fn handle_signature_help_trigger(
+        &mut self,
+        new_text: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(provider) = self.lsp.signature_help_provider.clone() else {
+            return;
+        };
+
+        if self.signature_help_popover.is_none()
+            && !provider.is_signature_help_trigger(self.cursor(), new_text, cx)
+        {
+            return;
+        }
+
+        self.request_signature_help(window, cx);
+    }
+
+    pub(crate) fn hide_signature_help(&mut self, cx: &mut Context<Self>) {
+        if self.signature_help_popover.take().is_some() {
+            self.lsp._signature_help_task = Task::ready(Ok(()));
+            cx.notify();
+        }
+    }
+
+    fn request_signature_help(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(provider) = self.lsp.signature_help_provider.clone() else {
+            return;
+        };
+
+        let offset = self.cursor();
+        let task = provider.signature_help(&self.text, offset, window, cx);
+        let editor = cx.entity();
+        self.lsp._signature_help_task = cx.spawn_in(window, async move |_, cx| {
+            let result = task.await?;
+
+            _ = editor.update_in(cx, |editor, window, cx| {
+                if !editor.focus_handle.is_focused(window) {
+                    return;
+                }
+
+                match result {
+                    Some(signature_help) if !signature_help.signatures.is_empty() => {
+                        editor.signature_help_popover = Some(SignatureHelpPopover::new(
+                            cx.entity(),
+                            offset,
+                            &signature_help,
+                            cx,
+                        ));
+                    }
+                    _ => editor.signature_help_popover = None,
+                }
+                cx.notify();
+            });
+
+            Ok(())
+        });
+    }
+}
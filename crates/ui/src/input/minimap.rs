@@ -0,0 +1,439 @@
+use std::ops::Range;
+
+use gpui::{
+    App, Bounds, Context, CursorStyle, Element, ElementId, GlobalElementId, HighlightStyle, Hitbox,
+    HitboxBehavior, Hsla, InspectorElementId, IntoElement, LayoutId, MouseButton, MouseDownEvent,
+    MouseMoveEvent, MouseUpEvent, Pixels, Style, Window, fill, point, px, relative, size,
+};
+
+use crate::{
+    ActiveTheme as _,
+    input::{InputState, RopeExt as _, mode::InputMode},
+};
+
+/// The width of the minimap column.
+pub(super) const MINIMAP_WIDTH: Pixels = px(100.);
+/// The height of a line in the minimap.
+const LINE_HEIGHT: Pixels = px(3.);
+/// The width of a char in the minimap.
+const CHAR_WIDTH: Pixels = px(1.);
+/// The left padding of the lines in the minimap.
+const PADDING_LEFT: Pixels = px(6.);
+/// The width of the overview ruler markers at the right of the minimap.
+const MARKER_WIDTH: Pixels = px(4.);
+
+impl InputMode {
+    #[inline]
+    pub(super) fn has_minimap(&self) -> bool {
+        match self {
+            InputMode::CodeEditor {
+                minimap,
+                multi_line,
+                ..
+            } => *minimap && *multi_line,
+            _ => false,
+        }
+    }
+}
+
+impl InputState {
+    /// Set whether to show the minimap in code editor mode, default is false.
+    ///
+    /// Only for [`InputMode::CodeEditor`] mode.
+    pub fn minimap(mut self, minimap: bool) -> Self {
+        debug_assert!(self.mode.is_code_editor() && self.mode.is_multi_line());
+        if let InputMode::CodeEditor { minimap: m, .. } = &mut self.mode {
+            *m = minimap;
+        }
+        self
+    }
+
+    /// Set minimap in code editor mode.
+    ///
+    /// Only for [`InputMode::CodeEditor`] mode.
+    pub fn set_minimap(&mut self, minimap: bool, _: &mut Window, cx: &mut Context<Self>) {
+        debug_assert!(self.mode.is_code_editor());
+        if let InputMode::CodeEditor { minimap: m, .. } = &mut self.mode {
+            *m = minimap;
+        }
+        cx.notify();
+    }
+
+    /// Scroll to make the `row` in the center of the viewport.
+    fn scroll_to_row_center(&mut self, row: usize, cx: &mut Context<Self>) {
+        let total_rows = self.text.lines_len().max(1);
+        let y = self.scroll_size.height * (row as f32 / total_rows as f32)
+            - self.input_bounds.size.height / 2.;
+        let offset = self.scroll_handle.offset();
+        self.update_scroll_offset(Some(point(offset.x, -y)), cx);
+    }
+}
+
+/// Split the line into blocks of the non-whitespace chars with the same color.
+///
+/// Returns the (columns, color) of the blocks, the `styles` are the sorted highlight styles in bytes.
+fn line_blocks(
+    line: &str,
+    line_offset: usize,
+    styles: &[(Range<usize>, HighlightStyle)],
+    tab_size: usize,
+) -> Vec<(Range<usize>, Option<Hsla>)> {
+    let mut blocks: Vec<(Range<usize>, Option<Hsla>)> = vec![];
+    let mut style_ix = styles.partition_point(|(range, _)| range.end <= line_offset);
+    let mut column = 0;
+
+    for (ix, c) in line.char_indices() {
+        let offset = line_offset + ix;
+        while style_ix < styles.len() && styles[style_ix].0.end <= offset {
+            style_ix += 1;
+        }
+
+        let width = if c == '\t' { tab_size } else { 1 };
+        if !c.is_whitespace() {
+            let color = styles
+                .get(style_ix)
+                .filter(|(range, _)| range.start <= offset)
+                .and_then(|(_, style)| style.color);
+
+            match blocks.last_mut() {
+                Some((range, last_color)) if range.end == column && *last_color == color => {
+                    range.end += width;
+                }
+                _ => blocks.push((column..column + width, color)),
+            }
+        }
+        column += width;
+    }
+
+    blocks
+}
+
+/// A minimap of the code editor, with the overview ruler markers for
+/// diagnostics, search matches and the cursor.
+pub(super) struct Minimap {
+    state: gpui::Entity<InputState>,
+}
+
+impl Minimap {
+    pub(super) fn new(state: gpui::Entity<InputState>) -> Self {
+        Self { state }
+    }
+}
+
+pub(super) struct MinimapPrepaintState {
+    hitbox: Hitbox,
+    /// The first row of the minimap lines.
+    first_row: usize,
+    /// The y offset of the first row, for smooth scrolling.
+    first_row_top: Pixels,
+    blocks: Vec<(Bounds<Pixels>, Hsla)>,
+    slider_bounds: Option<Bounds<Pixels>>,
+    markers: Vec<(Bounds<Pixels>, Hsla)>,
+}
+
+impl IntoElement for Minimap {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+impl Element for Minimap {
+    type RequestLayoutState = ();
+    type PrepaintState = MinimapPrepaintState;
+
+    fn id(&self) -> Option<ElementId> {
+        None
+    }
+
+    fn source_location(&self) -> Option<&'static std::panic::Location<'static>> {
+        None
+    }
+
+    fn request_layout(
+        &mut self,
+        _: Option<&GlobalElementId>,
+        _: Option<&InspectorElementId>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        let mut style = Style::default();
+        style.size.width = MINIMAP_WIDTH.into();
+        style.size.height = relative(1.).into();
+        style.flex_shrink = 0.;
+
+        (window.request_layout(style, [], cx), ())
+    }
+
+    fn prepaint(
+        &mut self,
+        _: Option<&GlobalElementId>,
+        _: Option<&InspectorElementId>,
+        bounds: Bounds<Pixels>,
+        _: &mut Self::RequestLayoutState,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Self::PrepaintState {
+        let hitbox = window.insert_hitbox(bounds, HitboxBehavior::Normal);
+        let state = self.state.read(cx);
+        let text = &state.text;
+        let total_rows = text.lines_len().max(1);
+
+        // Scroll the minimap in proportion to the editor, if it is higher than the bounds.
+        let max_scroll = (state.scroll_size.height - state.input_bounds.size.height).max(px(0.));
+        let scroll_ratio = if max_scroll > px(0.) {
+            (-state.scroll_handle.offset().y / max_scroll).clamp(0., 1.)
+        } else {
+            0.
+        };
+        let overflow = (LINE_HEIGHT * total_rows as f32 - bounds.size.height).max(px(0.));
+        let minimap_top = overflow * scroll_ratio;
+        let first_row = (minimap_top / LINE_HEIGHT).floor() as usize;
+        let first_row_top = LINE_HEIGHT * first_row as f32 - minimap_top;
+        let row_top = |row: usize| {
+            bounds.top() + first_row_top + LINE_HEIGHT * (row as f32 - first_row as f32)
+        };
+
+        let rows_len = (bounds.size.height / LINE_HEIGHT).ceil() as usize + 1;
+        let last_row = (first_row + rows_len).min(total_rows);
+        let theme = cx.theme();
+        let styles = match &state.mode {
+            InputMode::CodeEditor { highlighter, .. } if first_row < last_row => highlighter
+                .borrow()
+                .as_ref()
+                .map(|highlighter| {
+                    let range =
+                        text.line_start_offset(first_row)..text.line_end_offset(last_row - 1);
+                    highlighter.styles(&range, &theme.highlight_theme)
+                })
+                .unwrap_or_default(),
+            _ => vec![],
+        };
+
+        let tab_size = state.mode.tab_size().tab_size;
+        let max_width = bounds.size.width - PADDING_LEFT - MARKER_WIDTH;
+        let mut blocks = vec![];
+        let mut line_offset = text.line_start_offset(first_row);
+        for (ix, line) in text
+            .iter_lines()
+            .skip(first_row)
+            .take(last_row - first_row)
+            .enumerate()
+        {
+            let line = line.to_string();
+            let y = row_top(first_row + ix);
+            for (columns, color) in line_blocks(&line, line_offset, &styles, tab_size) {
+                let x = CHAR_WIDTH * columns.start as f32;
+                if x >= max_width {
+                    break;
+                }
+                let width = (CHAR_WIDTH * columns.len() as f32).min(max_width - x);
+                blocks.push((
+                    Bounds::new(
+                        point(bounds.left() + PADDING_LEFT + x, y),
+                        size(width, LINE_HEIGHT - px(1.)),
+                    ),
+                    color.unwrap_or(theme.foreground).opacity(0.6),
+                ));
+            }
+            // +1 for `\n`
+            line_offset += line.len() + 1;
+        }
+
+        let slider_bounds = state.last_layout.as_ref().map(|last_layout| {
+            let visible_range = &last_layout.visible_range;
+            Bounds::new(
+                point(bounds.left(), row_top(visible_range.start)),
+                size(
+                    bounds.size.width,
+                    LINE_HEIGHT * visible_range.len().max(1) as f32,
+                ),
+            )
+        });
+
+        // The overview ruler markers, in proportion to the whole document.
+        let mut markers = vec![];
+        let marker_left = bounds.right() - MARKER_WIDTH;
+        let mut push_marker = |row: usize, color: Hsla, full_width: bool| {
+            let y = bounds.top() + bounds.size.height * (row as f32 / total_rows as f32);
+            let (x, width) = if full_width {
+                (bounds.left(), bounds.size.width)
+            } else {
+                (marker_left, MARKER_WIDTH)
+            };
+            let marker = (Bounds::new(point(x, y), size(width, px(2.))), color);
+            // Skip the marker at the same position to avoid painting too many.
+            if markers.last() != Some(&marker) {
+                markers.push(marker);
+            }
+        };
+
+        if let Some(matcher) = state
+            .search_panel
+            .as_ref()
+            .and_then(|panel| panel.read(cx).matcher())
+        {
+            for range in matcher.matched_ranges.iter() {
+                push_marker(text.offset_to_point(range.start).row, theme.yellow, false);
+            }
+        }
+        if let Some(diagnostics) = state.mode.diagnostics() {
+            for entry in diagnostics.iter() {
+                push_marker(
+                    text.offset_to_point(entry.range.start).row,
+                    entry.severity.fg(cx),
+                    false,
+                );
+            }
+        }
+        push_marker(text.offset_to_point(state.cursor()).row, theme.caret, true);
+
+        MinimapPrepaintState {
+            hitbox,
+            first_row,
+            first_row_top,
+            blocks,
+            slider_bounds,
+            markers,
+        }
+    }
+
+    fn paint(
+        &mut self,
+        _: Option<&GlobalElementId>,
+        _: Option<&InspectorElementId>,
+        bounds: Bounds<Pixels>,
+        _: &mut Self::RequestLayoutState,
+        prepaint: &mut Self::PrepaintState,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        window.paint_quad(fill(bounds, cx.theme().editor_background()));
+        window.paint_quad(fill(
+            Bounds::new(bounds.origin, size(px(1.), bounds.size.height)),
+            cx.theme().border,
+        ));
+
+        window.with_content_mask(Some(gpui::ContentMask { bounds }), |window| {
+            for (block_bounds, color) in prepaint.blocks.iter() {
+                window.paint_quad(fill(*block_bounds, *color));
+            }
+
+            if let Some(slider_bounds) = prepaint.slider_bounds {
+                let is_dragging = self.state.read(cx).minimap_dragging;
+                let opacity = if is_dragging || prepaint.hitbox.is_hovered(window) {
+                    0.15
+                } else {
+                    0.08
+                };
+                window.paint_quad(fill(slider_bounds, cx.theme().foreground.opacity(opacity)));
+            }
+
+            for (marker_bounds, color) in prepaint.markers.iter() {
+                window.paint_quad(fill(*marker_bounds, *color));
+            }
+        });
+
+        window.set_cursor_style(CursorStyle::Arrow, &prepaint.hitbox);
+
+        let total_rows = self.state.read(cx).text.lines_len().max(1);
+        let first_row = prepaint.first_row;
+        let first_row_top = prepaint.first_row_top;
+        let row_for_y = move |y: Pixels| {
+            let row = first_row as f32 + ((y - bounds.top() - first_row_top) / LINE_HEIGHT);
+            (row.max(0.) as usize).min(total_rows - 1)
+        };
+
+        window.on_mouse_event({
+            let state = self.state.clone();
+            let hitbox = prepaint.hitbox.clone();
+            move |event: &MouseDownEvent, phase, window, cx| {
+                if !phase.bubble()
+                    || event.button != MouseButton::Left
+                    || !hitbox.is_hovered(window)
+                {
+                    return;
+                }
+
+                cx.stop_propagation();
+                let row = row_for_y(event.position.y);
+                state.update(cx, |state, cx| {
+                    state.minimap_dragging = true;
+                    state.scroll_to_row_center(row, cx);
+                });
+            }
+        });
+
+        window.on_mouse_event({
+            let state = self.state.clone();
+            move |event: &MouseMoveEvent, phase, _, cx| {
+                if !phase.bubble() || !state.read(cx).minimap_dragging {
+                    return;
+                }
+
+                if event.pressed_button != Some(MouseButton::Left) {
+                    state.update(cx, |state, _| state.minimap_dragging = false);
+                    return;
+                }
+
+                cx.stop_propagation();
+                let row = row_for_y(event.position.y);
+                state.update(cx, |state, cx| {
+                    state.scroll_to_row_center(row, cx);
+                });
+            }
+        });
+
+        window.on_mouse_event({
+            let state = self.state.clone();
+            move |_: &MouseUpEvent, phase, _, cx| {
+                if phase.bubble() && state.read(cx).minimap_dragging {
+                    state.update(cx, |state, cx| {
+                        state.minimap_dragging = false;
+                        cx.notify();
+                    });
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{HighlightStyle, hsla};
+
+    use super::line_blocks;
+
+    #[test]
+    fn test_line_blocks() {
+        let red = hsla(0., 1., 0.5, 1.);
+        let blue = hsla(0.6, 1., 0.5, 1.);
+        let style = |color| HighlightStyle {
+            color: Some(color),
+            ..Default::default()
+        };
+
+        // `\tlet a = 1;` at the offset 10.
+        let styles = vec![
+            (10..11, HighlightStyle::default()),
+            (11..14, style(red)),
+            (14..19, HighlightStyle::default()),
+            (19..20, style(blue)),
+            (20..21, HighlightStyle::default()),
+        ];
+        assert_eq!(
+            line_blocks("\tlet a = 1;", 10, &styles, 4),
+            vec![
+                (4..7, Some(red)),
+                (8..9, None),
+                (10..11, None),
+                (12..13, Some(blue)),
+                (13..14, None),
+            ]
+        );
+
+        assert_eq!(line_blocks("  foo.bar", 0, &[], 4), vec![(2..9, None)]);
+        assert_eq!(line_blocks("", 0, &[], 4), vec![]);
+    }
+}
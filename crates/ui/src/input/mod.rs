@@ -1,14 +1,20 @@
 mod blink_cursor;
+mod bracket;
 mod change;
 mod clear_button;
 mod cursor;
+mod diff;
 mod element;
+mod fold;
 mod indent;
 mod input;
+mod line_edit;
 mod lsp;
 mod mask_pattern;
+mod minimap;
 mod mode;
 mod movement;
+mod multi_cursor;
 mod number_input;
 mod otp_input;
 pub(crate) mod popovers;
@@ -20,6 +26,7 @@ mod selection;
 
 pub(crate) use clear_button::*;
 pub use cursor::*;
+pub use diff::{DiffHunk, DiffHunkKind};
 pub use indent::TabSize;
 pub use input::*;
 pub use lsp::*;
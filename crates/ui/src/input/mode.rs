@@ -33,6 +33,8 @@ pub(crate) enum InputMode {
         line_number: bool,
         language: SharedString,
         indent_guides: bool,
+        /// Show minimap
+        minimap: bool,
         highlighter: Rc<RefCell<Option<SyntaxHighlighter>>>,
         diagnostics: DiagnosticSet,
     },
@@ -65,6 +67,7 @@ impl InputMode {
             highlighter: Rc::new(RefCell::new(None)),
             line_number: true,
             indent_guides: true,
+            minimap: false,
             diagnostics: DiagnosticSet::new(&Rope::new()),
         }
     }
@@ -279,6 +282,7 @@ mod tests {
         assert_eq!(mode.is_single_line(), false);
         assert_eq!(mode.line_number(), true);
         assert_eq!(mode.has_indent_guides(), true);
+        assert_eq!(mode.has_minimap(), false);
         assert_eq!(mode.max_rows(), usize::MAX);
         assert_eq!(mode.min_rows(), 1);
 
@@ -286,6 +290,7 @@ mod tests {
             multi_line: false,
             line_number: true,
             indent_guides: true,
+            minimap: true,
             rows: 0,
             tab: Default::default(),
             language: "rust".into(),
@@ -297,6 +302,7 @@ mod tests {
         assert_eq!(mode.is_single_line(), true);
         assert_eq!(mode.line_number(), false);
         assert_eq!(mode.has_indent_guides(), false);
+        assert_eq!(mode.has_minimap(), false);
         assert_eq!(mode.max_rows(), 1);
         assert_eq!(mode.min_rows(), 1);
     }
@@ -46,11 +46,14 @@ impl InputState {
         cx: &mut Context<Self>,
     ) {
         let offset = offset.clamp(0, self.text.len());
+        self.unfold_offset(offset);
+        self.secondary_selections.clear();
         self.selected_range = (offset..offset).into();
         self.scroll_to(offset, direction, cx);
         self.pause_blink_cursor(cx);
         self.update_preferred_column();
         self.hide_context_menu(cx);
+        self.hide_signature_help(cx);
         cx.notify()
     }
 
@@ -115,22 +118,29 @@ impl InputState {
         cx.notify();
     }
 
-    pub(super) fn left(&mut self, _: &MoveLeft, _: &mut Window, cx: &mut Context<Self>) {
+    pub(super) fn left(&mut self, _: &MoveLeft, window: &mut Window, cx: &mut Context<Self>) {
         self.pause_blink_cursor(cx);
-        if self.selected_range.is_empty() {
-            self.move_to(self.previous_boundary(self.cursor()), None, cx);
-        } else {
-            self.move_to(self.selected_range.start, None, cx)
-        }
+        self.for_each_cursor(window, cx, |this, _, _, cx| {
+            if this.selected_range.is_empty() {
+                let offset = this.skip_folded_offset(this.previous_boundary(this.cursor()), false);
+                this.move_to(offset, None, cx);
+            } else {
+                this.move_to(this.selected_range.start, None, cx)
+            }
+        });
     }
 
-    pub(super) fn right(&mut self, _: &MoveRight, _: &mut Window, cx: &mut Context<Self>) {
+    pub(super) fn right(&mut self, _: &MoveRight, window: &mut Window, cx: &mut Context<Self>) {
         self.pause_blink_cursor(cx);
-        if self.selected_range.is_empty() {
-            self.move_to(self.next_boundary(self.selected_range.end), None, cx);
-        } else {
-            self.move_to(self.selected_range.end, None, cx)
-        }
+        self.for_each_cursor(window, cx, |this, _, _, cx| {
+            if this.selected_range.is_empty() {
+                let offset =
+                    this.skip_folded_offset(this.next_boundary(this.selected_range.end), true);
+                this.move_to(offset, None, cx);
+            } else {
+                this.move_to(this.selected_range.end, None, cx)
+            }
+        });
     }
 
     pub(super) fn up(&mut self, action: &MoveUp, window: &mut Window, cx: &mut Context<Self>) {
@@ -142,15 +152,17 @@ impl InputState {
             return;
         }
 
-        if !self.selected_range.is_empty() {
-            self.move_to(
-                self.previous_boundary(self.selected_range.start.saturating_sub(1)),
-                Some(MoveDirection::Up),
-                cx,
-            );
-        }
         self.pause_blink_cursor(cx);
-        self.move_vertical(-1, window, cx);
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            if !this.selected_range.is_empty() {
+                this.move_to(
+                    this.previous_boundary(this.selected_range.start.saturating_sub(1)),
+                    Some(MoveDirection::Up),
+                    cx,
+                );
+            }
+            this.move_vertical(-1, window, cx);
+        });
     }
 
     pub(super) fn down(&mut self, action: &MoveDown, window: &mut Window, cx: &mut Context<Self>) {
@@ -162,16 +174,17 @@ impl InputState {
             return;
         }
 
-        if !self.selected_range.is_empty() {
-            self.move_to(
-                self.next_boundary(self.selected_range.end.saturating_sub(1)),
-                Some(MoveDirection::Down),
-                cx,
-            );
-        }
-
         self.pause_blink_cursor(cx);
-        self.move_vertical(1, window, cx);
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            if !this.selected_range.is_empty() {
+                this.move_to(
+                    this.next_boundary(this.selected_range.end.saturating_sub(1)),
+                    Some(MoveDirection::Down),
+                    cx,
+                );
+            }
+            this.move_vertical(1, window, cx);
+        });
     }
 
     pub(super) fn page_up(&mut self, _: &MovePageUp, window: &mut Window, cx: &mut Context<Self>) {
@@ -205,16 +218,20 @@ impl InputState {
         self.move_vertical(display_lines, window, cx);
     }
 
-    pub(super) fn home(&mut self, _: &MoveHome, _: &mut Window, cx: &mut Context<Self>) {
+    pub(super) fn home(&mut self, _: &MoveHome, window: &mut Window, cx: &mut Context<Self>) {
         self.pause_blink_cursor(cx);
-        let offset = self.start_of_line();
-        self.move_to(offset, Some(MoveDirection::Up), cx);
+        self.for_each_cursor(window, cx, |this, _, _, cx| {
+            let offset = this.start_of_line();
+            this.move_to(offset, Some(MoveDirection::Up), cx);
+        });
     }
 
-    pub(super) fn end(&mut self, _: &MoveEnd, _: &mut Window, cx: &mut Context<Self>) {
+    pub(super) fn end(&mut self, _: &MoveEnd, window: &mut Window, cx: &mut Context<Self>) {
         self.pause_blink_cursor(cx);
-        let offset = self.end_of_line();
-        self.move_to(offset, Some(MoveDirection::Down), cx);
+        self.for_each_cursor(window, cx, |this, _, _, cx| {
+            let offset = this.end_of_line();
+            this.move_to(offset, Some(MoveDirection::Down), cx);
+        });
     }
 
     pub(super) fn move_to_start(
@@ -233,20 +250,24 @@ impl InputState {
     pub(super) fn move_to_previous_word(
         &mut self,
         _: &MoveToPreviousWord,
-        _: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let offset = self.previous_start_of_word();
-        self.move_to(offset, None, cx);
+        self.for_each_cursor(window, cx, |this, _, _, cx| {
+            let offset = this.previous_start_of_word();
+            this.move_to(offset, None, cx);
+        });
     }
 
     pub(super) fn move_to_next_word(
         &mut self,
         _: &MoveToNextWord,
-        _: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let offset = self.next_end_of_word();
-        self.move_to(offset, None, cx);
+        self.for_each_cursor(window, cx, |this, _, _, cx| {
+            let offset = this.next_end_of_word();
+            this.move_to(offset, None, cx);
+        });
     }
 }
@@ -0,0 +1,546 @@
+use std::ops::Range;
+
+use gpui::{Context, MouseDownEvent, Window};
+use ropey::Rope;
+use sum_tree::Bias;
+
+use crate::input::{
+    AddCursorAbove, AddCursorBelow, InputEvent, InputState, RopeExt as _, SelectNextOccurrence,
+    Selection,
+};
+
+/// A cursor with its selection, used for the multi-cursor editing.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub(crate) struct CursorSelection {
+    /// The selected range, the `start` is always less than or equal to `end`.
+    pub(crate) range: Selection,
+    /// Whether the cursor is at the `start` of the range.
+    pub(crate) reversed: bool,
+}
+
+impl CursorSelection {
+    pub(crate) fn new(range: impl Into<Selection>, reversed: bool) -> Self {
+        Self {
+            range: range.into(),
+            reversed,
+        }
+    }
+
+    /// Get the byte offset of the cursor.
+    #[inline]
+    pub(crate) fn cursor(&self) -> usize {
+        if self.reversed {
+            self.range.start
+        } else {
+            self.range.end
+        }
+    }
+
+    /// Move the selection by the given delta, and clamp it into `0..=max`.
+    fn shift(&mut self, delta: isize, max: usize) {
+        self.range.start = self.range.start.saturating_add_signed(delta).min(max);
+        self.range.end = self.range.end.saturating_add_signed(delta).min(max);
+    }
+}
+
+/// The edits of all cursors in one multi-cursor action, to update the text states at once.
+#[derive(Debug, Clone)]
+pub(crate) struct BatchedEdit {
+    /// The text before the edits.
+    old_text: Rope,
+    /// The offset of the first edited byte, the text before it is not changed by the edits.
+    start: usize,
+    /// The length of the text after the last edited byte, it is not changed by the edits.
+    tail_len: usize,
+}
+
+impl BatchedEdit {
+    fn new(old_text: &Rope, range: &Range<usize>) -> Self {
+        Self {
+            old_text: old_text.clone(),
+            start: range.start,
+            tail_len: old_text.len() - range.end,
+        }
+    }
+
+    /// Add the edit of the `range` in the `old_text` (the text before this edit).
+    fn push(&mut self, old_text: &Rope, range: &Range<usize>) {
+        self.start = self.start.min(range.start);
+        self.tail_len = self.tail_len.min(old_text.len() - range.end);
+    }
+
+    /// Returns the range in the text before the edits, and the new text of it.
+    fn edit(&self, text: &Rope) -> (Range<usize>, String) {
+        let range = self.start..self.old_text.len() - self.tail_len;
+        let new_text = text
+            .slice(self.start..text.len() - self.tail_len)
+            .to_string();
+        (range, new_text)
+    }
+}
+
+/// Merge the overlapping cursors and sort them by offset.
+///
+/// Returns the merged cursors and the new index of the cursor at `primary_ix`.
+pub(crate) fn merge_cursor_selections(
+    cursors: Vec<CursorSelection>,
+    primary_ix: usize,
+) -> (Vec<CursorSelection>, usize) {
+    let mut indexed = cursors.into_iter().enumerate().collect::<Vec<_>>();
+    indexed.sort_by_key(|(_, c)| (c.range.start, c.range.end));
+
+    let mut merged: Vec<CursorSelection> = Vec::with_capacity(indexed.len());
+    let mut new_primary_ix = 0;
+    for (ix, cursor) in indexed {
+        if let Some(last) = merged.last_mut() {
+            let overlaps = cursor.range.start < last.range.end
+                || (cursor.range.start == last.range.end
+                    && (cursor.range.is_empty() || last.range.is_empty()));
+            if overlaps {
+                last.range.end = last.range.end.max(cursor.range.end);
+                if ix == primary_ix {
+                    new_primary_ix = merged.len() - 1;
+                }
+                continue;
+            }
+        }
+
+        if ix == primary_ix {
+            new_primary_ix = merged.len();
+        }
+        merged.push(cursor);
+    }
+
+    (merged, new_primary_ix)
+}
+
+/// Build the selections of a column (box) selection from `anchor` to `head`.
+///
+/// The `anchor` and `head` is the (row, column) point, the column is in bytes.
+///
+/// The last item is the selection in `head` row.
+pub(crate) fn column_selections(
+    text: &Rope,
+    anchor: tree_sitter::Point,
+    head: tree_sitter::Point,
+) -> Vec<CursorSelection> {
+    let reversed = head.column < anchor.column;
+    let (start_col, end_col) = if reversed {
+        (head.column, anchor.column)
+    } else {
+        (anchor.column, head.column)
+    };
+
+    let rows = if head.row >= anchor.row {
+        (anchor.row..=head.row).collect::<Vec<_>>()
+    } else {
+        (head.row..=anchor.row).rev().collect::<Vec<_>>()
+    };
+
+    rows.into_iter()
+        .map(|row| {
+            let line_start = text.line_start_offset(row);
+            let line_len = text.slice_line(row).len();
+            let start = text.clip_offset(line_start + start_col.min(line_len), Bias::Left);
+            let end = text.clip_offset(line_start + end_col.min(line_len), Bias::Left);
+            CursorSelection::new(start..end, reversed)
+        })
+        .collect()
+}
+
+impl InputState {
+    /// Return all the selections (the primary selection is first) in UTF-8 byte ranges.
+    pub fn selections(&self) -> Vec<Range<usize>> {
+        std::iter::once(self.selected_range.into())
+            .chain(self.secondary_selections.iter().map(|c| c.range.into()))
+            .collect()
+    }
+
+    /// Add a new selection (or a cursor if the range is empty) for multi-cursor editing.
+    ///
+    /// The new selection will be the primary selection.
+    pub fn add_selection(&mut self, range: Range<usize>, _: &mut Window, cx: &mut Context<Self>) {
+        let start = self
+            .text
+            .clip_offset(range.start.min(self.text.len()), Bias::Left);
+        let end = self
+            .text
+            .clip_offset(range.end.min(self.text.len()), Bias::Right);
+        self.push_cursor(CursorSelection::new(start..end, false), cx);
+    }
+
+    /// Remove all secondary cursors, only keep the primary cursor.
+    pub fn clear_secondary_cursors(&mut self, _: &mut Window, cx: &mut Context<Self>) {
+        self.secondary_selections.clear();
+        cx.notify();
+    }
+
+    #[inline]
+    pub(super) fn has_multiple_cursors(&self) -> bool {
+        !self.secondary_selections.is_empty()
+    }
+
+    /// Make the `cursor` as the new primary cursor, and keep the old primary cursor as secondary.
+    fn push_cursor(&mut self, cursor: CursorSelection, cx: &mut Context<Self>) {
+        let primary = CursorSelection::new(self.selected_range, self.selection_reversed);
+        let mut cursors = std::mem::take(&mut self.secondary_selections);
+        cursors.push(primary);
+        cursors.push(cursor);
+        let primary_ix = cursors.len() - 1;
+        self.set_cursor_selections(cursors, primary_ix);
+        self.scroll_to(self.cursor(), None, cx);
+        cx.notify();
+    }
+
+    /// Replace all the cursors, the `primary_ix` is the index of the primary cursor in `cursors`.
+    pub(super) fn set_cursor_selections(
+        &mut self,
+        cursors: Vec<CursorSelection>,
+        primary_ix: usize,
+    ) {
+        if cursors.is_empty() {
+            return;
+        }
+
+        let max = self.text.len();
+        let cursors = cursors
+            .into_iter()
+            .map(|mut c| {
+                c.shift(0, max);
+                c
+            })
+            .collect();
+        let (mut cursors, primary_ix) = merge_cursor_selections(cursors, primary_ix);
+        let primary = cursors.remove(primary_ix);
+        self.selected_range = primary.range;
+        self.selection_reversed = primary.reversed;
+        self.secondary_selections = cursors;
+        self.selected_word_range = None;
+    }
+
+    /// Run `f` for each cursor, if there are multiple cursors.
+    ///
+    /// The `f` will be called with the cursor as the `selected_range`, from the
+    /// last cursor to the first, so an edit only shifts the cursors that have
+    /// been processed. The second argument of `f` is the index of the cursor
+    /// in the document order.
+    ///
+    /// All changes made in this call are recorded as one undo step, and the
+    /// undo will restore all cursors.
+    pub(super) fn for_each_cursor(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        mut f: impl FnMut(&mut Self, usize, &mut Window, &mut Context<Self>),
+    ) {
+        if !self.has_multiple_cursors() {
+            f(self, 0, window, cx);
+            return;
+        }
+
+        let mut cursors = std::mem::take(&mut self.secondary_selections);
+        cursors.push(CursorSelection::new(
+            self.selected_range,
+            self.selection_reversed,
+        ));
+        let primary_ix = cursors.len() - 1;
+        self.cursors_before_edit = Some(cursors.clone());
+
+        let mut order = (0..cursors.len()).collect::<Vec<_>>();
+        order.sort_by_key(|ix| std::cmp::Reverse(cursors[*ix].range.start));
+
+        let total = order.len();
+        let mut processed: Vec<usize> = Vec::with_capacity(total);
+        for (i, ix) in order.into_iter().enumerate() {
+            self.selected_range = cursors[ix].range;
+            self.selection_reversed = cursors[ix].reversed;
+            self.update_preferred_column();
+
+            let old_len = self.text.len();
+            f(self, total - 1 - i, window, cx);
+            // Ensure no nested cursors was added by `f`.
+            self.secondary_selections.clear();
+
+            let delta = self.text.len() as isize - old_len as isize;
+            if delta != 0 {
+                let max = self.text.len();
+                for processed_ix in &processed {
+                    cursors[*processed_ix].shift(delta, max);
+                }
+            }
+
+            cursors[ix] = CursorSelection::new(self.selected_range, self.selection_reversed);
+            processed.push(ix);
+        }
+
+        self.cursors_before_edit = None;
+        self.history.end_grouping();
+        self.set_cursor_selections(cursors, primary_ix);
+        if let Some(batched_edit) = self.batched_edit.take() {
+            let (range, new_text) = batched_edit.edit(&self.text);
+            self.update_for_edit(&batched_edit.old_text, &range, &new_text, window, cx);
+            cx.emit(InputEvent::Change);
+        }
+        self.scroll_to(self.cursor(), None, cx);
+        cx.notify();
+    }
+
+    /// Record the edit of a cursor, the text states are updated after all cursors are edited.
+    pub(super) fn batch_edit(&mut self, old_text: &Rope, range: &Range<usize>) {
+        match self.batched_edit.as_mut() {
+            Some(batched_edit) => batched_edit.push(old_text, range),
+            None => self.batched_edit = Some(BatchedEdit::new(old_text, range)),
+        }
+    }
+
+    pub(super) fn add_cursor_above(
+        &mut self,
+        _: &AddCursorAbove,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.add_cursor_vertical(true, cx);
+    }
+
+    pub(super) fn add_cursor_below(
+        &mut self,
+        _: &AddCursorBelow,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.add_cursor_vertical(false, cx);
+    }
+
+    /// Add a cursor at the line above the top most cursor (or below the bottom most cursor).
+    fn add_cursor_vertical(&mut self, above: bool, cx: &mut Context<Self>) {
+        if self.mode.is_single_line() {
+            return;
+        }
+
+        let edge_offset = self
+            .secondary_selections
+            .iter()
+            .map(|c| c.cursor())
+            .chain(std::iter::once(self.cursor()));
+        let edge_offset = if above {
+            edge_offset.min()
+        } else {
+            edge_offset.max()
+        }
+        .unwrap_or_default();
+
+        let point = self.text.offset_to_point(edge_offset);
+        let row = if above {
+            let Some(row) = point.row.checked_sub(1) else {
+                return;
+            };
+            row
+        } else {
+            if point.row + 1 >= self.text.lines_len() {
+                return;
+            }
+            point.row + 1
+        };
+
+        // Keep the column of the primary cursor.
+        let column = self.text.offset_to_point(self.cursor()).column;
+        let line_start = self.text.line_start_offset(row);
+        let line_len = self.text.slice_line(row).len();
+        let offset = self
+            .text
+            .clip_offset(line_start + column.min(line_len), Bias::Left);
+
+        self.push_cursor(CursorSelection::new(offset..offset, false), cx);
+    }
+
+    pub(super) fn select_next_occurrence(
+        &mut self,
+        _: &SelectNextOccurrence,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.selected_range.is_empty() {
+            let Some(range) = self.text.word_range(self.cursor()) else {
+                return;
+            };
+            self.selected_range = range.into();
+            self.selection_reversed = false;
+            cx.notify();
+            return;
+        }
+
+        let query = self.selected_text().to_string();
+        if query.is_empty() {
+            return;
+        }
+
+        // Search from the end of the last cursor, and wrap around to the start.
+        let search_start = self
+            .secondary_selections
+            .iter()
+            .map(|c| c.range.end)
+            .chain(std::iter::once(self.selected_range.end))
+            .max()
+            .unwrap_or_default();
+
+        let text = self.text.to_string();
+        let found = text[search_start..]
+            .find(&query)
+            .map(|ix| search_start + ix)
+            .or_else(|| text[..search_start].find(&query));
+        let Some(start) = found else {
+            return;
+        };
+
+        let range: Selection = (start..start + query.len()).into();
+        let exists = range == self.selected_range
+            || self.secondary_selections.iter().any(|c| c.range == range);
+        if exists {
+            return;
+        }
+
+        self.add_selection(range.into(), window, cx);
+    }
+
+    /// Handle the mouse down with `alt` modifier, to add a cursor or start a column selection.
+    pub(super) fn handle_alt_mouse_down(
+        &mut self,
+        event: &MouseDownEvent,
+        offset: usize,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if !event.modifiers.alt || self.mode.is_single_line() {
+            return false;
+        }
+
+        self.column_select_anchor = Some(self.text.offset_to_point(offset));
+        self.push_cursor(CursorSelection::new(offset..offset, false), cx);
+        true
+    }
+
+    /// Update the column selection when alt-drag, returns true if handled.
+    pub(super) fn handle_column_select_drag(
+        &mut self,
+        offset: usize,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(anchor) = self.column_select_anchor else {
+            return false;
+        };
+
+        let head = self.text.offset_to_point(offset);
+        let cursors = column_selections(&self.text, anchor, head);
+        let primary_ix = cursors.len().saturating_sub(1);
+        self.set_cursor_selections(cursors, primary_ix);
+        cx.notify();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use gpui::{AppContext as _, TestAppContext};
+    use ropey::Rope;
+
+    use super::{CursorSelection, column_selections, merge_cursor_selections};
+    use crate::input::{InputEvent, InputState, RopeExt as _};
+
+    #[test]
+    fn test_merge_cursor_selections() {
+        let cursors = vec![
+            CursorSelection::new(10..10, false),
+            CursorSelection::new(2..5, false),
+            CursorSelection::new(4..8, true),
+            CursorSelection::new(10..10, false),
+            CursorSelection::new(20..22, false),
+        ];
+
+        let (merged, primary_ix) = merge_cursor_selections(cursors.clone(), 2);
+        assert_eq!(
+            merged
+                .iter()
+                .map(|c| c.range.start..c.range.end)
+                .collect::<Vec<_>>(),
+            vec![2..8, 10..10, 20..22]
+        );
+        assert_eq!(primary_ix, 0);
+
+        let (_, primary_ix) = merge_cursor_selections(cursors.clone(), 3);
+        assert_eq!(primary_ix, 1);
+        let (_, primary_ix) = merge_cursor_selections(cursors, 4);
+        assert_eq!(primary_ix, 2);
+
+        // Adjacent selections are not merged.
+        let cursors = vec![
+            CursorSelection::new(0..3, false),
+            CursorSelection::new(3..6, false),
+        ];
+        let (merged, _) = merge_cursor_selections(cursors, 0);
+        assert_eq!(merged.len(), 2);
+    }
+
+    #[gpui::test]
+    fn test_edit_multiple_cursors(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let changes = Rc::new(Cell::new(0));
+        let state = cx.update(|window, cx| {
+            let state = cx.new(|cx| {
+                InputState::new(window, cx)
+                    .multi_line(true)
+                    .default_value("a\nb\nc")
+            });
+            let changes = changes.clone();
+            cx.subscribe(&state, move |_, event: &InputEvent, _| {
+                if matches!(event, InputEvent::Change) {
+                    changes.set(changes.get() + 1);
+                }
+            })
+            .detach();
+            state
+        });
+
+        cx.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                let cursors = [1, 3, 5]
+                    .map(|offset| CursorSelection::new(offset..offset, false))
+                    .to_vec();
+                state.set_cursor_selections(cursors, 2);
+                state.replace_text_in_range_silent(None, "x\n", window, cx);
+
+                assert_eq!(state.text.to_string(), "ax\n\nbx\n\ncx\n");
+                assert_eq!(state.text_wrapper.len(), state.text.lines_len());
+                assert!(state.batched_edit.is_none());
+                let offsets = state
+                    .secondary_selections
+                    .iter()
+                    .map(|c| c.cursor())
+                    .chain([state.cursor()])
+                    .collect::<Vec<_>>();
+                assert_eq!(offsets, vec![3, 7, 11]);
+            })
+        });
+        // All the cursors are edited in one change.
+        assert_eq!(changes.get(), 1);
+    }
+
+    #[test]
+    fn test_column_selections() {
+        let text = Rope::from("hello world\nhi\n\nfoo bar baz");
+
+        let anchor = tree_sitter::Point::new(0, 1);
+        let head = tree_sitter::Point::new(3, 4);
+        let ranges = column_selections(&text, anchor, head)
+            .iter()
+            .map(|c| text.slice(c.range).to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, vec!["ell", "i", "", "oo "]);
+
+        // Reversed from bottom-right to top-left, the head row is the last.
+        let cursors = column_selections(&text, head, anchor);
+        assert_eq!(cursors.len(), 4);
+        assert!(cursors.iter().all(|c| c.reversed));
+        assert_eq!(cursors.last().unwrap().range.start, 1);
+    }
+}
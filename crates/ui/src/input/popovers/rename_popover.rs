@@ -0,0 +1,89 @@
+use std::ops::Range;
+
+use gpui::{
+    App, AppContext as _, Context, Entity, InteractiveElement as _, IntoElement,
+    ParentElement as _, Render, Styled as _, Subscription, Window, div, px,
+};
+
+use crate::{
+    Sizable as _,
+    input::{Enter, Escape, Input, InputEvent, InputState, SelectAll, popovers::Popover},
+};
+
+/// A popover with an input to enter the new name of the symbol to rename.
+pub struct RenamePopover {
+    editor: Entity<InputState>,
+    /// The symbol range byte to rename.
+    pub(crate) symbol_range: Range<usize>,
+    input: Entity<InputState>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl RenamePopover {
+    pub fn new(
+        editor: Entity<InputState>,
+        symbol_range: Range<usize>,
+        placeholder: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        cx.new(|cx| {
+            let input = cx.new(|cx| InputState::new(window, cx).default_value(placeholder));
+            input.update(cx, |input, cx| {
+                input.select_all(&SelectAll, window, cx);
+                input.focus(window, cx);
+            });
+
+            let _subscriptions = vec![cx.subscribe_in(
+                &input,
+                window,
+                |this: &mut Self, input, event: &InputEvent, window, cx| match event {
+                    InputEvent::PressEnter { .. } => {
+                        let new_name = input.read(cx).value();
+                        this.editor.update(cx, |editor, cx| {
+                            editor.confirm_rename(&new_name, window, cx);
+                        });
+                    }
+                    InputEvent::Blur => {
+                        this.editor.update(cx, |editor, cx| {
+                            editor.rename_popover = None;
+                            cx.notify();
+                        });
+                    }
+                    _ => {}
+                },
+            )];
+
+            Self {
+                editor,
+                symbol_range,
+                input,
+                _subscriptions,
+            }
+        })
+    }
+}
+
+impl Render for RenamePopover {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        let editor = self.editor.clone();
+        let input = self.input.clone();
+
+        Popover::new(
+            "rename-popover",
+            self.editor.clone(),
+            self.symbol_range.clone(),
+            move |_, _| {
+                let editor = editor.clone();
+                div()
+                    .w(px(240.))
+                    // Stop the Enter propagation to the editor, the event is handled by `PressEnter`.
+                    .on_action(|_: &Enter, _, _| {})
+                    .on_action(move |_: &Escape, window, cx| {
+                        editor.update(cx, |editor, cx| editor.cancel_rename(window, cx));
+                    })
+                    .child(Input::new(&input).small())
+            },
+        )
+    }
+}
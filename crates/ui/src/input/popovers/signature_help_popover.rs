@@ -0,0 +1,193 @@
+use std::{ops::Range, rc::Rc};
+
+use gpui::{
+    App, AppContext as _, Entity, FontWeight, HighlightStyle, IntoElement, ParentElement as _,
+    Render, SharedString, Styled as _, StyledText, Window, div, prelude::FluentBuilder as _,
+};
+
+use crate::{
+    ActiveTheme as _,
+    input::{InputState, popovers::Popover, popovers::render_markdown},
+    v_flex,
+};
+
+pub struct SignatureHelpPopover {
+    editor: Entity<InputState>,
+    /// The byte offset of the cursor that requested the signature help.
+    pub(crate) offset: usize,
+    pub(crate) signature_help: Rc<lsp_types::SignatureHelp>,
+}
+
+impl SignatureHelpPopover {
+    pub fn new(
+        editor: Entity<InputState>,
+        offset: usize,
+        signature_help: &lsp_types::SignatureHelp,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        let signature_help = Rc::new(signature_help.clone());
+
+        cx.new(|_| Self {
+            editor,
+            offset,
+            signature_help,
+        })
+    }
+}
+
+/// Returns the byte range of the parameter in the signature label.
+fn parameter_range(
+    label: &str,
+    parameter: &lsp_types::ParameterInformation,
+) -> Option<Range<usize>> {
+    match &parameter.label {
+        lsp_types::ParameterLabel::Simple(text) => {
+            if text.is_empty() {
+                return None;
+            }
+
+            label
+                .find(text.as_str())
+                .map(|start| start..start + text.len())
+        }
+        lsp_types::ParameterLabel::LabelOffsets([start, end]) => {
+            // The offsets are UTF-16 code units.
+            let mut utf16_offset = 0;
+            let mut range = None::<Range<usize>>;
+            for (ix, c) in label.char_indices() {
+                if utf16_offset == *start as usize {
+                    range = Some(ix..label.len());
+                }
+                if utf16_offset == *end as usize {
+                    range = range.map(|range| range.start..ix);
+                    break;
+                }
+                utf16_offset += c.len_utf16();
+            }
+
+            range
+        }
+    }
+}
+
+fn documentation_text(documentation: &lsp_types::Documentation) -> String {
+    match documentation {
+        lsp_types::Documentation::String(s) => s.clone(),
+        lsp_types::Documentation::MarkupContent(markup) => markup.value.clone(),
+    }
+}
+
+impl Render for SignatureHelpPopover {
+    fn render(&mut self, _: &mut Window, cx: &mut gpui::Context<Self>) -> impl IntoElement {
+        let signature_help = self.signature_help.clone();
+        let active_ix = signature_help.active_signature.unwrap_or(0) as usize;
+        let Some(signature) = signature_help
+            .signatures
+            .get(active_ix)
+            .or(signature_help.signatures.first())
+            .cloned()
+        else {
+            return div().into_any_element();
+        };
+
+        let signatures_count = signature_help.signatures.len();
+        let active_parameter = signature
+            .active_parameter
+            .or(signature_help.active_parameter)
+            .unwrap_or(0) as usize;
+        let parameter = signature
+            .parameters
+            .as_ref()
+            .and_then(|parameters| parameters.get(active_parameter))
+            .cloned();
+
+        let label = SharedString::from(signature.label.clone());
+        let mut highlights = vec![];
+        if let Some(range) = parameter
+            .as_ref()
+            .and_then(|parameter| parameter_range(&label, parameter))
+        {
+            highlights.push((
+                range,
+                HighlightStyle {
+                    color: Some(cx.theme().blue),
+                    font_weight: Some(FontWeight::BOLD),
+                    ..Default::default()
+                },
+            ));
+        }
+
+        let documentation = parameter
+            .and_then(|parameter| parameter.documentation)
+            .or(signature.documentation)
+            .map(|documentation| documentation_text(&documentation))
+            .filter(|documentation| !documentation.is_empty());
+        let muted_foreground = cx.theme().muted_foreground;
+
+        Popover::new(
+            "signature-help-popover",
+            self.editor.clone(),
+            self.offset..self.offset,
+            move |window, cx| {
+                v_flex()
+                    .gap_1()
+                    .child(
+                        div()
+                            .flex()
+                            .gap_2()
+                            .font_family(cx.theme().mono_font_family.clone())
+                            .when(signatures_count > 1, |this| {
+                                this.child(
+                                    div()
+                                        .flex_none()
+                                        .text_color(muted_foreground)
+                                        .child(format!("{}/{}", active_ix + 1, signatures_count)),
+                                )
+                            })
+                            .child(
+                                StyledText::new(label.clone()).with_highlights(highlights.clone()),
+                            ),
+                    )
+                    .when_some(documentation.clone(), |this, documentation| {
+                        this.child(render_markdown("documentation", documentation, window, cx))
+                    })
+            },
+        )
+        .into_any_element()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{ParameterInformation, ParameterLabel};
+
+    use super::parameter_range;
+
+    #[test]
+    fn test_parameter_range() {
+        let label = "fn add(a: i32, b: i32) -> i32";
+        let parameter = |label: ParameterLabel| ParameterInformation {
+            label,
+            documentation: None,
+        };
+
+        assert_eq!(
+            parameter_range(label, &parameter(ParameterLabel::Simple("b: i32".into()))),
+            Some(15..21)
+        );
+        assert_eq!(
+            parameter_range(label, &parameter(ParameterLabel::LabelOffsets([7, 13]))),
+            Some(7..13)
+        );
+        assert_eq!(
+            parameter_range(label, &parameter(ParameterLabel::Simple("".into()))),
+            None
+        );
+
+        let label = "fn 你好(名: &str)";
+        assert_eq!(
+            parameter_range(label, &parameter(ParameterLabel::LabelOffsets([6, 13]))),
+            Some(10..19)
+        );
+    }
+}
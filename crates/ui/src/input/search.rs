@@ -1,11 +1,12 @@
 use aho_corasick::AhoCorasick;
+use regex::{Regex, RegexBuilder};
 use rust_i18n::t;
-use std::{ops::Range, rc::Rc};
+use std::{ops::Range, rc::Rc, time::Duration};
 
 use gpui::{
     App, AppContext as _, Context, Empty, Entity, FocusHandle, Focusable, Half,
     InteractiveElement as _, IntoElement, KeyBinding, ParentElement as _, Pixels, Render, Styled,
-    Subscription, Window, actions, canvas, div, prelude::FluentBuilder as _,
+    Subscription, Task, Window, actions, canvas, div, prelude::FluentBuilder as _,
 };
 use ropey::Rope;
 
@@ -16,13 +17,15 @@ use crate::{
     h_flex,
     input::{
         Enter, Escape, IndentInline, Input, InputEvent, InputState, RopeExt as _, Search,
-        movement::MoveDirection,
+        movement::MoveDirection, selection::TextSelector,
     },
     label::Label,
     v_flex,
 };
 
 const CONTEXT: &'static str = "SearchPanel";
+/// The delay to match the regular expression again after the text changed.
+const UPDATE_MATCHES_DELAY: Duration = Duration::from_millis(100);
 
 actions!(input, [Tab]);
 
@@ -34,10 +37,114 @@ pub(super) fn init(cx: &mut App) {
     )]);
 }
 
+/// The compiled search query.
+#[derive(Debug, Clone)]
+pub enum SearchQuery {
+    Literal(AhoCorasick),
+    Regex(Regex),
+}
+
+/// A [`std::io::Read`] of the rope chunks, to stream find in the rope without copying.
+struct RopeReader<'a> {
+    chunks: ropey::iter::Chunks<'a>,
+    chunk: &'a [u8],
+}
+
+impl<'a> RopeReader<'a> {
+    fn new(text: &'a Rope) -> Self {
+        Self {
+            chunks: text.chunks(),
+            chunk: &[],
+        }
+    }
+}
+
+impl std::io::Read for RopeReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.chunk.is_empty() {
+            match self.chunks.next() {
+                Some(chunk) => self.chunk = chunk.as_bytes(),
+                None => return Ok(0),
+            }
+        }
+
+        let len = buf.len().min(self.chunk.len());
+        buf[..len].copy_from_slice(&self.chunk[..len]);
+        self.chunk = &self.chunk[len..];
+        Ok(len)
+    }
+}
+
+/// Returns true if the `range` is not a part of a larger word.
+fn is_whole_word(text: &Rope, range: &Range<usize>) -> bool {
+    if range.is_empty() {
+        return false;
+    }
+
+    let start_matched =
+        TextSelector::word_range(text, range.start).is_none_or(|word| word.start == range.start);
+    let end_matched =
+        TextSelector::word_range(text, range.end - 1).is_none_or(|word| word.end == range.end);
+
+    start_matched && end_matched
+}
+
+/// Returns the new offset of the `offset` after the `range` is replaced by `new_len` bytes.
+///
+/// The offsets in the replaced range are moved to the start of the range.
+fn offset_after_edit(offset: usize, range: &Range<usize>, new_len: usize) -> usize {
+    if offset >= range.end {
+        offset + new_len - range.len()
+    } else {
+        offset.min(range.start)
+    }
+}
+
+/// Returns the ranges of the `query` matched in the `text`.
+fn find_matches(
+    query: Option<&SearchQuery>,
+    text: &Rope,
+    whole_word: bool,
+    scope: Option<&Range<usize>>,
+) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = match query {
+        Some(SearchQuery::Literal(query)) => query
+            .stream_find_iter(RopeReader::new(text))
+            .filter_map(|query_match| query_match.ok())
+            .map(|query_match| query_match.range())
+            .collect(),
+        Some(SearchQuery::Regex(query)) => {
+            let text = text.to_string();
+            query
+                .find_iter(&text)
+                .filter(|query_match| !query_match.is_empty())
+                .map(|query_match| query_match.range())
+                .collect()
+        }
+        None => vec![],
+    };
+
+    if whole_word {
+        ranges.retain(|range| is_whole_word(text, range));
+    }
+    if let Some(scope) = scope {
+        ranges.retain(|range| range.start >= scope.start && range.end <= scope.end);
+    }
+    ranges
+}
+
 #[derive(Debug, Clone)]
 pub struct SearchMatcher {
     text: Rope,
-    pub query: Option<AhoCorasick>,
+    pub query: Option<SearchQuery>,
+    /// Match the query as a regular expression.
+    pub(super) regex: bool,
+    /// Only match the whole words.
+    pub(super) whole_word: bool,
+    /// Only match in this range (UTF-8 offsets), for search in selection.
+    pub(super) scope: Option<Range<usize>>,
+    /// Is the regular expression invalid.
+    pub(super) invalid_query: bool,
 
     pub(super) matched_ranges: Rc<Vec<Range<usize>>>,
     pub(super) current_match_ix: usize,
@@ -50,6 +157,10 @@ impl SearchMatcher {
         Self {
             text: "".into(),
             query: None,
+            regex: false,
+            whole_word: false,
+            scope: None,
+            invalid_query: false,
             matched_ranges: Rc::new(Vec::new()),
             current_match_ix: 0,
             replacing: false,
@@ -63,43 +174,155 @@ impl SearchMatcher {
         }
 
         self.text = text.clone();
-        self.update_matches();
+        if let Some(scope) = self.scope.as_mut() {
+            scope.end = scope.end.min(text.len());
+            scope.start = scope.start.min(scope.end);
+        }
+
+        let ranges = find_matches(
+            self.query.as_ref(),
+            &self.text,
+            self.whole_word,
+            self.scope.as_ref(),
+        );
+        self.set_matches(ranges);
     }
 
-    fn update_matches(&mut self) {
-        let mut new_ranges = Vec::new();
-        if let Some(query) = &self.query {
-            let text = self.text.to_string();
-            // FIXME: Use stream find
-            let matches = query.stream_find_iter(text.as_bytes());
-
-            for query_match in matches.into_iter() {
-                let query_match = query_match.expect("query match for select all action");
-                new_ranges.push(query_match.range());
+    /// Move the scope and the matches after the `range` of the text is replaced by `new_len` bytes.
+    ///
+    /// The matches overlapping the edit are removed, until the text is matched again.
+    fn edit(&mut self, range: &Range<usize>, new_len: usize) {
+        if let Some(scope) = self.scope.as_mut() {
+            // The text inserted at the edges of the scope is in the scope.
+            let start = if scope.start <= range.start {
+                scope.start
+            } else {
+                offset_after_edit(scope.start, range, new_len)
+            };
+            let end = if scope.end > range.start && scope.end < range.end {
+                range.start + new_len
+            } else {
+                offset_after_edit(scope.end, range, new_len)
+            };
+            *scope = start..end;
+        }
+
+        let current_match_ix = self.current_match_ix;
+        let mut removed_before_current = 0;
+        let mut ranges = Vec::with_capacity(self.matched_ranges.len());
+        for (ix, match_range) in self.matched_ranges.iter().enumerate() {
+            if match_range.end <= range.start {
+                ranges.push(match_range.clone());
+            } else if match_range.start >= range.end {
+                ranges.push(
+                    offset_after_edit(match_range.start, range, new_len)
+                        ..offset_after_edit(match_range.end, range, new_len),
+                );
+            } else if ix < current_match_ix {
+                removed_before_current += 1;
             }
         }
-        self.matched_ranges = Rc::new(new_ranges);
-        if !self.replacing {
-            self.current_match_ix = 0;
+        self.current_match_ix =
+            (current_match_ix - removed_before_current).min(ranges.len().saturating_sub(1));
+        self.matched_ranges = Rc::new(ranges);
+    }
+
+    /// Set the matched ranges of the text, and keep the current match at the same position.
+    fn set_matches(&mut self, ranges: Vec<Range<usize>>) {
+        let current_start = self
+            .matched_ranges
+            .get(self.current_match_ix)
+            .map(|range| range.start);
+        let current_match_ix = self.current_match_ix;
+        self.matched_ranges = Rc::new(ranges);
+        self.current_match_ix = 0;
+        if self.replacing {
+            self.current_match_ix = current_match_ix.min(self.len().saturating_sub(1));
             self.replacing = false;
+        } else if let Some(start) = current_start {
+            self.current_match_ix = self
+                .matched_ranges
+                .iter()
+                .position(|range| range.start >= start)
+                .unwrap_or(0);
         }
     }
 
+    fn update_matches(&mut self) {
+        self.matched_ranges = Rc::new(find_matches(
+            self.query.as_ref(),
+            &self.text,
+            self.whole_word,
+            self.scope.as_ref(),
+        ));
+        self.current_match_ix = 0;
+    }
+
     /// Update the search query and reset the current match index.
     pub fn update_query(&mut self, query: &str, case_insensitive: bool) {
-        if query.len() > 0 {
-            self.query = Some(
+        self.invalid_query = false;
+        self.query = if query.is_empty() {
+            None
+        } else if self.regex {
+            match RegexBuilder::new(query)
+                .case_insensitive(case_insensitive)
+                .multi_line(true)
+                .build()
+            {
+                Ok(regex) => Some(SearchQuery::Regex(regex)),
+                Err(_) => {
+                    self.invalid_query = true;
+                    None
+                }
+            }
+        } else {
+            Some(SearchQuery::Literal(
                 AhoCorasick::builder()
                     .ascii_case_insensitive(case_insensitive)
                     .build(&[query.to_string()])
                     .expect("failed to build AhoCorasick query in SearchMatcher"),
-            );
-        } else {
-            self.query = None;
-        }
+            ))
+        };
         self.update_matches();
     }
 
+    /// Returns the replacement of the matched `range` in the `text`.
+    ///
+    /// For the regular expression, the capture groups (e.g. `$1`, `${name}`) in `replace` are expanded.
+    fn replacement(&self, text: &str, range: &Range<usize>, replace: &str) -> String {
+        let Some(SearchQuery::Regex(regex)) = &self.query else {
+            return replace.to_string();
+        };
+
+        let Some(captures) = regex
+            .captures_at(text, range.start)
+            .filter(|captures| captures.get(0).is_some_and(|m| m.range() == *range))
+        else {
+            return replace.to_string();
+        };
+
+        let mut new_text = String::new();
+        captures.expand(replace, &mut new_text);
+        new_text
+    }
+
+    /// Returns the replacements of the matched `ranges`.
+    ///
+    /// The text is only copied when the capture groups of the regular expression need expanding.
+    fn replacements(&self, ranges: &[Range<usize>], replace: &str) -> Vec<String> {
+        let has_captures =
+            matches!(self.query, Some(SearchQuery::Regex(_))) && replace.contains('$');
+        if !has_captures {
+            return vec![replace.to_string(); ranges.len()];
+        }
+
+        let text = self.text.to_string();
+        ranges
+            .iter()
+            .map(|range| self.replacement(&text, range, replace))
+            .collect()
+    }
+
     /// Returns the number of matches found.
     #[allow(unused)]
     #[inline]
@@ -115,7 +338,11 @@ impl SearchMatcher {
         if self.len() == 0 {
             return "0/0".to_string();
         }
-        format!("{}/{}", self.current_match_ix + 1, self.len())
+        format!(
+            "{}/{}",
+            self.current_match_ix.min(self.len() - 1) + 1,
+            self.len()
+        )
     }
 
     /// Update the current match index based on the given offset.
@@ -174,19 +401,20 @@ pub(super) struct SearchPanel {
     input_width: Pixels,
 
     open: bool,
+    _update_matches_task: Task<()>,
     _subscriptions: Vec<Subscription>,
 }
 
 impl InputState {
-    /// Update the search matcher when text changes.
-    pub(super) fn update_search(&mut self, cx: &mut App) {
+    /// Update the search matcher after the `range` of the text is replaced by `new_len` bytes.
+    pub(super) fn update_search(&mut self, range: &Range<usize>, new_len: usize, cx: &mut App) {
         let Some(search_panel) = self.search_panel.as_ref() else {
             return;
         };
 
         let text = self.text.clone();
-        search_panel.update(cx, |this, _| {
-            this.matcher.update(&text);
+        search_panel.update(cx, |this, cx| {
+            this.update_text(&text, range, new_len, cx);
         });
     }
 
@@ -208,10 +436,13 @@ impl InputState {
         let text = self.text.clone();
         let editor = cx.entity();
         let selected_text = Rope::from(self.selected_text());
+        // Search in the selection, if select multiple lines.
+        let scope = (selected_text.lines_len() > 1)
+            .then_some(self.selected_range.start..self.selected_range.end);
         search_panel.update(cx, |this, cx| {
             this.editor = editor;
             this.matcher.update(&text);
-            this.show(&selected_text, window, cx);
+            this.show(&selected_text, scope, window, cx);
         });
         self.search_panel = Some(search_panel);
         cx.notify();
@@ -246,6 +477,7 @@ impl SearchPanel {
                 matcher: SearchMatcher::new(),
                 open: true,
                 input_width: Pixels::ZERO,
+                _update_matches_task: Task::ready(()),
                 _subscriptions,
             }
         })
@@ -254,14 +486,21 @@ impl SearchPanel {
     pub(super) fn show(
         &mut self,
         selected_text: &Rope,
+        scope: Option<Range<usize>>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
         self.open = true;
         self.search_input.read(cx).focus_handle.focus(window);
 
+        let in_selection = scope.is_some();
+        if self.matcher.scope != scope {
+            self.matcher.scope = scope;
+            self.update_search_query(cx);
+        }
+
         self.search_input.update(cx, |this, cx| {
-            if selected_text.len() > 0 {
+            if selected_text.len() > 0 && !in_selection {
                 // Set value will emit to update_search_query
                 this.set_value(selected_text.to_string(), window, cx);
             }
@@ -269,7 +508,48 @@ impl SearchPanel {
         });
     }
 
+    /// Update the matches after the `range` of the editor text is replaced by `new_len` bytes.
+    ///
+    /// The regular expression is matched again in the background after a delay,
+    /// to avoid copying the whole text on every keystroke.
+    fn update_text(
+        &mut self,
+        text: &Rope,
+        range: &Range<usize>,
+        new_len: usize,
+        cx: &mut Context<Self>,
+    ) {
+        self.matcher.edit(range, new_len);
+        if !matches!(self.matcher.query, Some(SearchQuery::Regex(_))) {
+            self._update_matches_task = Task::ready(());
+            self.matcher.update(text);
+            return;
+        }
+
+        self.matcher.text = text.clone();
+        let text = text.clone();
+        let query = self.matcher.query.clone();
+        let whole_word = self.matcher.whole_word;
+        let scope = self.matcher.scope.clone();
+        self._update_matches_task = cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(UPDATE_MATCHES_DELAY).await;
+
+            let ranges = cx
+                .background_executor()
+                .spawn(
+                    async move { find_matches(query.as_ref(), &text, whole_word, scope.as_ref()) },
+                )
+                .await;
+            _ = this.update(cx, |this, cx| {
+                this.matcher.set_matches(ranges);
+                cx.notify();
+            });
+        });
+    }
+
     fn update_search_query(&mut self, cx: &mut Context<Self>) {
+        // The query is matched with the current text at once.
+        self._update_matches_task = Task::ready(());
         let query = self.search_input.read(cx).value();
         let visible_range_offset = self
             .editor
@@ -288,6 +568,30 @@ impl SearchPanel {
         cx.notify();
     }
 
+    fn toggle_whole_word(&mut self, cx: &mut Context<Self>) {
+        self.matcher.whole_word = !self.matcher.whole_word;
+        self.update_search_query(cx);
+    }
+
+    fn toggle_regex(&mut self, cx: &mut Context<Self>) {
+        self.matcher.regex = !self.matcher.regex;
+        self.update_search_query(cx);
+    }
+
+    /// Toggle to search in the selected range of the editor.
+    fn toggle_in_selection(&mut self, cx: &mut Context<Self>) {
+        if self.matcher.scope.is_some() {
+            self.matcher.scope = None;
+        } else {
+            let selected_range = self.editor.read(cx).selected_range;
+            if selected_range.is_empty() {
+                return;
+            }
+            self.matcher.scope = Some(selected_range.start..selected_range.end);
+        }
+        self.update_search_query(cx);
+    }
+
     pub(super) fn hide(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.open = false;
         self.editor.read(cx).focus_handle.focus(window);
@@ -335,7 +639,7 @@ impl SearchPanel {
     }
 
     fn replace_next(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        let new_text = self.replace_input.read(cx).value();
+        let replace = self.replace_input.read(cx).value();
         self.matcher.replacing = true;
         if let Some(range) = self
             .matcher
@@ -344,6 +648,10 @@ impl SearchPanel {
             .cloned()
         {
             let text_state = self.editor.clone();
+            let new_text = self
+                .matcher
+                .replacements(std::slice::from_ref(&range), replace.as_str())
+                .remove(0);
 
             let next_range = self.matcher.peek().unwrap_or(range.clone());
             cx.spawn_in(window, async move |_, cx| {
@@ -365,28 +673,30 @@ impl SearchPanel {
     }
 
     fn replace_all(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        let new_text = self.replace_input.read(cx).value();
+        let replace = self.replace_input.read(cx).value();
         self.matcher.replacing = true;
         let ranges = self.matcher.matched_ranges.clone();
         if ranges.is_empty() {
             return;
         }
 
+        let new_texts = self.matcher.replacements(&ranges, replace.as_str());
         let editor = self.editor.clone();
         cx.spawn_in(window, async move |_, cx| {
             cx.update(|window, cx| {
                 editor.update(cx, |state, cx| {
-                    // Replace from the end to avoid messing up the ranges.
-                    let mut rope = state.text.clone();
-                    for range in ranges.iter().rev() {
-                        rope.replace(range.clone(), new_text.as_str());
+                    // Only replace the text from the first to the last match,
+                    // to keep the search scope around them.
+                    let edit_range = ranges[0].start..ranges[ranges.len() - 1].end;
+                    let mut new_text = String::new();
+                    let mut offset = edit_range.start;
+                    for (range, text) in ranges.iter().zip(new_texts.iter()) {
+                        new_text.push_str(&state.text.slice(offset..range.start).to_string());
+                        new_text.push_str(text);
+                        offset = range.end;
                     }
-                    state.replace_text_in_range_silent(
-                        Some(0..state.text.len()),
-                        &rope.to_string(),
-                        window,
-                        cx,
-                    );
+                    let range_utf16 = state.range_to_utf16(&edit_range);
+                    state.replace_text_in_range_silent(Some(range_utf16), &new_text, window, cx);
                     state.scroll_to(0, Some(MoveDirection::Down), cx);
                 });
             })
@@ -440,17 +750,44 @@ impl Render for SearchPanel {
                                 Input::new(&self.search_input)
                                     .focus_bordered(false)
                                     .suffix(
-                                        Button::new("case-insensitive")
-                                            .selected(!self.case_insensitive)
-                                            .xsmall()
-                                            .compact()
-                                            .ghost()
-                                            .icon(IconName::CaseSensitive)
-                                            .on_click(cx.listener(|this, _, _, cx| {
-                                                this.case_insensitive = !this.case_insensitive;
-                                                this.update_search_query(cx);
-                                                cx.notify();
-                                            })),
+                                        h_flex()
+                                            .gap_0p5()
+                                            .child(
+                                                Button::new("case-insensitive")
+                                                    .selected(!self.case_insensitive)
+                                                    .xsmall()
+                                                    .compact()
+                                                    .ghost()
+                                                    .icon(IconName::CaseSensitive)
+                                                    .on_click(cx.listener(|this, _, _, cx| {
+                                                        this.case_insensitive =
+                                                            !this.case_insensitive;
+                                                        this.update_search_query(cx);
+                                                        cx.notify();
+                                                    })),
+                                            )
+                                            .child(
+                                                Button::new("whole-word")
+                                                    .selected(self.matcher.whole_word)
+                                                    .xsmall()
+                                                    .compact()
+                                                    .ghost()
+                                                    .icon(IconName::WholeWord)
+                                                    .on_click(cx.listener(|this, _, _, cx| {
+                                                        this.toggle_whole_word(cx);
+                                                    })),
+                                            )
+                                            .child(
+                                                Button::new("regex")
+                                                    .selected(self.matcher.regex)
+                                                    .xsmall()
+                                                    .compact()
+                                                    .ghost()
+                                                    .icon(IconName::Regex)
+                                                    .on_click(cx.listener(|this, _, _, cx| {
+                                                        this.toggle_regex(cx);
+                                                    })),
+                                            ),
                                     )
                                     .small()
                                     .w_full()
@@ -472,6 +809,16 @@ impl Render for SearchPanel {
                                 .size_full(),
                             ),
                     )
+                    .child(
+                        Button::new("in-selection")
+                            .xsmall()
+                            .ghost()
+                            .icon(IconName::TextSelect)
+                            .selected(self.matcher.scope.is_some())
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.toggle_in_selection(cx);
+                            })),
+                    )
                     .child(
                         Button::new("replace-mode")
                             .xsmall()
@@ -513,6 +860,9 @@ impl Render for SearchPanel {
                             .when(!has_matches, |this| {
                                 this.text_color(cx.theme().muted_foreground)
                             })
+                            .when(self.matcher.invalid_query, |this| {
+                                this.text_color(cx.theme().danger)
+                            })
                             .text_left()
                             .min_w_16(),
                     )
@@ -612,6 +962,167 @@ mod tests {
         assert_eq!(matcher.label(), "0/0");
     }
 
+    #[test]
+    fn test_search_regex() {
+        let mut matcher = SearchMatcher::new();
+        matcher.update(&Rope::from("let a1 = 10;\nlet b22 = 200;"));
+        matcher.regex = true;
+        matcher.update_query(r"(\w)(\d+) = (\d+)", false);
+        assert_eq!(matcher.len(), 2);
+        assert_eq!(matcher.matched_ranges.as_ref(), &vec![4..11, 17..26]);
+
+        let text = matcher.text.to_string();
+        assert_eq!(
+            matcher.replacement(&text, &(17..26), "${3} = $1$2"),
+            "200 = b22"
+        );
+        assert_eq!(
+            matcher.replacements(&[4..11, 17..26], "$1 = $3"),
+            vec!["a = 10", "b = 200"]
+        );
+
+        matcher.update_query(r"^let", false);
+        assert_eq!(matcher.matched_ranges.as_ref(), &vec![0..3, 13..16]);
+
+        matcher.update_query(r"(unclosed", false);
+        assert!(matcher.invalid_query);
+        assert_eq!(matcher.len(), 0);
+        assert_eq!(matcher.label(), "0/0");
+
+        // Not expand the `$1` for the literal query.
+        matcher.regex = false;
+        matcher.update_query("a1", false);
+        assert!(!matcher.invalid_query);
+        let text = matcher.text.to_string();
+        assert_eq!(matcher.replacement(&text, &(4..6), "$1"), "$1");
+        assert_eq!(matcher.replacements(&[4..6], "$1"), vec!["$1"]);
+    }
+
+    #[test]
+    fn test_search_whole_word_and_scope() {
+        let mut matcher = SearchMatcher::new();
+        matcher.update(&Rope::from("test testing test_a test"));
+        matcher.update_query("test", false);
+        assert_eq!(matcher.len(), 4);
+
+        matcher.whole_word = true;
+        matcher.update_query("test", false);
+        assert_eq!(matcher.matched_ranges.as_ref(), &vec![0..4, 20..24]);
+
+        matcher.whole_word = false;
+        matcher.scope = Some(5..19);
+        matcher.update_query("test", false);
+        assert_eq!(matcher.matched_ranges.as_ref(), &vec![5..9, 13..17]);
+    }
+
+    #[test]
+    fn test_search_large_rope() {
+        let line = "hello world\n";
+        let text = Rope::from(line.repeat(10_000));
+        let mut matcher = SearchMatcher::new();
+        matcher.update(&text);
+        matcher.update_query("world", false);
+        assert_eq!(matcher.len(), 10_000);
+        assert_eq!(matcher.matched_ranges[9_999].start, 9_999 * line.len() + 6);
+
+        // Keep the current match after the text changed.
+        matcher.current_match_ix = 5;
+        let mut text = text.clone();
+        text.insert(0, "world ");
+        matcher.update(&text);
+        assert_eq!(matcher.len(), 10_001);
+        assert_eq!(matcher.label(), "7/10001");
+    }
+
+    #[test]
+    fn test_search_edit() {
+        let mut text = Rope::from("foo bar\nfoo bar\nfoo bar");
+        let mut matcher = SearchMatcher::new();
+        matcher.scope = Some(8..15);
+        matcher.update(&text);
+        matcher.update_query("foo", false);
+        assert_eq!(matcher.matched_ranges.as_ref(), &vec![8..11]);
+
+        // The scope is moved by the edit before it.
+        text.replace(0..3, "hello");
+        matcher.edit(&(0..3), 5);
+        matcher.update(&text);
+        assert_eq!(matcher.scope, Some(10..17));
+        assert_eq!(matcher.matched_ranges.as_ref(), &vec![10..13]);
+
+        // The scope is resized by the edit in it.
+        text.replace(14..17, "foo foo");
+        matcher.edit(&(14..17), 7);
+        matcher.update(&text);
+        assert_eq!(matcher.scope, Some(10..21));
+        assert_eq!(
+            matcher.matched_ranges.as_ref(),
+            &vec![10..13, 14..17, 18..21]
+        );
+
+        // The scope is not changed by the edit after it.
+        text.replace(22..25, "");
+        matcher.edit(&(22..25), 0);
+        matcher.update(&text);
+        assert_eq!(matcher.scope, Some(10..21));
+
+        // The scope is cut by the edit overlapping its start.
+        text.replace(8..12, "");
+        matcher.edit(&(8..12), 0);
+        matcher.update(&text);
+        assert_eq!(matcher.scope, Some(8..17));
+        assert_eq!(matcher.matched_ranges.as_ref(), &vec![10..13, 14..17]);
+    }
+
+    #[gpui::test]
+    fn test_search_regex_after_edit(cx: &mut gpui::TestAppContext) {
+        let cx = cx.add_empty_window();
+        let (editor, search_panel) = cx.update(|window, cx| {
+            let editor = cx.new(|cx| {
+                InputState::new(window, cx)
+                    .multi_line(true)
+                    .default_value("a1 b2\nc3")
+            });
+            let search_panel = SearchPanel::new(editor.clone(), window, cx);
+            let text = editor.read(cx).text.clone();
+            search_panel.update(cx, |this, _| {
+                this.matcher.regex = true;
+                this.matcher.update(&text);
+                this.matcher.update_query(r"\w\d", false);
+            });
+            editor.update(cx, |editor, _| {
+                editor.search_panel = Some(search_panel.clone())
+            });
+            (editor, search_panel)
+        });
+        search_panel.read_with(cx, |this, _| {
+            assert_eq!(
+                this.matcher.matched_ranges.as_ref(),
+                &vec![0..2, 3..5, 6..8]
+            );
+        });
+
+        // The matches are moved at once, the edited one is removed.
+        cx.update(|window, cx| {
+            editor.update(cx, |editor, cx| {
+                editor.replace_text_in_range_silent(Some(3..4), "d4 e", window, cx);
+            })
+        });
+        search_panel.read_with(cx, |this, _| {
+            assert_eq!(this.matcher.matched_ranges.as_ref(), &vec![0..2, 9..11]);
+        });
+
+        // The text is matched again after the delay.
+        cx.executor().advance_clock(UPDATE_MATCHES_DELAY);
+        cx.run_until_parked();
+        search_panel.read_with(cx, |this, _| {
+            assert_eq!(
+                this.matcher.matched_ranges.as_ref(),
+                &vec![0..2, 3..5, 6..8, 9..11]
+            );
+        });
+    }
+
     #[test]
     fn test_select_range_start() {
         let mut matcher = SearchMatcher::new();
@@ -18,8 +18,16 @@ use sum_tree::Bias;
 use unicode_segmentation::*;
 
 use super::{
-    blink_cursor::BlinkCursor, change::Change, element::TextElement, mask_pattern::MaskPattern,
-    mode::InputMode, number_input, text_wrapper::TextWrapper,
+    blink_cursor::BlinkCursor,
+    change::Change,
+    diff::DiffHunk,
+    element::TextElement,
+    fold::FoldRange,
+    mask_pattern::MaskPattern,
+    mode::InputMode,
+    multi_cursor::{BatchedEdit, CursorSelection},
+    number_input,
+    text_wrapper::TextWrapper,
 };
 use crate::Size;
 use crate::actions::{SelectDown, SelectLeft, SelectRight, SelectUp};
@@ -27,7 +35,10 @@ use crate::input::movement::MoveDirection;
 use crate::input::{
     HoverDefinition, Lsp, Position,
     element::RIGHT_MARGIN,
-    popovers::{ContextMenu, DiagnosticPopover, HoverPopover, MouseContextMenu},
+    popovers::{
+        ContextMenu, DiagnosticPopover, HoverPopover, MouseContextMenu, RenamePopover,
+        SignatureHelpPopover,
+    },
     search::{self, SearchPanel},
     text_wrapper::LineLayout,
 };
@@ -86,6 +97,29 @@ actions!(
         ToggleCodeActions,
         Search,
         GoToDefinition,
+        AddCursorAbove,
+        AddCursorBelow,
+        SelectNextOccurrence,
+        Fold,
+        Unfold,
+        FoldAll,
+        UnfoldAll,
+        Rename,
+        Format,
+        FormatSelection,
+        ShowSignatureHelp,
+        GoToNextHunk,
+        GoToPreviousHunk,
+        RevertHunk,
+        MoveToMatchingBracket,
+        Reindent,
+        MoveLineUp,
+        MoveLineDown,
+        DuplicateLine,
+        JoinLines,
+        DeleteLine,
+        ToggleLineComment,
+        ToggleBlockComment,
     ]
 );
 
@@ -216,6 +250,77 @@ pub(crate) fn init(cx: &mut App) {
         KeyBinding::new("cmd-f", Search, Some(CONTEXT)),
         #[cfg(not(target_os = "macos"))]
         KeyBinding::new("ctrl-f", Search, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-alt-up", AddCursorAbove, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-alt-up", AddCursorAbove, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-alt-down", AddCursorBelow, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-alt-down", AddCursorBelow, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-d", SelectNextOccurrence, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-d", SelectNextOccurrence, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-alt-[", Fold, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-shift-[", Fold, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-alt-]", Unfold, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-shift-]", Unfold, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-k cmd-0", FoldAll, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-k ctrl-0", FoldAll, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-k cmd-j", UnfoldAll, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-k ctrl-j", UnfoldAll, Some(CONTEXT)),
+        KeyBinding::new("f2", Rename, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("alt-shift-f", Format, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-shift-i", Format, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-k cmd-f", FormatSelection, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-k ctrl-f", FormatSelection, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-shift-space", ShowSignatureHelp, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-shift-space", ShowSignatureHelp, Some(CONTEXT)),
+        KeyBinding::new("alt-f5", GoToNextHunk, Some(CONTEXT)),
+        KeyBinding::new("alt-shift-f5", GoToPreviousHunk, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-alt-z", RevertHunk, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-alt-z", RevertHunk, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-shift-\\", MoveToMatchingBracket, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-shift-\\", MoveToMatchingBracket, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-k cmd-i", Reindent, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-k ctrl-i", Reindent, Some(CONTEXT)),
+        KeyBinding::new("alt-up", MoveLineUp, Some(CONTEXT)),
+        KeyBinding::new("alt-down", MoveLineDown, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-shift-d", DuplicateLine, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-shift-d", DuplicateLine, Some(CONTEXT)),
+        KeyBinding::new("ctrl-j", JoinLines, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-shift-k", DeleteLine, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-shift-k", DeleteLine, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-/", ToggleLineComment, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-/", ToggleLineComment, Some(CONTEXT)),
+        KeyBinding::new("alt-shift-a", ToggleBlockComment, Some(CONTEXT)),
     ]);
 
     search::init(cx);
@@ -264,6 +369,11 @@ pub struct InputState {
     pub(super) text: Rope,
     pub(super) text_wrapper: TextWrapper,
     pub(super) history: History<Change>,
+    /// The revision of the text, increased on every change.
+    ///
+    /// Unlike the version of the `history`, this is changed by the edits in a same undo group,
+    /// use this to check if the text has changed since an async request was sent.
+    pub(super) revision: usize,
     pub(super) blink_cursor: Entity<BlinkCursor>,
     pub(super) loading: bool,
     /// Range in UTF-8 length for the selected text.
@@ -271,6 +381,24 @@ pub struct InputState {
     /// - "Hello 世界💝" = 16
     /// - "💝" = 4
     pub(super) selected_range: Selection,
+    /// The secondary cursors for multi-cursor editing, the primary cursor is the `selected_range`.
+    pub(super) secondary_selections: Vec<CursorSelection>,
+    /// The (row, column) anchor of the column selection, when alt-drag.
+    pub(super) column_select_anchor: Option<tree_sitter::Point>,
+    /// The cursors before the current multi-cursor edit, used to restore the cursors on undo.
+    pub(super) cursors_before_edit: Option<Vec<CursorSelection>>,
+    /// The edits of the current multi-cursor edit, to update the text states once after it.
+    pub(super) batched_edit: Option<BatchedEdit>,
+    /// The foldable ranges (sorted by `start_row`) of the text, None means need to update.
+    pub(super) fold_ranges: Option<Vec<FoldRange>>,
+    /// The base text to compare with, to show the changes in the gutter.
+    pub(super) diff_base: Option<Rope>,
+    /// The diff hunks (sorted by row) compared to the `diff_base`, None means need to update.
+    pub(super) diff_hunks: Option<Vec<DiffHunk>>,
+    /// The text revision of the `diff_hunks`, the hunks are stale if it is not the `revision`.
+    pub(super) diff_hunks_revision: usize,
+    /// Is dragging on the minimap to scroll.
+    pub(super) minimap_dragging: bool,
     pub(super) search_panel: Option<Entity<SearchPanel>>,
     pub(super) searchable: bool,
     /// Range for save the selected word, use to keep word range when drag move.
@@ -311,6 +439,8 @@ pub struct InputState {
     /// A flag to indicate if we are currently inserting a completion item.
     pub(super) completion_inserting: bool,
     pub(super) hover_popover: Option<Entity<HoverPopover>>,
+    pub(super) rename_popover: Option<Entity<RenamePopover>>,
+    pub(super) signature_help_popover: Option<Entity<SignatureHelpPopover>>,
     /// The LSP definitions locations for "Go to Definition" feature.
     pub(super) hover_definition: HoverDefinition,
 
@@ -331,6 +461,7 @@ pub struct InputState {
     _subscriptions: Vec<Subscription>,
 
     pub(super) _context_menu_task: Task<Result<()>>,
+    pub(super) _diff_hunks_task: Task<Result<()>>,
 }
 
 impl EventEmitter<InputEvent> for InputState {}
@@ -371,7 +502,17 @@ impl InputState {
             text_wrapper: TextWrapper::new(text_style.font(), window.rem_size(), None),
             blink_cursor,
             history,
+            revision: 0,
             selected_range: Selection::default(),
+            secondary_selections: vec![],
+            column_select_anchor: None,
+            cursors_before_edit: None,
+            batched_edit: None,
+            fold_ranges: None,
+            diff_base: None,
+            diff_hunks: None,
+            diff_hunks_revision: 0,
+            minimap_dragging: false,
             search_panel: None,
             searchable: false,
             selected_word_range: None,
@@ -403,11 +544,14 @@ impl InputState {
             mouse_context_menu,
             completion_inserting: false,
             hover_popover: None,
+            rename_popover: None,
+            signature_help_popover: None,
             hover_definition: HoverDefinition::default(),
             silent_replace_text: false,
             size: Size::default(),
             _subscriptions,
             _context_menu_task: Task::ready(Ok(())),
+            _diff_hunks_task: Task::ready(Ok(())),
             _pending_update: false,
         }
     }
@@ -521,6 +665,7 @@ impl InputState {
             } => {
                 *language = new_language.into();
                 *highlighter.borrow_mut() = None;
+                self.fold_ranges = None;
             }
             _ => {}
         }
@@ -606,6 +751,7 @@ impl InputState {
         self.replace_text(value, window, cx);
         self.disabled = was_disabled;
         self.history.ignore = false;
+        self.secondary_selections.clear();
         // Ensure cursor to start when set text
         if self.mode.is_single_line() {
             self.selected_range = (self.text.len()..self.text.len()).into();
@@ -823,31 +969,59 @@ impl InputState {
         });
     }
 
-    pub(super) fn select_left(&mut self, _: &SelectLeft, _: &mut Window, cx: &mut Context<Self>) {
-        self.select_to(self.previous_boundary(self.cursor()), cx);
+    pub(super) fn select_left(
+        &mut self,
+        _: &SelectLeft,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.for_each_cursor(window, cx, |this, _, _, cx| {
+            let offset = this.skip_folded_offset(this.previous_boundary(this.cursor()), false);
+            this.select_to(offset, cx);
+        });
     }
 
-    pub(super) fn select_right(&mut self, _: &SelectRight, _: &mut Window, cx: &mut Context<Self>) {
-        self.select_to(self.next_boundary(self.cursor()), cx);
+    pub(super) fn select_right(
+        &mut self,
+        _: &SelectRight,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.for_each_cursor(window, cx, |this, _, _, cx| {
+            let offset = this.skip_folded_offset(this.next_boundary(this.cursor()), true);
+            this.select_to(offset, cx);
+        });
     }
 
-    pub(super) fn select_up(&mut self, _: &SelectUp, _: &mut Window, cx: &mut Context<Self>) {
+    pub(super) fn select_up(&mut self, _: &SelectUp, window: &mut Window, cx: &mut Context<Self>) {
         if self.mode.is_single_line() {
             return;
         }
-        let offset = self.start_of_line().saturating_sub(1);
-        self.select_to(self.previous_boundary(offset), cx);
+        self.for_each_cursor(window, cx, |this, _, _, cx| {
+            let offset = this.start_of_line().saturating_sub(1);
+            let offset = this.skip_folded_offset(this.previous_boundary(offset), false);
+            this.select_to(offset, cx);
+        });
     }
 
-    pub(super) fn select_down(&mut self, _: &SelectDown, _: &mut Window, cx: &mut Context<Self>) {
+    pub(super) fn select_down(
+        &mut self,
+        _: &SelectDown,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         if self.mode.is_single_line() {
             return;
         }
-        let offset = (self.end_of_line() + 1).min(self.text.len());
-        self.select_to(self.next_boundary(offset), cx);
+        self.for_each_cursor(window, cx, |this, _, _, cx| {
+            let offset = (this.end_of_line() + 1).min(this.text.len());
+            let offset = this.skip_folded_offset(this.next_boundary(offset), true);
+            this.select_to(offset, cx);
+        });
     }
 
     pub(super) fn select_all(&mut self, _: &SelectAll, _: &mut Window, cx: &mut Context<Self>) {
+        self.secondary_selections.clear();
         self.selected_range = (0..self.text.len()).into();
         cx.notify();
     }
@@ -855,60 +1029,72 @@ impl InputState {
     pub(super) fn select_to_start(
         &mut self,
         _: &SelectToStart,
-        _: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        self.select_to(0, cx);
+        self.for_each_cursor(window, cx, |this, _, _, cx| {
+            this.select_to(0, cx);
+        });
     }
 
     pub(super) fn select_to_end(
         &mut self,
         _: &SelectToEnd,
-        _: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let end = self.text.len();
-        self.select_to(end, cx);
+        self.for_each_cursor(window, cx, |this, _, _, cx| {
+            let end = this.text.len();
+            this.select_to(end, cx);
+        });
     }
 
     pub(super) fn select_to_start_of_line(
         &mut self,
         _: &SelectToStartOfLine,
-        _: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let offset = self.start_of_line();
-        self.select_to(offset, cx);
+        self.for_each_cursor(window, cx, |this, _, _, cx| {
+            let offset = this.start_of_line();
+            this.select_to(offset, cx);
+        });
     }
 
     pub(super) fn select_to_end_of_line(
         &mut self,
         _: &SelectToEndOfLine,
-        _: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let offset = self.end_of_line();
-        self.select_to(offset, cx);
+        self.for_each_cursor(window, cx, |this, _, _, cx| {
+            let offset = this.end_of_line();
+            this.select_to(offset, cx);
+        });
     }
 
     pub(super) fn select_to_previous_word(
         &mut self,
         _: &SelectToPreviousWordStart,
-        _: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let offset = self.previous_start_of_word();
-        self.select_to(offset, cx);
+        self.for_each_cursor(window, cx, |this, _, _, cx| {
+            let offset = this.previous_start_of_word();
+            this.select_to(offset, cx);
+        });
     }
 
     pub(super) fn select_to_next_word(
         &mut self,
         _: &SelectToNextWordEnd,
-        _: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let offset = self.next_end_of_word();
-        self.select_to(offset, cx);
+        self.for_each_cursor(window, cx, |this, _, _, cx| {
+            let offset = this.next_end_of_word();
+            this.select_to(offset, cx);
+        });
     }
 
     /// Return the start offset of the previous word.
@@ -984,58 +1170,26 @@ impl InputState {
         line
     }
 
-    /// Get indent string of next line.
-    ///
-    /// To get current and next line indent, to return more depth one.
-    pub(super) fn indent_of_next_line(&mut self) -> String {
-        if self.mode.is_single_line() {
-            return "".into();
-        }
-
-        let mut current_indent = String::new();
-        let mut next_indent = String::new();
-        let current_line_start_pos = self.start_of_line();
-        let next_line_start_pos = self.end_of_line();
-        for c in self.text.slice(current_line_start_pos..).chars() {
-            if !c.is_whitespace() {
-                break;
-            }
-            if c == '\n' || c == '\r' {
-                break;
-            }
-            current_indent.push(c);
-        }
-
-        for c in self.text.slice(next_line_start_pos..).chars() {
-            if !c.is_whitespace() {
-                break;
-            }
-            if c == '\n' || c == '\r' {
-                break;
-            }
-            next_indent.push(c);
-        }
-
-        if next_indent.len() > current_indent.len() {
-            return next_indent;
-        } else {
-            return current_indent;
-        }
-    }
-
     pub(super) fn backspace(&mut self, _: &Backspace, window: &mut Window, cx: &mut Context<Self>) {
-        if self.selected_range.is_empty() {
-            self.select_to(self.previous_boundary(self.cursor()), cx)
-        }
-        self.replace_text_in_range(None, "", window, cx);
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            if let Some(range) = this.empty_bracket_pair_at_cursor() {
+                // Delete the auto closed pair together, e.g.: `(|)`.
+                this.selected_range = range.into();
+            } else if this.selected_range.is_empty() {
+                this.select_to(this.previous_boundary(this.cursor()), cx)
+            }
+            this.replace_text_in_range(None, "", window, cx);
+        });
         self.pause_blink_cursor(cx);
     }
 
     pub(super) fn delete(&mut self, _: &Delete, window: &mut Window, cx: &mut Context<Self>) {
-        if self.selected_range.is_empty() {
-            self.select_to(self.next_boundary(self.cursor()), cx)
-        }
-        self.replace_text_in_range(None, "", window, cx);
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            if this.selected_range.is_empty() {
+                this.select_to(this.next_boundary(this.cursor()), cx)
+            }
+            this.replace_text_in_range(None, "", window, cx);
+        });
         self.pause_blink_cursor(cx);
     }
 
@@ -1045,22 +1199,23 @@ impl InputState {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if !self.selected_range.is_empty() {
-            self.replace_text_in_range(None, "", window, cx);
-            self.pause_blink_cursor(cx);
-            return;
-        }
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            if !this.selected_range.is_empty() {
+                this.replace_text_in_range(None, "", window, cx);
+                return;
+            }
 
-        let mut offset = self.start_of_line();
-        if offset == self.cursor() {
-            offset = offset.saturating_sub(1);
-        }
-        self.replace_text_in_range_silent(
-            Some(self.range_to_utf16(&(offset..self.cursor()))),
-            "",
-            window,
-            cx,
-        );
+            let mut offset = this.start_of_line();
+            if offset == this.cursor() {
+                offset = offset.saturating_sub(1);
+            }
+            this.replace_text_in_range_silent(
+                Some(this.range_to_utf16(&(offset..this.cursor()))),
+                "",
+                window,
+                cx,
+            );
+        });
         self.pause_blink_cursor(cx);
     }
 
@@ -1070,22 +1225,23 @@ impl InputState {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if !self.selected_range.is_empty() {
-            self.replace_text_in_range(None, "", window, cx);
-            self.pause_blink_cursor(cx);
-            return;
-        }
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            if !this.selected_range.is_empty() {
+                this.replace_text_in_range(None, "", window, cx);
+                return;
+            }
 
-        let mut offset = self.end_of_line();
-        if offset == self.cursor() {
-            offset = (offset + 1).clamp(0, self.text.len());
-        }
-        self.replace_text_in_range_silent(
-            Some(self.range_to_utf16(&(self.cursor()..offset))),
-            "",
-            window,
-            cx,
-        );
+            let mut offset = this.end_of_line();
+            if offset == this.cursor() {
+                offset = (offset + 1).clamp(0, this.text.len());
+            }
+            this.replace_text_in_range_silent(
+                Some(this.range_to_utf16(&(this.cursor()..offset))),
+                "",
+                window,
+                cx,
+            );
+        });
         self.pause_blink_cursor(cx);
     }
 
@@ -1095,19 +1251,20 @@ impl InputState {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if !self.selected_range.is_empty() {
-            self.replace_text_in_range(None, "", window, cx);
-            self.pause_blink_cursor(cx);
-            return;
-        }
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            if !this.selected_range.is_empty() {
+                this.replace_text_in_range(None, "", window, cx);
+                return;
+            }
 
-        let offset = self.previous_start_of_word();
-        self.replace_text_in_range_silent(
-            Some(self.range_to_utf16(&(offset..self.cursor()))),
-            "",
-            window,
-            cx,
-        );
+            let offset = this.previous_start_of_word();
+            this.replace_text_in_range_silent(
+                Some(this.range_to_utf16(&(offset..this.cursor()))),
+                "",
+                window,
+                cx,
+            );
+        });
         self.pause_blink_cursor(cx);
     }
 
@@ -1117,19 +1274,20 @@ impl InputState {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if !self.selected_range.is_empty() {
-            self.replace_text_in_range(None, "", window, cx);
-            self.pause_blink_cursor(cx);
-            return;
-        }
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            if !this.selected_range.is_empty() {
+                this.replace_text_in_range(None, "", window, cx);
+                return;
+            }
 
-        let offset = self.next_end_of_word();
-        self.replace_text_in_range_silent(
-            Some(self.range_to_utf16(&(self.cursor()..offset))),
-            "",
-            window,
-            cx,
-        );
+            let offset = this.next_end_of_word();
+            this.replace_text_in_range_silent(
+                Some(this.range_to_utf16(&(this.cursor()..offset))),
+                "",
+                window,
+                cx,
+            );
+        });
         self.pause_blink_cursor(cx);
     }
 
@@ -1139,16 +1297,13 @@ impl InputState {
         }
 
         if self.mode.is_multi_line() {
-            // Get current line indent
-            let indent = if self.mode.is_code_editor() {
-                self.indent_of_next_line()
-            } else {
-                "".to_string()
-            };
-
-            // Add newline and indent
-            let new_line_text = format!("\n{}", indent);
-            self.replace_text_in_range_silent(None, &new_line_text, window, cx);
+            self.for_each_cursor(window, cx, |this, _, window, cx| {
+                if this.mode.is_code_editor() {
+                    this.insert_new_line(window, cx);
+                } else {
+                    this.replace_text_in_range_silent(None, "\n", window, cx);
+                }
+            });
             self.pause_blink_cursor(cx);
         } else {
             // Single line input, just emit the event (e.g.: In a dialog to confirm).
@@ -1162,6 +1317,7 @@ impl InputState {
 
     pub(super) fn clean(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.replace_text("", window, cx);
+        self.secondary_selections.clear();
         self.selected_range = (0..0).into();
         self.scroll_to(0, None, cx);
     }
@@ -1175,6 +1331,16 @@ impl InputState {
             self.unmark_text(window, cx);
         }
 
+        if self.signature_help_popover.is_some() {
+            self.hide_signature_help(cx);
+            return;
+        }
+
+        if self.has_multiple_cursors() {
+            self.clear_secondary_cursors(window, cx);
+            return;
+        }
+
         if self.clean_on_escape {
             return self.clean(window, cx);
         }
@@ -1196,6 +1362,10 @@ impl InputState {
             }
         }
 
+        if self.handle_fold_gutter_click(event, cx) {
+            return;
+        }
+
         self.selecting = true;
         let offset = self.index_for_mouse_position(event.position);
 
@@ -1215,6 +1385,11 @@ impl InputState {
             return;
         }
 
+        // Alt click to add cursor, or alt drag to column select.
+        if self.handle_alt_mouse_down(event, offset, cx) {
+            return;
+        }
+
         if event.modifiers.shift {
             self.select_to(offset, cx);
         } else {
@@ -1233,6 +1408,7 @@ impl InputState {
         }
         self.selecting = false;
         self.selected_word_range = None;
+        self.column_select_anchor = None;
     }
 
     pub(super) fn on_mouse_move(
@@ -1407,24 +1583,39 @@ impl InputState {
         window.show_character_palette();
     }
 
+    /// Return the selected text of all cursors in the document order, joined by `\n`.
+    fn selected_texts(&self) -> String {
+        let mut ranges = self.selections();
+        ranges.sort_by_key(|range| range.start);
+        ranges
+            .into_iter()
+            .filter(|range| !range.is_empty())
+            .map(|range| self.text.slice(range).to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     pub(super) fn copy(&mut self, _: &Copy, _: &mut Window, cx: &mut Context<Self>) {
-        if self.selected_range.is_empty() {
+        let selected_text = self.selected_texts();
+        if selected_text.is_empty() {
             return;
         }
 
-        let selected_text = self.text.slice(self.selected_range).to_string();
         cx.write_to_clipboard(ClipboardItem::new_string(selected_text));
     }
 
     pub(super) fn cut(&mut self, _: &Cut, window: &mut Window, cx: &mut Context<Self>) {
-        if self.selected_range.is_empty() {
+        let selected_text = self.selected_texts();
+        if selected_text.is_empty() {
             return;
         }
 
-        let selected_text = self.text.slice(self.selected_range).to_string();
         cx.write_to_clipboard(ClipboardItem::new_string(selected_text));
-
-        self.replace_text_in_range_silent(None, "", window, cx);
+        self.for_each_cursor(window, cx, |this, _, window, cx| {
+            if !this.selected_range.is_empty() {
+                this.replace_text_in_range_silent(None, "", window, cx);
+            }
+        });
     }
 
     pub(super) fn paste(&mut self, _: &Paste, window: &mut Window, cx: &mut Context<Self>) {
@@ -1434,11 +1625,46 @@ impl InputState {
                 new_text = new_text.replace('\n', "");
             }
 
-            self.replace_text_in_range_silent(None, &new_text, window, cx);
+            // If the clipboard lines count is same as the cursors, paste one line for each cursor.
+            let cursors_count = self.secondary_selections.len() + 1;
+            let lines = new_text.lines().collect::<Vec<_>>();
+            let distribute = cursors_count > 1 && lines.len() == cursors_count;
+
+            self.for_each_cursor(window, cx, |this, ix, window, cx| {
+                let text = if distribute { lines[ix] } else { &new_text };
+                this.replace_text_in_range_silent(None, text, window, cx);
+            });
             self.scroll_to(self.cursor(), None, cx);
         }
     }
 
+    /// Update the text states after the `range` of the `old_text` is replaced by the `new_text`.
+    pub(super) fn update_for_edit(
+        &mut self,
+        old_text: &Rope,
+        range: &Range<usize>,
+        new_text: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(diagnostics) = self.mode.diagnostics_mut() {
+            diagnostics.reset(&self.text)
+        }
+        self.text_wrapper
+            .update(&self.text, range, &Rope::from(new_text), cx);
+        self.mode
+            .update_highlighter(range, &self.text, new_text, true, cx);
+        self.fold_ranges = None;
+        self.update_diff_hunks_for_edit(old_text, range, new_text, window, cx);
+        self.lsp.update(&self.text, window, cx);
+        self.update_search(
+            range,
+            (self.text.len() + range.len()).saturating_sub(old_text.len()),
+            cx,
+        );
+        self.mode.update_auto_grow(&self.text_wrapper);
+    }
+
     fn push_history(&mut self, text: &Rope, range: &Range<usize>, new_text: &str) {
         if self.history.ignore {
             return;
@@ -1447,17 +1673,27 @@ impl InputState {
         let old_text = text.slice(range.clone()).to_string();
         let new_range = range.start..range.start + new_text.len();
 
-        self.history
-            .push(Change::new(range.clone(), &old_text, new_range, new_text));
+        let mut change = Change::new(range.clone(), &old_text, new_range, new_text);
+        if let Some(cursors) = self.cursors_before_edit.as_ref() {
+            change = change.cursors(cursors.clone());
+        }
+        self.history.push(change);
     }
 
     pub(super) fn undo(&mut self, _: &Undo, window: &mut Window, cx: &mut Context<Self>) {
         self.history.ignore = true;
         if let Some(changes) = self.history.undo() {
-            for change in changes {
+            self.secondary_selections.clear();
+            for change in &changes {
                 let range_utf16 = self.range_to_utf16(&change.new_range.into());
                 self.replace_text_in_range_silent(Some(range_utf16), &change.old_text, window, cx);
             }
+
+            // Restore the cursors before the earliest multi-cursor change.
+            if let Some(change) = changes.iter().rev().find(|c| !c.cursors.is_empty()) {
+                let primary_ix = change.cursors.len() - 1;
+                self.set_cursor_selections(change.cursors.clone(), primary_ix);
+            }
         }
         self.history.ignore = false;
     }
@@ -1465,9 +1701,27 @@ impl InputState {
     pub(super) fn redo(&mut self, _: &Redo, window: &mut Window, cx: &mut Context<Self>) {
         self.history.ignore = true;
         if let Some(changes) = self.history.redo() {
-            for change in changes {
+            self.secondary_selections.clear();
+            // Track the cursor of each change, to restore the cursors for multi-cursor changes.
+            let mut cursors: Vec<CursorSelection> = vec![];
+            for change in &changes {
                 let range_utf16 = self.range_to_utf16(&change.old_range.into());
                 self.replace_text_in_range_silent(Some(range_utf16), &change.new_text, window, cx);
+
+                let delta = change.new_text.len() as isize - change.old_text.len() as isize;
+                for cursor in cursors.iter_mut() {
+                    if cursor.range.start >= change.old_range.start {
+                        let offset = cursor.range.start.saturating_add_signed(delta);
+                        cursor.range = (offset..offset).into();
+                    }
+                }
+                let offset = change.new_range.end;
+                cursors.push(CursorSelection::new(offset..offset, false));
+            }
+
+            if changes.iter().any(|c| !c.cursors.is_empty()) {
+                let primary_ix = cursors.len() - 1;
+                self.set_cursor_selections(cursors, primary_ix);
             }
         }
         self.history.ignore = false;
@@ -1593,6 +1847,7 @@ impl InputState {
     /// Ensure the offset use self.next_boundary or self.previous_boundary to get the correct offset.
     pub(crate) fn select_to(&mut self, offset: usize, cx: &mut Context<Self>) {
         let offset = offset.clamp(0, self.text.len());
+        self.unfold_offset(offset);
         if self.selection_reversed {
             self.selected_range.start = offset
         } else {
@@ -1691,6 +1946,7 @@ impl InputState {
         // Because maybe user want to copy the selected text by AppMenuBar (will take focus handle).
 
         self.hover_popover = None;
+        self.signature_help_popover = None;
         self.diagnostic_popover = None;
         self.context_menu = None;
         self.blink_cursor.update(cx, |cursor, cx| {
@@ -1736,6 +1992,9 @@ impl InputState {
         }
 
         let offset = self.index_for_mouse_position(event.position);
+        if self.handle_column_select_drag(offset, cx) {
+            return;
+        }
         self.select_to(offset, cx);
     }
 
@@ -1933,6 +2192,22 @@ impl EntityInputHandler for InputState {
             return;
         }
 
+        // Insert the text at each cursor, when there are multiple cursors.
+        if range_utf16.is_none() && self.ime_marked_range.is_none() && self.has_multiple_cursors() {
+            self.for_each_cursor(window, cx, |this, _, window, cx| {
+                this.replace_text_in_range(None, new_text, window, cx);
+            });
+            return;
+        }
+
+        if range_utf16.is_none()
+            && self.ime_marked_range.is_none()
+            && !self.silent_replace_text
+            && self.handle_bracket_input(new_text, window, cx)
+        {
+            return;
+        }
+
         self.pause_blink_cursor(cx);
 
         let range = range_utf16
@@ -1966,23 +2241,24 @@ impl EntityInputHandler for InputState {
             }
         }
 
+        self.revision += 1;
         self.push_history(&old_text, &range, &new_text);
-        self.history.end_grouping();
-        if let Some(diagnostics) = self.mode.diagnostics_mut() {
-            diagnostics.reset(&self.text)
-        }
-        self.text_wrapper
-            .update(&self.text, &range, &Rope::from(new_text), cx);
-        self.mode
-            .update_highlighter(&range, &self.text, &new_text, true, cx);
-        self.lsp.update(&self.text, window, cx);
         self.selected_range = (new_offset..new_offset).into();
         self.ime_marked_range.take();
         self.update_preferred_column();
-        self.update_search(cx);
-        self.mode.update_auto_grow(&self.text_wrapper);
+        if self.cursors_before_edit.is_some() {
+            // Keep grouping the changes of all cursors into one undo step,
+            // and update the text states once after all cursors are edited.
+            self.history.start_grouping();
+            self.batch_edit(&old_text, &range);
+            return;
+        }
+
+        self.history.end_grouping();
+        self.update_for_edit(&old_text, &range, new_text, window, cx);
         if !self.silent_replace_text {
             self.handle_completion_trigger(&range, &new_text, window, cx);
+            self.handle_signature_help_trigger(&new_text, window, cx);
         }
         cx.emit(InputEvent::Change);
         cx.notify();
@@ -2002,6 +2278,8 @@ impl EntityInputHandler for InputState {
         }
 
         self.lsp.reset();
+        // IME composition is only applied to the primary cursor.
+        self.secondary_selections.clear();
 
         let range = range_utf16
             .as_ref()
@@ -2023,6 +2301,7 @@ impl EntityInputHandler for InputState {
             }
         }
 
+        self.revision += 1;
         if let Some(diagnostics) = self.mode.diagnostics_mut() {
             diagnostics.reset(&self.text)
         }
@@ -2030,6 +2309,8 @@ impl EntityInputHandler for InputState {
             .update(&self.text, &range, &Rope::from(new_text), cx);
         self.mode
             .update_highlighter(&range, &self.text, &new_text, true, cx);
+        self.fold_ranges = None;
+        self.update_diff_hunks_for_edit(&old_text, &range, new_text, window, cx);
         self.lsp.update(&self.text, window, cx);
         if new_text.is_empty() {
             // Cancel selection, when cancel IME input.
@@ -2138,6 +2419,8 @@ impl Render for InputState {
         if self._pending_update {
             self.mode
                 .update_highlighter(&(0..0), &self.text, "", false, cx);
+            self.fold_ranges = None;
+            self.diff_hunks = None;
             self.lsp.update(&self.text, window, cx);
             self._pending_update = false;
         }
@@ -2152,5 +2435,7 @@ impl Render for InputState {
             .children(self.diagnostic_popover.clone())
             .children(self.context_menu.as_ref().map(|menu| menu.render()))
             .children(self.hover_popover.clone())
+            .children(self.signature_help_popover.clone())
+            .children(self.rename_popover.clone())
     }
 }
@@ -4,7 +4,7 @@ use gpui::{App, Font, LineFragment, Pixels, Point, ShapedLine, Size, Window, poi
 use ropey::Rope;
 use smallvec::SmallVec;
 
-use crate::input::RopeExt;
+use crate::input::{RopeExt, fold::FoldRange};
 
 /// A line with soft wrapped lines info.
 #[derive(Debug, Clone)]
@@ -15,6 +15,8 @@ pub(super) struct LineItem {
     ///
     /// Not contains the line end `\n`.
     pub(super) wrapped_lines: Vec<Range<usize>>,
+    /// Whether this line is hidden by a fold.
+    pub(super) folded: bool,
 }
 
 impl LineItem {
@@ -25,8 +27,14 @@ impl LineItem {
     }
 
     /// Get number of soft wrapped lines of this line (include the first line).
+    ///
+    /// Returns 0 if this line is hidden by a fold.
     #[inline]
     pub(super) fn lines_len(&self) -> usize {
+        if self.folded {
+            return 0;
+        }
+
         self.wrapped_lines.len()
     }
 
@@ -59,6 +67,8 @@ pub(super) struct TextWrapper {
     pub(super) longest_row: LongestRow,
     /// The lines by split \n
     pub(super) lines: Vec<LineItem>,
+    /// The folded ranges, sorted by `start_row`.
+    folds: Vec<FoldRange>,
 
     _initialized: bool,
 }
@@ -74,6 +84,7 @@ impl TextWrapper {
             soft_lines: 0,
             longest_row: LongestRow::default(),
             lines: Vec::new(),
+            folds: Vec::new(),
             _initialized: false,
         }
     }
@@ -175,6 +186,13 @@ impl TextWrapper {
 
         let mut longest_row_ix = self.longest_row.row;
         let mut longest_row_len = self.longest_row.len;
+        let old_rows_len = end_row - start_row + 1;
+        // The rows that content changed, the row is not changed if the edit ends at the line start.
+        let edit_rows = if self.text.offset_to_point(range.end).column == 0 {
+            start_row..end_row
+        } else {
+            start_row..end_row + 1
+        };
 
         // To add the new lines.
         let new_start_row = changed_text.offset_to_point(range.start).row;
@@ -219,9 +237,11 @@ impl TextWrapper {
             new_lines.push(LineItem {
                 line: Rope::from(line),
                 wrapped_lines,
+                folded: false,
             });
         }
 
+        let rows_delta = new_lines.len() as isize - old_rows_len as isize;
         if self.lines.len() == 0 {
             self.lines = new_lines;
         } else {
@@ -229,6 +249,7 @@ impl TextWrapper {
         }
 
         self.text = changed_text.clone();
+        self.update_folds_for_edit(edit_rows, rows_delta);
         self.soft_lines = self.lines.iter().map(|l| l.lines_len()).sum();
         self.longest_row = LongestRow {
             row: longest_row_ix,
@@ -240,7 +261,120 @@ impl TextWrapper {
     ///
     /// If the `text` is the same as the current text, do nothing.
     fn update_all(&mut self, text: &Rope, cx: &mut App) {
+        // Keep the folds, the text is not changed.
+        let mut folds = std::mem::take(&mut self.folds);
         self.update(text, &(0..text.len()), &text, cx);
+        folds.retain(|fold| fold.end_row < self.lines.len());
+        self.folds = folds;
+        self.apply_folds();
+    }
+
+    /// Get the folded ranges, sorted by `start_row`.
+    #[inline]
+    pub(super) fn folds(&self) -> &[FoldRange] {
+        &self.folds
+    }
+
+    /// Returns true if the `row` is hidden by a fold.
+    pub(super) fn is_row_folded(&self, row: usize) -> bool {
+        self.lines.get(row).map_or(false, |line| line.folded)
+    }
+
+    /// Fold the given range, returns false if it is invalid or already folded.
+    pub(super) fn fold(&mut self, range: FoldRange) -> bool {
+        if range.end_row <= range.start_row || range.end_row >= self.lines.len() {
+            return false;
+        }
+
+        match self
+            .folds
+            .binary_search_by_key(&(range.start_row, range.end_row), |f| {
+                (f.start_row, f.end_row)
+            }) {
+            Ok(_) => false,
+            Err(ix) => {
+                self.folds.insert(ix, range);
+                self.apply_folds();
+                true
+            }
+        }
+    }
+
+    /// Unfold the folds that start at the `row`, returns false if nothing unfolded.
+    pub(super) fn unfold(&mut self, row: usize) -> bool {
+        let len = self.folds.len();
+        self.folds.retain(|f| f.start_row != row);
+        if self.folds.len() == len {
+            return false;
+        }
+
+        self.apply_folds();
+        true
+    }
+
+    /// Unfold all folds that hide the `row`, returns false if nothing unfolded.
+    pub(super) fn unfold_containing(&mut self, row: usize) -> bool {
+        let len = self.folds.len();
+        self.folds.retain(|f| !f.hides(row));
+        if self.folds.len() == len {
+            return false;
+        }
+
+        self.apply_folds();
+        true
+    }
+
+    /// Unfold all folds.
+    pub(super) fn unfold_all(&mut self) {
+        self.folds.clear();
+        self.apply_folds();
+    }
+
+    /// Shift or remove the folds by an edit.
+    ///
+    /// - `rows`: The rows range of the edit in the old text.
+    /// - `rows_delta`: The changed count of rows after the edit.
+    fn update_folds_for_edit(&mut self, rows: Range<usize>, rows_delta: isize) {
+        if self.folds.is_empty() {
+            return;
+        }
+
+        let total_rows = self.lines.len();
+        self.folds.retain_mut(|fold| {
+            if rows.end <= fold.start_row {
+                // The edit is before the fold.
+                fold.start_row = fold.start_row.saturating_add_signed(rows_delta);
+                fold.end_row = fold.end_row.saturating_add_signed(rows_delta);
+            } else if rows.start > fold.end_row {
+                // The edit is after the fold.
+            } else if rows == (fold.start_row..fold.start_row + 1) && rows_delta == 0 {
+                // The edit is only in the first line of the fold.
+            } else {
+                // The edit touched the folded lines, unfold it.
+                return false;
+            }
+
+            fold.end_row < total_rows
+        });
+        self.apply_folds();
+    }
+
+    /// Update the `folded` state of the lines by the folds.
+    fn apply_folds(&mut self) {
+        for line in self.lines.iter_mut() {
+            line.folded = false;
+        }
+        for fold in self.folds.iter() {
+            for line in self
+                .lines
+                .iter_mut()
+                .take(fold.end_row + 1)
+                .skip(fold.start_row + 1)
+            {
+                line.folded = true;
+            }
+        }
+        self.soft_lines = self.lines.iter().map(|l| l.lines_len()).sum();
     }
 
     /// Return display point (with soft wrap) from the given byte offset in the text.
@@ -335,12 +469,20 @@ impl DisplayPoint {
     }
 }
 
+/// The inlays (virtual text) of a wrapped line.
+///
+/// Each item is `(offset, len)`, the `offset` is the byte index in the wrapped line without inlays,
+/// and the `len` is the bytes length of the inlay text in the shaped line.
+pub(crate) type LineInlays = SmallVec<[(usize, usize); 2]>;
+
 /// The layout info of a line with soft wrapped lines.
 pub(crate) struct LineLayout {
     /// Total bytes length of this line.
     len: usize,
     /// The soft wrapped lines of this line (Include the first line).
     pub(crate) wrapped_lines: SmallVec<[ShapedLine; 1]>,
+    /// The inlays of each wrapped line, empty if there is no inlay in this line.
+    inlays: SmallVec<[LineInlays; 1]>,
     pub(crate) longest_width: Pixels,
 }
 
@@ -350,6 +492,17 @@ impl LineLayout {
             len: 0,
             longest_width: px(0.),
             wrapped_lines: SmallVec::new(),
+            inlays: SmallVec::new(),
+        }
+    }
+
+    /// Create a line layout for the line hidden by a fold, it has no wrapped lines and zero height.
+    pub(crate) fn folded(len: usize) -> Self {
+        Self {
+            len,
+            longest_width: px(0.),
+            wrapped_lines: SmallVec::new(),
+            inlays: SmallVec::new(),
         }
     }
 
@@ -358,8 +511,41 @@ impl LineLayout {
         self
     }
 
+    /// Set the inlays of the wrapped lines, must be called before [`LineLayout::set_wrapped_lines`].
+    pub(crate) fn set_inlays(&mut self, inlays: SmallVec<[LineInlays; 1]>) {
+        if inlays.iter().all(|inlays| inlays.is_empty()) {
+            self.inlays = SmallVec::new();
+        } else {
+            self.inlays = inlays;
+        }
+    }
+
+    #[inline]
+    fn inlays_at(&self, wrapped_ix: usize) -> &[(usize, usize)] {
+        self.inlays
+            .get(wrapped_ix)
+            .map(|inlays| inlays.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// The bytes length of the wrapped line text without inlays.
+    #[inline]
+    fn wrapped_line_len(&self, wrapped_ix: usize) -> usize {
+        let Some(line) = self.wrapped_lines.get(wrapped_ix) else {
+            return 0;
+        };
+
+        let inlays_len: usize = self.inlays_at(wrapped_ix).iter().map(|(_, len)| len).sum();
+        line.len - inlays_len
+    }
+
     pub(crate) fn set_wrapped_lines(&mut self, wrapped_lines: SmallVec<[ShapedLine; 1]>) {
-        self.len = wrapped_lines.iter().map(|l| l.len).sum();
+        let inlays_len: usize = self
+            .inlays
+            .iter()
+            .flat_map(|inlays| inlays.iter().map(|(_, len)| len))
+            .sum();
+        self.len = wrapped_lines.iter().map(|l| l.len).sum::<usize>() - inlays_len;
         let width = wrapped_lines
             .iter()
             .map(|l| l.width)
@@ -388,11 +574,13 @@ impl LineLayout {
 
         for (i, line) in self.wrapped_lines.iter().enumerate() {
             let is_last = i + 1 == self.wrapped_lines.len();
-            let line_len = if is_last { line.len + 1 } else { line.len };
+            let line_len = self.wrapped_line_len(i);
+            let line_len = if is_last { line_len + 1 } else { line_len };
 
             let range = acc_len..(acc_len + line_len);
             if range.contains(&offset) {
-                let x = line.x_for_index(offset.saturating_sub(acc_len));
+                let ix = to_display_index(self.inlays_at(i), offset.saturating_sub(acc_len));
+                let x = line.x_for_index(ix);
                 return Some(point(x, offset_y));
             }
             acc_len += line_len;
@@ -415,9 +603,9 @@ impl LineLayout {
                     ix = ix.saturating_sub(c_len);
                 }
 
-                return acc_len + ix;
+                return acc_len + to_buffer_index(self.inlays_at(i), ix);
             }
-            acc_len += line.text.len();
+            acc_len += self.wrapped_line_len(i);
         }
 
         acc_len
@@ -444,10 +632,10 @@ impl LineLayout {
                     let c_len = line.text.chars().last().map(|c| c.len_utf8()).unwrap_or(0);
                     ix = ix.saturating_sub(c_len);
                 }
-                return Some(offset + ix);
+                return Some(offset + to_buffer_index(self.inlays_at(i), ix));
             }
 
-            offset += line.text.len();
+            offset += self.wrapped_line_len(i);
             line_top = line_bottom;
         }
 
@@ -461,14 +649,14 @@ impl LineLayout {
     ) -> Option<usize> {
         let mut offset = 0;
         let mut line_top = px(0.);
-        for line in self.wrapped_lines.iter() {
+        for (i, line) in self.wrapped_lines.iter().enumerate() {
             let line_bottom = line_top + line_height;
             if pos.y >= line_top && pos.y < line_bottom {
                 let ix = line.index_for_x(pos.x)?;
-                return Some(offset + ix);
+                return Some(offset + to_buffer_index(self.inlays_at(i), ix));
             }
 
-            offset += line.text.len();
+            offset += self.wrapped_line_len(i);
             line_top = line_bottom;
         }
 
@@ -497,6 +685,37 @@ impl LineLayout {
     }
 }
 
+/// Convert the byte index in the text without inlays to the index in the text with inlays.
+///
+/// The inlay at the same index is after the index.
+fn to_display_index(inlays: &[(usize, usize)], ix: usize) -> usize {
+    let inlays_len: usize = inlays
+        .iter()
+        .take_while(|(offset, _)| *offset < ix)
+        .map(|(_, len)| len)
+        .sum();
+    ix + inlays_len
+}
+
+/// Convert the byte index in the text with inlays to the index in the text without inlays.
+///
+/// The index inside an inlay is snapped to the offset of the inlay.
+fn to_buffer_index(inlays: &[(usize, usize)], display_ix: usize) -> usize {
+    let mut inlays_len = 0;
+    for (offset, len) in inlays {
+        let start = offset + inlays_len;
+        if display_ix <= start {
+            break;
+        }
+        if display_ix <= start + len {
+            return *offset;
+        }
+        inlays_len += len;
+    }
+
+    display_ix - inlays_len
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -699,6 +918,68 @@ mod tests {
         assert_eq!(wrapper.lines.len(), 2);
     }
 
+    #[test]
+    fn test_folds() {
+        let font = gpui::Font {
+            family: "Arial".into(),
+            weight: FontWeight::default(),
+            style: FontStyle::Normal,
+            features: FontFeatures::default(),
+            fallbacks: None,
+        };
+
+        fn fake_wrap_line(_line: &str, _wrap_width: Pixels) -> Vec<Boundary> {
+            vec![]
+        }
+
+        let mut wrapper = TextWrapper::new(font, px(14.), None);
+        let mut text = Rope::from("fn a() {\n    1\n    2\n}\nfn b() {\n    3\n}");
+        wrapper._update(&text, &(0..text.len()), &text, &mut fake_wrap_line);
+        assert_eq!(wrapper.len(), 7);
+
+        assert!(wrapper.fold(FoldRange::new(0, 2)));
+        assert!(!wrapper.fold(FoldRange::new(0, 2)));
+        assert!(!wrapper.fold(FoldRange::new(6, 7)));
+        assert!(wrapper.fold(FoldRange::new(4, 5)));
+        assert_eq!(wrapper.len(), 4);
+        assert!(!wrapper.is_row_folded(0));
+        assert!(wrapper.is_row_folded(1));
+        assert!(wrapper.is_row_folded(2));
+        assert!(!wrapper.is_row_folded(3));
+        assert!(wrapper.is_row_folded(5));
+
+        // Insert a new line before the folds.
+        let range = 0..0;
+        text.replace(range.clone(), "\n");
+        wrapper._update(&text, &range, &Rope::from("\n"), &mut fake_wrap_line);
+        assert_eq!(
+            wrapper.folds(),
+            &[FoldRange::new(1, 3), FoldRange::new(5, 6)]
+        );
+        assert_eq!(wrapper.len(), 5);
+
+        // Edit in the fold header line.
+        let range = 4..4;
+        text.replace(range.clone(), "_");
+        wrapper._update(&text, &range, &Rope::from("_"), &mut fake_wrap_line);
+        assert_eq!(
+            wrapper.folds(),
+            &[FoldRange::new(1, 3), FoldRange::new(5, 6)]
+        );
+
+        // Edit the folded lines will unfold it.
+        let start = text.line_start_offset(2);
+        let range = start..start + 1;
+        text.replace(range.clone(), "");
+        wrapper._update(&text, &range, &Rope::from(""), &mut fake_wrap_line);
+        assert_eq!(wrapper.folds(), &[FoldRange::new(5, 6)]);
+        assert_eq!(wrapper.len(), 7);
+
+        assert!(wrapper.unfold_containing(6));
+        assert_eq!(wrapper.folds(), &[]);
+        assert_eq!(wrapper.len(), 8);
+    }
+
     #[test]
     fn test_line_layout() {
         let mut line_layout = LineLayout::new();
@@ -709,6 +990,43 @@ mod tests {
         line_layout.set_wrapped_lines(wrapped_lines);
         assert_eq!(line_layout.len(), 150);
         assert_eq!(line_layout.wrapped_lines.len(), 2);
+
+        let mut line_layout = LineLayout::new();
+        line_layout.set_inlays(smallvec::smallvec![
+            smallvec::smallvec![(10, 5)],
+            smallvec::smallvec![]
+        ]);
+        let line1 = ShapedLine::default().with_len(105);
+        let line2 = ShapedLine::default().with_len(50);
+        line_layout.set_wrapped_lines(smallvec::smallvec![line1, line2]);
+        assert_eq!(line_layout.len(), 150);
+        assert_eq!(line_layout.wrapped_line_len(0), 100);
+        assert_eq!(line_layout.wrapped_line_len(1), 50);
+    }
+
+    #[test]
+    fn test_inlay_index() {
+        // "let a = 1;" with inlay ": i32" at 5
+        let inlays = [(5, 5)];
+        assert_eq!(to_display_index(&inlays, 0), 0);
+        assert_eq!(to_display_index(&inlays, 5), 5);
+        assert_eq!(to_display_index(&inlays, 6), 11);
+        assert_eq!(to_buffer_index(&inlays, 5), 5);
+        assert_eq!(to_buffer_index(&inlays, 7), 5);
+        assert_eq!(to_buffer_index(&inlays, 10), 5);
+        assert_eq!(to_buffer_index(&inlays, 11), 6);
+
+        let inlays = [(0, 2), (3, 4)];
+        assert_eq!(to_display_index(&inlays, 0), 0);
+        assert_eq!(to_display_index(&inlays, 1), 3);
+        assert_eq!(to_display_index(&inlays, 3), 5);
+        assert_eq!(to_display_index(&inlays, 4), 10);
+        assert_eq!(to_buffer_index(&inlays, 1), 0);
+        assert_eq!(to_buffer_index(&inlays, 3), 1);
+        assert_eq!(to_buffer_index(&inlays, 7), 3);
+        assert_eq!(to_buffer_index(&inlays, 10), 4);
+        assert_eq!(to_buffer_index(&inlays, 12), 6);
+        assert_eq!(to_buffer_index(&[], 12), 12);
     }
 
     #[test]
@@ -730,21 +1048,25 @@ mod tests {
             LineItem {
                 line: Rope::from("Hello, 世界!\r"),
                 wrapped_lines: vec![0..15],
+                folded: false,
             },
             // range: 16..36
             LineItem {
                 line: Rope::from("This is second line."),
                 wrapped_lines: vec![0..10, 10..20],
+                folded: false,
             },
             // range: 37..56
             LineItem {
                 line: Rope::from("This is third line."),
                 wrapped_lines: vec![0..9, 9..15, 15..20],
+                folded: false,
             },
             // range: 57..79
             LineItem {
                 line: Rope::from("这里是第 4 行。"),
                 wrapped_lines: vec![0..22],
+                folded: false,
             },
         ];
 
@@ -147,6 +147,43 @@ impl RowsCache {
         path
     }
 
+    /// Returns the items between `start` and `end` (both inclusive) across sections.
+    pub(crate) fn range(&self, start: IndexPath, end: IndexPath) -> Vec<IndexPath> {
+        let (start, end) = if end < start {
+            (end, start)
+        } else {
+            (start, end)
+        };
+
+        (start.section..=end.section)
+            .flat_map(|section| {
+                let rows_count = self.rows_count(section);
+                let first = if section == start.section {
+                    start.row
+                } else {
+                    0
+                };
+                let last = if section == end.section {
+                    (end.row + 1).min(rows_count)
+                } else {
+                    rows_count
+                };
+                (first..last).map(move |row| IndexPath::new(row).section(section))
+            })
+            .collect()
+    }
+
+    /// Returns all the items in all sections.
+    pub(crate) fn items(&self) -> Vec<IndexPath> {
+        self.sections
+            .iter()
+            .enumerate()
+            .flat_map(|(section, rows_count)| {
+                (0..*rows_count).map(move |row| IndexPath::new(row).section(section))
+            })
+            .collect()
+    }
+
     pub(crate) fn prepare_if_needed<F>(
         &mut self,
         sections_count: usize,
@@ -276,4 +313,29 @@ mod tests {
             IndexPath::new(3).section(1)
         );
     }
+
+    #[test]
+    fn test_range() {
+        let mut row_cache = RowsCache::default();
+        row_cache.sections = Rc::new(vec![2, 0, 3]);
+
+        assert_eq!(row_cache.items().len(), 5);
+        assert_eq!(
+            row_cache.range(IndexPath::new(1).section(0), IndexPath::new(1).section(2)),
+            vec![
+                IndexPath::new(1).section(0),
+                IndexPath::new(0).section(2),
+                IndexPath::new(1).section(2),
+            ]
+        );
+        assert_eq!(
+            row_cache.range(IndexPath::new(2).section(2), IndexPath::new(2).section(2)),
+            vec![IndexPath::new(2).section(2)]
+        );
+        // The reversed range is the same.
+        assert_eq!(
+            row_cache.range(IndexPath::new(0).section(2), IndexPath::new(0).section(0)),
+            row_cache.range(IndexPath::new(0).section(0), IndexPath::new(0).section(2))
+        );
+    }
 }
@@ -118,6 +118,17 @@ pub trait ListDelegate: Sized + 'static {
         cx: &mut Context<ListState<Self>>,
     );
 
+    /// Set the selected indices in the multi-select mode, sorted by section and row.
+    ///
+    /// See [`ListState::multi_select`].
+    fn set_selected_indices(
+        &mut self,
+        indices: &[IndexPath],
+        window: &mut Window,
+        cx: &mut Context<ListState<Self>>,
+    ) {
+    }
+
     /// Set the confirm and give the selected index,
     /// this is means user have clicked the item or pressed Enter.
     ///
@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::ops::Range;
 use std::time::Duration;
 
@@ -10,7 +11,9 @@ use crate::{
     scroll::Scrollbar,
     v_flex,
 };
-use crate::{Icon, IndexPath, Selectable, Sizable, StyledExt};
+use crate::{
+    Disableable as _, Icon, IndexPath, Selectable, Sizable, StyledExt, checkbox::Checkbox, h_flex,
+};
 use crate::{VirtualListScrollHandle, list::ListDelegate, v_virtual_list};
 use gpui::{
     App, AvailableSpace, ClickEvent, Context, DefiniteLength, EdgesRefinement, EventEmitter,
@@ -19,11 +22,14 @@ use gpui::{
 };
 use gpui::{
     AppContext, Entity, FocusHandle, Focusable, InteractiveElement, IntoElement, KeyBinding,
-    Length, MouseButton, ParentElement, Render, Styled, Task, Window, div, prelude::FluentBuilder,
+    Length, MouseButton, ParentElement, Render, Styled, Task, Window, actions, div,
+    prelude::FluentBuilder,
 };
 use rust_i18n::t;
 use smol::Timer;
 
+actions!(list, [SelectAll, ExtendSelectionUp, ExtendSelectionDown]);
+
 pub(crate) fn init(cx: &mut App) {
     let context: Option<&str> = Some("List");
     cx.bind_keys([
@@ -32,6 +38,9 @@ pub(crate) fn init(cx: &mut App) {
         KeyBinding::new("secondary-enter", Confirm { secondary: true }, context),
         KeyBinding::new("up", SelectUp, context),
         KeyBinding::new("down", SelectDown, context),
+        KeyBinding::new("shift-up", ExtendSelectionUp, context),
+        KeyBinding::new("shift-down", ExtendSelectionDown, context),
+        KeyBinding::new("secondary-a", SelectAll, context),
     ]);
 }
 
@@ -43,6 +52,10 @@ pub enum ListEvent {
     Confirm(IndexPath),
     /// Pressed ESC to deselect the item.
     Cancel,
+    /// Toggled the item by secondary-click or the checkbox in the multi-select mode.
+    Toggle(IndexPath, bool),
+    /// The selected items changed in the multi-select mode, sorted by section and row.
+    SelectionChange(Vec<IndexPath>),
 }
 
 struct ListOptions {
@@ -77,12 +90,17 @@ pub struct ListState<D: ListDelegate> {
     scroll_handle: VirtualListScrollHandle,
     rows_cache: RowsCache,
     selected_index: Option<IndexPath>,
+    selected_indices: BTreeSet<IndexPath>,
+    /// The start item of the range selection by shift-click or shift-up/down.
+    selection_anchor: Option<IndexPath>,
     item_to_measure_index: IndexPath,
     deferred_scroll_to_index: Option<(IndexPath, ScrollStrategy)>,
     mouse_right_clicked_index: Option<IndexPath>,
     reset_on_cancel: bool,
     searchable: bool,
     selectable: bool,
+    multi_select: bool,
+    checkbox: bool,
     _search_task: Task<()>,
     _load_more_task: Task<()>,
     _query_input_subscription: Subscription,
@@ -107,7 +125,11 @@ where
             query_input,
             last_query: None,
             selected_index: None,
+            selected_indices: BTreeSet::new(),
+            selection_anchor: None,
             selectable: true,
+            multi_select: false,
+            checkbox: false,
             searchable: false,
             item_to_measure_index: IndexPath::default(),
             deferred_scroll_to_index: None,
@@ -145,6 +167,49 @@ where
         cx.notify();
     }
 
+    /// Sets whether the list supports selecting multiple items, default is false.
+    ///
+    /// - `secondary`-click (cmd on macOS, ctrl on others) to toggle an item, instead of confirm.
+    /// - `shift`-click or `shift-up`/`shift-down` to select a range of items, across sections.
+    /// - `secondary-a` to select all items.
+    ///
+    /// See [`ListState::selected_indices`].
+    pub fn multi_select(mut self, multi_select: bool) -> Self {
+        self.multi_select = multi_select;
+        self
+    }
+
+    /// Sets whether the list supports selecting multiple items, default is false.
+    pub fn set_multi_select(
+        &mut self,
+        multi_select: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.multi_select = multi_select;
+        if !multi_select {
+            self.selection_anchor = None;
+            self.update_selected_indices(BTreeSet::new(), window, cx);
+        }
+        cx.notify();
+    }
+
+    /// Sets whether to show a checkbox at the start of each item to toggle it, default is false.
+    ///
+    /// This also enables the multi-select mode, see [`ListState::multi_select`].
+    pub fn checkbox(mut self, checkbox: bool) -> Self {
+        self.checkbox = checkbox;
+        self.multi_select |= checkbox;
+        self
+    }
+
+    /// Sets whether to show a checkbox at the start of each item to toggle it, default is false.
+    pub fn set_checkbox(&mut self, checkbox: bool, cx: &mut Context<Self>) {
+        self.checkbox = checkbox;
+        self.multi_select |= checkbox;
+        cx.notify();
+    }
+
     pub fn delegate(&self) -> &D {
         &self.delegate
     }
@@ -196,6 +261,83 @@ where
         self.selected_index
     }
 
+    /// Returns the selected items sorted by section and row.
+    ///
+    /// If not in the multi-select mode, returns the [`ListState::selected_index`].
+    pub fn selected_indices(&self) -> Vec<IndexPath> {
+        if self.multi_select {
+            self.selected_indices.iter().copied().collect()
+        } else {
+            self.selected_index.into_iter().collect()
+        }
+    }
+
+    /// Set the selected items in the multi-select mode,
+    /// this method will not emit [`ListEvent::SelectionChange`].
+    pub fn set_selected_indices(
+        &mut self,
+        indices: impl IntoIterator<Item = IndexPath>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.selected_indices = indices.into_iter().collect();
+        self.delegate
+            .set_selected_indices(&self.selected_indices(), window, cx);
+        cx.notify();
+    }
+
+    /// Select all items in the multi-select mode.
+    pub fn select_all(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.selectable || !self.multi_select {
+            return;
+        }
+
+        let indices = self.rows_cache.items().into_iter().collect();
+        self.update_selected_indices(indices, window, cx);
+    }
+
+    /// Update the selected items, and emit [`ListEvent::SelectionChange`] if changed.
+    fn update_selected_indices(
+        &mut self,
+        indices: BTreeSet<IndexPath>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if indices == self.selected_indices {
+            return;
+        }
+
+        self.selected_indices = indices;
+        let indices = self.selected_indices();
+        self.delegate.set_selected_indices(&indices, window, cx);
+        cx.emit(ListEvent::SelectionChange(indices));
+        cx.notify();
+    }
+
+    /// Toggle the item in the multi-select mode, and make it the active item.
+    fn toggle_item(&mut self, ix: IndexPath, window: &mut Window, cx: &mut Context<Self>) {
+        let mut indices = self.selected_indices.clone();
+        let selected = indices.insert(ix);
+        if !selected {
+            indices.remove(&ix);
+        }
+
+        self.selection_anchor = Some(ix);
+        self.set_selected_index(Some(ix), window, cx);
+        cx.emit(ListEvent::Toggle(ix, selected));
+        self.update_selected_indices(indices, window, cx);
+    }
+
+    /// Select the items from the selection anchor to the `ix`, and make `ix` the active item.
+    fn select_range_to(&mut self, ix: IndexPath, window: &mut Window, cx: &mut Context<Self>) {
+        let anchor = self.selection_anchor.or(self.selected_index).unwrap_or(ix);
+        let indices = self.rows_cache.range(anchor, ix).into_iter().collect();
+
+        self.selection_anchor = Some(anchor);
+        self._set_selected_index(Some(ix), window, cx);
+        self.update_selected_indices(indices, window, cx);
+    }
+
     /// Set a specific list item for measurement.
     pub fn set_item_to_measure_index(
         &mut self,
@@ -257,6 +399,10 @@ where
                 self.set_searching(true, window, cx);
                 let search = self.delegate.perform_search(&text, window, cx);
 
+                // The indices are changed by the search.
+                self.selection_anchor = None;
+                self.update_selected_indices(BTreeSet::new(), window, cx);
+
                 if self.rows_cache.len() > 0 {
                     self._set_selected_index(Some(IndexPath::default()), window, cx);
                 } else {
@@ -330,6 +476,8 @@ where
         cx.propagate();
         if self.reset_on_cancel {
             self._set_selected_index(None, window, cx);
+            self.selection_anchor = None;
+            self.update_selected_indices(BTreeSet::new(), window, cx);
         }
 
         self.delegate.cancel(window, cx);
@@ -367,6 +515,10 @@ where
         self.delegate.set_selected_index(Some(ix), window, cx);
         self.scroll_to_selected_item(window, cx);
         cx.emit(ListEvent::Select(ix));
+        if self.multi_select {
+            self.selection_anchor = Some(ix);
+            self.update_selected_indices(BTreeSet::from([ix]), window, cx);
+        }
         cx.notify();
     }
 
@@ -398,6 +550,51 @@ where
         self.select_item(next_ix, window, cx);
     }
 
+    fn extend_selection(&mut self, forward: bool, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.selectable || !self.multi_select {
+            cx.propagate();
+            return;
+        }
+        if self.rows_cache.items_count() == 0 {
+            return;
+        }
+
+        // Stop at the first or last item instead of wrapping around.
+        let ix = match self.selected_index {
+            Some(ix) if forward => self.rows_cache.next(Some(ix)).max(ix),
+            Some(ix) => self.rows_cache.prev(Some(ix)).min(ix),
+            None => IndexPath::default(),
+        };
+        self.select_range_to(ix, window, cx);
+    }
+
+    fn on_action_extend_selection_up(
+        &mut self,
+        _: &ExtendSelectionUp,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.extend_selection(false, window, cx);
+    }
+
+    fn on_action_extend_selection_down(
+        &mut self,
+        _: &ExtendSelectionDown,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.extend_selection(true, window, cx);
+    }
+
+    fn on_action_select_all(&mut self, _: &SelectAll, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.selectable || !self.multi_select {
+            cx.propagate();
+            return;
+        }
+
+        self.select_all(window, cx);
+    }
+
     fn prepare_items_if_needed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let sections_count = self.delegate.sections_count(cx);
 
@@ -429,6 +626,16 @@ where
             .prepare_if_needed(sections_count, measured_size, cx, |section_ix, cx| {
                 self.delegate.items_count(section_ix, cx)
             });
+
+        // Drop the selected items that no longer exist.
+        let rows_cache = &self.rows_cache;
+        let indices = self
+            .selected_indices
+            .iter()
+            .filter(|ix| ix.row < rows_cache.rows_count(ix.section))
+            .copied()
+            .collect();
+        self.update_selected_indices(indices, window, cx);
     }
 
     fn render_list_item(
@@ -438,25 +645,67 @@ where
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
         let selectable = self.selectable;
-        let selected = self.selected_index.map(|s| s.eq_row(ix)).unwrap_or(false);
+        let selected = if self.multi_select {
+            self.selected_indices.contains(&ix)
+        } else {
+            self.selected_index.map(|s| s.eq_row(ix)).unwrap_or(false)
+        };
+        let checkbox = self.multi_select && self.checkbox;
         let mouse_right_clicked = self
             .mouse_right_clicked_index
             .map(|s| s.eq_row(ix))
             .unwrap_or(false);
         let id = SharedString::from(format!("list-item-{}", ix));
 
+        let item = self.delegate.render_item(ix, window, cx).map(|item| {
+            item.selected(selected)
+                .secondary_selected(mouse_right_clicked)
+        });
+
         div()
             .id(id)
             .w_full()
             .relative()
             .overflow_hidden()
-            .children(self.delegate.render_item(ix, window, cx).map(|item| {
-                item.selected(selected)
-                    .secondary_selected(mouse_right_clicked)
-            }))
+            .map(|this| {
+                if checkbox {
+                    this.child(
+                        h_flex()
+                            .w_full()
+                            .gap_1()
+                            .pl_2()
+                            .child(
+                                Checkbox::new("checkbox")
+                                    .checked(selected)
+                                    .disabled(!selectable)
+                                    .on_click(cx.listener(move |this, _: &bool, window, cx| {
+                                        cx.stop_propagation();
+                                        this.toggle_item(ix, window, cx);
+                                    })),
+                            )
+                            .child(div().flex_1().overflow_hidden().children(item)),
+                    )
+                } else {
+                    this.children(item)
+                }
+            })
             .when(selectable, |this| {
                 this.on_click(cx.listener(move |this, e: &ClickEvent, window, cx| {
                     this.mouse_right_clicked_index = None;
+                    if this.multi_select {
+                        let modifiers = e.modifiers();
+                        if modifiers.shift {
+                            this.select_range_to(ix, window, cx);
+                            return;
+                        }
+                        if modifiers.secondary() {
+                            this.toggle_item(ix, window, cx);
+                            return;
+                        }
+
+                        this.selection_anchor = Some(ix);
+                        this.update_selected_indices(BTreeSet::from([ix]), window, cx);
+                    }
                     this.selected_index = Some(ix);
                     this.on_action_confirm(
                         &Confirm {
@@ -646,6 +895,9 @@ where
                     .on_action(cx.listener(Self::on_action_confirm))
                     .on_action(cx.listener(Self::on_action_select_next))
                     .on_action(cx.listener(Self::on_action_select_prev))
+                    .on_action(cx.listener(Self::on_action_extend_selection_up))
+                    .on_action(cx.listener(Self::on_action_extend_selection_down))
+                    .on_action(cx.listener(Self::on_action_select_all))
                     .map(|this| {
                         if let Some(view) = initial_view {
                             this.child(view)
@@ -741,3 +993,170 @@ where
             .child(self.state.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use gpui::{
+        App, AppContext as _, Context, Entity, IntoElement as _, TestAppContext, VisualTestContext,
+        Window, point, px, size,
+    };
+
+    use super::{ExtendSelectionDown, List, ListEvent, ListState};
+    use crate::{
+        IndexPath,
+        list::{ListDelegate, ListItem},
+    };
+
+    struct TestDelegate {
+        sections: Vec<usize>,
+        selected_indices: Vec<IndexPath>,
+    }
+
+    impl ListDelegate for TestDelegate {
+        type Item = ListItem;
+
+        fn sections_count(&self, _: &App) -> usize {
+            self.sections.len()
+        }
+
+        fn items_count(&self, section: usize, _: &App) -> usize {
+            self.sections[section]
+        }
+
+        fn render_item(
+            &mut self,
+            ix: IndexPath,
+            _: &mut Window,
+            _: &mut Context<ListState<Self>>,
+        ) -> Option<Self::Item> {
+            Some(ListItem::new(ix.row))
+        }
+
+        fn set_selected_index(
+            &mut self,
+            _: Option<IndexPath>,
+            _: &mut Window,
+            _: &mut Context<ListState<Self>>,
+        ) {
+        }
+
+        fn set_selected_indices(
+            &mut self,
+            indices: &[IndexPath],
+            _: &mut Window,
+            _: &mut Context<ListState<Self>>,
+        ) {
+            self.selected_indices = indices.to_vec();
+        }
+    }
+
+    /// Build a multi-select list, returns the list and the emitted selection changes.
+    fn build_list(
+        sections: Vec<usize>,
+        cx: &mut TestAppContext,
+    ) -> (
+        Entity<ListState<TestDelegate>>,
+        Rc<RefCell<Vec<Vec<IndexPath>>>>,
+        &mut VisualTestContext,
+    ) {
+        let cx = cx.add_empty_window();
+        let changes = Rc::new(RefCell::new(vec![]));
+        let state = cx.update(|window, cx| {
+            crate::init(cx);
+            let delegate = TestDelegate {
+                sections,
+                selected_indices: vec![],
+            };
+            let state = cx.new(|cx| ListState::new(delegate, window, cx).multi_select(true));
+            let changes = changes.clone();
+            cx.subscribe(&state, move |_, event: &ListEvent, _| {
+                if let ListEvent::SelectionChange(indices) = event {
+                    changes.borrow_mut().push(indices.clone());
+                }
+            })
+            .detach();
+            state
+        });
+        draw_list(&state, cx);
+        (state, changes, cx)
+    }
+
+    fn draw_list(state: &Entity<ListState<TestDelegate>>, cx: &mut VisualTestContext) {
+        cx.draw(point(px(0.), px(0.)), size(px(400.), px(400.)), |_, _| {
+            List::new(state).into_any_element()
+        });
+    }
+
+    fn ix(section: usize, row: usize) -> IndexPath {
+        IndexPath::new(row).section(section)
+    }
+
+    #[gpui::test]
+    fn test_toggle_item(cx: &mut TestAppContext) {
+        let (state, changes, cx) = build_list(vec![3], cx);
+
+        state.update_in(cx, |state, window, cx| {
+            state.toggle_item(ix(0, 0), window, cx);
+            state.toggle_item(ix(0, 2), window, cx);
+            assert_eq!(state.selected_indices(), vec![ix(0, 0), ix(0, 2)]);
+            assert_eq!(state.selected_index(), Some(ix(0, 2)));
+
+            state.toggle_item(ix(0, 0), window, cx);
+            assert_eq!(state.selected_indices(), vec![ix(0, 2)]);
+            assert_eq!(state.delegate().selected_indices, vec![ix(0, 2)]);
+        });
+        assert_eq!(
+            *changes.borrow(),
+            vec![vec![ix(0, 0)], vec![ix(0, 0), ix(0, 2)], vec![ix(0, 2)]]
+        );
+    }
+
+    #[gpui::test]
+    fn test_select_range(cx: &mut TestAppContext) {
+        let (state, changes, cx) = build_list(vec![2, 3], cx);
+
+        state.update_in(cx, |state, window, cx| {
+            // Shift-click selects across the sections from the anchor.
+            state.toggle_item(ix(0, 1), window, cx);
+            state.select_range_to(ix(1, 1), window, cx);
+            assert_eq!(state.selected_indices(), vec![ix(0, 1), ix(1, 0), ix(1, 1)]);
+            assert_eq!(state.selected_index(), Some(ix(1, 1)));
+
+            // The anchor is kept, to shrink the range backward.
+            state.select_range_to(ix(0, 0), window, cx);
+            assert_eq!(state.selected_indices(), vec![ix(0, 0), ix(0, 1)]);
+
+            // Shift-down extends from the active item.
+            state.on_action_extend_selection_down(&ExtendSelectionDown, window, cx);
+            assert_eq!(state.selected_indices(), vec![ix(0, 1)]);
+            assert_eq!(state.delegate().selected_indices, vec![ix(0, 1)]);
+        });
+        assert_eq!(changes.borrow().len(), 4);
+    }
+
+    #[gpui::test]
+    fn test_select_all(cx: &mut TestAppContext) {
+        let (state, changes, cx) = build_list(vec![2, 1], cx);
+
+        state.update_in(cx, |state, window, cx| {
+            state.select_all(window, cx);
+            assert_eq!(state.selected_indices(), vec![ix(0, 0), ix(0, 1), ix(1, 0)]);
+            // Not changed, not emit again.
+            state.select_all(window, cx);
+
+            // The items no longer exist are unselected.
+            state.delegate_mut().sections = vec![1, 1];
+        });
+        draw_list(&state, cx);
+        state.read_with(cx, |state, _| {
+            assert_eq!(state.selected_indices(), vec![ix(0, 0), ix(1, 0)]);
+            assert_eq!(state.delegate().selected_indices, vec![ix(0, 0), ix(1, 0)]);
+        });
+        assert_eq!(
+            *changes.borrow(),
+            vec![vec![ix(0, 0), ix(0, 1), ix(1, 0)], vec![ix(0, 0), ix(1, 0)]]
+        );
+    }
+}
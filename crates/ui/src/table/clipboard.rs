@@ -0,0 +1,172 @@
+use gpui::SharedString;
+
+/// The text format to copy the [`crate::table::Table`] selection.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TableCopyFormat {
+    /// Tab-separated values, can be pasted into the spreadsheet apps.
+    #[default]
+    Tsv,
+    /// Comma-separated values.
+    Csv,
+    /// Markdown table with the column names as header.
+    Markdown,
+}
+
+/// Quote the `cell` if it contains the `separator`, quote or line break.
+fn quote_cell(cell: &str, separator: char) -> String {
+    if cell.contains([separator, '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+fn to_separated_values(rows: &[Vec<SharedString>], separator: char) -> String {
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| quote_cell(cell, separator))
+                .collect::<Vec<_>>()
+                .join(&separator.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Serialize the rows as TSV (Tab-separated values).
+pub fn to_tsv(rows: &[Vec<SharedString>]) -> String {
+    to_separated_values(rows, '\t')
+}
+
+/// Serialize the rows as CSV (Comma-separated values).
+pub fn to_csv(rows: &[Vec<SharedString>]) -> String {
+    to_separated_values(rows, ',')
+}
+
+/// Serialize the rows as Markdown table, with the `headers` as the table header.
+pub fn to_markdown(headers: &[SharedString], rows: &[Vec<SharedString>]) -> String {
+    fn escape(cell: &str) -> String {
+        cell.replace('|', "\\|")
+            .replace("\r\n", "<br>")
+            .replace('\n', "<br>")
+    }
+
+    let line = |cells: &[SharedString]| {
+        let cells = cells.iter().map(|cell| escape(cell)).collect::<Vec<_>>();
+        format!("| {} |", cells.join(" | "))
+    };
+
+    let mut lines = Vec::with_capacity(rows.len() + 2);
+    lines.push(line(headers));
+    lines.push(format!("|{}", " --- |".repeat(headers.len())));
+    lines.extend(rows.iter().map(|row| line(row)));
+    lines.join("\n")
+}
+
+/// Parse the TSV (Tab-separated values) text into rows, the quoted cells are supported.
+///
+/// The trailing line break is ignored.
+pub fn parse_tsv(text: &str) -> Vec<Vec<String>> {
+    let text = text
+        .strip_suffix("\r\n")
+        .or_else(|| text.strip_suffix('\n'))
+        .unwrap_or(text);
+    if text.is_empty() {
+        return vec![];
+    }
+
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut cell = String::new();
+    let mut chars = text.chars().peekable();
+    let mut in_quotes = false;
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    cell.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => cell.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if cell.is_empty() => in_quotes = true,
+            '\t' => row.push(std::mem::take(&mut cell)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                row.push(std::mem::take(&mut cell));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => cell.push(c),
+        }
+    }
+
+    row.push(cell);
+    rows.push(row);
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::SharedString;
+
+    use super::*;
+
+    fn rows(rows: &[&[&'static str]]) -> Vec<Vec<SharedString>> {
+        rows.iter()
+            .map(|row| row.iter().map(|cell| SharedString::from(*cell)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_to_tsv_and_csv() {
+        let data = rows(&[&["AAPL", "1,200.5", ""], &["Say \"Hi\"", "a\tb", "c\nd"]]);
+
+        assert_eq!(
+            to_tsv(&data),
+            "AAPL\t1,200.5\t\n\"Say \"\"Hi\"\"\"\t\"a\tb\"\t\"c\nd\""
+        );
+        assert_eq!(
+            to_csv(&data),
+            "AAPL,\"1,200.5\",\n\"Say \"\"Hi\"\"\",a\tb,\"c\nd\""
+        );
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let headers = rows(&[&["Name", "Price"]]).remove(0);
+        let data = rows(&[&["AAPL", "1.5"], &["a|b", "c\nd"]]);
+
+        assert_eq!(
+            to_markdown(&headers, &data),
+            "| Name | Price |\n| --- | --- |\n| AAPL | 1.5 |\n| a\\|b | c<br>d |"
+        );
+    }
+
+    #[test]
+    fn test_parse_tsv() {
+        assert!(parse_tsv("").is_empty());
+        assert!(parse_tsv("\n").is_empty());
+        assert_eq!(
+            parse_tsv("a\tb\r\nc\td\n"),
+            vec![vec!["a", "b"], vec!["c", "d"]]
+        );
+        assert_eq!(parse_tsv("a\t\t"), vec![vec!["a", "", ""]]);
+        assert_eq!(
+            parse_tsv("\"Say \"\"Hi\"\"\"\t\"c\nd\"\nx"),
+            vec![vec!["Say \"Hi\"", "c\nd"], vec!["x"]]
+        );
+
+        let data = rows(&[&["AAPL", "1,200.5", ""], &["Say \"Hi\"", "a\tb", "c\nd"]]);
+        let expected = data
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.to_string()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(parse_tsv(&to_tsv(&data)), expected);
+    }
+}
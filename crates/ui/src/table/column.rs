@@ -2,8 +2,12 @@ use gpui::{
     div, prelude::FluentBuilder, px, Bounds, Context, Edges, Empty, EntityId, IntoElement,
     ParentElement as _, Pixels, Render, SharedString, Styled as _, TextAlign, Window,
 };
+use serde::{Deserialize, Serialize};
 
-use crate::ActiveTheme as _;
+use crate::{
+    ActiveTheme as _,
+    table::{ColumnAggregate, ColumnFilter, ColumnFilterValue},
+};
 
 /// Represents a column in a table, used for initializing table columns.
 #[derive(Debug, Clone)]
@@ -22,11 +26,20 @@ pub struct Column {
     ///
     /// If `None`, the column is not sortable.
     pub sort: Option<ColumnSort>,
+    /// The filter of the column, if any.
+    ///
+    /// If `None`, the column is not filterable.
+    pub filter: Option<ColumnFilter>,
+    /// The aggregate function of the column, shown in the group footer rows if any.
+    pub aggregate: Option<ColumnAggregate>,
     /// The padding of the column.
     pub paddings: Option<Edges<Pixels>>,
     /// The width of the column.
     pub width: Pixels,
-    /// Whether the column is fixed, the fixed column will pin at the left side when scrolling horizontally.
+    /// Whether the column is fixed, the fixed column will pin at the left or right side
+    /// when scrolling horizontally.
+    ///
+    /// The left fixed columns must be the first columns, and the right fixed ones must be the last.
     pub fixed: Option<ColumnFixed>,
     /// Whether the column is resizable.
     pub resizable: bool,
@@ -34,6 +47,11 @@ pub struct Column {
     pub movable: bool,
     /// Whether the column is selectable, if true this column's cells can be selected in column selection mode.
     pub selectable: bool,
+    /// Whether the column is the tree column, default is false.
+    ///
+    /// The cells of the tree column are indented by the row depth, with a disclosure icon
+    /// to expand or collapse the children rows, see [`crate::table::TableDelegate::row_depth`].
+    pub tree: bool,
 }
 
 impl Default for Column {
@@ -43,12 +61,15 @@ impl Default for Column {
             name: SharedString::new(""),
             align: TextAlign::Left,
             sort: None,
+            filter: None,
+            aggregate: None,
             paddings: None,
             width: px(100.),
             fixed: None,
             resizable: true,
             movable: true,
             selectable: true,
+            tree: false,
         }
     }
 }
@@ -91,6 +112,23 @@ impl Column {
         self
     }
 
+    /// Set the column to be filterable with the given filter, default is None (not filterable).
+    ///
+    /// See also [`crate::table::TableDelegate::perform_filter`].
+    pub fn filter(mut self, filter: ColumnFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Set the aggregate function of the column, default is None.
+    ///
+    /// The aggregated value of each group is shown in the group footer rows,
+    /// see [`crate::table::TableDelegate::groups_count`].
+    pub fn aggregate(mut self, aggregate: ColumnAggregate) -> Self {
+        self.aggregate = Some(aggregate);
+        self
+    }
+
     /// Set the alignment of the column text, default is left.
     ///
     /// Only `text_left`, `text_right` is supported.
@@ -129,6 +167,12 @@ impl Column {
         self
     }
 
+    /// Set whether the column is fixed on right side, default is false.
+    pub fn fixed_right(mut self) -> Self {
+        self.fixed = Some(ColumnFixed::Right);
+        self
+    }
+
     /// Set whether the column is resizable, default is true.
     pub fn resizable(mut self, resizable: bool) -> Self {
         self.resizable = resizable;
@@ -146,13 +190,22 @@ impl Column {
         self.selectable = selectable;
         self
     }
+
+    /// Set whether the column is the tree column, default is false.
+    pub fn tree(mut self, tree: bool) -> Self {
+        self.tree = tree;
+        self
+    }
 }
 
 impl FluentBuilder for Column {}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ColumnFixed {
+    #[serde(rename = "left")]
     Left,
+    #[serde(rename = "right")]
+    Right,
 }
 
 /// Used to sort the column runtime info in Table internal.
@@ -165,6 +218,10 @@ pub(crate) struct ColGroup {
     pub(crate) width: Pixels,
     /// The bounds of the column in the table after it renders.
     pub(crate) bounds: Bounds<Pixels>,
+    /// The active filter of the column.
+    pub(crate) filter: Option<ColumnFilterValue>,
+    /// Whether the column is hidden by the column visibility menu.
+    pub(crate) hidden: bool,
 }
 
 impl ColGroup {
@@ -182,14 +239,17 @@ pub(crate) struct DragColumn {
 }
 
 /// The sorting behavior of a column.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ColumnSort {
     /// No sorting.
     #[default]
+    #[serde(rename = "default")]
     Default,
     /// Sort in ascending order.
+    #[serde(rename = "asc")]
     Ascending,
     /// Sort in descending order.
+    #[serde(rename = "desc")]
     Descending,
 }
 
@@ -1,14 +1,17 @@
 use std::ops::Range;
 
 use gpui::{
-    App, Context, Div, InteractiveElement as _, IntoElement, ParentElement as _, Stateful,
-    Styled as _, Window, div,
+    App, Context, Div, InteractiveElement as _, IntoElement, ParentElement as _, SharedString,
+    Stateful, Styled as _, Window, div,
 };
 
 use crate::{
     ActiveTheme as _, Icon, IconName, Size, h_flex,
     menu::PopupMenu,
-    table::{Column, ColumnSort, TableState, loading::Loading},
+    table::{
+        Column, ColumnAggregate, ColumnFilterValue, ColumnSort, RowPin, TableCell, TableCellEditor,
+        TableState, loading::Loading,
+    },
 };
 
 /// A delegate trait for providing data and rendering for a table.
@@ -35,6 +38,35 @@ pub trait TableDelegate: Sized + 'static {
     ) {
     }
 
+    /// Perform sort on multiple columns, the `sorts` are the sorted columns with the sort order,
+    /// in the sort priority order, empty if no column is sorted.
+    ///
+    /// Called instead of [`TableDelegate::perform_sort`] when [`TableState::multi_sort`] is enabled.
+    ///
+    /// Default to call [`TableDelegate::perform_sort`] with the primary sorted column.
+    fn perform_multi_sort(
+        &mut self,
+        sorts: Vec<(usize, ColumnSort)>,
+        window: &mut Window,
+        cx: &mut Context<TableState<Self>>,
+    ) {
+        if let Some((col_ix, sort)) = sorts.first() {
+            self.perform_sort(*col_ix, *sort, window, cx);
+        }
+    }
+
+    /// Perform filter on the column at the given index, `None` to clear the filter of the column.
+    ///
+    /// The filters of the other columns are still active, see [`TableState::filters`].
+    fn perform_filter(
+        &mut self,
+        col_ix: usize,
+        filter: Option<ColumnFilterValue>,
+        window: &mut Window,
+        cx: &mut Context<TableState<Self>>,
+    ) {
+    }
+
     /// Render the header cell at the given column index, default to the column name.
     fn render_th(
         &mut self,
@@ -57,6 +89,135 @@ pub trait TableDelegate: Sized + 'static {
         h_flex().id(("row", row_ix))
     }
 
+    /// Return the number of the row groups, default 0 to disable grouping.
+    ///
+    /// When grouping, each group is rendered with a collapsible header row,
+    /// and a footer row with the aggregated values if any column has [`Column::aggregate`].
+    fn groups_count(&self, cx: &App) -> usize {
+        0
+    }
+
+    /// Return the rows range of the group at the given index.
+    ///
+    /// The groups should be in the rows order and not overlapped, the rows not in any group are hidden.
+    fn group_rows(&self, group_ix: usize, cx: &App) -> Range<usize> {
+        0..0
+    }
+
+    /// Return the id of the group at the given index, default to the group index.
+    ///
+    /// The collapsed state of the groups is kept by the id, return a stable id (e.g. the group key)
+    /// to keep the groups collapsed when the groups are changed.
+    fn group_id(&self, group_ix: usize, cx: &App) -> SharedString {
+        group_ix.to_string().into()
+    }
+
+    /// Render the content of the group header row at the given group index.
+    ///
+    /// The disclosure icon and the rows count are rendered by the table.
+    fn render_group_header(
+        &mut self,
+        group_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<TableState<Self>>,
+    ) -> impl IntoElement {
+        format!("Group {}", group_ix + 1)
+    }
+
+    /// Return the numeric value of the cell at the given row and column, used to compute the aggregates.
+    ///
+    /// Default to parse the [`TableDelegate::cell_text`].
+    fn cell_value(&self, row_ix: usize, col_ix: usize, cx: &App) -> Option<f64> {
+        self.cell_text(row_ix, col_ix, cx).trim().parse().ok()
+    }
+
+    /// Render the aggregated `value` of the column at the given index in the group footer row.
+    fn render_aggregate(
+        &mut self,
+        group_ix: usize,
+        col_ix: usize,
+        aggregate: ColumnAggregate,
+        value: f64,
+        window: &mut Window,
+        cx: &mut Context<TableState<Self>>,
+    ) -> impl IntoElement {
+        aggregate.format(value)
+    }
+
+    /// Return the number of the pinned rows at the top or bottom of the table, default 0.
+    ///
+    /// The pinned rows stay visible when scrolling vertically, for example the favourites rows
+    /// at the top or the totals rows at the bottom. They are not selectable.
+    fn pinned_rows_count(&self, pin: RowPin, cx: &App) -> usize {
+        0
+    }
+
+    /// Render the cell of the pinned row at the given index and column.
+    fn render_pinned_td(
+        &mut self,
+        pin: RowPin,
+        ix: usize,
+        col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<TableState<Self>>,
+    ) -> impl IntoElement {
+        div()
+    }
+
+    /// Return the depth of the row at the given index in the tree, default 0.
+    ///
+    /// The tree rows are flattened by the delegate in the tree order, the children rows follow their parent row,
+    /// and the cells of the [`Column::tree`] column are indented by the depth.
+    fn row_depth(&self, row_ix: usize, cx: &App) -> usize {
+        0
+    }
+
+    /// Return the number of the children of the row at the given index, default `Some(0)` for a leaf row.
+    ///
+    /// Return `None` if the row has children but they are not loaded yet,
+    /// the [`TableDelegate::load_children`] will be called when the row is expanded.
+    fn row_children_count(&self, row_ix: usize, cx: &App) -> Option<usize> {
+        Some(0)
+    }
+
+    /// Return true if the row at the given index is expanded, default false.
+    fn is_row_expanded(&self, row_ix: usize, cx: &App) -> bool {
+        false
+    }
+
+    /// Expand or collapse the row at the given index.
+    ///
+    /// The delegate should insert or remove the children rows after the row.
+    fn set_row_expanded(
+        &mut self,
+        row_ix: usize,
+        expanded: bool,
+        window: &mut Window,
+        cx: &mut Context<TableState<Self>>,
+    ) {
+    }
+
+    /// Return the id of the row at the given index in the tree, default to the row index.
+    ///
+    /// The rows may be changed before [`TableDelegate::load_children`] is called,
+    /// return a stable id (e.g. the path) to load the children of the right row.
+    fn row_id(&self, row_ix: usize, cx: &App) -> SharedString {
+        row_ix.to_string().into()
+    }
+
+    /// Load the children of the row at the given index, when the row is expanded and the children are not loaded.
+    ///
+    /// This will be performed in a background task, like [`TableDelegate::load_more`].
+    ///
+    /// After loaded, insert the children rows after the row and call [`TableState::refresh_rows`].
+    fn load_children(
+        &mut self,
+        row_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<TableState<Self>>,
+    ) {
+    }
+
     /// Render the context menu for the row at the given row index.
     fn context_menu(
         &mut self,
@@ -77,6 +238,74 @@ pub trait TableDelegate: Sized + 'static {
         cx: &mut Context<TableState<Self>>,
     ) -> impl IntoElement;
 
+    /// Render the selected cell at the given row and column, default to [`TableDelegate::render_td`].
+    ///
+    /// The selected background is always rendered by the table,
+    /// override this to render a different content for the selected cells.
+    fn render_selected_td(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<TableState<Self>>,
+    ) -> impl IntoElement {
+        self.render_td(row_ix, col_ix, window, cx)
+    }
+
+    /// Return the plain text value of the cell at the given row and column, default to empty.
+    ///
+    /// This is used to copy the selection to the clipboard.
+    fn cell_text(&self, row_ix: usize, col_ix: usize, cx: &App) -> SharedString {
+        SharedString::default()
+    }
+
+    /// Paste the `rows` parsed from the clipboard text into the table, start from the `cell`.
+    ///
+    /// Return true if the paste is handled, default is false for the read-only table.
+    fn paste(
+        &mut self,
+        cell: TableCell,
+        rows: Vec<Vec<String>>,
+        window: &mut Window,
+        cx: &mut Context<TableState<Self>>,
+    ) -> bool {
+        false
+    }
+
+    /// Return true if the cell at the given row and column is editable, default false.
+    ///
+    /// The editable cell can be edited by double click or `enter`, see [`TableDelegate::build_cell_editor`].
+    fn is_cell_editable(&self, row_ix: usize, col_ix: usize, cx: &App) -> bool {
+        false
+    }
+
+    /// Build the editor of the cell at the given row and column, with the current value of the cell.
+    ///
+    /// Return `None` to not edit the cell.
+    fn build_cell_editor(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<TableState<Self>>,
+    ) -> Option<TableCellEditor> {
+        None
+    }
+
+    /// Commit the edited value of the cell at the given row and column, read the value from the `editor`.
+    ///
+    /// Return `Err` with the message to show the validation error in the cell and keep editing.
+    fn on_cell_edited(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        editor: &TableCellEditor,
+        window: &mut Window,
+        cx: &mut Context<TableState<Self>>,
+    ) -> Result<(), SharedString> {
+        Ok(())
+    }
+
     /// Move the column at the given `col_ix` to insert before the column at the given `to_ix`.
     fn move_column(
         &mut self,
@@ -157,6 +386,9 @@ pub trait TableDelegate: Sized + 'static {
     ///
     /// This can used to handle some data update, to only update the visible rows.
     /// Please ensure that the data is updated in the background task.
+    ///
+    /// When the rows are grouped, this is called for each range of the rendered rows,
+    /// the rows of the collapsed groups are not included.
     fn visible_rows_changed(
         &mut self,
         visible_range: Range<usize>,
@@ -0,0 +1,80 @@
+use gpui::{
+    AnyElement, App, Entity, FocusHandle, Focusable as _, InteractiveElement as _, IntoElement,
+    ParentElement as _, SharedString, StatefulInteractiveElement as _, Styled as _, div,
+    prelude::FluentBuilder as _,
+};
+
+use crate::{
+    ActiveTheme as _, Icon, IconName, Sizable as _, Size,
+    date_picker::{DatePicker, DatePickerState},
+    h_flex,
+    input::{Input, InputState, NumberInput},
+    select::{Select, SelectState},
+    tooltip::Tooltip,
+};
+
+/// The editor to edit a [`crate::table::Table`] cell, built by [`crate::table::TableDelegate::build_cell_editor`].
+///
+/// The state is created by the delegate with the current value of the cell,
+/// and read by the delegate in [`crate::table::TableDelegate::on_cell_edited`] to commit.
+#[derive(Clone)]
+pub enum TableCellEditor {
+    /// Edit with [`Input`].
+    Input(Entity<InputState>),
+    /// Edit with [`NumberInput`].
+    NumberInput(Entity<InputState>),
+    /// Edit with [`Select`] of the string items.
+    Select(Entity<SelectState<Vec<SharedString>>>),
+    /// Edit with [`DatePicker`].
+    DatePicker(Entity<DatePickerState>),
+}
+
+impl TableCellEditor {
+    pub(super) fn focus_handle(&self, cx: &App) -> FocusHandle {
+        match self {
+            Self::Input(state) | Self::NumberInput(state) => state.focus_handle(cx),
+            Self::Select(state) => state.focus_handle(cx),
+            Self::DatePicker(state) => state.focus_handle(cx),
+        }
+    }
+
+    /// Render the editor in the cell, with the validation error if any.
+    pub(super) fn render(&self, size: Size, error: Option<SharedString>, cx: &App) -> AnyElement {
+        let size = size.smaller();
+        let editor = match self {
+            Self::Input(state) => Input::new(state).with_size(size).into_any_element(),
+            Self::NumberInput(state) => NumberInput::new(state).with_size(size).into_any_element(),
+            Self::Select(state) => Select::new(state).with_size(size).into_any_element(),
+            Self::DatePicker(state) => DatePicker::new(state).with_size(size).into_any_element(),
+        };
+
+        h_flex()
+            .size_full()
+            .gap_1()
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    .when(error.is_some(), |this| {
+                        this.rounded(cx.theme().radius)
+                            .border_1()
+                            .border_color(cx.theme().danger)
+                    })
+                    .child(editor),
+            )
+            .when_some(error, |this, error| {
+                this.child(
+                    div()
+                        .id("cell-error")
+                        .flex_shrink_0()
+                        .child(
+                            Icon::new(IconName::CircleX)
+                                .small()
+                                .text_color(cx.theme().danger),
+                        )
+                        .tooltip(move |window, cx| Tooltip::new(error.clone()).build(window, cx)),
+                )
+            })
+            .into_any_element()
+    }
+}
@@ -0,0 +1,375 @@
+use chrono::NaiveDate;
+use gpui::{
+    App, AppContext as _, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement as _, IntoElement, ParentElement as _, Render, SharedString,
+    StatefulInteractiveElement as _, Styled as _, Subscription, Window, px,
+};
+use rust_i18n::t;
+
+use crate::{
+    Sizable as _,
+    button::{Button, ButtonVariants as _},
+    calendar::Date,
+    checkbox::Checkbox,
+    date_picker::{DatePicker, DatePickerState},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    v_flex,
+};
+
+/// The filter definition of a [`crate::table::Column`], decides the editor in the header filter popover.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnFilter {
+    /// Filter by the text the cell contains.
+    Text,
+    /// Filter by the numeric range.
+    Number,
+    /// Filter by selecting multiple of the given options.
+    Enum(Vec<SharedString>),
+    /// Filter by the date range, picked with [`DatePicker`].
+    Date,
+}
+
+/// The value of an active column filter, passed to [`crate::table::TableDelegate::perform_filter`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnFilterValue {
+    /// The text to match, case-insensitive.
+    Text(SharedString),
+    /// The numeric range (both inclusive), `None` means unbounded.
+    Number { min: Option<f64>, max: Option<f64> },
+    /// The selected options.
+    Enum(Vec<SharedString>),
+    /// The date range (both inclusive), `None` means unbounded.
+    Date {
+        start: Option<NaiveDate>,
+        end: Option<NaiveDate>,
+    },
+}
+
+impl ColumnFilterValue {
+    /// Returns true if this filter does not filter out anything.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Text(text) => text.trim().is_empty(),
+            Self::Number { min, max } => min.is_none() && max.is_none(),
+            Self::Enum(options) => options.is_empty(),
+            Self::Date { start, end } => start.is_none() && end.is_none(),
+        }
+    }
+
+    /// Returns true if the `text` matches the [`ColumnFilterValue::Text`] or [`ColumnFilterValue::Enum`] filter.
+    ///
+    /// Always true for the other filters.
+    pub fn matches_text(&self, text: &str) -> bool {
+        match self {
+            Self::Text(query) => text.to_lowercase().contains(&query.trim().to_lowercase()),
+            Self::Enum(options) => {
+                options.is_empty() || options.iter().any(|option| option.as_ref() == text)
+            }
+            _ => true,
+        }
+    }
+
+    /// Returns true if the `value` matches the [`ColumnFilterValue::Number`] filter.
+    ///
+    /// Always true for the other filters.
+    pub fn matches_number(&self, value: f64) -> bool {
+        match self {
+            Self::Number { min, max } => {
+                min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max)
+            }
+            _ => true,
+        }
+    }
+
+    /// Returns true if the `date` matches the [`ColumnFilterValue::Date`] filter.
+    ///
+    /// Always true for the other filters.
+    pub fn matches_date(&self, date: NaiveDate) -> bool {
+        match self {
+            Self::Date { start, end } => {
+                start.is_none_or(|start| date >= start) && end.is_none_or(|end| date <= end)
+            }
+            _ => true,
+        }
+    }
+}
+
+enum FilterInput {
+    Text(Entity<InputState>),
+    Number {
+        min: Entity<InputState>,
+        max: Entity<InputState>,
+    },
+    Enum {
+        options: Vec<SharedString>,
+        checked: Vec<SharedString>,
+    },
+    Date(Entity<DatePickerState>),
+}
+
+pub(super) enum ColumnFilterEditorEvent {
+    /// Apply the filter, `None` to clear the filter.
+    Apply(Option<ColumnFilterValue>),
+}
+
+/// The editor in the header filter popover to edit the filter of a column.
+pub(super) struct ColumnFilterEditor {
+    focus_handle: FocusHandle,
+    input: FilterInput,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl EventEmitter<ColumnFilterEditorEvent> for ColumnFilterEditor {}
+
+impl ColumnFilterEditor {
+    pub(super) fn new(
+        filter: &ColumnFilter,
+        value: Option<&ColumnFilterValue>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut inputs = vec![];
+        let input = match filter {
+            ColumnFilter::Text => {
+                let text = match value {
+                    Some(ColumnFilterValue::Text(text)) => text.clone(),
+                    _ => SharedString::default(),
+                };
+                let state = cx.new(|cx| {
+                    InputState::new(window, cx)
+                        .placeholder(t!("Table.filter_placeholder"))
+                        .default_value(text)
+                });
+                inputs.push(state.clone());
+                FilterInput::Text(state)
+            }
+            ColumnFilter::Number => {
+                let (min, max) = match value {
+                    Some(ColumnFilterValue::Number { min, max }) => (*min, *max),
+                    _ => (None, None),
+                };
+                let mut number_input = |placeholder: SharedString, value: Option<f64>| {
+                    let state = cx.new(|cx| {
+                        InputState::new(window, cx)
+                            .placeholder(placeholder)
+                            .default_value(value.map(|v| v.to_string()).unwrap_or_default())
+                    });
+                    inputs.push(state.clone());
+                    state
+                };
+                FilterInput::Number {
+                    min: number_input(t!("Table.Min").into(), min),
+                    max: number_input(t!("Table.Max").into(), max),
+                }
+            }
+            ColumnFilter::Enum(options) => FilterInput::Enum {
+                options: options.clone(),
+                checked: match value {
+                    Some(ColumnFilterValue::Enum(checked)) => checked.clone(),
+                    _ => vec![],
+                },
+            },
+            ColumnFilter::Date => {
+                let (start, end) = match value {
+                    Some(ColumnFilterValue::Date { start, end }) => (*start, *end),
+                    _ => (None, None),
+                };
+                FilterInput::Date(cx.new(|cx| {
+                    let mut state = DatePickerState::range(window, cx);
+                    state.set_date(Date::Range(start, end), window, cx);
+                    state
+                }))
+            }
+        };
+
+        let _subscriptions = inputs
+            .iter()
+            .map(|state| {
+                cx.subscribe_in(state, window, |this, _, event: &InputEvent, window, cx| {
+                    if let InputEvent::PressEnter { .. } = event {
+                        this.apply(window, cx);
+                    }
+                })
+            })
+            .collect();
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            input,
+            _subscriptions,
+        }
+    }
+
+    /// Returns the filter value of the editor, `None` if the filter is empty.
+    fn value(&self, cx: &App) -> Option<ColumnFilterValue> {
+        let parse_number = |state: &Entity<InputState>| state.read(cx).value().trim().parse().ok();
+
+        let value = match &self.input {
+            FilterInput::Text(state) => ColumnFilterValue::Text(state.read(cx).value()),
+            FilterInput::Number { min, max } => ColumnFilterValue::Number {
+                min: parse_number(min),
+                max: parse_number(max),
+            },
+            FilterInput::Enum { options, checked } => ColumnFilterValue::Enum(
+                // Keep the options order.
+                options
+                    .iter()
+                    .filter(|option| checked.contains(option))
+                    .cloned()
+                    .collect(),
+            ),
+            FilterInput::Date(state) => match state.read(cx).date() {
+                Date::Range(start, end) => ColumnFilterValue::Date { start, end },
+                Date::Single(date) => ColumnFilterValue::Date {
+                    start: date,
+                    end: date,
+                },
+            },
+        };
+
+        (!value.is_empty()).then_some(value)
+    }
+
+    fn apply(&mut self, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(ColumnFilterEditorEvent::Apply(self.value(cx)));
+    }
+
+    fn clear(&mut self, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(ColumnFilterEditorEvent::Apply(None));
+    }
+
+    fn toggle_option(&mut self, option: SharedString, checked: bool, cx: &mut Context<Self>) {
+        if let FilterInput::Enum {
+            checked: checked_options,
+            ..
+        } = &mut self.input
+        {
+            checked_options.retain(|item| item != &option);
+            if checked {
+                checked_options.push(option);
+            }
+            cx.notify();
+        }
+    }
+}
+
+impl Focusable for ColumnFilterEditor {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        match &self.input {
+            FilterInput::Text(state) | FilterInput::Number { min: state, .. } => {
+                state.focus_handle(cx)
+            }
+            _ => self.focus_handle.clone(),
+        }
+    }
+}
+
+impl Render for ColumnFilterEditor {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let editor = match &self.input {
+            FilterInput::Text(state) => Input::new(state).small().into_any_element(),
+            FilterInput::Number { min, max } => h_flex()
+                .gap_2()
+                .child(Input::new(min).small())
+                .child("-")
+                .child(Input::new(max).small())
+                .into_any_element(),
+            FilterInput::Enum { options, checked } => v_flex()
+                .id("options")
+                .max_h(px(240.))
+                .overflow_y_scroll()
+                .gap_2()
+                .children(options.iter().enumerate().map(|(ix, option)| {
+                    let option = option.clone();
+                    Checkbox::new(("option", ix))
+                        .label(option.clone())
+                        .checked(checked.contains(&option))
+                        .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                            this.toggle_option(option.clone(), *checked, cx);
+                        }))
+                }))
+                .into_any_element(),
+            FilterInput::Date(state) => DatePicker::new(state).small().into_any_element(),
+        };
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .w(px(220.))
+            .gap_3()
+            .child(editor)
+            .child(
+                h_flex()
+                    .justify_end()
+                    .gap_2()
+                    .child(
+                        Button::new("clear")
+                            .small()
+                            .ghost()
+                            .label(t!("Table.Clear"))
+                            .on_click(cx.listener(|this, _, window, cx| this.clear(window, cx))),
+                    )
+                    .child(
+                        Button::new("apply")
+                            .small()
+                            .primary()
+                            .label(t!("Table.Apply"))
+                            .on_click(cx.listener(|this, _, window, cx| this.apply(window, cx))),
+                    ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::ColumnFilterValue;
+
+    #[test]
+    fn test_column_filter_value() {
+        let filter = ColumnFilterValue::Text(" app ".into());
+        assert!(!filter.is_empty());
+        assert!(filter.matches_text("AAPL Apple Inc."));
+        assert!(!filter.matches_text("Microsoft"));
+        assert!(filter.matches_number(1.));
+        assert!(ColumnFilterValue::Text("  ".into()).is_empty());
+
+        let filter = ColumnFilterValue::Enum(vec!["US".into(), "HK".into()]);
+        assert!(filter.matches_text("HK"));
+        assert!(!filter.matches_text("SZ"));
+        assert!(ColumnFilterValue::Enum(vec![]).is_empty());
+
+        let filter = ColumnFilterValue::Number {
+            min: Some(1.5),
+            max: None,
+        };
+        assert!(filter.matches_number(1.5));
+        assert!(filter.matches_number(1000.));
+        assert!(!filter.matches_number(1.));
+        assert!(filter.matches_text("anything"));
+        let filter = ColumnFilterValue::Number {
+            min: None,
+            max: Some(-1.),
+        };
+        assert!(filter.matches_number(-2.));
+        assert!(!filter.matches_number(0.));
+
+        let date = |day| NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+        let filter = ColumnFilterValue::Date {
+            start: Some(date(2)),
+            end: Some(date(4)),
+        };
+        assert!(filter.matches_date(date(2)));
+        assert!(filter.matches_date(date(4)));
+        assert!(!filter.matches_date(date(1)));
+        assert!(!filter.matches_date(date(5)));
+        assert!(
+            ColumnFilterValue::Date {
+                start: None,
+                end: None
+            }
+            .is_empty()
+        );
+    }
+}
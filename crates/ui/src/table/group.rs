@@ -0,0 +1,272 @@
+use std::ops::Range;
+
+use gpui::SharedString;
+
+/// The aggregate function of a [`crate::table::Column`], shown in the group footer rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnAggregate {
+    /// The sum of the values.
+    Sum,
+    /// The average of the values.
+    Avg,
+    /// The count of the rows.
+    Count,
+    /// The minimum of the values.
+    Min,
+    /// The maximum of the values.
+    Max,
+}
+
+impl ColumnAggregate {
+    /// Returns the label of the aggregate function.
+    pub fn label(&self) -> SharedString {
+        match self {
+            Self::Sum => "Sum",
+            Self::Avg => "Avg",
+            Self::Count => "Count",
+            Self::Min => "Min",
+            Self::Max => "Max",
+        }
+        .into()
+    }
+
+    /// Compute the aggregate of the values, one value for each row.
+    ///
+    /// The `None` values (not a number) are only counted by [`ColumnAggregate::Count`],
+    /// returns `None` if there is no value to aggregate.
+    pub fn compute(&self, values: impl IntoIterator<Item = Option<f64>>) -> Option<f64> {
+        let mut count = 0;
+        let values = values
+            .into_iter()
+            .inspect(|_| count += 1)
+            .flatten()
+            .collect::<Vec<_>>();
+
+        match self {
+            Self::Count => return Some(count as f64),
+            _ if values.is_empty() => return None,
+            _ => {}
+        }
+
+        let sum = values.iter().sum::<f64>();
+        Some(match self {
+            Self::Sum => sum,
+            Self::Avg => sum / values.len() as f64,
+            Self::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            Self::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Self::Count => unreachable!(),
+        })
+    }
+
+    /// Format the aggregated value, the count is formatted as integer.
+    pub fn format(&self, value: f64) -> SharedString {
+        match self {
+            Self::Count => format!("{}: {}", self.label(), value as usize),
+            _ => format!("{}: {:.2}", self.label(), value),
+        }
+        .into()
+    }
+}
+
+/// The position of the pinned rows, see [`crate::table::TableDelegate::pinned_rows_count`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowPin {
+    /// Pinned at the top of the table body, below the table head.
+    Top,
+    /// Pinned at the bottom of the table body.
+    Bottom,
+}
+
+/// A row in the table body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TableRow {
+    /// The header row of the group.
+    GroupHeader(usize),
+    /// The data row.
+    Row(usize),
+    /// The aggregates footer row of the group.
+    GroupFooter(usize),
+}
+
+/// The rows layout of the grouped table, to map the list items to the [`TableRow`].
+///
+/// The rows not in any group are not shown.
+#[derive(Debug, Default)]
+pub(crate) struct GroupLayout {
+    /// The data rows of each group.
+    groups: Vec<Range<usize>>,
+    /// The list item index of each group header.
+    starts: Vec<usize>,
+    collapsed: Vec<bool>,
+    len: usize,
+}
+
+impl GroupLayout {
+    /// Create the layout with the rows and the collapsed state of each group.
+    pub(crate) fn new(groups: Vec<Range<usize>>, collapsed: Vec<bool>, has_footer: bool) -> Self {
+        debug_assert_eq!(groups.len(), collapsed.len());
+        let mut starts = Vec::with_capacity(groups.len());
+        let mut len = 0;
+        for (rows, collapsed) in groups.iter().zip(collapsed.iter()) {
+            starts.push(len);
+            len += 1;
+            if !collapsed {
+                len += rows.len() + has_footer as usize;
+            }
+        }
+
+        Self {
+            groups,
+            starts,
+            collapsed,
+            len,
+        }
+    }
+
+    /// Returns the number of the list items.
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the data rows of the group.
+    pub(crate) fn group_rows(&self, group_ix: usize) -> Range<usize> {
+        self.groups.get(group_ix).cloned().unwrap_or_default()
+    }
+
+    /// Returns true if the group is collapsed.
+    pub(crate) fn is_collapsed(&self, group_ix: usize) -> bool {
+        self.collapsed.get(group_ix).copied().unwrap_or(false)
+    }
+
+    /// Returns the row at the list item index.
+    pub(crate) fn row_at(&self, ix: usize) -> Option<TableRow> {
+        if ix >= self.len {
+            return None;
+        }
+
+        let group_ix = self.starts.partition_point(|start| *start <= ix) - 1;
+        let offset = ix - self.starts[group_ix];
+        let rows = &self.groups[group_ix];
+        Some(if offset == 0 {
+            TableRow::GroupHeader(group_ix)
+        } else if offset <= rows.len() {
+            TableRow::Row(rows.start + offset - 1)
+        } else {
+            TableRow::GroupFooter(group_ix)
+        })
+    }
+
+    /// Returns the index of the group that contains the data row.
+    pub(crate) fn group_of_row(&self, row_ix: usize) -> Option<usize> {
+        self.groups.iter().position(|rows| rows.contains(&row_ix))
+    }
+
+    /// Returns the list item index of the data row, `None` if the row is hidden.
+    pub(crate) fn index_of_row(&self, row_ix: usize) -> Option<usize> {
+        let group_ix = self.group_of_row(row_ix)?;
+        if self.collapsed[group_ix] {
+            return None;
+        }
+
+        Some(self.starts[group_ix] + 1 + row_ix - self.groups[group_ix].start)
+    }
+
+    /// Returns the list item index of the group header.
+    pub(crate) fn index_of_group(&self, group_ix: usize) -> Option<usize> {
+        self.starts.get(group_ix).copied()
+    }
+
+    /// Returns the next visible data row after (or before if not `forward`) the `row_ix`.
+    ///
+    /// If `row_ix` is `None` or hidden, returns the first (or last) visible data row.
+    pub(crate) fn next_row(&self, row_ix: Option<usize>, forward: bool) -> Option<usize> {
+        let rows = |ix: usize| match self.row_at(ix) {
+            Some(TableRow::Row(row_ix)) => Some(row_ix),
+            _ => None,
+        };
+
+        match row_ix.and_then(|row_ix| self.index_of_row(row_ix)) {
+            Some(ix) if forward => (ix + 1..self.len).find_map(rows),
+            Some(ix) => (0..ix).rev().find_map(rows),
+            None if forward => (0..self.len).find_map(rows),
+            None => (0..self.len).rev().find_map(rows),
+        }
+    }
+
+    /// Returns the data rows rendered in the list items range, split by the hidden rows.
+    pub(crate) fn rows_ranges(&self, range: Range<usize>) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = vec![];
+        for ix in range {
+            let Some(TableRow::Row(row_ix)) = self.row_at(ix) else {
+                continue;
+            };
+            match ranges.last_mut() {
+                Some(last) if last.end == row_ix => last.end += 1,
+                _ => ranges.push(row_ix..row_ix + 1),
+            }
+        }
+        ranges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_aggregate() {
+        let values = [Some(1.), None, Some(4.), Some(-2.)];
+        assert_eq!(ColumnAggregate::Sum.compute(values), Some(3.));
+        assert_eq!(ColumnAggregate::Avg.compute(values), Some(1.));
+        assert_eq!(ColumnAggregate::Count.compute(values), Some(4.));
+        assert_eq!(ColumnAggregate::Min.compute(values), Some(-2.));
+        assert_eq!(ColumnAggregate::Max.compute(values), Some(4.));
+        assert_eq!(ColumnAggregate::Avg.compute([None]), None);
+        assert_eq!(ColumnAggregate::Count.compute([]), Some(0.));
+
+        assert_eq!(ColumnAggregate::Count.format(4.), "Count: 4");
+        assert_eq!(ColumnAggregate::Avg.format(1. / 3.), "Avg: 0.33");
+    }
+
+    #[test]
+    fn test_group_layout() {
+        let layout = GroupLayout::new(vec![0..2, 2..5, 5..6], vec![false, true, false], true);
+        assert_eq!(layout.len(), 8);
+        assert_eq!(
+            (0..layout.len())
+                .filter_map(|ix| layout.row_at(ix))
+                .collect::<Vec<_>>(),
+            vec![
+                TableRow::GroupHeader(0),
+                TableRow::Row(0),
+                TableRow::Row(1),
+                TableRow::GroupFooter(0),
+                TableRow::GroupHeader(1),
+                TableRow::GroupHeader(2),
+                TableRow::Row(5),
+                TableRow::GroupFooter(2),
+            ]
+        );
+        assert_eq!(layout.row_at(8), None);
+
+        assert_eq!(layout.index_of_row(1), Some(2));
+        assert_eq!(layout.index_of_row(3), None);
+        assert_eq!(layout.index_of_row(5), Some(6));
+        assert_eq!(layout.index_of_group(2), Some(5));
+        assert_eq!(layout.group_of_row(3), Some(1));
+        assert_eq!(layout.group_of_row(6), None);
+
+        assert_eq!(layout.next_row(None, true), Some(0));
+        assert_eq!(layout.next_row(None, false), Some(5));
+        assert_eq!(layout.next_row(Some(1), true), Some(5));
+        assert_eq!(layout.next_row(Some(5), false), Some(1));
+        assert_eq!(layout.next_row(Some(5), true), None);
+        assert_eq!(layout.rows_ranges(1..7), vec![0..2, 5..6]);
+        assert!(layout.rows_ranges(3..6).is_empty());
+
+        let layout = GroupLayout::new(vec![0..2, 2..3], vec![false, false], false);
+        assert_eq!(layout.len(), 5);
+        assert_eq!(layout.row_at(3), Some(TableRow::GroupHeader(1)));
+        assert_eq!(layout.row_at(4), Some(TableRow::Row(2)));
+    }
+}
@@ -7,16 +7,44 @@ use gpui::{
     IntoElement, KeyBinding, ParentElement, RenderOnce, Styled, Window,
 };
 
+mod clipboard;
 mod column;
 mod delegate;
+mod editing;
+mod filter;
+mod group;
 mod loading;
+mod selection;
 mod state;
+mod view_state;
 
+pub use clipboard::*;
 pub use column::*;
 pub use delegate::*;
+pub use editing::*;
+pub use filter::*;
+pub use group::*;
+pub use selection::*;
 pub use state::*;
+pub use view_state::*;
 
-actions!(table, [SelectPrevColumn, SelectNextColumn]);
+actions!(
+    table,
+    [
+        SelectPrevColumn,
+        SelectNextColumn,
+        ExtendSelectionUp,
+        ExtendSelectionDown,
+        ExtendSelectionLeft,
+        ExtendSelectionRight,
+        SelectAll,
+        Copy,
+        Paste,
+        EditCell,
+        EditNextCell,
+        EditPrevCell
+    ]
+);
 
 const CONTEXT: &'static str = "Table";
 pub(crate) fn init(cx: &mut App) {
@@ -26,6 +54,25 @@ pub(crate) fn init(cx: &mut App) {
         KeyBinding::new("down", SelectDown, Some(CONTEXT)),
         KeyBinding::new("left", SelectPrevColumn, Some(CONTEXT)),
         KeyBinding::new("right", SelectNextColumn, Some(CONTEXT)),
+        KeyBinding::new("shift-up", ExtendSelectionUp, Some(CONTEXT)),
+        KeyBinding::new("shift-down", ExtendSelectionDown, Some(CONTEXT)),
+        KeyBinding::new("shift-left", ExtendSelectionLeft, Some(CONTEXT)),
+        KeyBinding::new("shift-right", ExtendSelectionRight, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-a", SelectAll, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-a", SelectAll, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-c", Copy, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-c", Copy, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-v", Paste, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-v", Paste, Some(CONTEXT)),
+        KeyBinding::new("enter", EditCell, Some(CONTEXT)),
+        KeyBinding::new("tab", EditNextCell, Some(CONTEXT)),
+        KeyBinding::new("shift-tab", EditPrevCell, Some(CONTEXT)),
     ]);
 }
 
@@ -123,6 +170,16 @@ where
             .on_action(window.listener_for(&self.state, TableState::action_select_prev))
             .on_action(window.listener_for(&self.state, TableState::action_select_next_col))
             .on_action(window.listener_for(&self.state, TableState::action_select_prev_col))
+            .on_action(window.listener_for(&self.state, TableState::action_extend_selection_up))
+            .on_action(window.listener_for(&self.state, TableState::action_extend_selection_down))
+            .on_action(window.listener_for(&self.state, TableState::action_extend_selection_left))
+            .on_action(window.listener_for(&self.state, TableState::action_extend_selection_right))
+            .on_action(window.listener_for(&self.state, TableState::action_select_all))
+            .on_action(window.listener_for(&self.state, TableState::action_copy))
+            .on_action(window.listener_for(&self.state, TableState::action_paste))
+            .on_action(window.listener_for(&self.state, TableState::action_edit_cell))
+            .on_action(window.listener_for(&self.state, TableState::action_edit_next_cell))
+            .on_action(window.listener_for(&self.state, TableState::action_edit_prev_cell))
             .bg(cx.theme().table)
             .when(bordered, |this| {
                 this.rounded(cx.theme().radius)
@@ -0,0 +1,318 @@
+use std::ops::Range;
+
+/// A cell position in the table.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TableCell {
+    /// The row index.
+    pub row: usize,
+    /// The column index.
+    pub col: usize,
+}
+
+impl TableCell {
+    /// Create a new cell position with the given row and column index.
+    pub fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+}
+
+/// A rectangular range of cells, from the `anchor` cell to the `head` cell (both inclusive).
+///
+/// The `head` is the cell moved by the mouse dragging or the keyboard, it may be before the `anchor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellRange {
+    /// The cell where the selection started.
+    pub anchor: TableCell,
+    /// The cell where the selection ended.
+    pub head: TableCell,
+}
+
+impl CellRange {
+    /// Create a new cell range from the `anchor` to the `head`.
+    pub fn new(anchor: TableCell, head: TableCell) -> Self {
+        Self { anchor, head }
+    }
+
+    /// Returns the range of the rows (end exclusive).
+    pub fn rows(&self) -> Range<usize> {
+        self.anchor.row.min(self.head.row)..self.anchor.row.max(self.head.row) + 1
+    }
+
+    /// Returns the range of the columns (end exclusive).
+    pub fn cols(&self) -> Range<usize> {
+        self.anchor.col.min(self.head.col)..self.anchor.col.max(self.head.col) + 1
+    }
+
+    /// Returns true if the cell at the given row and column is in this range.
+    pub fn contains(&self, row_ix: usize, col_ix: usize) -> bool {
+        self.rows().contains(&row_ix) && self.cols().contains(&col_ix)
+    }
+}
+
+/// The selection of the [`crate::table::Table`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum TableSelection {
+    /// Nothing selected.
+    #[default]
+    None,
+    /// The selected rows, the ranges are sorted and not overlapped.
+    Rows(Vec<Range<usize>>),
+    /// The selected cell ranges, the last one is the active range.
+    Cells(Vec<CellRange>),
+}
+
+impl TableSelection {
+    /// Create a rows selection with the given row ranges, the ranges can be unsorted or overlapped.
+    pub fn rows(ranges: impl IntoIterator<Item = Range<usize>>) -> Self {
+        let ranges = normalize_ranges(ranges.into_iter().collect());
+        if ranges.is_empty() {
+            Self::None
+        } else {
+            Self::Rows(ranges)
+        }
+    }
+
+    /// Create a cells selection with the given cell range.
+    pub fn cells(range: CellRange) -> Self {
+        Self::Cells(vec![range])
+    }
+
+    /// Returns true if nothing selected.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::None => true,
+            Self::Rows(ranges) => ranges.is_empty(),
+            Self::Cells(ranges) => ranges.is_empty(),
+        }
+    }
+
+    /// Returns true if the row is selected, always false for the cells selection.
+    pub fn contains_row(&self, row_ix: usize) -> bool {
+        match self {
+            Self::Rows(ranges) => ranges.iter().any(|range| range.contains(&row_ix)),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the cell at the given row and column is selected.
+    ///
+    /// All cells of the selected rows are selected.
+    pub fn contains_cell(&self, row_ix: usize, col_ix: usize) -> bool {
+        match self {
+            Self::None => false,
+            Self::Rows(_) => self.contains_row(row_ix),
+            Self::Cells(ranges) => ranges.iter().any(|range| range.contains(row_ix, col_ix)),
+        }
+    }
+
+    /// Returns the selected row indices in ascending order.
+    ///
+    /// For cells selection, this returns the rows that contain any selected cell.
+    pub fn selected_rows(&self) -> Vec<usize> {
+        match self {
+            Self::None => vec![],
+            Self::Rows(ranges) => ranges.iter().flat_map(|range| range.clone()).collect(),
+            Self::Cells(ranges) => normalize_ranges(ranges.iter().map(|r| r.rows()).collect())
+                .into_iter()
+                .flatten()
+                .collect(),
+        }
+    }
+
+    /// Returns the selected cell ranges, empty if not the cells selection.
+    pub fn cell_ranges(&self) -> &[CellRange] {
+        match self {
+            Self::Cells(ranges) => ranges,
+            _ => &[],
+        }
+    }
+
+    /// Toggle the selection of the row, the selection will be changed to rows selection.
+    pub(crate) fn toggle_row(&mut self, row_ix: usize) {
+        let mut ranges = match self {
+            Self::Rows(ranges) => std::mem::take(ranges),
+            _ => vec![],
+        };
+
+        if let Some(ix) = ranges.iter().position(|range| range.contains(&row_ix)) {
+            let range = ranges.remove(ix);
+            ranges.push(range.start..row_ix);
+            ranges.push(row_ix + 1..range.end);
+        } else {
+            ranges.push(row_ix..row_ix + 1);
+        }
+
+        *self = Self::rows(ranges);
+    }
+
+    /// Returns the selection with the rows moved by [`shift_row`].
+    pub(crate) fn shift_rows(&self, row_ix: usize, delta: isize) -> Self {
+        let shift = |row| shift_row(row, row_ix, delta);
+        match self {
+            Self::None => Self::None,
+            Self::Rows(ranges) => Self::rows(
+                ranges
+                    .iter()
+                    .map(|range| shift(range.start)..shift(range.end - 1) + 1),
+            ),
+            Self::Cells(ranges) => Self::Cells(
+                ranges
+                    .iter()
+                    .map(|range| {
+                        CellRange::new(
+                            TableCell::new(shift(range.anchor.row), range.anchor.col),
+                            TableCell::new(shift(range.head.row), range.head.col),
+                        )
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Returns the selection with only the valid rows and columns.
+    pub(crate) fn clamp(&self, rows_count: usize, cols_count: usize) -> Self {
+        match self {
+            Self::None => Self::None,
+            Self::Rows(ranges) => Self::rows(
+                ranges
+                    .iter()
+                    .map(|range| range.start.min(rows_count)..range.end.min(rows_count)),
+            ),
+            Self::Cells(ranges) => {
+                if rows_count == 0 || cols_count == 0 {
+                    return Self::None;
+                }
+
+                let clamp_cell = |cell: TableCell| TableCell {
+                    row: cell.row.min(rows_count - 1),
+                    col: cell.col.min(cols_count - 1),
+                };
+                Self::Cells(
+                    ranges
+                        .iter()
+                        .map(|range| {
+                            CellRange::new(clamp_cell(range.anchor), clamp_cell(range.head))
+                        })
+                        .collect(),
+                )
+            }
+        }
+    }
+}
+
+/// Returns the new index of the `row`, when `delta` rows are inserted (or removed if negative)
+/// after the row at `row_ix`, the removed rows are moved to `row_ix`.
+pub(crate) fn shift_row(row: usize, row_ix: usize, delta: isize) -> usize {
+    if row <= row_ix {
+        row
+    } else if delta >= 0 {
+        row + delta as usize
+    } else {
+        row.saturating_sub(delta.unsigned_abs()).max(row_ix)
+    }
+}
+
+/// Sort and merge the overlapped or adjacent ranges, and remove the empty ranges.
+fn normalize_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.retain(|range| !range.is_empty());
+    ranges.sort_by_key(|range| range.start);
+
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_ranges() {
+        assert!(normalize_ranges(vec![4..4]).is_empty());
+        assert_eq!(
+            normalize_ranges(vec![5..8, 0..2, 1..3, 3..4, 10..10]),
+            vec![0..4, 5..8]
+        );
+    }
+
+    #[test]
+    fn test_cell_range() {
+        let range = CellRange::new(TableCell::new(5, 3), TableCell::new(2, 4));
+        assert_eq!(range.rows(), 2..6);
+        assert_eq!(range.cols(), 3..5);
+        assert!(range.contains(2, 3));
+        assert!(range.contains(5, 4));
+        assert!(!range.contains(6, 4));
+        assert!(!range.contains(3, 2));
+    }
+
+    #[test]
+    fn test_table_selection() {
+        assert!(TableSelection::None.is_empty());
+        assert!(TableSelection::rows([3..3]).is_empty());
+
+        let mut selection = TableSelection::rows([2..5]);
+        selection.toggle_row(8);
+        assert_eq!(selection, TableSelection::Rows(vec![2..5, 8..9]));
+        assert_eq!(selection.selected_rows(), vec![2, 3, 4, 8]);
+        assert!(selection.contains_cell(3, 100));
+
+        selection.toggle_row(3);
+        assert_eq!(selection, TableSelection::Rows(vec![2..3, 4..5, 8..9]));
+        selection.toggle_row(3);
+        selection.toggle_row(8);
+        assert_eq!(selection, TableSelection::Rows(vec![2..5]));
+        assert_eq!(selection.clamp(4, 3), TableSelection::Rows(vec![2..4]));
+
+        selection = TableSelection::Cells(vec![
+            CellRange::new(TableCell::new(1, 1), TableCell::new(2, 2)),
+            CellRange::new(TableCell::new(6, 0), TableCell::new(4, 0)),
+        ]);
+        assert!(!selection.contains_row(1));
+        assert!(selection.contains_cell(2, 1));
+        assert!(selection.contains_cell(5, 0));
+        assert!(!selection.contains_cell(3, 1));
+        assert_eq!(selection.selected_rows(), vec![1, 2, 4, 5, 6]);
+        assert_eq!(
+            selection.clamp(5, 1).cell_ranges(),
+            &[
+                CellRange::new(TableCell::new(1, 0), TableCell::new(2, 0)),
+                CellRange::new(TableCell::new(4, 0), TableCell::new(4, 0)),
+            ]
+        );
+
+        selection.toggle_row(0);
+        assert_eq!(selection, TableSelection::Rows(vec![0..1]));
+    }
+
+    #[test]
+    fn test_shift_rows() {
+        assert_eq!(shift_row(2, 2, 3), 2);
+        assert_eq!(shift_row(3, 2, 3), 6);
+        assert_eq!(shift_row(4, 2, -2), 2);
+        assert_eq!(shift_row(5, 2, -2), 3);
+
+        let selection = TableSelection::rows([0..1, 3..5, 7..8]);
+        assert_eq!(
+            selection.shift_rows(2, 2),
+            TableSelection::Rows(vec![0..1, 5..7, 9..10])
+        );
+        // The rows 3 and 4 are removed, so moved to the row 2.
+        assert_eq!(
+            selection.shift_rows(2, -2),
+            TableSelection::Rows(vec![0..1, 2..3, 5..6])
+        );
+
+        let selection =
+            TableSelection::cells(CellRange::new(TableCell::new(1, 0), TableCell::new(4, 2)));
+        assert_eq!(
+            selection.shift_rows(2, 1).cell_ranges(),
+            &[CellRange::new(TableCell::new(1, 0), TableCell::new(5, 2))]
+        );
+    }
+}
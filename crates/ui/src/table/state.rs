@@ -1,22 +1,37 @@
-use std::{ops::Range, rc::Rc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+    rc::Rc,
+    time::Duration,
+};
 
 use crate::{
     ActiveTheme, Icon, IconName, StyleSized as _, StyledExt, VirtualListScrollHandle,
     actions::{Cancel, SelectDown, SelectUp},
+    button::{Button, ButtonVariants as _},
+    date_picker::DatePickerEvent,
     h_flex,
-    menu::{ContextMenuExt, PopupMenu},
+    input::InputEvent,
+    menu::{ContextMenuExt, PopupMenu, PopupMenuItem},
+    popover::Popover,
     scroll::{ScrollableMask, Scrollbar},
+    select::SelectEvent,
+    spinner::Spinner,
     v_flex,
 };
 use gpui::{
-    AppContext, Axis, Bounds, ClickEvent, Context, Div, DragMoveEvent, EventEmitter, FocusHandle,
-    Focusable, InteractiveElement, IntoElement, ListSizingBehavior, MouseButton, MouseDownEvent,
-    ParentElement, Pixels, Point, Render, ScrollStrategy, SharedString, Stateful,
-    StatefulInteractiveElement as _, Styled, Task, UniformListScrollHandle, Window, canvas, div,
+    AnyElement, App, AppContext, Axis, Bounds, ClickEvent, ClipboardItem, Context, Corner, Div,
+    DragMoveEvent, ElementId, EventEmitter, FocusHandle, Focusable, InteractiveElement,
+    IntoElement, ListSizingBehavior, MouseButton, MouseDownEvent, MouseMoveEvent, ParentElement,
+    Pixels, Point, Render, ScrollStrategy, SharedString, Stateful, StatefulInteractiveElement as _,
+    Styled, Subscription, Task, TextAlign, UniformListScrollHandle, Window, canvas, div,
     prelude::FluentBuilder, px, uniform_list,
 };
 
-use super::*;
+use super::{
+    filter::{ColumnFilterEditor, ColumnFilterEditorEvent},
+    *,
+};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 enum SelectionState {
@@ -33,6 +48,12 @@ pub enum TableEvent {
     DoubleClickedRow(usize),
     /// Selected column.
     SelectColumn(usize),
+    /// Selected multiple rows, by shift/secondary click or shift + up/down keys.
+    ///
+    /// The ranges are sorted and not overlapped.
+    SelectRows(Vec<Range<usize>>),
+    /// Selected cells, the last range is the active range.
+    SelectCells(Vec<CellRange>),
     /// The column widths have changed.
     ///
     /// The `Vec<Pixels>` contains the new widths of all columns.
@@ -42,6 +63,26 @@ pub enum TableEvent {
     /// The first `usize` is the original index of the column,
     /// and the second `usize` is the new index of the column.
     MoveColumn(usize, usize),
+    /// A column has been hidden or shown.
+    ///
+    /// The `bool` is true if the column is hidden.
+    ColumnVisibilityChanged(usize, bool),
+}
+
+/// The cell is being edited.
+struct EditingCell {
+    cell: TableCell,
+    editor: TableCellEditor,
+    /// The validation error returned by [`TableDelegate::on_cell_edited`].
+    error: Option<SharedString>,
+    _subscription: Subscription,
+}
+
+/// The header filter popover is open on the column.
+struct FilterPopover {
+    col_ix: usize,
+    editor: Entity<ColumnFilterEditor>,
+    _subscription: Subscription,
 }
 
 /// The visible range of the rows and columns.
@@ -55,6 +96,8 @@ pub struct TableVisibleRange {
 
 impl TableVisibleRange {
     /// Returns the visible range of the rows.
+    ///
+    /// When the rows are grouped, the rows of the collapsed groups in the range are not visible.
     pub fn rows(&self) -> &Range<usize> {
         &self.rows
     }
@@ -74,6 +117,8 @@ pub struct TableState<D: TableDelegate> {
     bounds: Bounds<Pixels>,
     /// The bounds of the fixed head cols.
     fixed_head_cols_bounds: Bounds<Pixels>,
+    /// The bounds of the right fixed head cols.
+    fixed_right_head_cols_bounds: Bounds<Pixels>,
 
     col_groups: Vec<ColGroup>,
 
@@ -85,12 +130,22 @@ pub struct TableState<D: TableDelegate> {
     pub col_selectable: bool,
     /// Whether the table can select row.
     pub row_selectable: bool,
+    /// Whether the table can select multiple rows or cell ranges, default is false.
+    pub multi_select: bool,
+    /// Whether the table select cells instead of rows, default is false.
+    pub cell_selectable: bool,
     /// Whether the table can sort.
     pub sortable: bool,
+    /// Whether the table can sort by multiple columns with shift-click, default is false.
+    pub multi_sort: bool,
+    /// Whether the table can filter, default is true.
+    pub filterable: bool,
     /// Whether the table can resize columns.
     pub col_resizable: bool,
     /// Whether the table can move columns.
     pub col_movable: bool,
+    /// Whether the columns can be hidden by the header context menu, default is true.
+    pub col_hidable: bool,
     /// Enable/disable fixed columns feature.
     pub col_fixed: bool,
 
@@ -100,7 +155,30 @@ pub struct TableState<D: TableDelegate> {
     selected_row: Option<usize>,
     selection_state: SelectionState,
     right_clicked_row: Option<usize>,
+    /// Whether the table head is right clicked, to show the columns menu.
+    right_clicked_head: bool,
     selected_col: Option<usize>,
+    selection: TableSelection,
+    /// The cell where the selection started, used to extend the selection.
+    selection_anchor: Option<TableCell>,
+    /// The active cell (the head of the last cell range) of the cells selection.
+    active_cell: Option<TableCell>,
+    /// Whether the mouse is dragging to select cells.
+    selecting_cells: bool,
+    editing: Option<EditingCell>,
+    filter_popover: Option<FilterPopover>,
+    /// The keys of the sorted columns, in the sort priority order.
+    sort_keys: Vec<SharedString>,
+    /// The ids of the collapsed groups, see [`TableDelegate::group_id`].
+    collapsed_groups: HashSet<SharedString>,
+    /// The rows layout when the table is grouped, prepared on render if the data has changed.
+    group_layout: Option<GroupLayout>,
+    /// Whether the delegate data may have changed since the `group_layout` was prepared.
+    group_layout_dirty: bool,
+    /// The aggregated values of the group footers, keyed by the group and column index.
+    group_aggregates: HashMap<(usize, usize), Option<f64>>,
+    /// The parent row of each row in the tree, prepared when navigating to the parent row.
+    tree_parents: Option<Vec<Option<usize>>>,
 
     /// The column index that is being resized.
     resizing_col: Option<usize>,
@@ -128,16 +206,35 @@ where
             selection_state: SelectionState::Row,
             selected_row: None,
             right_clicked_row: None,
+            right_clicked_head: false,
             selected_col: None,
+            selection: TableSelection::None,
+            selection_anchor: None,
+            active_cell: None,
+            selecting_cells: false,
+            editing: None,
+            filter_popover: None,
+            sort_keys: Vec::new(),
+            collapsed_groups: HashSet::new(),
+            group_layout: None,
+            group_layout_dirty: true,
+            group_aggregates: HashMap::new(),
+            tree_parents: None,
             resizing_col: None,
             bounds: Bounds::default(),
             fixed_head_cols_bounds: Bounds::default(),
+            fixed_right_head_cols_bounds: Bounds::default(),
             visible_range: TableVisibleRange::default(),
             loop_selection: true,
             col_selectable: true,
             row_selectable: true,
+            multi_select: false,
+            cell_selectable: false,
             sortable: true,
+            multi_sort: false,
+            filterable: true,
             col_movable: true,
+            col_hidable: true,
             col_resizable: true,
             col_fixed: true,
             _load_more_task: Task::ready(()),
@@ -154,6 +251,8 @@ where
     }
 
     /// Returns a mutable reference to the delegate.
+    ///
+    /// Call [`TableState::refresh_rows`] after changing the rows data.
     pub fn delegate_mut(&mut self) -> &mut D {
         &mut self.delegate
     }
@@ -170,6 +269,12 @@ where
         self
     }
 
+    /// Set to enable/disable hiding columns by the header context menu, default to true.
+    pub fn col_hidable(mut self, col_hidable: bool) -> Self {
+        self.col_hidable = col_hidable;
+        self
+    }
+
     /// Set to enable/disable column resizable, default to true.
     pub fn col_resizable(mut self, col_resizable: bool) -> Self {
         self.col_resizable = col_resizable;
@@ -182,6 +287,21 @@ where
         self
     }
 
+    /// Set to enable/disable sorting by multiple columns, default false
+    ///
+    /// When enabled, shift-click the sort icon to add the column as the next sort key,
+    /// and the [`TableDelegate::perform_multi_sort`] is called instead of [`TableDelegate::perform_sort`].
+    pub fn multi_sort(mut self, multi_sort: bool) -> Self {
+        self.multi_sort = multi_sort;
+        self
+    }
+
+    /// Set to enable/disable column filterable, default true
+    pub fn filterable(mut self, filterable: bool) -> Self {
+        self.filterable = filterable;
+        self
+    }
+
     /// Set to enable/disable row selectable, default true
     pub fn row_selectable(mut self, row_selectable: bool) -> Self {
         self.row_selectable = row_selectable;
@@ -194,21 +314,53 @@ where
         self
     }
 
+    /// Set to enable/disable multiple selection, default false
+    ///
+    /// When enabled, shift-click or shift + arrow keys extend the selection,
+    /// secondary-click (`cmd` on macOS, `ctrl` on others) toggles a row or adds a cell range.
+    pub fn multi_select(mut self, multi_select: bool) -> Self {
+        self.multi_select = multi_select;
+        self
+    }
+
+    /// Set to enable/disable cells selection instead of rows, default false
+    ///
+    /// When enabled, mouse dragging selects a rectangle of cells.
+    pub fn cell_selectable(mut self, cell_selectable: bool) -> Self {
+        self.cell_selectable = cell_selectable;
+        self
+    }
+
     /// When we update columns or rows, we need to refresh the table.
     pub fn refresh(&mut self, cx: &mut Context<Self>) {
         self.prepare_col_groups(cx);
+        self.invalidate_rows();
+        self.selection = self
+            .selection
+            .clamp(self.delegate.rows_count(cx), self.col_groups.len());
+    }
+
+    /// When the rows data is changed (rows added, removed or updated), call this to recompute
+    /// the groups, aggregates and tree rows cached from the delegate.
+    pub fn refresh_rows(&mut self, cx: &mut Context<Self>) {
+        self.invalidate_rows();
+        cx.notify();
     }
 
     /// Scroll to the row at the given index.
     pub fn scroll_to_row(&mut self, row_ix: usize, cx: &mut Context<Self>) {
         self.vertical_scroll_handle
-            .scroll_to_item(row_ix, ScrollStrategy::Top);
+            .scroll_to_item(self.list_ix(row_ix), ScrollStrategy::Top);
         cx.notify();
     }
 
     // Scroll to the column at the given index.
     pub fn scroll_to_col(&mut self, col_ix: usize, cx: &mut Context<Self>) {
-        let col_ix = col_ix.saturating_sub(self.fixed_left_cols_count());
+        let scrollable_cols_count =
+            self.col_groups.len() - self.fixed_left_cols_count() - self.fixed_right_cols_count();
+        let col_ix = col_ix
+            .saturating_sub(self.fixed_left_cols_count())
+            .min(scrollable_cols_count.saturating_sub(1));
 
         self.horizontal_scroll_handle
             .scroll_to_item(col_ix, ScrollStrategy::Top);
@@ -230,9 +382,12 @@ where
         self.selection_state = SelectionState::Row;
         self.right_clicked_row = None;
         self.selected_row = Some(row_ix);
+        self.selection = TableSelection::rows([row_ix..row_ix + 1]);
+        self.selection_anchor = Some(TableCell::new(row_ix, 0));
+        self.active_cell = None;
         if let Some(row_ix) = self.selected_row {
             self.vertical_scroll_handle.scroll_to_item(
-                row_ix,
+                self.list_ix(row_ix),
                 if is_down {
                     ScrollStrategy::Bottom
                 } else {
@@ -260,215 +415,1209 @@ where
         cx.notify();
     }
 
+    /// Returns the selection of the rows or cells.
+    pub fn selection(&self) -> &TableSelection {
+        &self.selection
+    }
+
+    /// Sets the selection of the rows or cells.
+    pub fn set_selection(&mut self, selection: TableSelection, cx: &mut Context<Self>) {
+        self.selection_state = SelectionState::Row;
+        self.selection = selection.clamp(
+            self.delegate.rows_count(cx),
+            self.delegate.columns_count(cx),
+        );
+        match &self.selection {
+            TableSelection::None => {
+                self.selected_row = None;
+                self.selection_anchor = None;
+                self.active_cell = None;
+            }
+            TableSelection::Rows(ranges) => {
+                self.selected_row = ranges.last().map(|range| range.end - 1);
+                self.selection_anchor = ranges.first().map(|range| TableCell::new(range.start, 0));
+                self.active_cell = None;
+            }
+            TableSelection::Cells(ranges) => {
+                self.selected_row = None;
+                self.selection_anchor = ranges.last().map(|range| range.anchor);
+                self.active_cell = ranges.last().map(|range| range.head);
+            }
+        }
+        self.emit_selection(cx);
+        cx.notify();
+    }
+
+    /// Returns the active cell of the cells selection.
+    pub fn active_cell(&self) -> Option<TableCell> {
+        self.active_cell
+    }
+
+    /// Sets the active cell, the cells selection will be changed to the single cell.
+    pub fn set_active_cell(&mut self, cell: TableCell, cx: &mut Context<Self>) {
+        self.select_cells(CellRange::new(cell, cell), false, cx);
+    }
+
     /// Clear the selection of the table.
     pub fn clear_selection(&mut self, cx: &mut Context<Self>) {
         self.selection_state = SelectionState::Row;
         self.selected_row = None;
         self.selected_col = None;
+        self.selection = TableSelection::None;
+        self.selection_anchor = None;
+        self.active_cell = None;
         cx.notify();
     }
 
-    /// Returns the visible range of the rows and columns.
-    ///
-    /// See [`TableVisibleRange`].
-    pub fn visible_range(&self) -> &TableVisibleRange {
-        &self.visible_range
+    /// Returns the cell that is being edited.
+    pub fn editing_cell(&self) -> Option<TableCell> {
+        self.editing.as_ref().map(|editing| editing.cell)
     }
 
-    fn prepare_col_groups(&mut self, cx: &mut Context<Self>) {
-        self.col_groups = (0..self.delegate.columns_count(cx))
-            .map(|col_ix| {
-                let column = self.delegate().column(col_ix, cx);
-                ColGroup {
-                    width: column.width,
-                    bounds: Bounds::default(),
-                    column: column.clone(),
-                }
-            })
-            .collect();
+    /// Start editing the cell with the editor built by [`TableDelegate::build_cell_editor`].
+    ///
+    /// Returns false if the cell is not editable, or the current editing cell failed to commit.
+    pub fn start_editing(
+        &mut self,
+        cell: TableCell,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.editing_cell() == Some(cell) {
+            return true;
+        }
+        if !self.commit_editing(window, cx) {
+            return false;
+        }
+        if !self.delegate.is_cell_editable(cell.row, cell.col, cx) {
+            return false;
+        }
+        let Some(editor) = self
+            .delegate
+            .build_cell_editor(cell.row, cell.col, window, cx)
+        else {
+            return false;
+        };
+
+        let _subscription = match &editor {
+            TableCellEditor::Input(state) | TableCellEditor::NumberInput(state) => cx.subscribe_in(
+                state,
+                window,
+                move |this, _, event: &InputEvent, window, cx| {
+                    if matches!(event, InputEvent::Blur) {
+                        this.commit_editing_cell(cell, window, cx);
+                    }
+                },
+            ),
+            TableCellEditor::Select(state) => cx.subscribe_in(
+                state,
+                window,
+                move |this, _, _: &SelectEvent<Vec<SharedString>>, window, cx| {
+                    this.commit_editing_cell(cell, window, cx);
+                },
+            ),
+            TableCellEditor::DatePicker(state) => cx.subscribe_in(
+                state,
+                window,
+                move |this, _, _: &DatePickerEvent, window, cx| {
+                    this.commit_editing_cell(cell, window, cx);
+                },
+            ),
+        };
+
+        editor.focus_handle(cx).focus(window);
+        self.editing = Some(EditingCell {
+            cell,
+            editor,
+            error: None,
+            _subscription,
+        });
         cx.notify();
+        true
     }
 
-    fn fixed_left_cols_count(&self) -> usize {
-        if !self.col_fixed {
-            return 0;
-        }
+    /// Commit the editing cell by [`TableDelegate::on_cell_edited`].
+    ///
+    /// Returns false if the validation failed, the error will be shown in the cell and keep editing.
+    pub fn commit_editing(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        let Some(mut editing) = self.editing.take() else {
+            return true;
+        };
 
-        self.col_groups
-            .iter()
-            .filter(|col| col.column.fixed == Some(ColumnFixed::Left))
-            .count()
+        let focused = editing.editor.focus_handle(cx).contains_focused(window, cx);
+        let cell = editing.cell;
+        match self
+            .delegate
+            .on_cell_edited(cell.row, cell.col, &editing.editor, window, cx)
+        {
+            Ok(()) => {
+                self.invalidate_rows();
+                if focused {
+                    self.focus_handle.focus(window);
+                }
+                cx.notify();
+                true
+            }
+            Err(error) => {
+                editing.error = Some(error);
+                self.editing = Some(editing);
+                cx.notify();
+                false
+            }
+        }
     }
 
-    fn on_row_right_click(
-        &mut self,
-        _: &MouseDownEvent,
-        row_ix: usize,
-        _: &mut Window,
-        _: &mut Context<Self>,
-    ) {
-        self.right_clicked_row = Some(row_ix);
+    /// Cancel the editing cell without commit.
+    pub fn cancel_editing(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(editing) = self.editing.take() else {
+            return;
+        };
+
+        if editing.editor.focus_handle(cx).contains_focused(window, cx) {
+            self.focus_handle.focus(window);
+        }
+        cx.notify();
     }
 
-    fn on_row_left_click(
+    fn commit_editing_cell(
         &mut self,
-        e: &ClickEvent,
-        row_ix: usize,
-        _: &mut Window,
+        cell: TableCell,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        self.set_selected_row(row_ix, cx);
-
-        if e.click_count() == 2 {
-            cx.emit(TableEvent::DoubleClickedRow(row_ix));
+        if self.editing_cell() == Some(cell) {
+            self.commit_editing(window, cx);
         }
     }
 
-    fn on_col_head_click(&mut self, col_ix: usize, _: &mut Window, cx: &mut Context<Self>) {
-        if !self.col_selectable {
+    /// Commit the editing cell, and start editing the next (or previous) editable cell.
+    fn edit_adjacent_cell(&mut self, backward: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(cell) = self.editing_cell() else {
+            cx.propagate();
+            return;
+        };
+        if !self.commit_editing(window, cx) {
             return;
         }
 
-        let Some(col_group) = self.col_groups.get(col_ix) else {
-            return;
-        };
+        if self.group_layout_dirty {
+            self.prepare_group_layout(cx);
+        }
 
-        if !col_group.column.selectable {
+        let cols_count = self.delegate.columns_count(cx);
+        let total = self.delegate.rows_count(cx) * cols_count;
+        let ix = cell.row * cols_count + cell.col;
+        for step in 1..total {
+            let next_ix = if backward {
+                (ix + total - step) % total
+            } else {
+                (ix + step) % total
+            };
+            let next = TableCell::new(next_ix / cols_count, next_ix % cols_count);
+            // Skip the hidden columns and the rows in the collapsed groups.
+            let row_hidden = self
+                .group_layout
+                .as_ref()
+                .is_some_and(|layout| layout.index_of_row(next.row).is_none());
+            if row_hidden
+                || self.is_column_hidden(next.col)
+                || !self.delegate.is_cell_editable(next.row, next.col, cx)
+            {
+                continue;
+            }
+
+            if self.cell_selectable {
+                self.set_active_cell(next, cx);
+            } else if self.selected_row != Some(next.row) {
+                self.set_selected_row(next.row, cx);
+            }
+            self.scroll_to_col(next.col, cx);
+            self.start_editing(next, window, cx);
             return;
         }
+    }
 
-        self.set_selected_col(col_ix, cx)
+    fn emit_selection(&self, cx: &mut Context<Self>) {
+        match &self.selection {
+            TableSelection::None => {}
+            TableSelection::Rows(ranges) => cx.emit(TableEvent::SelectRows(ranges.clone())),
+            TableSelection::Cells(ranges) => cx.emit(TableEvent::SelectCells(ranges.clone())),
+        }
     }
 
-    fn has_selection(&self) -> bool {
-        self.selected_row.is_some() || self.selected_col.is_some()
+    /// Scroll to make the row visible, the `is_down` is the direction of the selection moved.
+    fn scroll_to_row_if_need(&self, row_ix: usize, is_down: bool) {
+        self.vertical_scroll_handle.scroll_to_item(
+            self.list_ix(row_ix),
+            if is_down {
+                ScrollStrategy::Bottom
+            } else {
+                ScrollStrategy::Top
+            },
+        );
     }
 
-    pub(super) fn action_cancel(&mut self, _: &Cancel, _: &mut Window, cx: &mut Context<Self>) {
-        if self.has_selection() {
-            self.clear_selection(cx);
-            return;
-        }
-        cx.propagate();
+    /// Select the rows from the selection anchor to the `row_ix`.
+    fn extend_rows_selection(&mut self, row_ix: usize, cx: &mut Context<Self>) {
+        let anchor = self
+            .selection_anchor
+            .map(|cell| cell.row)
+            .or(self.selected_row)
+            .unwrap_or(row_ix);
+        let is_down = self
+            .selected_row
+            .map_or(true, |selected_row| row_ix > selected_row);
+
+        self.selection_state = SelectionState::Row;
+        self.right_clicked_row = None;
+        self.selected_row = Some(row_ix);
+        self.selection_anchor = Some(TableCell::new(anchor, 0));
+        self.selection = TableSelection::rows([anchor.min(row_ix)..anchor.max(row_ix) + 1]);
+        self.scroll_to_row_if_need(row_ix, is_down);
+        self.emit_selection(cx);
+        cx.notify();
     }
 
-    pub(super) fn action_select_prev(
-        &mut self,
-        _: &SelectUp,
-        _: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        let rows_count = self.delegate.rows_count(cx);
-        if rows_count < 1 {
-            return;
-        }
+    /// Toggle the selection of the row, and keep other selected rows.
+    fn toggle_row_selection(&mut self, row_ix: usize, cx: &mut Context<Self>) {
+        self.selection_state = SelectionState::Row;
+        self.right_clicked_row = None;
+        self.selection.toggle_row(row_ix);
+        self.selected_row = Some(row_ix);
+        self.selection_anchor = Some(TableCell::new(row_ix, 0));
+        self.emit_selection(cx);
+        cx.notify();
+    }
 
-        let mut selected_row = self.selected_row.unwrap_or(0);
-        if selected_row > 0 {
-            selected_row = selected_row.saturating_sub(1);
-        } else {
-            if self.loop_selection {
-                selected_row = rows_count.saturating_sub(1);
-            }
-        }
+    /// Select the cell range, if `add` is true, the range is added into the current cells selection.
+    fn select_cells(&mut self, range: CellRange, add: bool, cx: &mut Context<Self>) {
+        let is_down = self
+            .active_cell
+            .map_or(true, |cell| range.head.row >= cell.row);
 
-        self.set_selected_row(selected_row, cx);
+        self.selection_state = SelectionState::Row;
+        self.right_clicked_row = None;
+        self.selected_row = None;
+        self.selection_anchor = Some(range.anchor);
+        self.active_cell = Some(range.head);
+        match &mut self.selection {
+            TableSelection::Cells(ranges) if add => ranges.push(range),
+            _ => self.selection = TableSelection::cells(range),
+        }
+        self.scroll_to_row_if_need(range.head.row, is_down);
+        self.emit_selection(cx);
+        cx.notify();
     }
 
-    pub(super) fn action_select_next(
+    /// Move the active cell by the given delta, if `extend` is true, extend the last cell range.
+    ///
+    /// The row is moved to the adjacent visible row by the sign of `row_delta`.
+    fn move_active_cell(
         &mut self,
-        _: &SelectDown,
-        _: &mut Window,
+        row_delta: isize,
+        col_delta: isize,
+        extend: bool,
         cx: &mut Context<Self>,
     ) {
         let rows_count = self.delegate.rows_count(cx);
-        if rows_count < 1 {
+        let cols_count = self.delegate.columns_count(cx);
+        if rows_count == 0 || cols_count == 0 {
             return;
         }
 
-        let selected_row = match self.selected_row {
-            Some(selected_row) if selected_row < rows_count.saturating_sub(1) => selected_row + 1,
-            Some(selected_row) => {
-                if self.loop_selection {
-                    0
-                } else {
-                    selected_row
-                }
-            }
-            _ => 0,
+        let Some(active_cell) = self.active_cell else {
+            self.set_active_cell(TableCell::default(), cx);
+            return;
         };
 
-        self.set_selected_row(selected_row, cx);
-    }
+        let row_ix = match row_delta {
+            0 => active_cell.row,
+            _ => self.adjacent_row(active_cell.row, row_delta > 0, cx),
+        };
+        let col_ix = match col_delta {
+            0 => active_cell.col,
+            _ => self
+                .adjacent_col(active_cell.col, col_delta > 0)
+                .unwrap_or(active_cell.col),
+        };
+        let head = TableCell::new(row_ix, col_ix);
+        if col_delta != 0 {
+            self.scroll_to_col(head.col, cx);
+        }
 
-    pub(super) fn action_select_prev_col(
-        &mut self,
-        _: &SelectPrevColumn,
-        _: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        let mut selected_col = self.selected_col.unwrap_or(0);
-        let columns_count = self.delegate.columns_count(cx);
-        if selected_col > 0 {
-            selected_col = selected_col.saturating_sub(1);
-        } else {
-            if self.loop_selection {
-                selected_col = columns_count.saturating_sub(1);
+        if extend && self.multi_select {
+            let anchor = self.selection_anchor.unwrap_or(active_cell);
+            if let TableSelection::Cells(ranges) = &mut self.selection {
+                ranges.pop();
             }
+            self.select_cells(CellRange::new(anchor, head), true, cx);
+        } else {
+            self.set_active_cell(head, cx);
         }
-        self.set_selected_col(selected_col, cx);
     }
 
-    pub(super) fn action_select_next_col(
+    /// Returns the visible range of the rows and columns.
+    ///
+    /// See [`TableVisibleRange`].
+    pub fn visible_range(&self) -> &TableVisibleRange {
+        &self.visible_range
+    }
+
+    /// Returns true if the group at the given index is collapsed.
+    pub fn is_group_collapsed(&self, group_ix: usize, cx: &App) -> bool {
+        self.collapsed_groups
+            .contains(&self.delegate.group_id(group_ix, cx))
+    }
+
+    /// Collapse or expand the group at the given index.
+    pub fn set_group_collapsed(
         &mut self,
-        _: &SelectNextColumn,
-        _: &mut Window,
+        group_ix: usize,
+        collapsed: bool,
         cx: &mut Context<Self>,
     ) {
-        let mut selected_col = self.selected_col.unwrap_or(0);
-        if selected_col < self.delegate.columns_count(cx).saturating_sub(1) {
-            selected_col += 1;
+        let group_id = self.delegate.group_id(group_ix, cx);
+        if collapsed {
+            self.collapsed_groups.insert(group_id);
         } else {
-            if self.loop_selection {
-                selected_col = 0;
-            }
+            self.collapsed_groups.remove(&group_id);
         }
+        self.prepare_group_layout(cx);
+        cx.notify();
+    }
 
-        self.set_selected_col(selected_col, cx);
+    /// Toggle the collapsed state of the group at the given index.
+    pub fn toggle_group(&mut self, group_ix: usize, cx: &mut Context<Self>) {
+        let collapsed = !self.is_group_collapsed(group_ix, cx);
+        self.set_group_collapsed(group_ix, collapsed, cx);
     }
 
-    /// Scroll table when mouse position is near the edge of the table bounds.
-    fn scroll_table_by_col_resizing(
-        &mut self,
-        mouse_position: Point<Pixels>,
-        col_group: &ColGroup,
-    ) {
-        // Do nothing if pos out of the table bounds right for avoid scroll to the right.
-        if mouse_position.x > self.bounds.right() {
+    /// Collapse or expand all groups.
+    pub fn set_all_groups_collapsed(&mut self, collapsed: bool, cx: &mut Context<Self>) {
+        self.collapsed_groups = if collapsed {
+            (0..self.delegate.groups_count(cx))
+                .map(|group_ix| self.delegate.group_id(group_ix, cx))
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        self.prepare_group_layout(cx);
+        cx.notify();
+    }
+
+    /// Mark the rows data cached from the delegate to be recomputed, when the rows are changed.
+    fn invalidate_rows(&mut self) {
+        self.group_layout_dirty = true;
+        self.group_aggregates.clear();
+        self.tree_parents = None;
+    }
+
+    fn prepare_group_layout(&mut self, cx: &App) {
+        self.group_layout_dirty = false;
+        let groups_count = self.delegate.groups_count(cx);
+        if groups_count == 0 {
+            self.group_layout = None;
             return;
         }
 
-        let mut offset = self.horizontal_scroll_handle.offset();
-        let col_bounds = col_group.bounds;
+        let groups = (0..groups_count)
+            .map(|group_ix| self.delegate.group_rows(group_ix, cx))
+            .collect();
+        let collapsed = (0..groups_count)
+            .map(|group_ix| self.is_group_collapsed(group_ix, cx))
+            .collect();
+        let has_footer = self
+            .col_groups
+            .iter()
+            .any(|col_group| col_group.column.aggregate.is_some());
+        self.group_layout = Some(GroupLayout::new(groups, collapsed, has_footer));
+    }
 
-        if mouse_position.x < self.bounds.left()
-            && col_bounds.right() < self.bounds.left() + px(20.)
-        {
-            offset.x += px(1.);
-        } else if mouse_position.x > self.bounds.right()
-            && col_bounds.right() > self.bounds.right() - px(20.)
-        {
-            offset.x -= px(1.);
-        }
+    /// Returns the list item index of the row, it is the group header if the row is collapsed.
+    fn list_ix(&self, row_ix: usize) -> usize {
+        let Some(layout) = &self.group_layout else {
+            return row_ix;
+        };
 
-        self.horizontal_scroll_handle.set_offset(offset);
+        layout.index_of_row(row_ix).unwrap_or_else(|| {
+            layout
+                .group_of_row(row_ix)
+                .and_then(|group_ix| layout.index_of_group(group_ix))
+                .unwrap_or(0)
+        })
     }
 
-    /// The `ix`` is the index of the col to resize,
-    /// and the `size` is the new size for the col.
-    fn resize_cols(&mut self, ix: usize, size: Pixels, _: &mut Window, cx: &mut Context<Self>) {
-        if !self.col_resizable {
-            return;
+    /// Returns the adjacent visible row of the `row_ix`, or the `row_ix` if it is the first or last row.
+    fn adjacent_row(&self, row_ix: usize, forward: bool, cx: &App) -> usize {
+        match &self.group_layout {
+            Some(layout) => layout.next_row(Some(row_ix), forward).unwrap_or(row_ix),
+            None if forward => (row_ix + 1).min(self.delegate.rows_count(cx).saturating_sub(1)),
+            None => row_ix.saturating_sub(1),
+        }
+    }
+
+    /// Returns true if the table has the tree column, see [`Column::tree`].
+    fn is_tree(&self) -> bool {
+        self.col_groups
+            .iter()
+            .any(|col_group| col_group.column.tree)
+    }
+
+    /// Expand or collapse the row at the given index, see [`TableDelegate::set_row_expanded`].
+    ///
+    /// If the children of the row are not loaded, the [`TableDelegate::load_children`] will be called.
+    pub fn set_row_expanded(
+        &mut self,
+        row_ix: usize,
+        expanded: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.delegate.row_children_count(row_ix, cx) == Some(0)
+            || self.delegate.is_row_expanded(row_ix, cx) == expanded
+        {
+            return;
+        }
+
+        self.cancel_editing(window, cx);
+
+        let rows_count = self.delegate.rows_count(cx);
+        self.delegate.set_row_expanded(row_ix, expanded, window, cx);
+        self.shift_selected_rows(row_ix, rows_count, cx);
+        self.invalidate_rows();
+        if expanded && self.delegate.row_children_count(row_ix, cx).is_none() {
+            let row_id = self.delegate.row_id(row_ix, cx);
+            cx.spawn_in(window, async move |view, window| {
+                _ = view.update_in(window, |view, window, cx| {
+                    // The rows may be changed before loading, find the row by the id again.
+                    let Some(row_ix) = view.row_ix_of_id(&row_id, row_ix, cx) else {
+                        return;
+                    };
+                    if !view.delegate.is_row_expanded(row_ix, cx)
+                        || view.delegate.row_children_count(row_ix, cx).is_some()
+                    {
+                        return;
+                    }
+
+                    let rows_count = view.delegate.rows_count(cx);
+                    view.delegate.load_children(row_ix, window, cx);
+                    view.shift_selected_rows(row_ix, rows_count, cx);
+                    view.invalidate_rows();
+                });
+            })
+            .detach();
+        }
+        cx.notify();
+    }
+
+    /// Move the selected rows after the `row_ix` by the rows inserted or removed after it,
+    /// the `old_rows_count` is the rows count before the change.
+    fn shift_selected_rows(&mut self, row_ix: usize, old_rows_count: usize, cx: &App) {
+        let delta = self.delegate.rows_count(cx) as isize - old_rows_count as isize;
+        if delta == 0 {
+            return;
+        }
+
+        let shift = |row| shift_row(row, row_ix, delta);
+        let shift_cell = |cell: TableCell| TableCell::new(shift(cell.row), cell.col);
+        self.selection = self.selection.shift_rows(row_ix, delta);
+        self.selected_row = self.selected_row.map(shift);
+        self.right_clicked_row = self.right_clicked_row.map(shift);
+        self.active_cell = self.active_cell.map(shift_cell);
+        self.selection_anchor = self.selection_anchor.map(shift_cell);
+    }
+
+    /// Toggle the expanded state of the row at the given index.
+    pub fn toggle_row_expanded(
+        &mut self,
+        row_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let expanded = !self.delegate.is_row_expanded(row_ix, cx);
+        self.set_row_expanded(row_ix, expanded, window, cx);
+    }
+
+    /// Returns the index of the row with the id, see [`TableDelegate::row_id`].
+    ///
+    /// The `hint` row index is checked first.
+    fn row_ix_of_id(&self, row_id: &SharedString, hint: usize, cx: &App) -> Option<usize> {
+        let rows_count = self.delegate.rows_count(cx);
+        if hint < rows_count && &self.delegate.row_id(hint, cx) == row_id {
+            return Some(hint);
+        }
+
+        (0..rows_count).find(|ix| &self.delegate.row_id(*ix, cx) == row_id)
+    }
+
+    /// Returns the parent row index of the row at the given index in the tree.
+    ///
+    /// The parents of all rows are computed once and cached until the rows are changed.
+    fn parent_row(&mut self, row_ix: usize, cx: &App) -> Option<usize> {
+        let delegate = &self.delegate;
+        let parents = self.tree_parents.get_or_insert_with(|| {
+            // The rows of the current path from the root, with the depth.
+            let mut ancestors: Vec<(usize, usize)> = vec![];
+            (0..delegate.rows_count(cx))
+                .map(|ix| {
+                    let depth = delegate.row_depth(ix, cx);
+                    while ancestors.last().is_some_and(|(_, d)| *d >= depth) {
+                        ancestors.pop();
+                    }
+                    let parent = ancestors.last().map(|(ix, _)| *ix);
+                    ancestors.push((ix, depth));
+                    parent
+                })
+                .collect()
+        });
+
+        parents.get(row_ix).copied().flatten()
+    }
+
+    /// Expand (`forward`) or collapse the selected row in the tree,
+    /// or move the selection to the first child or parent row.
+    ///
+    /// Returns false if the table is not tree or no row selected.
+    fn navigate_tree(
+        &mut self,
+        forward: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if !self.is_tree() || self.cell_selectable || self.selection_state != SelectionState::Row {
+            return false;
+        }
+        let Some(row_ix) = self.selected_row else {
+            return false;
+        };
+
+        let has_children = self.delegate.row_children_count(row_ix, cx) != Some(0);
+        let expanded = self.delegate.is_row_expanded(row_ix, cx);
+        if forward {
+            if has_children && !expanded {
+                self.set_row_expanded(row_ix, true, window, cx);
+            } else if has_children
+                && row_ix + 1 < self.delegate.rows_count(cx)
+                && self.delegate.row_depth(row_ix + 1, cx) > self.delegate.row_depth(row_ix, cx)
+            {
+                self.set_selected_row(row_ix + 1, cx);
+            }
+        } else if has_children && expanded {
+            self.set_row_expanded(row_ix, false, window, cx);
+        } else if let Some(parent_ix) = self.parent_row(row_ix, cx) {
+            self.set_selected_row(parent_ix, cx);
+        }
+
+        true
+    }
+
+    fn prepare_col_groups(&mut self, cx: &mut Context<Self>) {
+        let old_col_groups = std::mem::take(&mut self.col_groups);
+        self.col_groups = (0..self.delegate.columns_count(cx))
+            .map(|col_ix| {
+                let column = self.delegate().column(col_ix, cx);
+                // Keep the active filter of the same column.
+                let old_col_group = old_col_groups
+                    .iter()
+                    .find(|group| group.column.key == column.key);
+                let filter = column
+                    .filter
+                    .as_ref()
+                    .and_then(|_| old_col_group.and_then(|group| group.filter.clone()));
+                ColGroup {
+                    width: column.width,
+                    bounds: Bounds::default(),
+                    column: column.clone(),
+                    filter,
+                    // Keep the hidden columns hidden.
+                    hidden: old_col_group.is_some_and(|group| group.hidden),
+                }
+            })
+            .collect();
+        self.filter_popover = None;
+        self.sync_sort_keys();
+        cx.notify();
+    }
+
+    /// Returns the number of the left fixed columns, only the first columns can be fixed on left.
+    fn fixed_left_cols_count(&self) -> usize {
+        if !self.col_fixed {
+            return 0;
+        }
+
+        self.col_groups
+            .iter()
+            .take_while(|col| col.column.fixed == Some(ColumnFixed::Left))
+            .count()
+    }
+
+    /// Returns the number of the right fixed columns, only the last columns can be fixed on right.
+    fn fixed_right_cols_count(&self) -> usize {
+        if !self.col_fixed {
+            return 0;
+        }
+
+        self.col_groups
+            .iter()
+            .rev()
+            .take_while(|col| col.column.fixed == Some(ColumnFixed::Right))
+            .count()
+    }
+
+    fn on_row_right_click(
+        &mut self,
+        _: &MouseDownEvent,
+        row_ix: usize,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+        self.right_clicked_row = Some(row_ix);
+        self.right_clicked_head = false;
+    }
+
+    fn on_row_left_click(
+        &mut self,
+        e: &ClickEvent,
+        row_ix: usize,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // The cells selection is handled by the mouse down on the cells.
+        if !self.cell_selectable {
+            let modifiers = e.modifiers();
+            if self.multi_select && modifiers.shift {
+                self.extend_rows_selection(row_ix, cx);
+            } else if self.multi_select && modifiers.secondary() {
+                self.toggle_row_selection(row_ix, cx);
+            } else {
+                self.set_selected_row(row_ix, cx);
+            }
+        }
+
+        if e.click_count() == 2 {
+            cx.emit(TableEvent::DoubleClickedRow(row_ix));
+        }
+    }
+
+    fn on_cell_mouse_down(
+        &mut self,
+        e: &MouseDownEvent,
+        cell: TableCell,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.editing_cell() == Some(cell) || !self.commit_editing(window, cx) {
+            return;
+        }
+        if e.click_count == 2 && self.start_editing(cell, window, cx) {
+            return;
+        }
+        if !self.cell_selectable {
+            return;
+        }
+
+        self.selecting_cells = true;
+        if self.multi_select && e.modifiers.shift {
+            let anchor = self.selection_anchor.unwrap_or(cell);
+            if let TableSelection::Cells(ranges) = &mut self.selection {
+                ranges.pop();
+            }
+            self.select_cells(CellRange::new(anchor, cell), true, cx);
+        } else {
+            let add = self.multi_select && e.modifiers.secondary();
+            self.select_cells(CellRange::new(cell, cell), add, cx);
+        }
+    }
+
+    fn on_cell_mouse_move(
+        &mut self,
+        e: &MouseMoveEvent,
+        cell: TableCell,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.selecting_cells || !e.dragging() || self.active_cell == Some(cell) {
+            return;
+        }
+
+        let TableSelection::Cells(ranges) = &mut self.selection else {
+            return;
+        };
+        let Some(range) = ranges.last_mut() else {
+            return;
+        };
+
+        range.head = cell;
+        self.active_cell = Some(cell);
+        self.emit_selection(cx);
+        cx.notify();
+    }
+
+    fn on_col_head_click(&mut self, col_ix: usize, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.col_selectable {
+            return;
+        }
+
+        let Some(col_group) = self.col_groups.get(col_ix) else {
+            return;
+        };
+
+        if !col_group.column.selectable {
+            return;
+        }
+
+        self.set_selected_col(col_ix, cx)
+    }
+
+    fn has_selection(&self) -> bool {
+        self.selected_row.is_some() || self.selected_col.is_some() || !self.selection.is_empty()
+    }
+
+    pub(super) fn action_cancel(
+        &mut self,
+        _: &Cancel,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.editing.is_some() {
+            self.cancel_editing(window, cx);
+            return;
+        }
+
+        if self.has_selection() {
+            self.clear_selection(cx);
+            return;
+        }
+        cx.propagate();
+    }
+
+    pub(super) fn action_select_prev(
+        &mut self,
+        _: &SelectUp,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.editing.is_some() {
+            return;
+        }
+        if self.cell_selectable {
+            self.move_active_cell(-1, 0, false, cx);
+            return;
+        }
+
+        let rows_count = self.delegate.rows_count(cx);
+        if rows_count < 1 {
+            return;
+        }
+
+        if let Some(layout) = &self.group_layout {
+            let row_ix = layout
+                .next_row(self.selected_row, false)
+                .or_else(|| self.loop_selection.then(|| layout.next_row(None, false))?);
+            if let Some(row_ix) = row_ix {
+                self.set_selected_row(row_ix, cx);
+            }
+            return;
+        }
+
+        let mut selected_row = self.selected_row.unwrap_or(0);
+        if selected_row > 0 {
+            selected_row = selected_row.saturating_sub(1);
+        } else {
+            if self.loop_selection {
+                selected_row = rows_count.saturating_sub(1);
+            }
+        }
+
+        self.set_selected_row(selected_row, cx);
+    }
+
+    pub(super) fn action_select_next(
+        &mut self,
+        _: &SelectDown,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.editing.is_some() {
+            return;
+        }
+        if self.cell_selectable {
+            self.move_active_cell(1, 0, false, cx);
+            return;
+        }
+
+        let rows_count = self.delegate.rows_count(cx);
+        if rows_count < 1 {
+            return;
+        }
+
+        if let Some(layout) = &self.group_layout {
+            let row_ix = layout
+                .next_row(self.selected_row, true)
+                .or_else(|| self.loop_selection.then(|| layout.next_row(None, true))?);
+            if let Some(row_ix) = row_ix {
+                self.set_selected_row(row_ix, cx);
+            }
+            return;
+        }
+
+        let selected_row = match self.selected_row {
+            Some(selected_row) if selected_row < rows_count.saturating_sub(1) => selected_row + 1,
+            Some(selected_row) => {
+                if self.loop_selection {
+                    0
+                } else {
+                    selected_row
+                }
+            }
+            _ => 0,
+        };
+
+        self.set_selected_row(selected_row, cx);
+    }
+
+    pub(super) fn action_select_prev_col(
+        &mut self,
+        _: &SelectPrevColumn,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.editing.is_some() {
+            return;
+        }
+        if self.cell_selectable {
+            self.move_active_cell(0, -1, false, cx);
+            return;
+        }
+        if self.navigate_tree(false, window, cx) {
+            return;
+        }
+
+        let selected_col = self.selected_col.unwrap_or(0);
+        let selected_col = match self.adjacent_col(selected_col, false) {
+            Some(col_ix) => col_ix,
+            None if self.loop_selection => self
+                .adjacent_col(self.col_groups.len(), false)
+                .unwrap_or(selected_col),
+            None => selected_col,
+        };
+        self.set_selected_col(selected_col, cx);
+    }
+
+    pub(super) fn action_select_next_col(
+        &mut self,
+        _: &SelectNextColumn,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.editing.is_some() {
+            return;
+        }
+        if self.cell_selectable {
+            self.move_active_cell(0, 1, false, cx);
+            return;
+        }
+        if self.navigate_tree(true, window, cx) {
+            return;
+        }
+
+        let selected_col = self.selected_col.unwrap_or(0);
+        let selected_col = match self.adjacent_col(selected_col, true) {
+            Some(col_ix) => col_ix,
+            None if self.loop_selection => self
+                .col_groups
+                .iter()
+                .position(|col_group| !col_group.hidden)
+                .unwrap_or(selected_col),
+            None => selected_col,
+        };
+
+        self.set_selected_col(selected_col, cx);
+    }
+
+    pub(super) fn action_extend_selection_up(
+        &mut self,
+        _: &ExtendSelectionUp,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.editing.is_some() {
+            return;
+        }
+        if self.cell_selectable {
+            self.move_active_cell(-1, 0, true, cx);
+            return;
+        }
+
+        match self.selected_row {
+            Some(row_ix) if self.multi_select => {
+                self.extend_rows_selection(self.adjacent_row(row_ix, false, cx), cx)
+            }
+            _ => self.action_select_prev(&SelectUp, window, cx),
+        }
+    }
+
+    pub(super) fn action_extend_selection_down(
+        &mut self,
+        _: &ExtendSelectionDown,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.editing.is_some() {
+            return;
+        }
+        if self.cell_selectable {
+            self.move_active_cell(1, 0, true, cx);
+            return;
+        }
+
+        match self.selected_row {
+            Some(row_ix) if self.multi_select => {
+                self.extend_rows_selection(self.adjacent_row(row_ix, true, cx), cx)
+            }
+            _ => self.action_select_next(&SelectDown, window, cx),
+        }
+    }
+
+    pub(super) fn action_extend_selection_left(
+        &mut self,
+        _: &ExtendSelectionLeft,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.cell_selectable || self.editing.is_some() {
+            cx.propagate();
+            return;
+        }
+
+        self.move_active_cell(0, -1, true, cx);
+    }
+
+    pub(super) fn action_extend_selection_right(
+        &mut self,
+        _: &ExtendSelectionRight,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.cell_selectable || self.editing.is_some() {
+            cx.propagate();
+            return;
+        }
+
+        self.move_active_cell(0, 1, true, cx);
+    }
+
+    pub(super) fn action_select_all(
+        &mut self,
+        _: &SelectAll,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let rows_count = self.delegate.rows_count(cx);
+        let cols_count = self.delegate.columns_count(cx);
+        if !self.multi_select || rows_count == 0 || cols_count == 0 {
+            cx.propagate();
+            return;
+        }
+
+        if self.cell_selectable {
+            let range = CellRange::new(
+                TableCell::new(0, 0),
+                TableCell::new(rows_count - 1, cols_count - 1),
+            );
+            self.set_selection(TableSelection::cells(range), cx);
+        } else {
+            self.set_selection(TableSelection::rows([0..rows_count]), cx);
+        }
+    }
+
+    /// Returns the selected column indices, all columns for the rows selection.
+    ///
+    /// The hidden columns are excluded.
+    fn selected_cols(&self) -> Vec<usize> {
+        if self.selection_state == SelectionState::Column {
+            return self.selected_col.into_iter().collect();
+        }
+
+        let mut cols = match &self.selection {
+            TableSelection::None => vec![],
+            TableSelection::Rows(_) => (0..self.col_groups.len()).collect(),
+            TableSelection::Cells(ranges) => {
+                let mut cols = ranges
+                    .iter()
+                    .flat_map(|range| range.cols())
+                    .collect::<Vec<_>>();
+                cols.sort_unstable();
+                cols.dedup();
+                cols
+            }
+        };
+        cols.retain(|&col_ix| !self.is_column_hidden(col_ix));
+        cols
+    }
+
+    /// Returns the texts of the selected cells by [`TableDelegate::cell_text`], grouped by rows.
+    ///
+    /// For multiple cell ranges, the unselected cells between them are empty.
+    pub fn selected_cells_text(&self, cx: &App) -> Vec<Vec<SharedString>> {
+        let cols = self.selected_cols();
+        let rows = if self.selection_state == SelectionState::Column {
+            (0..self.delegate.rows_count(cx)).collect()
+        } else {
+            self.selection.selected_rows()
+        };
+
+        rows.into_iter()
+            .map(|row_ix| {
+                cols.iter()
+                    .map(|&col_ix| {
+                        if self.selection_state == SelectionState::Column
+                            || self.selection.contains_cell(row_ix, col_ix)
+                        {
+                            self.delegate.cell_text(row_ix, col_ix, cx)
+                        } else {
+                            SharedString::default()
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the text of the selection in the given format, `None` if nothing selected.
+    pub fn selection_text(&self, format: TableCopyFormat, cx: &App) -> Option<String> {
+        let rows = self.selected_cells_text(cx);
+        if rows.is_empty() {
+            return None;
+        }
+
+        Some(match format {
+            TableCopyFormat::Tsv => to_tsv(&rows),
+            TableCopyFormat::Csv => to_csv(&rows),
+            TableCopyFormat::Markdown => {
+                let headers = self
+                    .selected_cols()
+                    .into_iter()
+                    .filter_map(|col_ix| self.col_groups.get(col_ix))
+                    .map(|col_group| col_group.column.name.clone())
+                    .collect::<Vec<_>>();
+                to_markdown(&headers, &rows)
+            }
+        })
+    }
+
+    /// Copy the selection to the clipboard in the given format, returns false if nothing selected.
+    pub fn copy_selection(&self, format: TableCopyFormat, cx: &mut App) -> bool {
+        let Some(text) = self.selection_text(format, cx) else {
+            return false;
+        };
+
+        cx.write_to_clipboard(ClipboardItem::new_string(text));
+        true
+    }
+
+    pub(super) fn action_copy(&mut self, _: &Copy, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.copy_selection(TableCopyFormat::Tsv, cx) {
+            cx.propagate();
+        }
+    }
+
+    pub(super) fn action_paste(&mut self, _: &Paste, window: &mut Window, cx: &mut Context<Self>) {
+        let cell = match (self.active_cell, self.selected_row, self.selection_state) {
+            (_, _, SelectionState::Column) => self.selected_col.map(|col| TableCell::new(0, col)),
+            (Some(cell), _, _) => Some(cell),
+            (None, Some(row_ix), _) => Some(TableCell::new(row_ix, 0)),
+            _ => None,
+        };
+        let text = cx.read_from_clipboard().and_then(|item| item.text());
+        let (Some(cell), Some(text)) = (cell, text) else {
+            cx.propagate();
+            return;
+        };
+
+        let rows = parse_tsv(&text);
+        if rows.is_empty() || !self.delegate.paste(cell, rows, window, cx) {
+            cx.propagate();
+            return;
+        }
+
+        cx.notify();
+    }
+
+    pub(super) fn action_edit_cell(
+        &mut self,
+        _: &EditCell,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.editing.is_some() {
+            self.commit_editing(window, cx);
+            return;
+        }
+
+        let cell = self.active_cell.or_else(|| {
+            let row_ix = self.selected_row?;
+            (0..self.delegate.columns_count(cx))
+                .find(|&col_ix| self.delegate.is_cell_editable(row_ix, col_ix, cx))
+                .map(|col_ix| TableCell::new(row_ix, col_ix))
+        });
+
+        match cell {
+            Some(cell) if self.start_editing(cell, window, cx) => {}
+            _ => cx.propagate(),
+        }
+    }
+
+    pub(super) fn action_edit_next_cell(
+        &mut self,
+        _: &EditNextCell,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.edit_adjacent_cell(false, window, cx);
+    }
+
+    pub(super) fn action_edit_prev_cell(
+        &mut self,
+        _: &EditPrevCell,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.edit_adjacent_cell(true, window, cx);
+    }
+
+    /// Scroll table when mouse position is near the edge of the table bounds.
+    fn scroll_table_by_col_resizing(
+        &mut self,
+        mouse_position: Point<Pixels>,
+        col_group: &ColGroup,
+    ) {
+        // Do nothing if pos out of the table bounds right for avoid scroll to the right.
+        if mouse_position.x > self.bounds.right() {
+            return;
+        }
+
+        let mut offset = self.horizontal_scroll_handle.offset();
+        let col_bounds = col_group.bounds;
+
+        if mouse_position.x < self.bounds.left()
+            && col_bounds.right() < self.bounds.left() + px(20.)
+        {
+            offset.x += px(1.);
+        } else if mouse_position.x > self.bounds.right()
+            && col_bounds.right() > self.bounds.right() - px(20.)
+        {
+            offset.x -= px(1.);
+        }
+
+        self.horizontal_scroll_handle.set_offset(offset);
+    }
+
+    /// The `ix`` is the index of the col to resize,
+    /// and the `size` is the new size for the col.
+    fn resize_cols(&mut self, ix: usize, size: Pixels, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.col_resizable {
+            return;
         }
 
         const MIN_WIDTH: Pixels = px(10.0);
@@ -497,55 +1646,408 @@ where
         cx.notify();
     }
 
-    fn perform_sort(&mut self, col_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+    /// Returns the sorted columns with the sort order, in the sort priority order.
+    pub fn sorts(&self) -> Vec<(usize, ColumnSort)> {
+        self.sort_keys
+            .iter()
+            .filter_map(|key| {
+                let col_ix = self
+                    .col_groups
+                    .iter()
+                    .position(|col_group| &col_group.column.key == key)?;
+                let sort = self.col_groups[col_ix].column.sort?;
+                (sort != ColumnSort::Default).then_some((col_ix, sort))
+            })
+            .collect()
+    }
+
+    /// Keep the sort keys of the sorted columns, and append the sorted columns not in the keys.
+    fn sync_sort_keys(&mut self) {
+        let is_sorted = |col_group: &ColGroup| {
+            col_group
+                .column
+                .sort
+                .is_some_and(|sort| sort != ColumnSort::Default)
+        };
+
+        let mut sort_keys = std::mem::take(&mut self.sort_keys);
+        sort_keys.retain(|key| {
+            self.col_groups
+                .iter()
+                .any(|col_group| &col_group.column.key == key && is_sorted(col_group))
+        });
+        for col_group in self.col_groups.iter() {
+            if is_sorted(col_group) && !sort_keys.contains(&col_group.column.key) {
+                sort_keys.push(col_group.column.key.clone());
+            }
+        }
+        self.sort_keys = sort_keys;
+    }
+
+    /// Toggle the sort of the column, if `add` the other sorted columns are kept in [`TableState::multi_sort`] mode.
+    fn perform_sort(
+        &mut self,
+        col_ix: usize,
+        add: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         if !self.sortable {
             return;
         }
+        let add = add && self.multi_sort;
+
+        let sort = self.col_groups.get(col_ix).and_then(|g| g.column.sort);
+        if sort.is_none() {
+            return;
+        }
+
+        let sort = sort.unwrap();
+        let sort = match sort {
+            ColumnSort::Ascending => ColumnSort::Default,
+            ColumnSort::Descending => ColumnSort::Ascending,
+            ColumnSort::Default => ColumnSort::Descending,
+        };
+
+        for (ix, col_group) in self.col_groups.iter_mut().enumerate() {
+            if ix == col_ix {
+                col_group.column.sort = Some(sort);
+            } else if !add {
+                if col_group.column.sort.is_some() {
+                    col_group.column.sort = Some(ColumnSort::Default);
+                }
+            }
+        }
+        self.sync_sort_keys();
+
+        if self.multi_sort {
+            let sorts = self.sorts();
+            self.delegate.perform_multi_sort(sorts, window, cx);
+        } else {
+            self.delegate.perform_sort(col_ix, sort, window, cx);
+        }
+        self.invalidate_rows();
+
+        cx.notify();
+    }
+
+    /// Returns the active filter of the column at the given index.
+    pub fn filter(&self, col_ix: usize) -> Option<&ColumnFilterValue> {
+        self.col_groups
+            .get(col_ix)
+            .and_then(|col_group| col_group.filter.as_ref())
+    }
+
+    /// Returns the active filters with the column index, in the columns order.
+    pub fn filters(&self) -> Vec<(usize, ColumnFilterValue)> {
+        self.col_groups
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, col_group)| col_group.filter.clone().map(|filter| (ix, filter)))
+            .collect()
+    }
+
+    /// Set the filter of the column at the given index, `None` or an empty filter to clear it.
+    ///
+    /// The [`TableDelegate::perform_filter`] will be called if the filter changed,
+    /// and the selection will be cleared.
+    pub fn set_filter(
+        &mut self,
+        col_ix: usize,
+        filter: Option<ColumnFilterValue>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let filter = filter.filter(|filter| !filter.is_empty());
+        let Some(col_group) = self.col_groups.get_mut(col_ix) else {
+            return;
+        };
+        if col_group.column.filter.is_none() || col_group.filter == filter {
+            return;
+        }
+
+        col_group.filter = filter.clone();
+        self.delegate.perform_filter(col_ix, filter, window, cx);
+        self.invalidate_rows();
+        self.cancel_editing(window, cx);
+        self.clear_selection(cx);
+    }
+
+    /// Clear the filters of all columns.
+    pub fn clear_filters(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        for col_ix in 0..self.col_groups.len() {
+            self.set_filter(col_ix, None, window, cx);
+        }
+    }
+
+    fn toggle_filter_popover(
+        &mut self,
+        col_ix: usize,
+        open: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.filter_popover = None;
+        cx.notify();
+        if !open {
+            return;
+        }
+
+        let Some(filter) = self
+            .col_groups
+            .get(col_ix)
+            .and_then(|col_group| col_group.column.filter.clone())
+        else {
+            return;
+        };
+
+        let value = self.filter(col_ix).cloned();
+        let editor = cx.new(|cx| ColumnFilterEditor::new(&filter, value.as_ref(), window, cx));
+        let _subscription = cx.subscribe_in(
+            &editor,
+            window,
+            move |this, _, event: &ColumnFilterEditorEvent, window, cx| match event {
+                ColumnFilterEditorEvent::Apply(filter) => {
+                    this.filter_popover = None;
+                    this.set_filter(col_ix, filter.clone(), window, cx);
+                    this.focus_handle.focus(window);
+                    cx.notify();
+                }
+            },
+        );
+        editor.focus_handle(cx).focus(window);
+        self.filter_popover = Some(FilterPopover {
+            col_ix,
+            editor,
+            _subscription,
+        });
+    }
+
+    fn move_column(
+        &mut self,
+        col_ix: usize,
+        to_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if col_ix == to_ix {
+            return;
+        }
+
+        self.delegate.move_column(col_ix, to_ix, window, cx);
+        self.invalidate_rows();
+        self.filter_popover = None;
+        let col_group = self.col_groups.remove(col_ix);
+        self.col_groups.insert(to_ix, col_group);
+
+        cx.emit(TableEvent::MoveColumn(col_ix, to_ix));
+        cx.notify();
+    }
+
+    /// Returns true if the column at the given index is hidden.
+    pub fn is_column_hidden(&self, col_ix: usize) -> bool {
+        self.col_groups
+            .get(col_ix)
+            .is_some_and(|col_group| col_group.hidden)
+    }
+
+    /// Hide or show the column at the given index.
+    ///
+    /// The hidden column is kept in the columns, with zero width and skipped by the keyboard navigation.
+    pub fn set_column_hidden(
+        &mut self,
+        col_ix: usize,
+        hidden: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(col_group) = self.col_groups.get_mut(col_ix) else {
+            return;
+        };
+        if col_group.hidden == hidden {
+            return;
+        }
+
+        col_group.hidden = hidden;
+        if hidden {
+            if self.editing_cell().is_some_and(|cell| cell.col == col_ix) {
+                self.cancel_editing(window, cx);
+            }
+            if self.selected_col == Some(col_ix) {
+                self.clear_selection(cx);
+            }
+        }
+
+        cx.emit(TableEvent::ColumnVisibilityChanged(col_ix, hidden));
+        cx.notify();
+    }
+
+    /// Returns the next visible column after (or before if not `forward`) the `col_ix`.
+    fn adjacent_col(&self, col_ix: usize, forward: bool) -> Option<usize> {
+        let is_visible = |ix: &usize| !self.is_column_hidden(*ix);
+        if forward {
+            (col_ix + 1..self.col_groups.len()).find(is_visible)
+        } else {
+            (0..col_ix.min(self.col_groups.len()))
+                .rev()
+                .find(is_visible)
+        }
+    }
+
+    /// Dump the columns layout (order, widths, visibility, sorts and fixed) to [`TableViewState`].
+    ///
+    /// See also [`TableState::load`].
+    pub fn dump(&self) -> TableViewState {
+        TableViewState {
+            columns: self
+                .col_groups
+                .iter()
+                .map(|col_group| TableColumnState {
+                    key: col_group.column.key.clone(),
+                    width: col_group.width,
+                    hidden: col_group.hidden,
+                    sort: col_group.column.sort,
+                    fixed: col_group.column.fixed,
+                })
+                .collect(),
+            sort_keys: self.sort_keys.clone(),
+        }
+    }
+
+    /// Load the columns layout from the [`TableViewState`].
+    ///
+    /// The columns are matched by key, the columns not in the state are kept after them
+    /// with their current layout, and the unknown keys are ignored.
+    ///
+    /// The columns are reordered without [`TableEvent::MoveColumn`], the delegate columns are kept
+    /// in the same order by [`TableDelegate::move_column`]. The fixed columns not at the start
+    /// (left) or the end (right) are unfixed, and the sorted columns are applied by
+    /// [`TableDelegate::perform_sort`] or [`TableDelegate::perform_multi_sort`].
+    ///
+    /// See also [`TableState::dump`].
+    pub fn load(&mut self, state: TableViewState, window: &mut Window, cx: &mut Context<Self>) {
+        self.cancel_editing(window, cx);
+        self.clear_selection(cx);
+
+        // The current indexes of the columns in the new order, the duplicated keys are skipped.
+        let mut order: Vec<usize> = vec![];
+        for col_state in state.columns.iter() {
+            if let Some(col_ix) = self
+                .col_groups
+                .iter()
+                .position(|col_group| col_group.column.key == col_state.key)
+                && !order.contains(&col_ix)
+            {
+                order.push(col_ix);
+            }
+        }
+        for col_ix in 0..self.col_groups.len() {
+            if !order.contains(&col_ix) {
+                order.push(col_ix);
+            }
+        }
+
+        let mut delegate_order = (0..self.col_groups.len()).collect::<Vec<_>>();
+        for (to_ix, col_ix) in order.iter().enumerate() {
+            let Some(from_ix) = delegate_order.iter().position(|ix| ix == col_ix) else {
+                continue;
+            };
+            if from_ix != to_ix {
+                self.delegate.move_column(from_ix, to_ix, window, cx);
+                delegate_order.remove(from_ix);
+                delegate_order.insert(to_ix, *col_ix);
+            }
+        }
+        let mut col_groups = std::mem::take(&mut self.col_groups)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        self.col_groups = order
+            .iter()
+            .filter_map(|col_ix| col_groups[*col_ix].take())
+            .collect();
+        self.filter_popover = None;
+        self.invalidate_rows();
+
+        for col_group in self.col_groups.iter_mut() {
+            let Some(col_state) = state.column(&col_group.column.key) else {
+                continue;
+            };
+
+            col_group.width = col_state.width;
+            col_group.hidden = col_state.hidden;
+            col_group.column.fixed = col_state.fixed;
+            // Only the sortable columns can be sorted.
+            if let (Some(_), Some(sort)) = (col_group.column.sort, col_state.sort) {
+                col_group.column.sort = Some(sort);
+            }
+        }
 
-        let sort = self.col_groups.get(col_ix).and_then(|g| g.column.sort);
-        if sort.is_none() {
-            return;
+        // Only the first columns can be fixed on left, and the last columns on right.
+        let left_count = self
+            .col_groups
+            .iter()
+            .take_while(|col_group| col_group.column.fixed == Some(ColumnFixed::Left))
+            .count();
+        let right_count = self.col_groups[left_count..]
+            .iter()
+            .rev()
+            .take_while(|col_group| col_group.column.fixed == Some(ColumnFixed::Right))
+            .count();
+        let cols_count = self.col_groups.len();
+        for col_group in self.col_groups[left_count..cols_count - right_count].iter_mut() {
+            col_group.column.fixed = None;
         }
 
-        let sort = sort.unwrap();
-        let sort = match sort {
-            ColumnSort::Ascending => ColumnSort::Default,
-            ColumnSort::Descending => ColumnSort::Ascending,
-            ColumnSort::Default => ColumnSort::Descending,
-        };
-
-        for (ix, col_group) in self.col_groups.iter_mut().enumerate() {
-            if ix == col_ix {
-                col_group.column.sort = Some(sort);
-            } else {
-                if col_group.column.sort.is_some() {
+        // The sort priority of the state first, then the columns order.
+        self.sort_keys = state.sort_keys.clone();
+        self.sync_sort_keys();
+        if !self.multi_sort {
+            // Only one column is sorted.
+            self.sort_keys.truncate(1);
+            for col_group in self.col_groups.iter_mut() {
+                if col_group.column.sort.is_some()
+                    && !self.sort_keys.contains(&col_group.column.key)
+                {
                     col_group.column.sort = Some(ColumnSort::Default);
                 }
             }
         }
 
-        self.delegate_mut().perform_sort(col_ix, sort, window, cx);
-
+        if self.sortable {
+            let sorts = self.sorts();
+            if self.multi_sort {
+                self.delegate.perform_multi_sort(sorts, window, cx);
+            } else if let Some(&(col_ix, sort)) = sorts.first() {
+                self.delegate.perform_sort(col_ix, sort, window, cx);
+            }
+        }
         cx.notify();
     }
 
-    fn move_column(
-        &mut self,
-        col_ix: usize,
-        to_ix: usize,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        if col_ix == to_ix {
-            return;
-        }
-
-        self.delegate.move_column(col_ix, to_ix, window, cx);
-        let col_group = self.col_groups.remove(col_ix);
-        self.col_groups.insert(to_ix, col_group);
+    /// Build the columns menu to hide or show the columns, shown by right clicking the table head.
+    fn columns_menu(&self, menu: PopupMenu, cx: &mut Context<Self>) -> PopupMenu {
+        let view = cx.entity();
+        let visible_count = self.col_groups.iter().filter(|col| !col.hidden).count();
 
-        cx.emit(TableEvent::MoveColumn(col_ix, to_ix));
-        cx.notify();
+        self.col_groups
+            .iter()
+            .enumerate()
+            .fold(menu, |menu, (col_ix, col_group)| {
+                let view = view.clone();
+                let hidden = col_group.hidden;
+                menu.item(
+                    PopupMenuItem::new(col_group.column.name.clone())
+                        .checked(!hidden)
+                        // Keep at least one column visible.
+                        .disabled(!hidden && visible_count <= 1)
+                        .on_click(move |_, window, cx| {
+                            view.update(cx, |state, cx| {
+                                state.set_column_hidden(col_ix, !hidden, window, cx);
+                            })
+                        }),
+                )
+            })
     }
 
     /// Dispatch delegate's `load_more` method when the visible range is near the end.
@@ -588,24 +2090,50 @@ where
             if self.visible_range.rows == visible_range {
                 return;
             }
-            self.delegate_mut()
+            self.delegate
                 .visible_rows_changed(visible_range.clone(), window, cx);
             self.visible_range.rows = visible_range;
         } else {
             if self.visible_range.cols == visible_range {
                 return;
             }
-            self.delegate_mut()
+            self.delegate
                 .visible_columns_changed(visible_range.clone(), window, cx);
             self.visible_range.cols = visible_range;
         }
     }
 
+    /// Like the vertical `update_visible_range_if_need` for the grouped rows, the delegate is
+    /// notified for each range of the rendered rows, without the rows of the collapsed groups.
+    fn update_visible_group_rows_if_need(
+        &mut self,
+        visible_rows: Vec<Range<usize>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (Some(first), Some(last)) = (visible_rows.first(), visible_rows.last()) else {
+            return;
+        };
+        let visible_range = first.start..last.end;
+        if visible_range.len() <= 1 || self.visible_range.rows == visible_range {
+            return;
+        }
+
+        for rows in visible_rows {
+            self.delegate.visible_rows_changed(rows, window, cx);
+        }
+        self.visible_range.rows = visible_range;
+    }
+
     fn render_cell(&self, col_ix: usize, _window: &mut Window, _cx: &mut Context<Self>) -> Div {
         let Some(col_group) = self.col_groups.get(col_ix) else {
             return div();
         };
 
+        if col_group.hidden {
+            return div().w_0().h_full().flex_shrink_0().overflow_hidden();
+        }
+
         let col_width = col_group.width;
         let col_padding = col_group.column.paddings;
 
@@ -627,8 +2155,46 @@ where
     }
 
     /// Show Column selection style, when the column is selected and the selection state is Column.
-    fn render_col_wrap(&self, col_ix: usize, _: &mut Window, cx: &mut Context<Self>) -> Div {
-        let el = h_flex().h_full();
+    ///
+    /// Or the cells selection style, when the table is cell selectable.
+    fn render_col_wrap(
+        &self,
+        row_ix: usize,
+        col_ix: usize,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let cell = TableCell::new(row_ix, col_ix);
+        let el = h_flex().h_full().on_mouse_down(
+            MouseButton::Left,
+            cx.listener(move |this, e, window, cx| {
+                this.on_cell_mouse_down(e, cell, window, cx);
+            }),
+        );
+
+        if self.cell_selectable {
+            let is_selected = self.selection.contains_cell(row_ix, col_ix)
+                && self.selection_state == SelectionState::Row;
+
+            return el
+                .relative()
+                .when(is_selected, |this| this.bg(cx.theme().table_active))
+                .when(self.active_cell == Some(cell), |this| {
+                    this.child(
+                        div()
+                            .absolute()
+                            .top_0()
+                            .left_0()
+                            .size_full()
+                            .border_1()
+                            .border_color(cx.theme().table_active_border),
+                    )
+                })
+                .on_mouse_move(cx.listener(move |this, e, window, cx| {
+                    this.on_cell_mouse_move(e, cell, window, cx);
+                }));
+        }
+
         let selectable = self.col_selectable
             && self
                 .col_groups
@@ -761,6 +2327,14 @@ where
             ColumnSort::Descending => (IconName::SortDescending, true),
             ColumnSort::Default => (IconName::ChevronsUpDown, false),
         };
+        // Show the sort priority when sorted by multiple columns.
+        let priority = (self.sort_keys.len() > 1)
+            .then(|| {
+                self.sort_keys
+                    .iter()
+                    .position(|key| key == &col_group.column.key)
+            })
+            .flatten();
 
         Some(
             div()
@@ -773,13 +2347,70 @@ where
                 })
                 .hover(|this| this.bg(cx.theme().secondary).opacity(7.))
                 .active(|this| this.bg(cx.theme().secondary_active).opacity(1.))
-                .on_click(
-                    cx.listener(move |table, _, window, cx| table.perform_sort(col_ix, window, cx)),
-                )
+                .on_click(cx.listener(move |table, e: &ClickEvent, window, cx| {
+                    table.perform_sort(col_ix, e.modifiers().shift, window, cx)
+                }))
+                .child(
+                    h_flex()
+                        .child(
+                            Icon::new(icon)
+                                .size_3()
+                                .text_color(cx.theme().secondary_foreground),
+                        )
+                        .when_some(priority, |this, priority| {
+                            this.child(
+                                div()
+                                    .text_xs()
+                                    .line_height(px(12.))
+                                    .text_color(cx.theme().secondary_foreground)
+                                    .child((priority + 1).to_string()),
+                            )
+                        }),
+                ),
+        )
+    }
+
+    fn render_filter_icon(
+        &self,
+        col_ix: usize,
+        col_group: &ColGroup,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        if !self.filterable || col_group.column.filter.is_none() {
+            return None;
+        }
+
+        let is_on = col_group.filter.is_some();
+        let editor = self
+            .filter_popover
+            .as_ref()
+            .filter(|popover| popover.col_ix == col_ix)
+            .map(|popover| popover.editor.clone());
+
+        Some(
+            div()
+                .id(("filter", col_ix))
+                // Avoid to select or drag the column when click the filter icon.
+                .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                .on_click(|_, _, cx| cx.stop_propagation())
                 .child(
-                    Icon::new(icon)
-                        .size_3()
-                        .text_color(cx.theme().secondary_foreground),
+                    Popover::new(("filter-popover", col_ix))
+                        .anchor(Corner::TopRight)
+                        .trigger(
+                            Button::new(("icon-filter", col_ix))
+                                .icon(Icon::new(IconName::Funnel).size_3())
+                                .ghost()
+                                .xsmall()
+                                .when(!is_on, |this| this.opacity(0.5)),
+                        )
+                        .open(editor.is_some())
+                        .on_open_change(cx.listener(move |table, open: &bool, window, cx| {
+                            table.toggle_filter_popover(col_ix, *open, window, cx)
+                        }))
+                        .when_some(editor, |this, editor| {
+                            this.track_focus(&editor.focus_handle(cx)).child(editor)
+                        }),
                 ),
         )
     }
@@ -792,6 +2423,11 @@ where
         let entity_id = cx.entity_id();
         let col_group = self.col_groups.get(col_ix).expect("BUG: invalid col index");
 
+        if col_group.hidden {
+            // Keep the zero bounds of the hidden column, the cells are rendered with it.
+            return h_flex().h_full().child(self.render_col_bounds(col_ix, cx));
+        }
+
         let movable = self.col_movable && col_group.column.movable;
         let paddings = col_group.column.paddings;
         let name = col_group.column.name.clone();
@@ -816,7 +2452,16 @@ where
                                     self.options.size.table_cell_padding().right - paddings.right;
                                 this.pr(offset_pr.max(px(0.)))
                             })
-                            .children(self.render_sort_icon(col_ix, &col_group, window, cx)),
+                            .child(
+                                h_flex()
+                                    .gap_0p5()
+                                    .children(
+                                        self.render_filter_icon(col_ix, &col_group, window, cx),
+                                    )
+                                    .children(
+                                        self.render_sort_icon(col_ix, &col_group, window, cx),
+                                    ),
+                            ),
                     )
                     .when(movable, |this| {
                         this.on_drag(
@@ -852,32 +2497,37 @@ where
             // resize handle
             .child(self.render_resize_handle(col_ix, window, cx))
             // to save the bounds of this col.
-            .child({
-                let view = cx.entity().clone();
-                canvas(
-                    move |bounds, _, cx| {
-                        view.update(cx, |r, _| r.col_groups[col_ix].bounds = bounds)
-                    },
-                    |_, _, _, _| {},
-                )
-                .absolute()
-                .size_full()
-            })
+            .child(self.render_col_bounds(col_ix, cx))
+    }
+
+    fn render_col_bounds(&self, col_ix: usize, cx: &mut Context<Self>) -> impl IntoElement {
+        let view = cx.entity().clone();
+        canvas(
+            move |bounds, _, cx| view.update(cx, |r, _| r.col_groups[col_ix].bounds = bounds),
+            |_, _, _, _| {},
+        )
+        .absolute()
+        .size_full()
     }
 
     fn render_table_head(
         &mut self,
         left_columns_count: usize,
+        right_columns_count: usize,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
         let view = cx.entity().clone();
         let horizontal_scroll_handle = self.horizontal_scroll_handle.clone();
+        let columns_count = self.col_groups.len();
 
         // Reset fixed head columns bounds, if no fixed columns are present
         if left_columns_count == 0 {
             self.fixed_head_cols_bounds = Bounds::default();
         }
+        if right_columns_count == 0 {
+            self.fixed_right_head_cols_bounds = Bounds::default();
+        }
 
         h_flex()
             .w_full()
@@ -886,6 +2536,14 @@ where
             .border_b_1()
             .border_color(cx.theme().border)
             .text_color(cx.theme().table_head_foreground)
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(|this, _, _, cx| {
+                    this.right_clicked_head = true;
+                    this.right_clicked_row = None;
+                    cx.notify();
+                }),
+            )
             .when(left_columns_count > 0, |this| {
                 let view = view.clone();
                 // Render left fixed columns
@@ -895,12 +2553,8 @@ where
                         .h_full()
                         .bg(cx.theme().table_head)
                         .children(
-                            self.col_groups
-                                .clone()
-                                .into_iter()
-                                .filter(|col| col.column.fixed == Some(ColumnFixed::Left))
-                                .enumerate()
-                                .map(|(col_ix, _)| self.render_th(col_ix, window, cx)),
+                            (0..left_columns_count)
+                                .map(|col_ix| self.render_th(col_ix, window, cx)),
                         )
                         .child(
                             // Fixed columns border
@@ -943,6 +2597,7 @@ where
                                     .clone()
                                     .into_iter()
                                     .skip(left_columns_count)
+                                    .take(columns_count - left_columns_count - right_columns_count)
                                     .enumerate()
                                     .map(|(col_ix, _)| {
                                         self.render_th(left_columns_count + col_ix, window, cx)
@@ -951,6 +2606,42 @@ where
                             .child(self.delegate.render_last_empty_col(window, cx)),
                     ),
             )
+            .when(right_columns_count > 0, |this| {
+                // Render right fixed columns
+                this.child(
+                    h_flex()
+                        .relative()
+                        .h_full()
+                        .flex_shrink_0()
+                        .bg(cx.theme().table_head)
+                        .children(
+                            (columns_count - right_columns_count..columns_count)
+                                .map(|col_ix| self.render_th(col_ix, window, cx)),
+                        )
+                        .child(
+                            // Fixed columns border
+                            div()
+                                .absolute()
+                                .top_0()
+                                .left_0()
+                                .bottom_0()
+                                .w_0()
+                                .flex_shrink_0()
+                                .border_l_1()
+                                .border_color(cx.theme().border),
+                        )
+                        .child(
+                            canvas(
+                                move |bounds, _, cx| {
+                                    view.update(cx, |r, _| r.fixed_right_head_cols_bounds = bounds)
+                                },
+                                |_, _, _, _| {},
+                            )
+                            .absolute()
+                            .size_full(),
+                        ),
+                )
+            })
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -959,6 +2650,7 @@ where
         row_ix: usize,
         rows_count: usize,
         left_columns_count: usize,
+        right_columns_count: usize,
         col_sizes: Rc<Vec<gpui::Size<Pixels>>>,
         columns_count: usize,
         is_filled: bool,
@@ -967,7 +2659,7 @@ where
     ) -> Stateful<Div> {
         let horizontal_scroll_handle = self.horizontal_scroll_handle.clone();
         let is_stripe_row = self.options.stripe && row_ix % 2 != 0;
-        let is_selected = self.selected_row == Some(row_ix);
+        let is_selected = self.selection.contains_row(row_ix);
         let view = cx.entity().clone();
         let row_height = self.options.size.table_row_height();
 
@@ -1003,11 +2695,13 @@ where
                                 let mut items = Vec::with_capacity(left_columns_count);
 
                                 (0..left_columns_count).for_each(|col_ix| {
-                                    items.push(self.render_col_wrap(col_ix, window, cx).child(
-                                        self.render_cell(col_ix, window, cx).child(
-                                            self.measure_render_td(row_ix, col_ix, window, cx),
+                                    items.push(
+                                        self.render_col_wrap(row_ix, col_ix, window, cx).child(
+                                            self.render_cell(col_ix, window, cx).child(
+                                                self.measure_render_td(row_ix, col_ix, window, cx),
+                                            ),
                                         ),
-                                    ));
+                                    );
                                 });
 
                                 items
@@ -1053,8 +2747,9 @@ where
 
                                         visible_range.for_each(|col_ix| {
                                             let col_ix = col_ix + left_columns_count;
-                                            let el =
-                                                table.render_col_wrap(col_ix, window, cx).child(
+                                            let el = table
+                                                .render_col_wrap(row_ix, col_ix, window, cx)
+                                                .child(
                                                     table.render_cell(col_ix, window, cx).child(
                                                         table.measure_render_td(
                                                             row_ix, col_ix, window, cx,
@@ -1073,25 +2768,55 @@ where
                         )
                         .child(self.delegate.render_last_empty_col(window, cx)),
                 )
-                // Row selected style
-                .when_some(self.selected_row, |this, _| {
-                    this.when(
-                        is_selected && self.selection_state == SelectionState::Row,
-                        |this| {
-                            this.border_color(gpui::transparent_white()).child(
+                .when(right_columns_count > 0, |this| {
+                    // Right fixed columns
+                    this.child(
+                        h_flex()
+                            .relative()
+                            .h_full()
+                            .flex_shrink_0()
+                            .children(
+                                (columns_count - right_columns_count..columns_count)
+                                    .map(|col_ix| {
+                                        self.render_col_wrap(row_ix, col_ix, window, cx).child(
+                                            self.render_cell(col_ix, window, cx).child(
+                                                self.measure_render_td(row_ix, col_ix, window, cx),
+                                            ),
+                                        )
+                                    })
+                                    .collect::<Vec<_>>(),
+                            )
+                            .child(
+                                // Fixed columns border
                                 div()
-                                    .top(if row_ix == 0 { px(0.) } else { px(-1.) })
-                                    .left(px(0.))
-                                    .right(px(0.))
-                                    .bottom(px(-1.))
                                     .absolute()
-                                    .bg(cx.theme().table_active)
-                                    .border_1()
-                                    .border_color(cx.theme().table_active_border),
-                            )
-                        },
+                                    .top_0()
+                                    .left_0()
+                                    .bottom_0()
+                                    .w_0()
+                                    .flex_shrink_0()
+                                    .border_l_1()
+                                    .border_color(cx.theme().border),
+                            ),
                     )
                 })
+                // Row selected style
+                .when(
+                    is_selected && self.selection_state == SelectionState::Row,
+                    |this| {
+                        this.border_color(gpui::transparent_white()).child(
+                            div()
+                                .top(if row_ix == 0 { px(0.) } else { px(-1.) })
+                                .left(px(0.))
+                                .right(px(0.))
+                                .bottom(px(-1.))
+                                .absolute()
+                                .bg(cx.theme().table_active)
+                                .border_1()
+                                .border_color(cx.theme().table_active_border),
+                        )
+                    },
+                )
                 // Row right click row style
                 .when(self.right_clicked_row == Some(row_ix), |this| {
                     this.border_color(gpui::transparent_white()).child(
@@ -1126,11 +2851,255 @@ where
                 .when(is_stripe_row, |this| this.bg(cx.theme().table_even))
                 .children((0..columns_count).map(|col_ix| {
                     h_flex()
-                        .left(horizontal_scroll_handle.offset().x)
-                        .child(self.render_cell(col_ix, window, cx))
-                }))
-                .child(self.delegate.render_last_empty_col(window, cx))
-        }
+                        .left(horizontal_scroll_handle.offset().x)
+                        .child(self.render_cell(col_ix, window, cx))
+                }))
+                .child(self.delegate.render_last_empty_col(window, cx))
+        }
+    }
+
+    fn render_group_header_row(
+        &mut self,
+        group_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Stateful<Div> {
+        let (rows, collapsed) = self
+            .group_layout
+            .as_ref()
+            .map(|layout| (layout.group_rows(group_ix), layout.is_collapsed(group_ix)))
+            .unwrap_or_default();
+
+        h_flex()
+            .id(("group-header", group_ix))
+            .w_full()
+            .h(self.options.size.table_row_height())
+            .gap_2()
+            .table_cell_size(self.options.size)
+            .bg(cx.theme().table_head)
+            .border_b_1()
+            .border_color(cx.theme().table_row_border)
+            .font_medium()
+            .child(
+                Icon::new(if collapsed {
+                    IconName::ChevronRight
+                } else {
+                    IconName::ChevronDown
+                })
+                .size_4()
+                .text_color(cx.theme().muted_foreground),
+            )
+            .child(self.delegate.render_group_header(group_ix, window, cx))
+            .child(
+                div()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(format!("({})", rows.len())),
+            )
+            .on_click(cx.listener(move |this, _, _, cx| this.toggle_group(group_ix, cx)))
+    }
+
+    fn render_group_footer_row(
+        &mut self,
+        group_ix: usize,
+        fixed_cols: (usize, usize),
+        col_sizes: Rc<Vec<gpui::Size<Pixels>>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Stateful<Div> {
+        self.render_extra_row(
+            ("group-footer", group_ix),
+            fixed_cols,
+            col_sizes,
+            Rc::new(move |table, col_ix, window, cx| {
+                table.render_aggregate_td(group_ix, col_ix, window, cx)
+            }),
+            window,
+            cx,
+        )
+        .border_b_1()
+        .border_color(cx.theme().table_row_border)
+        .bg(cx.theme().table_head)
+        .text_color(cx.theme().muted_foreground)
+    }
+
+    /// Render the pinned rows at the top or bottom of the table body, `None` if there is no pinned row.
+    fn render_pinned_rows(
+        &mut self,
+        pin: RowPin,
+        fixed_cols: (usize, usize),
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let count = self.delegate.pinned_rows_count(pin, cx);
+        if count == 0 {
+            return None;
+        }
+
+        let col_sizes: Rc<Vec<gpui::Size<Pixels>>> = Rc::new(
+            self.col_groups
+                .iter()
+                .skip(fixed_cols.0)
+                .take(self.col_groups.len() - fixed_cols.0 - fixed_cols.1)
+                .map(|col| col.bounds.size)
+                .collect(),
+        );
+        let id = match pin {
+            RowPin::Top => "pinned-top",
+            RowPin::Bottom => "pinned-bottom",
+        };
+
+        Some(
+            v_flex()
+                .w_full()
+                .flex_shrink_0()
+                .border_color(cx.theme().border)
+                .map(|this| match pin {
+                    RowPin::Top => this.border_b_1().bg(cx.theme().table),
+                    RowPin::Bottom => this.border_t_1().bg(cx.theme().table_head),
+                })
+                .children((0..count).map(|ix| {
+                    self.render_extra_row(
+                        (id, ix),
+                        fixed_cols,
+                        col_sizes.clone(),
+                        Rc::new(move |table, col_ix, window, cx| {
+                            let td = table
+                                .delegate
+                                .render_pinned_td(pin, ix, col_ix, window, cx)
+                                .into_any_element();
+                            table.render_cell(col_ix, window, cx).child(td)
+                        }),
+                        window,
+                        cx,
+                    )
+                    .when(ix + 1 < count, |this| {
+                        this.border_b_1().border_color(cx.theme().table_row_border)
+                    })
+                })),
+        )
+    }
+
+    /// Render a row that is not a data row (the group footer and pinned rows) in the left fixed,
+    /// scrollable and right fixed columns, the cells are rendered by `render_td`.
+    fn render_extra_row(
+        &mut self,
+        id: impl Into<ElementId>,
+        (left_columns_count, right_columns_count): (usize, usize),
+        col_sizes: Rc<Vec<gpui::Size<Pixels>>>,
+        render_td: Rc<dyn Fn(&mut Self, usize, &mut Window, &mut Context<Self>) -> Div>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Stateful<Div> {
+        let view = cx.entity().clone();
+        let columns_count = self.col_groups.len();
+
+        h_flex()
+            .id(id)
+            .w_full()
+            .h(self.options.size.table_row_height())
+            .when(left_columns_count > 0, |this| {
+                this.child(
+                    h_flex()
+                        .h_full()
+                        .border_r_1()
+                        .border_color(cx.theme().border)
+                        .children(
+                            (0..left_columns_count)
+                                .map(|col_ix| render_td(self, col_ix, window, cx))
+                                .collect::<Vec<_>>(),
+                        ),
+                )
+            })
+            .child(
+                h_flex()
+                    .flex_1()
+                    .h_full()
+                    .overflow_hidden()
+                    .relative()
+                    .child(
+                        crate::virtual_list::virtual_list(
+                            view,
+                            "cols",
+                            Axis::Horizontal,
+                            col_sizes,
+                            {
+                                let render_td = render_td.clone();
+                                move |table, visible_range: Range<usize>, window, cx| {
+                                    visible_range
+                                        .map(|col_ix| {
+                                            render_td(
+                                                table,
+                                                col_ix + left_columns_count,
+                                                window,
+                                                cx,
+                                            )
+                                        })
+                                        .collect::<Vec<_>>()
+                                }
+                            },
+                        )
+                        .with_scroll_handle(&self.horizontal_scroll_handle),
+                    ),
+            )
+            .when(right_columns_count > 0, |this| {
+                this.child(
+                    h_flex()
+                        .h_full()
+                        .flex_shrink_0()
+                        .border_l_1()
+                        .border_color(cx.theme().border)
+                        .children(
+                            (columns_count - right_columns_count..columns_count)
+                                .map(|col_ix| render_td(self, col_ix, window, cx))
+                                .collect::<Vec<_>>(),
+                        ),
+                )
+            })
+    }
+
+    /// Render the aggregated value of the column in the group footer row.
+    fn render_aggregate_td(
+        &mut self,
+        group_ix: usize,
+        col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let cell = self.render_cell(col_ix, window, cx);
+        let Some(col_group) = self.col_groups.get(col_ix) else {
+            return cell;
+        };
+        let Some(aggregate) = col_group.column.aggregate else {
+            return cell;
+        };
+        let align = col_group.column.align;
+
+        let value = match self.group_aggregates.get(&(group_ix, col_ix)) {
+            Some(value) => *value,
+            None => {
+                let rows = self
+                    .group_layout
+                    .as_ref()
+                    .map(|layout| layout.group_rows(group_ix))
+                    .unwrap_or_default();
+                let value = aggregate
+                    .compute(rows.map(|row_ix| self.delegate.cell_value(row_ix, col_ix, cx)));
+                self.group_aggregates.insert((group_ix, col_ix), value);
+                value
+            }
+        };
+
+        cell.child(
+            h_flex()
+                .size_full()
+                .when(align == TextAlign::Right, |this| this.justify_end())
+                .when_some(value, |this, value| {
+                    this.child(
+                        self.delegate
+                            .render_aggregate(group_ix, col_ix, aggregate, value, window, cx),
+                    )
+                }),
+        )
     }
 
     /// Calculate the extra rows needed to fill the table empty space when `stripe` is true.
@@ -1151,6 +3120,24 @@ where
     }
 
     #[inline]
+    fn render_delegate_td(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        if self.selection.contains_cell(row_ix, col_ix) {
+            self.delegate
+                .render_selected_td(row_ix, col_ix, window, cx)
+                .into_any_element()
+        } else {
+            self.delegate
+                .render_td(row_ix, col_ix, window, cx)
+                .into_any_element()
+        }
+    }
+
     fn measure_render_td(
         &mut self,
         row_ix: usize,
@@ -1158,17 +3145,77 @@ where
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
-        if !crate::measure_enable() {
-            return self
-                .delegate
-                .render_td(row_ix, col_ix, window, cx)
-                .into_any_element();
+        if let Some(editing) = self
+            .editing
+            .as_ref()
+            .filter(|editing| editing.cell == TableCell::new(row_ix, col_ix))
+        {
+            return editing
+                .editor
+                .render(self.options.size, editing.error.clone(), cx);
+        }
+
+        let el = if !crate::measure_enable() {
+            self.render_delegate_td(row_ix, col_ix, window, cx)
+        } else {
+            let start = std::time::Instant::now();
+            let el = self.render_delegate_td(row_ix, col_ix, window, cx);
+            self._measure.push(start.elapsed());
+            el
+        };
+
+        let is_tree_col = self
+            .col_groups
+            .get(col_ix)
+            .is_some_and(|col_group| col_group.column.tree);
+        if is_tree_col {
+            self.render_tree_td(row_ix, el, cx)
+        } else {
+            el
         }
+    }
 
-        let start = std::time::Instant::now();
-        let el = self.delegate.render_td(row_ix, col_ix, window, cx);
-        self._measure.push(start.elapsed());
-        el.into_any_element()
+    /// Render the cell of the tree column with the indentation and disclosure icon.
+    fn render_tree_td(&self, row_ix: usize, td: AnyElement, cx: &mut Context<Self>) -> AnyElement {
+        let depth = self.delegate.row_depth(row_ix, cx);
+        let children_count = self.delegate.row_children_count(row_ix, cx);
+        let expanded = self.delegate.is_row_expanded(row_ix, cx);
+
+        h_flex()
+            .size_full()
+            .gap_1()
+            .pl(px(16.) * depth as f32)
+            .child(
+                h_flex()
+                    .id(("disclosure", row_ix))
+                    .size_4()
+                    .flex_shrink_0()
+                    .justify_center()
+                    .map(|this| match children_count {
+                        Some(0) => this,
+                        // Loading the children.
+                        None if expanded => this.child(Spinner::new().xsmall()),
+                        _ => this
+                            .cursor_pointer()
+                            .child(
+                                Icon::new(if expanded {
+                                    IconName::ChevronDown
+                                } else {
+                                    IconName::ChevronRight
+                                })
+                                .size_3p5()
+                                .text_color(cx.theme().muted_foreground),
+                            )
+                            // Avoid to select the row when click the disclosure.
+                            .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                cx.stop_propagation();
+                                this.toggle_row_expanded(row_ix, window, cx);
+                            })),
+                    }),
+            )
+            .child(td)
+            .into_any_element()
     }
 
     fn measure(&mut self, _window: &mut Window, _cx: &mut Context<Self>) {
@@ -1197,15 +3244,20 @@ where
         &mut self,
 
         _: &mut Window,
-        _: &mut Context<Self>,
+        cx: &mut Context<Self>,
     ) -> Option<impl IntoElement> {
+        // Below the table head and the top pinned rows, above the bottom pinned rows.
+        let row_height = self.options.size.table_row_height();
+        let top_rows_count = self.delegate.pinned_rows_count(RowPin::Top, cx);
+        let bottom_rows_count = self.delegate.pinned_rows_count(RowPin::Bottom, cx);
+
         Some(
             div()
                 .occlude()
                 .absolute()
-                .top(self.options.size.table_row_height())
+                .top(row_height * (top_rows_count + 1) as f32)
                 .right_0()
-                .bottom_0()
+                .bottom(row_height * bottom_rows_count as f32)
                 .w(Scrollbar::width())
                 .child(Scrollbar::vertical(&self.vertical_scroll_handle).max_fps(60)),
         )
@@ -1220,7 +3272,7 @@ where
             .occlude()
             .absolute()
             .left(self.fixed_head_cols_bounds.size.width)
-            .right_0()
+            .right(self.fixed_right_head_cols_bounds.size.width)
             .bottom_0()
             .h(Scrollbar::width())
             .child(Scrollbar::horizontal(&self.horizontal_scroll_handle))
@@ -1243,14 +3295,20 @@ where
 {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         self.measure(window, cx);
+        if self.group_layout_dirty {
+            self.prepare_group_layout(cx);
+        }
 
         let columns_count = self.delegate.columns_count(cx);
-        let left_columns_count = self
-            .col_groups
-            .iter()
-            .filter(|col| self.col_fixed && col.column.fixed == Some(ColumnFixed::Left))
-            .count();
+        let left_columns_count = self.fixed_left_cols_count();
+        let right_columns_count = self.fixed_right_cols_count();
+        let fixed_cols = (left_columns_count, right_columns_count);
         let rows_count = self.delegate.rows_count(cx);
+        // The number of the list items, including the group header and footer rows.
+        let items_count = self
+            .group_layout
+            .as_ref()
+            .map_or(rows_count, |layout| layout.len());
         let loading = self.delegate.loading(cx);
 
         let row_height = self.options.size.table_row_height();
@@ -1262,13 +3320,13 @@ where
             .bounds()
             .size
             .height;
-        let actual_height = row_height * rows_count as f32;
+        let actual_height = row_height * items_count as f32;
         let extra_rows_count =
             self.calculate_extra_rows_needed(total_height, actual_height, row_height);
         let render_rows_count = if self.options.stripe {
-            rows_count + extra_rows_count
+            items_count + extra_rows_count
         } else {
-            rows_count
+            items_count
         };
         let right_clicked_row = self.right_clicked_row;
         let is_filled = total_height > Pixels::ZERO && total_height <= actual_height;
@@ -1298,13 +3356,21 @@ where
             .id("table-inner")
             .size_full()
             .overflow_hidden()
-            .child(self.render_table_head(left_columns_count, window, cx))
+            .child(self.render_table_head(left_columns_count, right_columns_count, window, cx))
+            .children(self.render_pinned_rows(RowPin::Top, fixed_cols, window, cx))
             .context_menu({
                 let view = cx.entity().clone();
                 move |this, window: &mut Window, cx: &mut Context<PopupMenu>| {
-                    if let Some(row_ix) = view.read(cx).right_clicked_row {
+                    let state = view.read(cx);
+                    if state.right_clicked_head {
+                        if state.col_hidable {
+                            view.update(cx, |state, cx| state.columns_menu(this, cx))
+                        } else {
+                            this
+                        }
+                    } else if let Some(row_ix) = state.right_clicked_row {
                         view.update(cx, |menu, cx| {
-                            menu.delegate_mut().context_menu(row_ix, this, window, cx)
+                            menu.delegate.context_menu(row_ix, this, window, cx)
                         })
                     } else {
                         this
@@ -1329,31 +3395,48 @@ where
                                                 .col_groups
                                                 .iter()
                                                 .skip(left_columns_count)
+                                                .take(
+                                                    columns_count
+                                                        - left_columns_count
+                                                        - right_columns_count,
+                                                )
                                                 .map(|col| col.bounds.size)
                                                 .collect(),
                                         );
 
                                         table.load_more_if_need(
-                                            rows_count,
+                                            items_count,
                                             visible_range.end,
                                             window,
                                             cx,
                                         );
-                                        table.update_visible_range_if_need(
-                                            visible_range.clone(),
-                                            Axis::Vertical,
-                                            window,
-                                            cx,
-                                        );
+                                        match &table.group_layout {
+                                            Some(layout) => {
+                                                let visible_rows =
+                                                    layout.rows_ranges(visible_range.clone());
+                                                table.update_visible_group_rows_if_need(
+                                                    visible_rows,
+                                                    window,
+                                                    cx,
+                                                );
+                                            }
+                                            None => table.update_visible_range_if_need(
+                                                visible_range.clone(),
+                                                Axis::Vertical,
+                                                window,
+                                                cx,
+                                            ),
+                                        }
 
-                                        if visible_range.end > rows_count {
-                                            table.scroll_to_row(
+                                        if visible_range.end > items_count {
+                                            table.vertical_scroll_handle.scroll_to_item(
                                                 std::cmp::min(
                                                     visible_range.start,
-                                                    rows_count.saturating_sub(1),
+                                                    items_count.saturating_sub(1),
                                                 ),
-                                                cx,
+                                                ScrollStrategy::Top,
                                             );
+                                            cx.notify();
                                         }
 
                                         let mut items = Vec::with_capacity(
@@ -1361,18 +3444,44 @@ where
                                         );
 
                                         // Render fake rows to fill the table
-                                        visible_range.for_each(|row_ix| {
-                                            // Render real rows for available data
-                                            items.push(table.render_table_row(
-                                                row_ix,
-                                                rows_count,
-                                                left_columns_count,
-                                                col_sizes.clone(),
-                                                columns_count,
-                                                is_filled,
-                                                window,
-                                                cx,
-                                            ));
+                                        visible_range.for_each(|ix| {
+                                            let row = match &table.group_layout {
+                                                Some(layout) => layout.row_at(ix),
+                                                None => Some(TableRow::Row(ix)),
+                                            };
+
+                                            items.push(match row {
+                                                Some(TableRow::GroupHeader(group_ix)) => table
+                                                    .render_group_header_row(group_ix, window, cx),
+                                                Some(TableRow::GroupFooter(group_ix)) => table
+                                                    .render_group_footer_row(
+                                                        group_ix,
+                                                        fixed_cols,
+                                                        col_sizes.clone(),
+                                                        window,
+                                                        cx,
+                                                    ),
+                                                row => {
+                                                    let row_ix = match row {
+                                                        Some(TableRow::Row(row_ix)) => row_ix,
+                                                        // The fake rows after the groups.
+                                                        _ => rows_count + ix - items_count,
+                                                    };
+
+                                                    // Render real rows for available data
+                                                    table.render_table_row(
+                                                        row_ix,
+                                                        rows_count,
+                                                        left_columns_count,
+                                                        right_columns_count,
+                                                        col_sizes.clone(),
+                                                        columns_count,
+                                                        is_filled,
+                                                        window,
+                                                        cx,
+                                                    )
+                                                }
+                                            });
                                         });
 
                                         items
@@ -1387,7 +3496,8 @@ where
                         ),
                     )
                 }
-            });
+            })
+            .children(self.render_pinned_rows(RowPin::Bottom, fixed_cols, window, cx));
 
         div()
             .size_full()
@@ -1398,6 +3508,16 @@ where
                         Axis::Horizontal,
                         &self.horizontal_scroll_handle,
                     ))
+                    .when(self.cell_selectable, |this| {
+                        this.on_mouse_up(
+                            MouseButton::Left,
+                            cx.listener(|this, _, _, _| this.selecting_cells = false),
+                        )
+                        .on_mouse_up_out(
+                            MouseButton::Left,
+                            cx.listener(|this, _, _, _| this.selecting_cells = false),
+                        )
+                    })
                     .when(right_clicked_row.is_some(), |this| {
                         this.on_mouse_down_out(cx.listener(|this, _, _, cx| {
                             this.right_clicked_row = None;
@@ -1429,3 +3549,836 @@ where
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use gpui::{AppContext as _, Entity, TestAppContext, VisualTestContext, point, size};
+
+    use super::*;
+    use crate::input::InputState;
+
+    struct TestDelegate {
+        columns: Vec<Column>,
+        rows: Vec<Vec<SharedString>>,
+        editable_cols: Vec<usize>,
+        groups: Vec<(SharedString, Range<usize>)>,
+    }
+
+    impl TestDelegate {
+        fn new(rows_count: usize, cols_count: usize) -> Self {
+            Self {
+                columns: (0..cols_count)
+                    .map(|col_ix| Column::new(format!("c{}", col_ix), format!("C{}", col_ix)))
+                    .collect(),
+                rows: (0..rows_count)
+                    .map(|row_ix| {
+                        (0..cols_count)
+                            .map(|col_ix| format!("{}-{}", row_ix, col_ix).into())
+                            .collect()
+                    })
+                    .collect(),
+                editable_cols: vec![],
+                groups: vec![],
+            }
+        }
+    }
+
+    impl TableDelegate for TestDelegate {
+        fn columns_count(&self, _: &App) -> usize {
+            self.columns.len()
+        }
+
+        fn rows_count(&self, _: &App) -> usize {
+            self.rows.len()
+        }
+
+        fn column(&self, col_ix: usize, _: &App) -> &Column {
+            &self.columns[col_ix]
+        }
+
+        fn render_td(
+            &mut self,
+            row_ix: usize,
+            col_ix: usize,
+            _: &mut Window,
+            _: &mut Context<TableState<Self>>,
+        ) -> impl IntoElement {
+            self.rows[row_ix][col_ix].clone()
+        }
+
+        fn move_column(
+            &mut self,
+            col_ix: usize,
+            to_ix: usize,
+            _: &mut Window,
+            _: &mut Context<TableState<Self>>,
+        ) {
+            let column = self.columns.remove(col_ix);
+            self.columns.insert(to_ix, column);
+            for row in self.rows.iter_mut() {
+                let cell = row.remove(col_ix);
+                row.insert(to_ix, cell);
+            }
+        }
+
+        fn cell_text(&self, row_ix: usize, col_ix: usize, _: &App) -> SharedString {
+            self.rows[row_ix][col_ix].clone()
+        }
+
+        fn groups_count(&self, _: &App) -> usize {
+            self.groups.len()
+        }
+
+        fn group_rows(&self, group_ix: usize, _: &App) -> Range<usize> {
+            self.groups[group_ix].1.clone()
+        }
+
+        fn group_id(&self, group_ix: usize, _: &App) -> SharedString {
+            self.groups[group_ix].0.clone()
+        }
+
+        fn is_cell_editable(&self, _: usize, col_ix: usize, _: &App) -> bool {
+            self.editable_cols.contains(&col_ix)
+        }
+
+        fn build_cell_editor(
+            &mut self,
+            row_ix: usize,
+            col_ix: usize,
+            window: &mut Window,
+            cx: &mut Context<TableState<Self>>,
+        ) -> Option<TableCellEditor> {
+            let value = self.rows[row_ix][col_ix].clone();
+            Some(TableCellEditor::Input(
+                cx.new(|cx| InputState::new(window, cx).default_value(value)),
+            ))
+        }
+
+        fn on_cell_edited(
+            &mut self,
+            row_ix: usize,
+            col_ix: usize,
+            editor: &TableCellEditor,
+            _: &mut Window,
+            cx: &mut Context<TableState<Self>>,
+        ) -> Result<(), SharedString> {
+            let TableCellEditor::Input(input) = editor else {
+                return Ok(());
+            };
+
+            let value = input.read(cx).value();
+            if value.is_empty() {
+                return Err("Required".into());
+            }
+            self.rows[row_ix][col_ix] = value;
+            Ok(())
+        }
+    }
+
+    struct TreeRow {
+        id: SharedString,
+        depth: usize,
+        expanded: bool,
+        children_count: Option<usize>,
+        collapsed_rows: Vec<TreeRow>,
+    }
+
+    impl TreeRow {
+        fn new(id: impl Into<SharedString>, depth: usize, children_count: Option<usize>) -> Self {
+            Self {
+                id: id.into(),
+                depth,
+                expanded: false,
+                children_count,
+                collapsed_rows: vec![],
+            }
+        }
+    }
+
+    /// A tree table, the children of the root rows are loaded lazily.
+    struct TreeDelegate {
+        columns: Vec<Column>,
+        rows: Vec<TreeRow>,
+        /// The ids of the rows that loaded the children.
+        loaded: Vec<SharedString>,
+    }
+
+    impl TreeDelegate {
+        fn new() -> Self {
+            Self {
+                columns: vec![Column::new("name", "Name").tree(true)],
+                rows: vec![TreeRow::new("a", 0, None), TreeRow::new("b", 0, None)],
+                loaded: vec![],
+            }
+        }
+
+        fn ids(&self) -> Vec<&str> {
+            self.rows.iter().map(|row| row.id.as_ref()).collect()
+        }
+    }
+
+    impl TableDelegate for TreeDelegate {
+        fn columns_count(&self, _: &App) -> usize {
+            self.columns.len()
+        }
+
+        fn rows_count(&self, _: &App) -> usize {
+            self.rows.len()
+        }
+
+        fn column(&self, col_ix: usize, _: &App) -> &Column {
+            &self.columns[col_ix]
+        }
+
+        fn render_td(
+            &mut self,
+            row_ix: usize,
+            _: usize,
+            _: &mut Window,
+            _: &mut Context<TableState<Self>>,
+        ) -> impl IntoElement {
+            self.rows[row_ix].id.clone()
+        }
+
+        fn row_id(&self, row_ix: usize, _: &App) -> SharedString {
+            self.rows[row_ix].id.clone()
+        }
+
+        fn row_depth(&self, row_ix: usize, _: &App) -> usize {
+            self.rows[row_ix].depth
+        }
+
+        fn row_children_count(&self, row_ix: usize, _: &App) -> Option<usize> {
+            self.rows[row_ix].children_count
+        }
+
+        fn is_row_expanded(&self, row_ix: usize, _: &App) -> bool {
+            self.rows[row_ix].expanded
+        }
+
+        fn set_row_expanded(
+            &mut self,
+            row_ix: usize,
+            expanded: bool,
+            _: &mut Window,
+            _: &mut Context<TableState<Self>>,
+        ) {
+            if expanded {
+                let rows = std::mem::take(&mut self.rows[row_ix].collapsed_rows);
+                self.rows.splice(row_ix + 1..row_ix + 1, rows);
+            } else {
+                let depth = self.rows[row_ix].depth;
+                let end = (row_ix + 1..self.rows.len())
+                    .find(|ix| self.rows[*ix].depth <= depth)
+                    .unwrap_or(self.rows.len());
+                self.rows[row_ix].collapsed_rows = self.rows.drain(row_ix + 1..end).collect();
+            }
+            self.rows[row_ix].expanded = expanded;
+        }
+
+        fn load_children(
+            &mut self,
+            row_ix: usize,
+            _: &mut Window,
+            _: &mut Context<TableState<Self>>,
+        ) {
+            let row = &mut self.rows[row_ix];
+            let children = (0..2)
+                .map(|ix| TreeRow::new(format!("{}/{}", row.id, ix), row.depth + 1, Some(0)))
+                .collect::<Vec<_>>();
+            row.children_count = Some(children.len());
+            self.loaded.push(row.id.clone());
+            self.rows.splice(row_ix + 1..row_ix + 1, children);
+        }
+    }
+
+    /// A sortable table, only the last `perform_sort` is recorded.
+    struct SortDelegate {
+        columns: Vec<Column>,
+        /// The column and order of the last `perform_sort`.
+        sorted: Option<(usize, ColumnSort)>,
+    }
+
+    impl SortDelegate {
+        fn new(cols_count: usize) -> Self {
+            Self {
+                columns: (0..cols_count)
+                    .map(|col_ix| {
+                        Column::new(format!("c{}", col_ix), format!("C{}", col_ix)).sortable()
+                    })
+                    .collect(),
+                sorted: None,
+            }
+        }
+    }
+
+    impl TableDelegate for SortDelegate {
+        fn columns_count(&self, _: &App) -> usize {
+            self.columns.len()
+        }
+
+        fn rows_count(&self, _: &App) -> usize {
+            0
+        }
+
+        fn column(&self, col_ix: usize, _: &App) -> &Column {
+            &self.columns[col_ix]
+        }
+
+        fn render_td(
+            &mut self,
+            _: usize,
+            _: usize,
+            _: &mut Window,
+            _: &mut Context<TableState<Self>>,
+        ) -> impl IntoElement {
+            div()
+        }
+
+        fn perform_sort(
+            &mut self,
+            col_ix: usize,
+            sort: ColumnSort,
+            _: &mut Window,
+            _: &mut Context<TableState<Self>>,
+        ) {
+            self.sorted = Some((col_ix, sort));
+        }
+    }
+
+    /// A table with the fixed columns and the pinned rows.
+    struct FrozenDelegate {
+        columns: Vec<Column>,
+        rows_count: usize,
+        /// The top and bottom pinned rows count.
+        pinned_rows: (usize, usize),
+        /// The pin, row and column of the rendered pinned cells.
+        pinned_cells: Vec<(RowPin, usize, usize)>,
+    }
+
+    impl FrozenDelegate {
+        fn new(rows_count: usize, columns: Vec<Column>) -> Self {
+            Self {
+                columns,
+                rows_count,
+                pinned_rows: (0, 0),
+                pinned_cells: vec![],
+            }
+        }
+    }
+
+    impl TableDelegate for FrozenDelegate {
+        fn columns_count(&self, _: &App) -> usize {
+            self.columns.len()
+        }
+
+        fn rows_count(&self, _: &App) -> usize {
+            self.rows_count
+        }
+
+        fn column(&self, col_ix: usize, _: &App) -> &Column {
+            &self.columns[col_ix]
+        }
+
+        fn render_td(
+            &mut self,
+            row_ix: usize,
+            col_ix: usize,
+            _: &mut Window,
+            _: &mut Context<TableState<Self>>,
+        ) -> impl IntoElement {
+            format!("{}-{}", row_ix, col_ix)
+        }
+
+        fn pinned_rows_count(&self, pin: RowPin, _: &App) -> usize {
+            match pin {
+                RowPin::Top => self.pinned_rows.0,
+                RowPin::Bottom => self.pinned_rows.1,
+            }
+        }
+
+        fn render_pinned_td(
+            &mut self,
+            pin: RowPin,
+            row_ix: usize,
+            col_ix: usize,
+            _: &mut Window,
+            _: &mut Context<TableState<Self>>,
+        ) -> impl IntoElement {
+            self.pinned_cells.push((pin, row_ix, col_ix));
+            format!("{:?}-{}-{}", pin, row_ix, col_ix)
+        }
+    }
+
+    fn build_table<D: TableDelegate>(
+        delegate: D,
+        cx: &mut TestAppContext,
+    ) -> (Entity<TableState<D>>, &mut VisualTestContext) {
+        let cx = cx.add_empty_window();
+        let state = cx.update(|window, cx| cx.new(|cx| TableState::new(delegate, window, cx)));
+        (state, cx)
+    }
+
+    fn set_editor_value(
+        state: &mut TableState<TestDelegate>,
+        value: &str,
+        window: &mut Window,
+        cx: &mut Context<TableState<TestDelegate>>,
+    ) {
+        let Some(TableCellEditor::Input(input)) = state.editing.as_ref().map(|e| e.editor.clone())
+        else {
+            panic!("no cell is editing");
+        };
+        input.update(cx, |input, cx| {
+            input.set_value(value.to_string(), window, cx)
+        });
+    }
+
+    #[gpui::test]
+    fn test_cell_editing(cx: &mut TestAppContext) {
+        let mut delegate = TestDelegate::new(2, 2);
+        delegate.editable_cols = vec![1];
+        let (state, cx) = build_table(delegate, cx);
+
+        cx.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                // Not editable
+                assert!(!state.start_editing(TableCell::new(0, 0), window, cx));
+                assert_eq!(state.editing_cell(), None);
+
+                // Enter to start editing the active cell, and enter again to commit.
+                state.set_active_cell(TableCell::new(0, 1), cx);
+                state.action_edit_cell(&EditCell, window, cx);
+                assert_eq!(state.editing_cell(), Some(TableCell::new(0, 1)));
+                set_editor_value(state, "new", window, cx);
+                state.action_edit_cell(&EditCell, window, cx);
+                assert_eq!(state.editing_cell(), None);
+                assert_eq!(state.delegate().rows[0][1], "new");
+
+                // Escape to cancel, the old value is kept.
+                state.action_edit_cell(&EditCell, window, cx);
+                set_editor_value(state, "changed", window, cx);
+                state.action_cancel(&Cancel, window, cx);
+                assert_eq!(state.editing_cell(), None);
+                assert_eq!(state.delegate().rows[0][1], "new");
+
+                // The validation error keeps the editor open with the error.
+                state.action_edit_cell(&EditCell, window, cx);
+                set_editor_value(state, "", window, cx);
+                state.action_edit_cell(&EditCell, window, cx);
+                assert_eq!(state.editing_cell(), Some(TableCell::new(0, 1)));
+                assert_eq!(
+                    state.editing.as_ref().and_then(|e| e.error.clone()),
+                    Some("Required".into())
+                );
+                assert_eq!(state.delegate().rows[0][1], "new");
+                // Can't start editing another cell until the error is fixed.
+                assert!(!state.start_editing(TableCell::new(1, 1), window, cx));
+                assert_eq!(state.editing_cell(), Some(TableCell::new(0, 1)));
+
+                set_editor_value(state, "fixed", window, cx);
+                assert!(state.commit_editing(window, cx));
+                assert_eq!(state.delegate().rows[0][1], "fixed");
+            })
+        });
+    }
+
+    #[gpui::test]
+    fn test_edit_adjacent_cell(cx: &mut TestAppContext) {
+        let mut delegate = TestDelegate::new(3, 3);
+        delegate.editable_cols = vec![0, 2];
+        let (state, cx) = build_table(delegate, cx);
+
+        cx.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                assert!(state.start_editing(TableCell::new(0, 0), window, cx));
+
+                // Tab skips the non-editable cell.
+                state.action_edit_next_cell(&EditNextCell, window, cx);
+                assert_eq!(state.editing_cell(), Some(TableCell::new(0, 2)));
+                // Tab wraps to the next row.
+                state.action_edit_next_cell(&EditNextCell, window, cx);
+                assert_eq!(state.editing_cell(), Some(TableCell::new(1, 0)));
+                assert_eq!(state.selected_row(), Some(1));
+                // Shift-Tab wraps to the previous row.
+                state.action_edit_prev_cell(&EditPrevCell, window, cx);
+                assert_eq!(state.editing_cell(), Some(TableCell::new(0, 2)));
+
+                // Wraps around the table.
+                state.action_edit_prev_cell(&EditPrevCell, window, cx);
+                state.action_edit_prev_cell(&EditPrevCell, window, cx);
+                assert_eq!(state.editing_cell(), Some(TableCell::new(2, 2)));
+                state.action_edit_next_cell(&EditNextCell, window, cx);
+                assert_eq!(state.editing_cell(), Some(TableCell::new(0, 0)));
+
+                // The edited value is committed when moving to the next cell.
+                set_editor_value(state, "edited", window, cx);
+                state.action_edit_next_cell(&EditNextCell, window, cx);
+                assert_eq!(state.delegate().rows[0][0], "edited");
+
+                // The validation error stops moving.
+                set_editor_value(state, "", window, cx);
+                state.action_edit_next_cell(&EditNextCell, window, cx);
+                assert_eq!(state.editing_cell(), Some(TableCell::new(0, 2)));
+            })
+        });
+    }
+
+    #[gpui::test]
+    fn test_edit_adjacent_cell_skips_hidden(cx: &mut TestAppContext) {
+        let mut delegate = TestDelegate::new(5, 3);
+        delegate.editable_cols = vec![0, 1, 2];
+        delegate.groups = vec![("a".into(), 0..1), ("b".into(), 1..3), ("c".into(), 3..5)];
+        let (state, cx) = build_table(delegate, cx);
+
+        cx.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                state.set_column_hidden(1, true, window, cx);
+                state.set_group_collapsed(1, true, cx);
+                assert!(state.start_editing(TableCell::new(0, 0), window, cx));
+
+                // Tab skips the hidden column.
+                state.action_edit_next_cell(&EditNextCell, window, cx);
+                assert_eq!(state.editing_cell(), Some(TableCell::new(0, 2)));
+                // Tab skips the rows of the collapsed group.
+                state.action_edit_next_cell(&EditNextCell, window, cx);
+                assert_eq!(state.editing_cell(), Some(TableCell::new(3, 0)));
+                // Shift-Tab skips them backward.
+                state.action_edit_prev_cell(&EditPrevCell, window, cx);
+                assert_eq!(state.editing_cell(), Some(TableCell::new(0, 2)));
+                state.action_edit_prev_cell(&EditPrevCell, window, cx);
+                assert_eq!(state.editing_cell(), Some(TableCell::new(0, 0)));
+                // Wraps around to the last visible cell.
+                state.action_edit_prev_cell(&EditPrevCell, window, cx);
+                assert_eq!(state.editing_cell(), Some(TableCell::new(4, 2)));
+            })
+        });
+    }
+
+    #[gpui::test]
+    fn test_groups(cx: &mut TestAppContext) {
+        let mut delegate = TestDelegate::new(5, 2);
+        delegate.columns[1] = Column::new("c1", "C1").aggregate(ColumnAggregate::Sum);
+        for (row_ix, row) in delegate.rows.iter_mut().enumerate() {
+            row[1] = row_ix.to_string().into();
+        }
+        delegate.groups = vec![("a".into(), 0..2), ("b".into(), 2..5)];
+        let (state, cx) = build_table(delegate, cx);
+
+        cx.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                state.set_group_collapsed(1, true, cx);
+                assert!(state.is_group_collapsed(1, cx));
+                assert!(!state.group_layout_dirty);
+                assert_eq!(
+                    state.group_layout.as_ref().map(|layout| layout.len()),
+                    Some(5)
+                );
+
+                _ = state.render_aggregate_td(0, 1, window, cx);
+                assert_eq!(state.group_aggregates.get(&(0, 1)), Some(&Some(1.)));
+
+                // The aggregates are cached until the rows are refreshed.
+                state.delegate_mut().rows[0][1] = "10".into();
+                state.update_visible_range_if_need(0..3, Axis::Vertical, window, cx);
+                _ = state.render_aggregate_td(0, 1, window, cx);
+                assert_eq!(state.group_aggregates.get(&(0, 1)), Some(&Some(1.)));
+                state.refresh_rows(cx);
+                assert!(state.group_layout_dirty);
+                assert!(state.group_aggregates.is_empty());
+                state.prepare_group_layout(cx);
+                _ = state.render_aggregate_td(0, 1, window, cx);
+                assert_eq!(state.group_aggregates.get(&(0, 1)), Some(&Some(11.)));
+
+                // The collapsed state is kept by the group id when the groups are changed.
+                state.delegate_mut().groups = vec![("b".into(), 0..3), ("a".into(), 3..5)];
+                state.refresh_rows(cx);
+                state.prepare_group_layout(cx);
+                let layout = state.group_layout.as_ref().unwrap();
+                assert!(layout.is_collapsed(0));
+                assert!(!layout.is_collapsed(1));
+                assert_eq!(layout.len(), 5);
+
+                state.set_all_groups_collapsed(true, cx);
+                assert!(state.is_group_collapsed(0, cx) && state.is_group_collapsed(1, cx));
+                state.set_all_groups_collapsed(false, cx);
+                assert!(state.collapsed_groups.is_empty());
+            })
+        });
+    }
+
+    #[gpui::test]
+    fn test_tree(cx: &mut TestAppContext) {
+        let (state, cx) = build_table(TreeDelegate::new(), cx);
+
+        // Right to expand the row, the children are loaded after.
+        state.update_in(cx, |state, window, cx| {
+            state.set_selected_row(0, cx);
+            state.action_select_next_col(&SelectNextColumn, window, cx);
+            assert!(state.delegate().rows[0].expanded);
+            assert_eq!(state.delegate().ids(), ["a", "b"]);
+        });
+        cx.run_until_parked();
+        state.update_in(cx, |state, window, cx| {
+            assert_eq!(state.delegate().loaded, ["a"]);
+            assert_eq!(state.delegate().ids(), ["a", "a/0", "a/1", "b"]);
+
+            // Right to move to the first child, and Left to move back to the parent.
+            state.action_select_next_col(&SelectNextColumn, window, cx);
+            assert_eq!(state.selected_row(), Some(1));
+            state.set_selected_row(2, cx);
+            state.action_select_prev_col(&SelectPrevColumn, window, cx);
+            assert_eq!(state.selected_row(), Some(0));
+
+            // Left to collapse the row, and Left again on the root row does nothing.
+            state.action_select_prev_col(&SelectPrevColumn, window, cx);
+            assert!(!state.delegate().rows[0].expanded);
+            assert_eq!(state.delegate().ids(), ["a", "b"]);
+            state.action_select_prev_col(&SelectPrevColumn, window, cx);
+            assert_eq!(state.selected_row(), Some(0));
+
+            // The selected row after the expanded or collapsed row is moved.
+            state.set_selected_row(1, cx);
+            state.set_row_expanded(0, true, window, cx);
+            assert_eq!(state.selected_row(), Some(3));
+            state.set_row_expanded(0, false, window, cx);
+            assert_eq!(state.selected_row(), Some(1));
+
+            // The rows are changed before loading, the children are loaded for the right row.
+            state.set_row_expanded(1, true, window, cx);
+            state.set_row_expanded(0, true, window, cx);
+            assert_eq!(state.delegate().ids(), ["a", "a/0", "a/1", "b"]);
+        });
+        cx.run_until_parked();
+        state.update_in(cx, |state, window, cx| {
+            assert_eq!(state.delegate().loaded, ["a", "b"]);
+            assert_eq!(
+                state.delegate().ids(),
+                ["a", "a/0", "a/1", "b", "b/0", "b/1"]
+            );
+            assert_eq!(state.selected_row(), Some(3));
+            assert_eq!(state.parent_row(5, cx), Some(3));
+
+            // Collapsed before loading, the children are not loaded.
+            state.delegate_mut().rows = vec![TreeRow::new("c", 0, None)];
+            state.set_row_expanded(0, true, window, cx);
+            state.set_row_expanded(0, false, window, cx);
+            assert_eq!(state.parent_row(0, cx), None);
+        });
+        cx.run_until_parked();
+        state.update(cx, |state, _| {
+            assert_eq!(state.delegate().loaded, ["a", "b"]);
+            assert_eq!(state.delegate().ids(), ["c"]);
+        });
+    }
+
+    #[gpui::test]
+    fn test_fixed_cols(cx: &mut TestAppContext) {
+        let delegate = FrozenDelegate::new(
+            2,
+            vec![
+                Column::new("c0", "C0").fixed_left(),
+                Column::new("c1", "C1").fixed_left(),
+                Column::new("c2", "C2"),
+                // Not the first columns, so not fixed.
+                Column::new("c3", "C3").fixed_left(),
+                Column::new("c4", "C4").fixed_right(),
+                Column::new("c5", "C5").fixed_right(),
+            ],
+        );
+        let (state, cx) = build_table(delegate, cx);
+
+        state.update(cx, |state, cx| {
+            assert_eq!(state.fixed_left_cols_count(), 2);
+            assert_eq!(state.fixed_right_cols_count(), 2);
+
+            // The right fixed columns must be the last columns.
+            state.delegate_mut().columns[4] = Column::new("c4", "C4");
+            state.refresh(cx);
+            assert_eq!(state.fixed_right_cols_count(), 1);
+            state.delegate_mut().columns[5] = Column::new("c5", "C5").fixed_left();
+            state.refresh(cx);
+            assert_eq!(state.fixed_left_cols_count(), 2);
+            assert_eq!(state.fixed_right_cols_count(), 0);
+
+            state.col_fixed = false;
+            assert_eq!(state.fixed_left_cols_count(), 0);
+        });
+    }
+
+    #[gpui::test]
+    fn test_pinned_rows(cx: &mut TestAppContext) {
+        let mut delegate = FrozenDelegate::new(
+            2,
+            vec![
+                Column::new("c0", "C0").fixed_left(),
+                Column::new("c1", "C1"),
+                Column::new("c2", "C2").fixed_right(),
+            ],
+        );
+        delegate.pinned_rows = (1, 2);
+        let (state, cx) = build_table(delegate, cx);
+        cx.update(|_, cx| crate::init(cx));
+
+        let draw = |cx: &mut VisualTestContext| {
+            cx.draw(point(px(0.), px(0.)), size(px(800.), px(600.)), |_, _| {
+                Table::new(&state).into_any_element()
+            });
+        };
+        draw(cx);
+        state.update(cx, |state, _| {
+            let mut cells = std::mem::take(&mut state.delegate_mut().pinned_cells);
+            // The scrollable cells may be rendered more than once by the virtual list.
+            cells.sort_by_key(|(pin, row_ix, col_ix)| (*pin == RowPin::Bottom, *row_ix, *col_ix));
+            cells.dedup();
+            assert_eq!(
+                cells,
+                [
+                    (RowPin::Top, 0, 0),
+                    (RowPin::Top, 0, 1),
+                    (RowPin::Top, 0, 2),
+                    (RowPin::Bottom, 0, 0),
+                    (RowPin::Bottom, 0, 1),
+                    (RowPin::Bottom, 0, 2),
+                    (RowPin::Bottom, 1, 0),
+                    (RowPin::Bottom, 1, 1),
+                    (RowPin::Bottom, 1, 2),
+                ]
+            );
+
+            state.delegate_mut().pinned_rows = (0, 0);
+        });
+        draw(cx);
+        state.update(cx, |state, _| {
+            assert!(state.delegate().pinned_cells.is_empty());
+        });
+    }
+
+    #[gpui::test]
+    fn test_multi_sort(cx: &mut TestAppContext) {
+        let (state, cx) = build_table(SortDelegate::new(3), cx);
+
+        state.update_in(cx, |state, window, cx| {
+            state.multi_sort = true;
+
+            // Click to sort the column, and Shift-click to add the columns by the priority.
+            state.perform_sort(2, false, window, cx);
+            state.perform_sort(0, true, window, cx);
+            state.perform_sort(0, true, window, cx);
+            state.perform_sort(1, true, window, cx);
+            assert_eq!(
+                state.sorts(),
+                [
+                    (2, ColumnSort::Descending),
+                    (0, ColumnSort::Ascending),
+                    (1, ColumnSort::Descending)
+                ]
+            );
+            // The default `perform_multi_sort` sorts by the primary column.
+            assert_eq!(state.delegate().sorted, Some((2, ColumnSort::Descending)));
+
+            // The next column becomes the primary when the sort is cleared.
+            state.perform_sort(2, true, window, cx);
+            state.perform_sort(2, true, window, cx);
+            assert_eq!(
+                state.sorts(),
+                [(0, ColumnSort::Ascending), (1, ColumnSort::Descending)]
+            );
+            assert_eq!(state.delegate().sorted, Some((0, ColumnSort::Ascending)));
+
+            // Click without Shift sorts only the column.
+            state.perform_sort(1, false, window, cx);
+            assert_eq!(state.sorts(), [(1, ColumnSort::Ascending)]);
+            assert_eq!(state.sort_keys, ["c1"]);
+
+            // The sort keys are kept by the column key after refresh,
+            // and the new sorted columns are appended.
+            let delegate = state.delegate_mut();
+            delegate.columns = vec![
+                Column::new("c2", "C2").sortable(),
+                Column::new("c0", "C0").descending(),
+                Column::new("c1", "C1").ascending(),
+            ];
+            state.refresh(cx);
+            assert_eq!(state.sort_keys, ["c1", "c0"]);
+            assert_eq!(
+                state.sorts(),
+                [(2, ColumnSort::Ascending), (1, ColumnSort::Descending)]
+            );
+        });
+    }
+
+    #[gpui::test]
+    fn test_load(cx: &mut TestAppContext) {
+        let (state, cx) = build_table(TestDelegate::new(1, 4), cx);
+        let moved = Rc::new(std::cell::Cell::new(false));
+        cx.update(|_, cx| {
+            let moved = moved.clone();
+            cx.subscribe(&state, move |_, event: &TableEvent, _| {
+                if matches!(event, TableEvent::MoveColumn(..)) {
+                    moved.set(true);
+                }
+            })
+            .detach();
+        });
+
+        let col_state = |key: &str, width: f32, fixed: Option<ColumnFixed>| TableColumnState {
+            key: key.to_string().into(),
+            width: px(width),
+            hidden: false,
+            sort: None,
+            fixed,
+        };
+        state.update_in(cx, |state, window, cx| {
+            state.load(
+                TableViewState {
+                    columns: vec![
+                        col_state("c2", 50., Some(ColumnFixed::Left)),
+                        col_state("unknown", 10., None),
+                        col_state("c0", 60., Some(ColumnFixed::Left)),
+                        col_state("c2", 20., None),
+                        // Not the last column, so not fixed.
+                        col_state("c1", 70., Some(ColumnFixed::Right)),
+                    ],
+                    sort_keys: vec![],
+                },
+                window,
+                cx,
+            );
+
+            let keys = |columns: Vec<&Column>| {
+                columns
+                    .into_iter()
+                    .map(|column| column.key.to_string())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                keys(state.col_groups.iter().map(|col| &col.column).collect()),
+                ["c2", "c0", "c1", "c3"]
+            );
+            // The delegate columns are moved to the same order.
+            assert_eq!(
+                keys(state.delegate().columns.iter().collect()),
+                ["c2", "c0", "c1", "c3"]
+            );
+            assert_eq!(state.delegate().rows[0], ["0-2", "0-0", "0-1", "0-3"]);
+            assert_eq!(
+                state
+                    .col_groups
+                    .iter()
+                    .map(|col| col.width)
+                    .collect::<Vec<_>>(),
+                [px(50.), px(60.), px(70.), state.col_groups[3].column.width]
+            );
+            assert_eq!(
+                state
+                    .col_groups
+                    .iter()
+                    .map(|col| col.column.fixed)
+                    .collect::<Vec<_>>(),
+                [Some(ColumnFixed::Left), Some(ColumnFixed::Left), None, None]
+            );
+        });
+        assert!(!moved.get());
+    }
+}
@@ -0,0 +1,78 @@
+use gpui::{Pixels, SharedString};
+use serde::{Deserialize, Serialize};
+
+use crate::table::{ColumnFixed, ColumnSort};
+
+/// Used to serialize and deserialize the columns layout of a [`crate::table::Table`].
+///
+/// See [`crate::table::TableState::dump`] and [`crate::table::TableState::load`].
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableViewState {
+    /// The columns in display order.
+    pub columns: Vec<TableColumnState>,
+    /// The keys of the sorted columns, in the sort priority order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sort_keys: Vec<SharedString>,
+}
+
+/// The layout state of a table column, identified by the [`crate::table::Column::key`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableColumnState {
+    pub key: SharedString,
+    pub width: Pixels,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hidden: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<ColumnSort>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed: Option<ColumnFixed>,
+}
+
+impl TableViewState {
+    /// Returns the state of the column by key.
+    pub fn column(&self, key: &str) -> Option<&TableColumnState> {
+        self.columns.iter().find(|col| col.key.as_ref() == key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::px;
+
+    use super::*;
+
+    #[test]
+    fn test_table_view_state() {
+        let state = TableViewState {
+            columns: vec![
+                TableColumnState {
+                    key: "name".into(),
+                    width: px(120.),
+                    hidden: false,
+                    sort: None,
+                    fixed: Some(ColumnFixed::Left),
+                },
+                TableColumnState {
+                    key: "price".into(),
+                    width: px(80.),
+                    hidden: true,
+                    sort: Some(ColumnSort::Descending),
+                    fixed: None,
+                },
+            ],
+            sort_keys: vec!["price".into()],
+        };
+
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(
+            json,
+            r#"{"columns":[{"key":"name","width":120.0,"fixed":"left"},{"key":"price","width":80.0,"hidden":true,"sort":"desc"}],"sort_keys":["price"]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<TableViewState>(&json).unwrap(),
+            state
+        );
+        assert_eq!(state.column("price").map(|col| col.hidden), Some(true));
+        assert!(state.column("volume").is_none());
+    }
+}
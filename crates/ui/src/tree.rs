@@ -1,20 +1,33 @@
-use std::{cell::RefCell, ops::Range, rc::Rc};
+use std::{
+    cell::RefCell,
+    ops::Range,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
 use gpui::{
-    App, Context, ElementId, Entity, FocusHandle, InteractiveElement as _, IntoElement, KeyBinding,
-    ListSizingBehavior, MouseButton, ParentElement, Render, RenderOnce, SharedString,
-    StyleRefinement, Styled, UniformListScrollHandle, Window, div, prelude::FluentBuilder as _,
-    uniform_list,
+    App, AppContext as _, Context, Div, DragMoveEvent, ElementId, Entity, EntityId, EventEmitter,
+    FocusHandle, InteractiveElement as _, IntoElement, KeyBinding, KeyDownEvent,
+    ListSizingBehavior, MouseButton, ParentElement, Render, RenderOnce, SharedString, Stateful,
+    StatefulInteractiveElement as _, StyleRefinement, Styled, Task, UniformListScrollHandle,
+    Window, div, prelude::FluentBuilder as _, px, uniform_list,
 };
+use rust_i18n::t;
 
 use crate::{
-    StyledExt,
+    ActiveTheme as _, Disableable as _, Sizable as _, StyledExt,
     actions::{Confirm, SelectDown, SelectLeft, SelectRight, SelectUp},
+    checkbox::Checkbox,
+    h_flex,
+    label::Label,
     list::ListItem,
     scroll::ScrollableElement,
+    spinner::Spinner,
 };
 
 const CONTEXT: &str = "Tree";
+/// The type-ahead query is reset after this duration without typing.
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_secs(1);
 pub(crate) fn init(cx: &mut App) {
     cx.bind_keys([
         KeyBinding::new("up", SelectUp, Some(CONTEXT)),
@@ -56,6 +69,10 @@ where
 struct TreeItemState {
     expanded: bool,
     disabled: bool,
+    /// The children are not loaded yet, see [`TreeItem::lazy`].
+    lazy: bool,
+    loading: bool,
+    checked: bool,
 }
 
 /// A tree item with a label, children, and an expanded state.
@@ -72,6 +89,10 @@ pub struct TreeItem {
 pub struct TreeEntry {
     item: TreeItem,
     depth: usize,
+    /// The loading row of the children of the `item`.
+    placeholder: bool,
+    /// The filter query if the item label matches it.
+    matched_query: Option<SharedString>,
 }
 
 impl TreeEntry {
@@ -88,6 +109,7 @@ impl TreeEntry {
     }
 
     #[inline]
+    #[allow(unused)]
     fn is_root(&self) -> bool {
         self.depth == 0
     }
@@ -108,6 +130,27 @@ impl TreeEntry {
     pub fn is_disabled(&self) -> bool {
         self.item.is_disabled()
     }
+
+    /// Return true if the item is checked.
+    #[inline]
+    pub fn is_checked(&self) -> bool {
+        self.item.is_checked()
+    }
+
+    /// Return true if the item label matches the filter query, see [`TreeState::set_filter_query`].
+    #[inline]
+    pub fn is_matched(&self) -> bool {
+        self.matched_query.is_some()
+    }
+
+    /// Returns a [`Label`] of the item, with the matched text highlighted when filtering.
+    pub fn label(&self) -> Label {
+        let label = Label::new(self.item.label.clone());
+        match &self.matched_query {
+            Some(query) => label.highlights(query.clone()),
+            None => label,
+        }
+    }
 }
 
 impl TreeItem {
@@ -129,6 +172,9 @@ impl TreeItem {
             state: Rc::new(RefCell::new(TreeItemState {
                 expanded: false,
                 disabled: false,
+                lazy: false,
+                loading: false,
+                checked: false,
             })),
         }
     }
@@ -157,10 +203,24 @@ impl TreeItem {
         self
     }
 
+    /// Set this item as a folder that loads the children when it is first expanded.
+    ///
+    /// See [`TreeState::on_load_children`] and [`TreeState::update_item_children`].
+    pub fn lazy(self, lazy: bool) -> Self {
+        self.state.borrow_mut().lazy = lazy;
+        self
+    }
+
+    /// Set checked state for this tree item, see [`TreeState::checkable`].
+    pub fn checked(self, checked: bool) -> Self {
+        self.state.borrow_mut().checked = checked;
+        self
+    }
+
     /// Whether this item is a folder (has children).
     #[inline]
     pub fn is_folder(&self) -> bool {
-        self.children.len() > 0
+        self.children.len() > 0 || self.is_lazy()
     }
 
     /// Return true if the item is disabled.
@@ -173,17 +233,243 @@ impl TreeItem {
     pub fn is_expanded(&self) -> bool {
         self.state.borrow().expanded
     }
+
+    /// Return true if the children of the item are loading.
+    #[inline]
+    pub fn is_loading(&self) -> bool {
+        self.state.borrow().loading
+    }
+
+    /// Return true if the item is checked.
+    #[inline]
+    pub fn is_checked(&self) -> bool {
+        self.state.borrow().checked
+    }
+
+    /// Return true if the item is not checked, but some of its descendants are checked.
+    pub fn is_indeterminate(&self) -> bool {
+        !self.is_checked()
+            && self
+                .children
+                .iter()
+                .any(|child| child.is_checked() || child.is_indeterminate())
+    }
+
+    #[inline]
+    fn is_lazy(&self) -> bool {
+        self.state.borrow().lazy
+    }
+
+    fn set_checked_deep(&self, checked: bool) {
+        self.state.borrow_mut().checked = checked;
+        for child in &self.children {
+            child.set_checked_deep(checked);
+        }
+    }
+}
+
+/// Sync the checked state of the folders from their children,
+/// returns true if all the items are checked.
+fn sync_checked(items: &[TreeItem]) -> bool {
+    items.iter().fold(true, |all_checked, item| {
+        if !item.children.is_empty() {
+            let checked = sync_checked(&item.children);
+            item.state.borrow_mut().checked = checked;
+        }
+        all_checked && item.is_checked()
+    })
+}
+
+fn find_item<'a>(items: &'a [TreeItem], id: &str) -> Option<&'a TreeItem> {
+    items.iter().find_map(|item| {
+        if item.id.as_ref() == id {
+            Some(item)
+        } else {
+            find_item(&item.children, id)
+        }
+    })
+}
+
+fn find_item_mut<'a>(items: &'a mut [TreeItem], id: &str) -> Option<&'a mut TreeItem> {
+    for item in items.iter_mut() {
+        if item.id.as_ref() == id {
+            return Some(item);
+        }
+        if let Some(found) = find_item_mut(&mut item.children, id) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+fn remove_item(items: &mut Vec<TreeItem>, id: &str) -> Option<TreeItem> {
+    if let Some(ix) = items.iter().position(|item| item.id.as_ref() == id) {
+        return Some(items.remove(ix));
+    }
+    items
+        .iter_mut()
+        .find_map(|item| remove_item(&mut item.children, id))
+}
+
+/// Returns the parent id (`None` for the root items) and the index of the item.
+fn position_of(items: &[TreeItem], id: &str) -> Option<(Option<SharedString>, usize)> {
+    if let Some(ix) = items.iter().position(|item| item.id.as_ref() == id) {
+        return Some((None, ix));
+    }
+    items.iter().find_map(|item| {
+        position_of(&item.children, id).map(|(parent, ix)| (parent.or(Some(item.id.clone())), ix))
+    })
+}
+
+fn collect_checked(items: &[TreeItem], ids: &mut Vec<SharedString>) {
+    for item in items {
+        if item.is_checked() {
+            ids.push(item.id.clone());
+        }
+        collect_checked(&item.children, ids);
+    }
+}
+
+fn add_entries(entries: &mut Vec<TreeEntry>, item: &TreeItem, depth: usize) {
+    entries.push(TreeEntry {
+        item: item.clone(),
+        depth,
+        placeholder: false,
+        matched_query: None,
+    });
+    if !item.is_expanded() {
+        return;
+    }
+
+    if item.is_loading() {
+        entries.push(TreeEntry {
+            item: item.clone(),
+            depth: depth + 1,
+            placeholder: true,
+            matched_query: None,
+        });
+    }
+    for child in &item.children {
+        add_entries(entries, child, depth + 1);
+    }
+}
+
+#[inline]
+fn is_label_matched(item: &TreeItem, query: &str) -> bool {
+    item.label.to_lowercase().contains(query)
+}
+
+/// Add the entries of the item if it or its descendants match the lowercase `query`,
+/// returns true if added.
+fn add_filtered_entries(
+    entries: &mut Vec<TreeEntry>,
+    item: &TreeItem,
+    depth: usize,
+    query: &SharedString,
+) -> bool {
+    let matched = is_label_matched(item, query);
+    let mut children = vec![];
+    for child in &item.children {
+        add_filtered_entries(&mut children, child, depth + 1, query);
+    }
+    if !matched && children.is_empty() {
+        return false;
+    }
+
+    entries.push(TreeEntry {
+        item: item.clone(),
+        depth,
+        placeholder: false,
+        matched_query: matched.then(|| query.clone()),
+    });
+    if item.is_expanded() {
+        entries.extend(children);
+    }
+    true
+}
+
+/// Expand the items that have descendants matching the lowercase `query`,
+/// returns true if any of the items or their descendants matches.
+fn expand_matched(items: &[TreeItem], query: &str) -> bool {
+    items.iter().fold(false, |any_matched, item| {
+        let matched = expand_matched(&item.children, query);
+        if matched {
+            item.state.borrow_mut().expanded = true;
+        }
+        any_matched || matched || is_label_matched(item, query)
+    })
+}
+
+/// Events emitted by the [`TreeState`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TreeEvent {
+    /// The folder item is expanded by the user.
+    Expand(SharedString),
+    /// The folder item is collapsed by the user.
+    Collapse(SharedString),
+    /// The item is checked or unchecked by the user, so are its descendants.
+    Check(SharedString, bool),
+    /// The item is moved by drag and drop, to the `index` of the children of the `parent`,
+    /// the `parent` is `None` for the root items.
+    Move {
+        id: SharedString,
+        parent: Option<SharedString>,
+        index: usize,
+    },
+}
+
+/// The drop position relative to the target item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DropPosition {
+    Before,
+    Inside,
+    After,
+}
+
+#[derive(Clone)]
+struct DragTreeItem {
+    entity_id: EntityId,
+    id: SharedString,
+    label: SharedString,
+}
+
+impl Render for DragTreeItem {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .px_3()
+            .py_1()
+            .bg(cx.theme().popover)
+            .text_color(cx.theme().popover_foreground)
+            .border_1()
+            .border_color(cx.theme().border)
+            .rounded(cx.theme().radius)
+            .shadow_md()
+            .opacity(0.9)
+            .child(self.label.clone())
+    }
 }
 
 /// State for managing tree items.
 pub struct TreeState {
     focus_handle: FocusHandle,
+    /// The root items.
+    items: Vec<TreeItem>,
     entries: Vec<TreeEntry>,
     scroll_handle: UniformListScrollHandle,
     selected_ix: Option<usize>,
+    checkable: bool,
+    draggable: bool,
+    drop_target: Option<(usize, DropPosition)>,
+    /// The lowercase filter query.
+    filter_query: SharedString,
+    type_ahead: String,
+    type_ahead_at: Option<Instant>,
+    load_children: Option<Rc<dyn Fn(&TreeItem, &mut Window, &mut App) -> Task<Vec<TreeItem>>>>,
     render_item: Rc<dyn Fn(usize, &TreeEntry, bool, &mut Window, &mut App) -> ListItem>,
 }
 
+impl EventEmitter<TreeEvent> for TreeState {}
+
 impl TreeState {
     /// Create a new empty tree state.
     pub fn new(cx: &mut App) -> Self {
@@ -191,32 +477,109 @@ impl TreeState {
             selected_ix: None,
             focus_handle: cx.focus_handle(),
             scroll_handle: UniformListScrollHandle::default(),
+            items: Vec::new(),
             entries: Vec::new(),
+            checkable: false,
+            draggable: false,
+            drop_target: None,
+            filter_query: SharedString::default(),
+            type_ahead: String::new(),
+            type_ahead_at: None,
+            load_children: None,
             render_item: Rc::new(|_, _, _, _, _| ListItem::new(0)),
         }
     }
 
     /// Set the tree items.
     pub fn items(mut self, items: impl Into<Vec<TreeItem>>) -> Self {
-        let items = items.into();
-        self.entries.clear();
-        for item in items.into_iter() {
-            self.add_entry(item, 0);
-        }
+        self.items = items.into();
+        sync_checked(&self.items);
+        self.rebuild_entries();
         self
     }
 
     /// Set the tree items.
     pub fn set_items(&mut self, items: impl Into<Vec<TreeItem>>, cx: &mut Context<Self>) {
-        let items = items.into();
-        self.entries.clear();
-        for item in items.into_iter() {
-            self.add_entry(item, 0);
-        }
+        self.items = items.into();
         self.selected_ix = None;
+        sync_checked(&self.items);
+        self.rebuild_entries();
+        cx.notify();
+    }
+
+    /// Set whether to show a checkbox before each item, default is false.
+    ///
+    /// Checking a folder checks all of its descendants, and a folder is checked
+    /// if all of its children are checked, or indeterminate if some of them are checked.
+    pub fn checkable(mut self, checkable: bool) -> Self {
+        self.checkable = checkable;
+        self
+    }
+
+    /// Set whether the items can be moved by drag and drop, default is false.
+    ///
+    /// Drop on the top or bottom edge of an item to move before or after it,
+    /// and drop on the middle of a folder to move into it.
+    pub fn draggable(mut self, draggable: bool) -> Self {
+        self.draggable = draggable;
+        self
+    }
+
+    /// Set the function to load the children of the lazy items, see [`TreeItem::lazy`].
+    ///
+    /// It is called when the lazy item is first expanded, with a loading row
+    /// shown under the item until the returned task is completed.
+    ///
+    /// Without this, you can handle the [`TreeEvent::Expand`] to load the children
+    /// and call [`TreeState::update_item_children`].
+    pub fn on_load_children<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&TreeItem, &mut Window, &mut App) -> Task<Vec<TreeItem>> + 'static,
+    {
+        self.load_children = Some(Rc::new(f));
+        self
+    }
+
+    /// Set the children of the item by `id`, e.g.: the loaded children of a lazy item.
+    pub fn update_item_children(
+        &mut self,
+        id: &str,
+        children: impl Into<Vec<TreeItem>>,
+        cx: &mut Context<Self>,
+    ) {
+        self.replace_children(id, children.into());
+        cx.notify();
+    }
+
+    /// Set the checked state of the item by `id` and its descendants.
+    pub fn set_checked(&mut self, id: &str, checked: bool, cx: &mut Context<Self>) {
+        self.check_item(id, checked);
         cx.notify();
     }
 
+    /// Returns the ids of the checked items, including the folders.
+    pub fn checked_ids(&self) -> Vec<SharedString> {
+        let mut ids = vec![];
+        collect_checked(&self.items, &mut ids);
+        ids
+    }
+
+    /// Set the filter query to only show the items whose label contains it (case-insensitive),
+    /// and their ancestors, the ancestors are expanded to show the matched items.
+    ///
+    /// Use [`TreeEntry::label`] to render the label with the matched text highlighted.
+    ///
+    /// The children of the lazy items are not filtered before they are loaded.
+    pub fn set_filter_query(&mut self, query: impl Into<SharedString>, cx: &mut Context<Self>) {
+        self.apply_filter_query(query.into());
+        cx.notify();
+    }
+
+    /// Returns the lowercase filter query, empty if not filtering.
+    pub fn filter_query(&self) -> &SharedString {
+        &self.filter_query
+    }
+
     /// Get the currently selected index, if any.
     pub fn selected_index(&self) -> Option<usize> {
         self.selected_ix
@@ -237,16 +600,11 @@ impl TreeState {
         self.selected_ix.and_then(|ix| self.entries.get(ix))
     }
 
-    fn add_entry(&mut self, item: TreeItem, depth: usize) {
-        self.entries.push(TreeEntry {
-            item: item.clone(),
-            depth,
-        });
-        if item.is_expanded() {
-            for child in &item.children {
-                self.add_entry(child.clone(), depth + 1);
-            }
-        }
+    /// Returns the index of the entry by item id.
+    fn index_of(&self, id: &str) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| !entry.placeholder && entry.item.id.as_ref() == id)
     }
 
     fn toggle_expand(&mut self, ix: usize) {
@@ -261,59 +619,298 @@ impl TreeState {
         self.rebuild_entries();
     }
 
+    /// Rebuild the entries from the items, and keep the selected item.
     fn rebuild_entries(&mut self) {
-        let root_items: Vec<TreeItem> = self
-            .entries
-            .iter()
-            .filter(|e| e.is_root())
-            .map(|e| e.item.clone())
-            .collect();
+        let selected_id = self.selected_entry().map(|entry| entry.item.id.clone());
+
         self.entries.clear();
-        for item in root_items.into_iter() {
-            self.add_entry(item, 0);
+        for item in &self.items {
+            if self.filter_query.is_empty() {
+                add_entries(&mut self.entries, item, 0);
+            } else {
+                add_filtered_entries(&mut self.entries, item, 0, &self.filter_query);
+            }
+        }
+
+        if let Some(id) = selected_id {
+            self.selected_ix = self.index_of(&id);
         }
     }
 
-    fn on_action_confirm(&mut self, _: &Confirm, _: &mut Window, cx: &mut Context<Self>) {
-        if let Some(selected_ix) = self.selected_ix {
-            if let Some(entry) = self.entries.get(selected_ix) {
-                if entry.is_folder() {
-                    self.toggle_expand(selected_ix);
-                    cx.notify();
+    fn apply_filter_query(&mut self, query: SharedString) {
+        let query: SharedString = query.trim().to_lowercase().into();
+        if query == self.filter_query {
+            return;
+        }
+
+        if !query.is_empty() {
+            expand_matched(&self.items, &query);
+        }
+        self.filter_query = query;
+        self.rebuild_entries();
+        if self.selected_ix.is_none() {
+            // Select the first matched item.
+            self.selected_ix = self.entries.iter().position(|entry| entry.is_matched());
+        }
+    }
+
+    /// Returns the index of the next item whose label starts with the type-ahead query.
+    ///
+    /// The current selected item is kept if it still matches the longer query.
+    fn type_ahead_match(&self) -> Option<usize> {
+        let len = self.entries.len();
+        let start = match self.selected_ix {
+            Some(ix) if self.type_ahead.chars().count() > 1 => ix,
+            Some(ix) => ix + 1,
+            None => 0,
+        };
+
+        (0..len).map(|i| (start + i) % len).find(|ix| {
+            let entry = &self.entries[*ix];
+            !entry.placeholder
+                && !entry.is_disabled()
+                && entry
+                    .item
+                    .label
+                    .to_lowercase()
+                    .starts_with(&self.type_ahead)
+        })
+    }
+
+    fn replace_children(&mut self, id: &str, children: Vec<TreeItem>) {
+        let Some(item) = find_item_mut(&mut self.items, id) else {
+            return;
+        };
+
+        if item.is_checked() {
+            for child in &children {
+                child.set_checked_deep(true);
+            }
+        }
+        item.children = children;
+        {
+            let mut state = item.state.borrow_mut();
+            state.lazy = false;
+            state.loading = false;
+        }
+
+        sync_checked(&self.items);
+        self.rebuild_entries();
+    }
+
+    fn check_item(&mut self, id: &str, checked: bool) {
+        let Some(item) = find_item(&self.items, id) else {
+            return;
+        };
+
+        item.set_checked_deep(checked);
+        sync_checked(&self.items);
+    }
+
+    /// Move the item before, after or into the target item,
+    /// returns the new parent and index of the item, `None` if not moved.
+    fn move_item(
+        &mut self,
+        id: &str,
+        target_id: &str,
+        position: DropPosition,
+    ) -> Option<(Option<SharedString>, usize)> {
+        let item = find_item(&self.items, id)?;
+        // Not allowed to move the item into itself or its descendants.
+        if id == target_id || find_item(&item.children, target_id).is_some() {
+            return None;
+        }
+        find_item(&self.items, target_id)?;
+
+        let item = remove_item(&mut self.items, id)?;
+        let (parent, index) = match position {
+            DropPosition::Inside => {
+                let target = find_item_mut(&mut self.items, target_id)?;
+                target.state.borrow_mut().expanded = true;
+                target.children.push(item);
+                (Some(target.id.clone()), target.children.len() - 1)
+            }
+            DropPosition::Before | DropPosition::After => {
+                let (parent, mut index) = position_of(&self.items, target_id)?;
+                if position == DropPosition::After {
+                    index += 1;
                 }
+                let siblings = match &parent {
+                    Some(parent) => &mut find_item_mut(&mut self.items, parent)?.children,
+                    None => &mut self.items,
+                };
+                siblings.insert(index, item);
+                (parent, index)
             }
+        };
+
+        sync_checked(&self.items);
+        self.rebuild_entries();
+        self.selected_ix = self.index_of(id);
+        Some((parent, index))
+    }
+
+    /// Toggle the folder by the user, emit the event and load the children if needed.
+    fn toggle_entry(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        if entry.placeholder || !entry.is_folder() {
+            return;
+        }
+
+        let item = entry.item.clone();
+        self.toggle_expand(ix);
+        if item.is_expanded() {
+            cx.emit(TreeEvent::Expand(item.id.clone()));
+            self.load_children_if_needed(&item, window, cx);
+        } else {
+            cx.emit(TreeEvent::Collapse(item.id.clone()));
         }
+        cx.notify();
     }
 
-    fn on_action_left(&mut self, _: &SelectLeft, _: &mut Window, cx: &mut Context<Self>) {
+    fn load_children_if_needed(
+        &mut self,
+        item: &TreeItem,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !item.is_lazy() || item.is_loading() {
+            return;
+        }
+
+        item.state.borrow_mut().loading = true;
+        self.rebuild_entries();
+
+        let Some(load_children) = self.load_children.clone() else {
+            return;
+        };
+
+        let task = load_children(item, window, cx);
+        let id = item.id.clone();
+        cx.spawn(async move |this, cx| {
+            let children = task.await;
+            _ = this.update(cx, |this, cx| {
+                this.update_item_children(&id, children, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn on_check(&mut self, ix: usize, checked: bool, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+
+        let id = entry.item.id.clone();
+        self.check_item(&id, checked);
+        cx.emit(TreeEvent::Check(id, checked));
+        cx.notify();
+    }
+
+    fn on_drag_move(
+        &mut self,
+        ix: usize,
+        event: &DragMoveEvent<DragTreeItem>,
+        cx: &mut Context<Self>,
+    ) {
+        let bounds = event.bounds;
+        let position = event.event.position;
+        if !bounds.contains(&position) || event.drag(cx).entity_id != cx.entity_id() {
+            return;
+        }
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+
+        // The lazy items are not allowed to drop into before the children are loaded.
+        let y = (position.y - bounds.top()) / bounds.size.height;
+        let drop_position = if entry.is_folder() && !entry.item.is_lazy() {
+            if y < 0.25 {
+                DropPosition::Before
+            } else if y > 0.75 && !entry.is_expanded() {
+                DropPosition::After
+            } else {
+                DropPosition::Inside
+            }
+        } else if y < 0.5 {
+            DropPosition::Before
+        } else {
+            DropPosition::After
+        };
+
+        let drop_target = (!entry.placeholder).then_some((ix, drop_position));
+        if self.drop_target != drop_target {
+            self.drop_target = drop_target;
+            cx.notify();
+        }
+    }
+
+    fn on_drop(&mut self, drag: &DragTreeItem, cx: &mut Context<Self>) {
+        let Some((ix, position)) = self.drop_target.take() else {
+            return;
+        };
+        cx.notify();
+        if drag.entity_id != cx.entity_id() {
+            return;
+        }
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+
+        let target_id = entry.item.id.clone();
+        if let Some((parent, index)) = self.move_item(&drag.id, &target_id, position) {
+            cx.emit(TreeEvent::Move {
+                id: drag.id.clone(),
+                parent,
+                index,
+            });
+        }
+    }
+
+    fn on_action_confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(selected_ix) = self.selected_ix {
+            self.toggle_entry(selected_ix, window, cx);
+        }
+    }
+
+    fn on_action_left(&mut self, _: &SelectLeft, window: &mut Window, cx: &mut Context<Self>) {
         if let Some(selected_ix) = self.selected_ix {
             if let Some(entry) = self.entries.get(selected_ix) {
                 if entry.is_folder() && entry.is_expanded() {
-                    self.toggle_expand(selected_ix);
-                    cx.notify();
+                    self.toggle_entry(selected_ix, window, cx);
                 }
             }
         }
     }
 
-    fn on_action_right(&mut self, _: &SelectRight, _: &mut Window, cx: &mut Context<Self>) {
+    fn on_action_right(&mut self, _: &SelectRight, window: &mut Window, cx: &mut Context<Self>) {
         if let Some(selected_ix) = self.selected_ix {
             if let Some(entry) = self.entries.get(selected_ix) {
                 if entry.is_folder() && !entry.is_expanded() {
-                    self.toggle_expand(selected_ix);
-                    cx.notify();
+                    self.toggle_entry(selected_ix, window, cx);
                 }
             }
         }
     }
 
     fn on_action_up(&mut self, _: &SelectUp, _: &mut Window, cx: &mut Context<Self>) {
-        let mut selected_ix = self.selected_ix.unwrap_or(0);
+        if self.entries.is_empty() {
+            return;
+        }
 
-        if selected_ix > 0 {
-            selected_ix = selected_ix - 1;
-        } else {
-            selected_ix = self.entries.len().saturating_sub(1);
+        let mut selected_ix = self.selected_ix.unwrap_or(0);
+        // Skip the loading rows.
+        loop {
+            if selected_ix > 0 {
+                selected_ix = selected_ix - 1;
+            } else {
+                selected_ix = self.entries.len().saturating_sub(1);
+            }
+            if !self.entries[selected_ix].placeholder {
+                break;
+            }
         }
 
         self.selected_ix = Some(selected_ix);
@@ -323,11 +920,21 @@ impl TreeState {
     }
 
     fn on_action_down(&mut self, _: &SelectDown, _: &mut Window, cx: &mut Context<Self>) {
+        if self.entries.is_empty() {
+            return;
+        }
+
         let mut selected_ix = self.selected_ix.unwrap_or(0);
-        if selected_ix + 1 < self.entries.len() {
-            selected_ix = selected_ix + 1;
-        } else {
-            selected_ix = 0;
+        // Skip the loading rows.
+        loop {
+            if selected_ix + 1 < self.entries.len() {
+                selected_ix = selected_ix + 1;
+            } else {
+                selected_ix = 0;
+            }
+            if !self.entries[selected_ix].placeholder {
+                break;
+            }
         }
 
         self.selected_ix = Some(selected_ix);
@@ -336,38 +943,177 @@ impl TreeState {
         cx.notify();
     }
 
-    fn on_entry_click(&mut self, ix: usize, _: &mut Window, cx: &mut Context<Self>) {
+    fn on_key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let keystroke = &event.keystroke;
+        let modifiers = keystroke.modifiers;
+        if modifiers.control || modifiers.alt || modifiers.platform || modifiers.function {
+            return;
+        }
+        // Ignore the whitespace to not conflict with the `space` key bindings.
+        let Some(text) = keystroke
+            .key_char
+            .as_ref()
+            .filter(|text| text.chars().all(|c| !c.is_control() && !c.is_whitespace()))
+        else {
+            return;
+        };
+
+        let now = Instant::now();
+        if self
+            .type_ahead_at
+            .is_none_or(|at| now.duration_since(at) > TYPE_AHEAD_TIMEOUT)
+        {
+            self.type_ahead.clear();
+        }
+        self.type_ahead_at = Some(now);
+        self.type_ahead.push_str(&text.to_lowercase());
+
+        if let Some(ix) = self.type_ahead_match() {
+            self.selected_ix = Some(ix);
+            self.scroll_handle
+                .scroll_to_item(ix, gpui::ScrollStrategy::Center);
+            cx.notify();
+        }
+        cx.stop_propagation();
+    }
+
+    fn on_entry_click(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
         self.selected_ix = Some(ix);
-        self.toggle_expand(ix);
+        self.toggle_entry(ix, window, cx);
         cx.notify();
     }
+
+    fn render_placeholder(&self, ix: usize, depth: usize) -> Stateful<Div> {
+        div().id(ix).child(
+            ListItem::new(ix)
+                .disabled(true)
+                .pl(px(16.) * depth + px(12.))
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(Spinner::new().small())
+                        .child(t!("Tree.loading").to_string()),
+                ),
+        )
+    }
 }
 
 impl Render for TreeState {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let render_item = self.render_item.clone();
+        if !cx.has_active_drag() {
+            self.drop_target = None;
+        }
 
         div().id("tree-state").size_full().relative().child(
             uniform_list("entries", self.entries.len(), {
                 cx.processor(move |state, visible_range: Range<usize>, window, cx| {
+                    let entity_id = cx.entity_id();
+                    let checkable = state.checkable;
+                    let draggable = state.draggable;
+
                     let mut items = Vec::with_capacity(visible_range.len());
                     for ix in visible_range {
                         let entry = &state.entries[ix];
+                        if entry.placeholder {
+                            items.push(state.render_placeholder(ix, entry.depth));
+                            continue;
+                        }
+
                         let selected = Some(ix) == state.selected_ix;
+                        let disabled = entry.item().is_disabled();
+                        let drop_position = state
+                            .drop_target
+                            .filter(|(target_ix, _)| *target_ix == ix)
+                            .map(|(_, position)| position);
                         let item = (render_item)(ix, entry, selected, window, cx);
+                        let item = div()
+                            .id("item")
+                            .flex_1()
+                            .overflow_hidden()
+                            .child(item.disabled(disabled).selected(selected))
+                            .when(!disabled, |this| {
+                                // Select on click to not toggle the folder when start dragging.
+                                if draggable {
+                                    this.on_click(cx.listener(move |this, _, window, cx| {
+                                        this.on_entry_click(ix, window, cx);
+                                    }))
+                                } else {
+                                    this.on_mouse_down(
+                                        MouseButton::Left,
+                                        cx.listener(move |this, _, window, cx| {
+                                            this.on_entry_click(ix, window, cx);
+                                        }),
+                                    )
+                                }
+                            });
 
                         let el = div()
                             .id(ix)
-                            .child(item.disabled(entry.item().is_disabled()).selected(selected))
-                            .when(!entry.item().is_disabled(), |this| {
-                                this.on_mouse_down(
-                                    MouseButton::Left,
-                                    cx.listener({
-                                        move |this, _, window, cx| {
-                                            this.on_entry_click(ix, window, cx);
-                                        }
-                                    }),
-                                )
+                            .relative()
+                            .map(|this| {
+                                if checkable {
+                                    this.child(
+                                        h_flex()
+                                            .w_full()
+                                            .gap_1()
+                                            .child(
+                                                Checkbox::new("checkbox")
+                                                    .checked(entry.is_checked())
+                                                    .indeterminate(entry.item.is_indeterminate())
+                                                    .disabled(disabled)
+                                                    .on_click(cx.listener(
+                                                        move |this, checked: &bool, _, cx| {
+                                                            this.on_check(ix, *checked, cx);
+                                                        },
+                                                    )),
+                                            )
+                                            .child(item),
+                                    )
+                                } else {
+                                    this.child(item)
+                                }
+                            })
+                            .when(draggable, |this| {
+                                this.when(!disabled, |this| {
+                                    this.on_drag(
+                                        DragTreeItem {
+                                            entity_id,
+                                            id: entry.item.id.clone(),
+                                            label: entry.item.label.clone(),
+                                        },
+                                        |drag, _, _, cx| {
+                                            cx.stop_propagation();
+                                            cx.new(|_| drag.clone())
+                                        },
+                                    )
+                                })
+                                .on_drag_move(cx.listener(
+                                    move |this, event: &DragMoveEvent<DragTreeItem>, _, cx| {
+                                        this.on_drag_move(ix, event, cx);
+                                    },
+                                ))
+                                .on_drop(cx.listener(
+                                    |this, drag: &DragTreeItem, _, cx| {
+                                        this.on_drop(drag, cx);
+                                    },
+                                ))
+                            })
+                            .when_some(drop_position, |this, position| {
+                                let color = cx.theme().drag_border;
+                                this.child(div().absolute().left_0().right_0().map(|this| {
+                                    match position {
+                                        DropPosition::Before => this.top_0().h(px(2.)).bg(color),
+                                        DropPosition::After => this.bottom_0().h(px(2.)).bg(color),
+                                        DropPosition::Inside => this
+                                            .top_0()
+                                            .bottom_0()
+                                            .bg(cx.theme().drop_target)
+                                            .border_1()
+                                            .border_color(color)
+                                            .rounded(cx.theme().radius),
+                                    }
+                                }))
                             });
 
                         items.push(el)
@@ -433,6 +1179,7 @@ impl RenderOnce for Tree {
             .on_action(window.listener_for(&self.state, TreeState::on_action_right))
             .on_action(window.listener_for(&self.state, TreeState::on_action_up))
             .on_action(window.listener_for(&self.state, TreeState::on_action_down))
+            .on_key_down(window.listener_for(&self.state, TreeState::on_key_down))
             .size_full()
             .child(self.state)
             .refine_style(&self.style)
@@ -444,7 +1191,7 @@ impl RenderOnce for Tree {
 mod tests {
     use indoc::indoc;
 
-    use super::TreeState;
+    use super::{DropPosition, TreeState};
     use gpui::AppContext as _;
 
     fn assert_entries(entries: &Vec<super::TreeEntry>, expected: &str) {
@@ -453,7 +1200,11 @@ mod tests {
             .map(|e| {
                 let mut s = String::new();
                 s.push_str(&"    ".repeat(e.depth));
-                s.push_str(e.item().label.as_str());
+                if e.placeholder {
+                    s.push_str("...");
+                } else {
+                    s.push_str(e.item().label.as_str());
+                }
                 s
             })
             .collect();
@@ -461,11 +1212,10 @@ mod tests {
         assert_eq!(actual.trim(), expected.trim());
     }
 
-    #[gpui::test]
-    fn test_tree_entry(cx: &mut gpui::TestAppContext) {
+    fn build_items() -> Vec<super::TreeItem> {
         use super::TreeItem;
 
-        let items = vec![
+        vec![
             TreeItem::new("src", "src")
                 .expanded(true)
                 .child(
@@ -479,9 +1229,12 @@ mod tests {
             TreeItem::new("Cargo.toml", "Cargo.toml"),
             TreeItem::new("Cargo.lock", "Cargo.lock").disabled(true),
             TreeItem::new("README.md", "README.md"),
-        ];
+        ]
+    }
 
-        let state = cx.new(|cx| TreeState::new(cx).items(items));
+    #[gpui::test]
+    fn test_tree_entry(cx: &mut gpui::TestAppContext) {
+        let state = cx.new(|cx| TreeState::new(cx).items(build_items()));
         state.update(cx, |state, _| {
             assert_entries(
                 &state.entries,
@@ -531,4 +1284,208 @@ mod tests {
             );
         })
     }
+
+    #[gpui::test]
+    fn test_lazy_children(cx: &mut gpui::TestAppContext) {
+        use super::TreeItem;
+
+        let items = vec![
+            TreeItem::new("src", "src").lazy(true).checked(true),
+            TreeItem::new("README.md", "README.md"),
+        ];
+        let state = cx.new(|cx| TreeState::new(cx).items(items));
+        state.update(cx, |state, _| {
+            assert_eq!(state.entries[0].is_folder(), true);
+
+            state.selected_ix = Some(1);
+            state.toggle_expand(0);
+            state.items[0].state.borrow_mut().loading = true;
+            state.rebuild_entries();
+            assert_entries(
+                &state.entries,
+                indoc! {
+                    r#"
+                src
+                    ...
+                README.md
+                "#
+                },
+            );
+            assert_eq!(state.selected_ix, Some(2));
+
+            state.replace_children(
+                "src",
+                vec![
+                    TreeItem::new("src/lib.rs", "lib.rs"),
+                    TreeItem::new("src/main.rs", "main.rs"),
+                ],
+            );
+            assert_entries(
+                &state.entries,
+                indoc! {
+                    r#"
+                src
+                    lib.rs
+                    main.rs
+                README.md
+                "#
+                },
+            );
+            assert_eq!(state.selected_ix, Some(3));
+            assert_eq!(state.entries[0].item().is_loading(), false);
+            // The loaded children follow the checked parent.
+            assert_eq!(
+                state.checked_ids(),
+                vec!["src", "src/lib.rs", "src/main.rs"]
+            );
+
+            state.replace_children("src", vec![]);
+            assert_eq!(state.entries[0].is_folder(), false);
+        })
+    }
+
+    #[gpui::test]
+    fn test_move_item(cx: &mut gpui::TestAppContext) {
+        let state = cx.new(|cx| TreeState::new(cx).items(build_items()));
+        state.update(cx, |state, _| {
+            assert_eq!(
+                state.move_item("README.md", "src/ui/icon.rs", DropPosition::Before),
+                Some((Some("src/ui".into()), 1))
+            );
+            assert_eq!(
+                state.move_item("Cargo.toml", "src/lib.rs", DropPosition::After),
+                Some((Some("src".into()), 2))
+            );
+            assert_eq!(
+                state.move_item("src/ui/mod.rs", "Cargo.lock", DropPosition::Before),
+                Some((None, 1))
+            );
+            assert_eq!(
+                state.move_item("src/lib.rs", "src/ui", DropPosition::Inside),
+                Some((Some("src/ui".into()), 3))
+            );
+            assert_eq!(state.selected_entry().unwrap().item().id, "src/lib.rs");
+            assert_entries(
+                &state.entries,
+                indoc! {
+                    r#"
+                src
+                    ui
+                        button.rs
+                        README.md
+                        icon.rs
+                        lib.rs
+                    Cargo.toml
+                mod.rs
+                Cargo.lock
+                "#
+                },
+            );
+
+            // Not allowed to move into itself or its descendants.
+            assert_eq!(state.move_item("src", "src", DropPosition::Inside), None);
+            assert_eq!(
+                state.move_item("src", "src/ui/icon.rs", DropPosition::After),
+                None
+            );
+            assert_eq!(state.move_item("src", "unknown", DropPosition::After), None);
+            assert_eq!(state.entries.len(), 9);
+        })
+    }
+
+    #[gpui::test]
+    fn test_checked(cx: &mut gpui::TestAppContext) {
+        let state = cx.new(|cx| TreeState::new(cx).items(build_items()));
+        state.update(cx, |state, _| {
+            state.check_item("src/ui", true);
+            assert_eq!(
+                state.checked_ids(),
+                vec![
+                    "src/ui",
+                    "src/ui/button.rs",
+                    "src/ui/icon.rs",
+                    "src/ui/mod.rs"
+                ]
+            );
+            let src = &state.entries[0];
+            assert_eq!(src.is_checked(), false);
+            assert_eq!(src.item().is_indeterminate(), true);
+
+            state.check_item("src/lib.rs", true);
+            assert_eq!(state.entries[0].is_checked(), true);
+            assert_eq!(state.entries[0].item().is_indeterminate(), false);
+
+            state.check_item("src/ui/icon.rs", false);
+            assert_eq!(state.entries[0].is_checked(), false);
+            assert_eq!(state.entries[1].is_checked(), false);
+            assert_eq!(state.entries[1].item().is_indeterminate(), true);
+
+            state.check_item("src", false);
+            assert!(state.checked_ids().is_empty());
+            assert_eq!(state.entries[0].item().is_indeterminate(), false);
+        })
+    }
+
+    #[gpui::test]
+    fn test_filter(cx: &mut gpui::TestAppContext) {
+        let state = cx.new(|cx| TreeState::new(cx).items(build_items()));
+        state.update(cx, |state, cx| {
+            state.toggle_expand(1);
+            state.set_filter_query(" MOD ", cx);
+            assert_eq!(state.filter_query(), "mod");
+            assert_entries(
+                &state.entries,
+                indoc! {
+                    r#"
+                src
+                    ui
+                        mod.rs
+                "#
+                },
+            );
+            assert_eq!(state.entries[1].is_expanded(), true);
+            assert_eq!(state.entries[1].is_matched(), false);
+            assert_eq!(state.entries[2].is_matched(), true);
+            assert_eq!(state.selected_index(), Some(2));
+
+            state.set_filter_query("cargo", cx);
+            assert_entries(
+                &state.entries,
+                indoc! {
+                    r#"
+                Cargo.toml
+                Cargo.lock
+                "#
+                },
+            );
+
+            state.set_filter_query("", cx);
+            assert_eq!(state.entries.len(), 9);
+            assert!(state.entries.iter().all(|entry| !entry.is_matched()));
+        })
+    }
+
+    #[gpui::test]
+    fn test_type_ahead(cx: &mut gpui::TestAppContext) {
+        let state = cx.new(|cx| TreeState::new(cx).items(build_items()));
+        state.update(cx, |state, _| {
+            state.type_ahead = "c".into();
+            assert_eq!(state.type_ahead_match(), Some(6));
+
+            // Wrap around to the selected item, the disabled `Cargo.lock` is skipped.
+            state.selected_ix = Some(6);
+            assert_eq!(state.type_ahead_match(), Some(6));
+
+            // Keep the selected item if it still matches.
+            state.type_ahead = "cargo.t".into();
+            assert_eq!(state.type_ahead_match(), Some(6));
+
+            state.selected_ix = Some(8);
+            state.type_ahead = "i".into();
+            assert_eq!(state.type_ahead_match(), Some(3));
+
+            state.type_ahead = "x".into();
+            assert_eq!(state.type_ahead_match(), None);
+        })
+    }
 }